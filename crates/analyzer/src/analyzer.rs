@@ -2,12 +2,16 @@ use crate::HashMap;
 use crate::analyzer::resource_table::PathId;
 use crate::analyzer_error::AnalyzerError;
 use crate::attribute_table;
+use crate::cdc;
 use crate::handlers::check_expression::CheckExpression;
 use crate::handlers::*;
 use crate::instance_history;
+use crate::lint;
 use crate::msb_table;
 use crate::namespace::Namespace;
 use crate::namespace_table;
+use crate::netlist::{self, NetlistCache};
+use crate::rdc;
 use crate::reference_table;
 use crate::symbol::{
     Direction, DocComment, Symbol, SymbolId, SymbolKind, TypeKind, VariableAffiliation,
@@ -15,6 +19,7 @@ use crate::symbol::{
 use crate::symbol_path::SymbolPathNamespace;
 use crate::symbol_table;
 use crate::type_dag;
+use crate::unused;
 use crate::var_ref::{
     AssignPosition, AssignPositionTree, AssignPositionType, ExpressionTargetType, VarRef,
     VarRefAffiliation, VarRefPath, VarRefType,
@@ -69,9 +74,9 @@ pub struct AnalyzerPass2Expression {
 }
 
 impl AnalyzerPass2Expression {
-    pub fn new(inst_context: Vec<TokenRange>) -> Self {
+    pub fn new(lint_opt: &Lint, inst_context: Vec<TokenRange>) -> Self {
         AnalyzerPass2Expression {
-            check_expression: CheckExpression::new(inst_context),
+            check_expression: CheckExpression::new(lint_opt, inst_context),
         }
     }
 
@@ -236,6 +241,49 @@ impl AnalyzerPass3 {
 
         ret
     }
+
+    pub fn check_hierarchy(&self, build_opt: &Build, lint_opt: &Lint) -> Vec<AnalyzerError> {
+        let mut ret = Vec::new();
+        let usage = unused::Usage::new(&self.symbols, &self.var_refs, lint_opt);
+        let cdc_enabled = cdc::CODES.iter().any(|x| lint::enabled(lint_opt, x));
+        let rdc_enabled = rdc::CODES.iter().any(|x| lint::enabled(lint_opt, x));
+        if !cdc_enabled && !rdc_enabled && usage.is_none() {
+            return ret;
+        }
+
+        let mut cache = NetlistCache::default();
+        for symbol in &self.symbols {
+            if symbol.token.source != self.path {
+                continue;
+            }
+            let SymbolKind::Module(x) = &symbol.kind else {
+                continue;
+            };
+
+            // Generic modules are checked through the instances,
+            // and synchronizers cross clock domains by design
+            let netlist =
+                if x.generic_parameters.is_empty() && netlist::blackbox_kind(symbol).is_none() {
+                    netlist::elaborate(symbol, build_opt.instance_depth_limit, &mut cache)
+                } else {
+                    None
+                };
+
+            if let Some(netlist) = netlist {
+                if cdc_enabled {
+                    ret.append(&mut cdc::check(netlist));
+                }
+                if rdc_enabled {
+                    ret.append(&mut rdc::check(netlist, &build_opt.reset_type));
+                }
+            }
+            if let Some(usage) = &usage {
+                ret.append(&mut unused::check(symbol, netlist, usage));
+            }
+        }
+
+        ret
+    }
 }
 
 pub struct Analyzer {
@@ -343,6 +391,7 @@ impl Analyzer {
         ret.append(&mut pass3.check_variables());
         ret.append(&mut pass3.check_assignment());
         ret.append(&mut pass3.check_unassigned());
+        ret.append(&mut pass3.check_hierarchy(&self.build_opt, &self.lint_opt));
 
         ret
     }
@@ -587,11 +636,17 @@ fn check_assign_position_tree(
         tree.add(pos.clone());
     }
 
-    if let Some(token) = tree.check_always_comb_uncovered() {
+    if let Some(x) = tree.check_always_comb_uncovered() {
         ret.push(AnalyzerError::uncovered_branch(
             &symbol.token.to_string(),
             &symbol.token.into(),
-            &token.into(),
+            &x.token.into(),
+        ));
+        ret.push(AnalyzerError::latch_inferred(
+            &symbol.token.to_string(),
+            &x.item.name,
+            &symbol.token.into(),
+            &x.item.range,
         ));
     }
 
@@ -1,8 +1,120 @@
 use crate::evaluator::EvaluatedError;
 use crate::multi_sources::{MultiSources, Source};
+use crate::suggestion::{Edit, Suggestion, indent_at};
 use miette::{self, Diagnostic, SourceSpan};
 use thiserror::Error;
 use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::Token;
+
+/// Codes of diagnostics which are allowed unless they are enabled through lint rules explicitly
+pub const ANALYZER_DEFAULT_ALLOWED: &[&str] = &[
+    "dead_logic",
+    "mixed_signedness",
+    "unread_register",
+    "unused_input_port",
+    "unused_output_port",
+    "unused_parameter",
+    "width_extension",
+    "width_truncation",
+];
+
+/// Codes of all diagnostics reported by the analyzer
+pub const ANALYZER_ERROR_CODES: &[&str] = &[
+    "anonymous_identifier_usage",
+    "asynchronous_reset_deassertion",
+    "call_non_function",
+    "combinational_logic_before_synchronizer",
+    "cyclice_type_dependency",
+    "dead_logic",
+    "duplicated_identifier",
+    "exceed_limit",
+    "include_failure",
+    "incompat_proto",
+    "infinite_recursion",
+    "invalid_assignment",
+    "invalid_assignment_to_const",
+    "invalid_case_condition_non_elaborative",
+    "invalid_cast",
+    "invalid_clock",
+    "invalid_clock_domain",
+    "invalid_connect_operand",
+    "invalid_direction",
+    "invalid_enum_variant_value",
+    "invalid_factor",
+    "invalid_identifier",
+    "invalid_import",
+    "invalid_lsb",
+    "invalid_modifier",
+    "invalid_modport_function_item",
+    "invalid_modport_variable_item",
+    "invalid_msb",
+    "invalid_number_character",
+    "invalid_port_default_value",
+    "invalid_reset",
+    "invalid_reset_non_elaborative",
+    "invalid_select",
+    "invalid_statement",
+    "invalid_test",
+    "invisible_identifier",
+    "latch_inferred",
+    "mismatch_assignment",
+    "mismatch_attribute_args",
+    "mismatch_clock_domain",
+    "mismatch_function_arity",
+    "mismatch_generics_arity",
+    "mismatch_type",
+    "missing_clock_domain",
+    "missing_clock_signal",
+    "missing_default_argument",
+    "missing_if_reset",
+    "missing_port",
+    "missing_reset_signal",
+    "missing_reset_statement",
+    "missing_tri",
+    "mixed_function_argument",
+    "mixed_register_reset",
+    "mixed_signedness",
+    "multi_bit_clock_domain_crossing",
+    "multiple_assignment",
+    "multiple_default_clock",
+    "multiple_default_reset",
+    "private_member",
+    "reconvergent_clock_domain_crossing",
+    "referring_package_before_definition",
+    "reserved_identifier",
+    "sv_keyword_usage",
+    "sv_with_implicit_reset",
+    "too_large_enum_variant",
+    "too_large_number",
+    "too_much_enum_variant",
+    "unassign_variable",
+    "unassignable_output",
+    "uncovered_branch",
+    "undefined_identifier",
+    "unenclosed_inner_if_expression",
+    "unevaluatable_enum_variant_value",
+    "unknown_attribute",
+    "unknown_embed_lang",
+    "unknown_embed_way",
+    "unknown_include_way",
+    "unknown_member",
+    "unknown_msb",
+    "unknown_param",
+    "unknown_port",
+    "unknown_unsafe",
+    "unread_register",
+    "unresolvable_generic_argument",
+    "unsynchronized_clock_domain_crossing",
+    "unsynchronized_reset_domain_crossing",
+    "unused_input_port",
+    "unused_output_port",
+    "unused_parameter",
+    "unused_return",
+    "unused_variable",
+    "width_extension",
+    "width_truncation",
+    "wrong_seperator",
+];
 
 #[derive(Error, Diagnostic, Debug)]
 pub enum AnalyzerError {
@@ -585,6 +697,232 @@ pub enum AnalyzerError {
         other_location: SourceSpan,
     },
 
+    #[diagnostic(
+        severity(Warning),
+        code(unsynchronized_clock_domain_crossing),
+        help("capture the signal through synchronizer"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unsynchronized_clock_domain_crossing"
+        )
+    )]
+    #[error(
+        "{src} in clock domain {src_domain} is captured by {dst} in clock domain {dst_domain} without synchronizer"
+    )]
+    UnsynchronizedClockDomainCrossing {
+        src: String,
+        src_domain: String,
+        dst: String,
+        dst_domain: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(multi_bit_clock_domain_crossing),
+        help("use async_fifo or async_handshake to transfer multi-bit data"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#multi_bit_clock_domain_crossing"
+        )
+    )]
+    #[error(
+        "multi-bit {src} in clock domain {src_domain} is captured by {dst} in clock domain {dst_domain} without synchronizer"
+    )]
+    MultiBitClockDomainCrossing {
+        src: String,
+        src_domain: String,
+        dst: String,
+        dst_domain: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(reconvergent_clock_domain_crossing),
+        help("synchronize the related signals through one synchronizer"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#reconvergent_clock_domain_crossing"
+        )
+    )]
+    #[error(
+        "outputs of synchronizer {synchronizer} and {other} from the same clock domain are reconverged at {dst}"
+    )]
+    ReconvergentClockDomainCrossing {
+        dst: String,
+        synchronizer: String,
+        other: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(combinational_logic_before_synchronizer),
+        help("put a register before synchronizer"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#combinational_logic_before_synchronizer"
+        )
+    )]
+    #[error("input of synchronizer {synchronizer} is driven by combinational logic at {driver}")]
+    CombinationalLogicBeforeSynchronizer {
+        synchronizer: String,
+        driver: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(unsynchronized_reset_domain_crossing),
+        help("reset the destination by a reset derived from the source reset"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unsynchronized_reset_domain_crossing"
+        )
+    )]
+    #[error(
+        "{src} reset by {src_reset} is captured by {dst} reset by {dst_reset} without reset synchronization"
+    )]
+    UnsynchronizedResetDomainCrossing {
+        src: String,
+        src_reset: String,
+        dst: String,
+        dst_reset: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(asynchronous_reset_deassertion),
+        help("synchronize deassertion of the reset like std::async_fifo_reset_sync"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#asynchronous_reset_deassertion"
+        )
+    )]
+    #[error(
+        "asynchronous reset {reset} in clock domain {src_domain} is deasserted asynchronously to {dst} in clock domain {dst_domain}"
+    )]
+    AsynchronousResetDeassertion {
+        reset: String,
+        src_domain: String,
+        dst: String,
+        dst_domain: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(mixed_register_reset),
+        help("reset all parts of the register, or split it into separate variables"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#mixed_register_reset"
+        )
+    )]
+    #[error("{identifier} is reset in some always_ff, but is left un-reset in another always_ff")]
+    MixedRegisterReset {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(unused_input_port),
+        help("remove the port, or add prefix `_` to the port name"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unused_input_port"
+        )
+    )]
+    #[error("input port {identifier} is not used in module {module}")]
+    UnusedInputPort {
+        identifier: String,
+        module: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(unused_output_port),
+        help("remove the port, or connect it in the instantiation"),
+        url(
+            "https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unused_output_port"
+        )
+    )]
+    #[error("output port {identifier} of module {module} is not read by any instantiation")]
+    UnusedOutputPort {
+        identifier: String,
+        module: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(dead_logic),
+        help("remove the logic, or connect its result to any output"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#dead_logic")
+    )]
+    #[error("{identifier} never reaches any output of top module {top}")]
+    DeadLogic {
+        identifier: String,
+        top: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(unread_register),
+        help("remove the register, or add prefix `_` to the register name"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unread_register")
+    )]
+    #[error("register {identifier} is written, but never read")]
+    UnreadRegister {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(unused_parameter),
+        help("remove the parameter"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#unused_parameter")
+    )]
+    #[error("parameter {identifier} is neither overridden by any instantiation nor used")]
+    UnusedParameter {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
     #[diagnostic(
         severity(Warning),
         code(mismatch_assignment),
@@ -605,6 +943,61 @@ pub enum AnalyzerError {
         inst_context: Vec<SourceSpan>,
     },
 
+    #[diagnostic(
+        severity(Warning),
+        code(width_truncation),
+        help("select the required bits explicitly"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#width_truncation")
+    )]
+    #[error("{src_width}-bit value is truncated to {dst_width}-bit \"{dst}\"")]
+    WidthTruncation {
+        src_width: usize,
+        dst: String,
+        dst_width: usize,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label(collection, "instantiated at")]
+        inst_context: Vec<SourceSpan>,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(width_extension),
+        help("extend the value explicitly by concatenation"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#width_extension")
+    )]
+    #[error("{src_width}-bit value is implicitly extended to {dst_width}-bit \"{dst}\"")]
+    WidthExtension {
+        src_width: usize,
+        dst: String,
+        dst_width: usize,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label(collection, "instantiated at")]
+        inst_context: Vec<SourceSpan>,
+    },
+
+    #[diagnostic(
+        severity(Warning),
+        code(mixed_signedness),
+        help("signed operand is treated as unsigned"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#mixed_signedness")
+    )]
+    #[error("signed and unsigned operands are mixed in \"{operator}\"")]
+    MixedSignedness {
+        operator: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label(collection, "instantiated at")]
+        inst_context: Vec<SourceSpan>,
+    },
+
     #[diagnostic(
         severity(Error),
         code(missing_if_reset),
@@ -633,6 +1026,7 @@ pub enum AnalyzerError {
         input: MultiSources,
         #[label("Error location")]
         error_location: SourceSpan,
+        suggestions: Vec<Suggestion>,
     },
 
     #[diagnostic(
@@ -684,6 +1078,7 @@ pub enum AnalyzerError {
         error_location: SourceSpan,
         #[label("Not reset")]
         reset: SourceSpan,
+        suggestions: Vec<Suggestion>,
     },
 
     #[diagnostic(
@@ -747,6 +1142,7 @@ pub enum AnalyzerError {
         input: MultiSources,
         #[label("Error location")]
         error_location: SourceSpan,
+        suggestions: Vec<Suggestion>,
     },
 
     #[diagnostic(
@@ -1112,6 +1508,7 @@ pub enum AnalyzerError {
         input: MultiSources,
         #[label("Error location")]
         error_location: SourceSpan,
+        suggestions: Vec<Suggestion>,
     },
 
     #[diagnostic(
@@ -1179,6 +1576,24 @@ pub enum AnalyzerError {
         uncovered: SourceSpan,
     },
 
+    #[diagnostic(
+        severity(Warning),
+        code(latch_inferred),
+        help("assign a value in the branch, or add a default assignment before the branch"),
+        url("https://doc.veryl-lang.org/book/07_appendix/02_semantic_error.html#latch_inferred")
+    )]
+    #[error("latch is inferred for {identifier} because it is not assigned in {branch} branch")]
+    LatchInferred {
+        identifier: String,
+        branch: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label("Uncovered")]
+        uncovered: SourceSpan,
+    },
+
     #[diagnostic(
         severity(Error),
         code(reserved_identifier),
@@ -1226,6 +1641,7 @@ pub enum AnalyzerError {
         input: MultiSources,
         #[label("Error location")]
         error_location: SourceSpan,
+        suggestions: Vec<Suggestion>,
     },
 
     #[diagnostic(severity(Error), code(infinite_recursion), help(""), url(""))]
@@ -1286,7 +1702,60 @@ fn source_with_context(
     (sources, ranges)
 }
 
+fn unused_variable_suggestions(identifier: &str, token: &Token) -> Vec<Suggestion> {
+    let mut ret = Vec::new();
+
+    let name = identifier.trim_start_matches("r#");
+    if let Some(x) = Edit::replace(token, &format!("_{name}")) {
+        ret.push(Suggestion::edit(
+            &format!("Add prefix `_` to {identifier}"),
+            vec![x],
+        ));
+    }
+
+    // The identifier of variable follows `var` or `let` keyword
+    let text = token.source.get_text();
+    if let Some(head) = text.get(..token.pos as usize) {
+        let head = head.trim_end();
+        if head.ends_with("var") || head.ends_with("let") {
+            let offset = head.len() - 3;
+            let attribute = format!("#[allow(unused_variable)]\n{}", indent_at(&text, offset));
+            if let Some(x) = Edit::insert(token, offset, &attribute) {
+                ret.push(Suggestion::edit("Add #[allow(unused_variable)]", vec![x]));
+            }
+        }
+    }
+
+    ret
+}
+
+fn missing_reset_statement_suggestions(name: &str, reset: &Token) -> Vec<Suggestion> {
+    let text = reset.source.get_text();
+    let pos = reset.pos as usize;
+    let Some(brace) = text.get(pos..).and_then(|x| x.find('{')) else {
+        return vec![];
+    };
+
+    let statement = format!("\n{}    {name} = '0;", indent_at(&text, pos));
+    Edit::insert(reset, pos + brace + 1, &statement)
+        .map(|x| Suggestion::edit(&format!("Reset {name} in if_reset"), vec![x]))
+        .into_iter()
+        .collect()
+}
+
 impl AnalyzerError {
+    /// Machine-applicable fixes of the error
+    pub fn suggestions(&self) -> &[Suggestion] {
+        match self {
+            AnalyzerError::MissingPort { suggestions, .. }
+            | AnalyzerError::MissingResetStatement { suggestions, .. }
+            | AnalyzerError::SvKeywordUsage { suggestions, .. }
+            | AnalyzerError::UnusedVariable { suggestions, .. }
+            | AnalyzerError::WrongSeparator { suggestions, .. } => suggestions,
+            _ => &[],
+        }
+    }
+
     pub fn anonymous_identifier_usage(token: &TokenRange) -> Self {
         AnalyzerError::AnonymousIdentifierUsage {
             input: source(token),
@@ -1598,6 +2067,153 @@ impl AnalyzerError {
         }
     }
 
+    pub fn unsynchronized_clock_domain_crossing(
+        src: &str,
+        src_domain: &str,
+        dst: &str,
+        dst_domain: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::UnsynchronizedClockDomainCrossing {
+            src: src.to_string(),
+            src_domain: src_domain.to_string(),
+            dst: dst.to_string(),
+            dst_domain: dst_domain.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn multi_bit_clock_domain_crossing(
+        src: &str,
+        src_domain: &str,
+        dst: &str,
+        dst_domain: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::MultiBitClockDomainCrossing {
+            src: src.to_string(),
+            src_domain: src_domain.to_string(),
+            dst: dst.to_string(),
+            dst_domain: dst_domain.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn reconvergent_clock_domain_crossing(
+        dst: &str,
+        synchronizer: &str,
+        other: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::ReconvergentClockDomainCrossing {
+            dst: dst.to_string(),
+            synchronizer: synchronizer.to_string(),
+            other: other.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn combinational_logic_before_synchronizer(
+        synchronizer: &str,
+        driver: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::CombinationalLogicBeforeSynchronizer {
+            synchronizer: synchronizer.to_string(),
+            driver: driver.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unsynchronized_reset_domain_crossing(
+        src: &str,
+        src_reset: &str,
+        dst: &str,
+        dst_reset: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::UnsynchronizedResetDomainCrossing {
+            src: src.to_string(),
+            src_reset: src_reset.to_string(),
+            dst: dst.to_string(),
+            dst_reset: dst_reset.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn asynchronous_reset_deassertion(
+        reset: &str,
+        src_domain: &str,
+        dst: &str,
+        dst_domain: &str,
+        token: &TokenRange,
+    ) -> Self {
+        AnalyzerError::AsynchronousResetDeassertion {
+            reset: reset.to_string(),
+            src_domain: src_domain.to_string(),
+            dst: dst.to_string(),
+            dst_domain: dst_domain.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn mixed_register_reset(identifier: &str, token: &TokenRange) -> Self {
+        AnalyzerError::MixedRegisterReset {
+            identifier: identifier.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unused_input_port(identifier: &str, module: &str, token: &TokenRange) -> Self {
+        AnalyzerError::UnusedInputPort {
+            identifier: identifier.to_string(),
+            module: module.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unused_output_port(identifier: &str, module: &str, token: &TokenRange) -> Self {
+        AnalyzerError::UnusedOutputPort {
+            identifier: identifier.to_string(),
+            module: module.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn dead_logic(identifier: &str, top: &str, token: &TokenRange) -> Self {
+        AnalyzerError::DeadLogic {
+            identifier: identifier.to_string(),
+            top: top.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unread_register(identifier: &str, token: &TokenRange) -> Self {
+        AnalyzerError::UnreadRegister {
+            identifier: identifier.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unused_parameter(identifier: &str, token: &TokenRange) -> Self {
+        AnalyzerError::UnusedParameter {
+            identifier: identifier.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
     pub fn mismatch_assignment(
         src: &str,
         dst: &str,
@@ -1614,6 +2230,42 @@ impl AnalyzerError {
         }
     }
 
+    pub fn width_truncation(
+        src_width: usize,
+        dst: &str,
+        dst_width: usize,
+        token: &TokenRange,
+        inst_context: &[TokenRange],
+    ) -> Self {
+        let (input, inst_context) = source_with_context(token, inst_context);
+        AnalyzerError::WidthTruncation {
+            src_width,
+            dst: dst.to_string(),
+            dst_width,
+            input,
+            error_location: token.into(),
+            inst_context,
+        }
+    }
+
+    pub fn width_extension(
+        src_width: usize,
+        dst: &str,
+        dst_width: usize,
+        token: &TokenRange,
+        inst_context: &[TokenRange],
+    ) -> Self {
+        let (input, inst_context) = source_with_context(token, inst_context);
+        AnalyzerError::WidthExtension {
+            src_width,
+            dst: dst.to_string(),
+            dst_width,
+            input,
+            error_location: token.into(),
+            inst_context,
+        }
+    }
+
     pub fn missing_clock_signal(token: &TokenRange) -> Self {
         AnalyzerError::MissingClockSignal {
             input: source(token),
@@ -1641,6 +2293,7 @@ impl AnalyzerError {
             input: source(token),
             error_location: token.into(),
             reset: reset.into(),
+            suggestions: missing_reset_statement_suggestions(name, &reset.beg),
         }
     }
 
@@ -1667,12 +2320,18 @@ impl AnalyzerError {
         }
     }
 
-    pub fn missing_port(name: &str, port: &str, token: &TokenRange) -> Self {
+    pub fn missing_port(name: &str, port: &str, token: &TokenRange, edits: Vec<Edit>) -> Self {
+        let suggestions = if edits.is_empty() {
+            vec![]
+        } else {
+            vec![Suggestion::edit(&format!("Connect \"{port}\" port"), edits)]
+        };
         AnalyzerError::MissingPort {
             name: name.to_string(),
             port: port.to_string(),
             input: source(token),
             error_location: token.into(),
+            suggestions,
         }
     }
 
@@ -1684,10 +2343,16 @@ impl AnalyzerError {
     }
 
     pub fn sv_keyword_usage(identifier: &str, token: &TokenRange) -> Self {
+        let name = format!("{}_", identifier.trim_start_matches("r#"));
         AnalyzerError::SvKeywordUsage {
             identifier: identifier.to_string(),
             input: source(token),
             error_location: token.into(),
+            suggestions: vec![Suggestion::rename(
+                &format!("Rename to {name}"),
+                &token.beg,
+                &name,
+            )],
         }
     }
 
@@ -1895,6 +2560,7 @@ impl AnalyzerError {
             identifier: identifier.to_string(),
             input: source(token),
             error_location: token.into(),
+            suggestions: unused_variable_suggestions(identifier, &token.beg),
         }
     }
 
@@ -1930,6 +2596,21 @@ impl AnalyzerError {
         }
     }
 
+    pub fn latch_inferred(
+        identifier: &str,
+        branch: &str,
+        token: &TokenRange,
+        uncovered: &TokenRange,
+    ) -> Self {
+        AnalyzerError::LatchInferred {
+            identifier: identifier.to_string(),
+            branch: branch.to_string(),
+            input: source(token),
+            error_location: token.into(),
+            uncovered: uncovered.into(),
+        }
+    }
+
     pub fn reserved_identifier(identifier: &str, token: &TokenRange) -> Self {
         AnalyzerError::ReservedIdentifier {
             identifier: identifier.to_string(),
@@ -1949,11 +2630,16 @@ impl AnalyzerError {
 
     pub fn wrong_seperator(separator: &str, token: &TokenRange) -> Self {
         let valid_separator = if separator == "." { "::" } else { "." };
+        let suggestions = Edit::replace(&token.beg, valid_separator)
+            .map(|x| Suggestion::edit(&format!("Replace with \"{valid_separator}\""), vec![x]))
+            .into_iter()
+            .collect();
         AnalyzerError::WrongSeparator {
             separator: separator.to_string(),
             valid_separator: valid_separator.to_string(),
             input: source(token),
             error_location: token.into(),
+            suggestions,
         }
     }
 
@@ -2003,6 +2689,15 @@ impl AnalyzerError {
                     inst_context,
                 }
             }
+            EvaluatedError::MixedSignedness { token } => {
+                let (input, inst_context) = source_with_context(&token.into(), inst_context);
+                AnalyzerError::MixedSignedness {
+                    operator: token.to_string(),
+                    input,
+                    error_location: token.into(),
+                    inst_context,
+                }
+            }
         }
     }
 }
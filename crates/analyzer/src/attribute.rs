@@ -1,3 +1,4 @@
+use crate::analyzer_error::ANALYZER_ERROR_CODES;
 use std::cell::RefCell;
 use std::fmt;
 use veryl_parser::resource_table::{self, StrId};
@@ -215,9 +216,8 @@ impl TryFrom<&veryl_parser::veryl_grammar_trait::Attribute> for Attribute {
             x if x == pat.allow => {
                 let arg = get_arg_ident(&value.attribute_opt, 0);
 
-                let err = AttributeError::MismatchArgs(
-                    "rule: (missing_port|missing_reset_statement|unused_variable)",
-                );
+                let err =
+                    AttributeError::MismatchArgs("rule: diagnostic code like unused_variable");
 
                 if let Some(arg) = arg {
                     match arg.text {
@@ -228,6 +228,9 @@ impl TryFrom<&veryl_parser::veryl_grammar_trait::Attribute> for Attribute {
                         x if x == pat.unused_variable => {
                             Ok(Attribute::Allow(AllowItem::UnusedVariable))
                         }
+                        x if ANALYZER_ERROR_CODES.contains(&x.to_string().as_str()) => {
+                            Ok(Attribute::Allow(AllowItem::Code(x)))
+                        }
                         _ => Err(err),
                     }
                 } else {
@@ -340,6 +343,8 @@ pub enum AllowItem {
     MissingPort,
     MissingResetStatement,
     UnusedVariable,
+    /// Other diagnostic code
+    Code(StrId),
 }
 
 impl fmt::Display for AllowItem {
@@ -348,6 +353,7 @@ impl fmt::Display for AllowItem {
             AllowItem::MissingPort => "missing_port",
             AllowItem::MissingResetStatement => "missing_reset_statement",
             AllowItem::UnusedVariable => "unused_variable",
+            AllowItem::Code(x) => return x.fmt(f),
         };
         text.fmt(f)
     }
@@ -0,0 +1,356 @@
+use crate::HashSet;
+use crate::analyzer_error::AnalyzerError;
+use crate::netlist::{BlackboxKind, DriverKind, NetId, Netlist};
+use crate::symbol::{ClockDomain, Direction, Port};
+use crate::symbol_table;
+
+/// Diagnostic codes reported by clock domain crossing check
+pub const CODES: &[&str] = &[
+    "combinational_logic_before_synchronizer",
+    "multi_bit_clock_domain_crossing",
+    "reconvergent_clock_domain_crossing",
+    "unsynchronized_clock_domain_crossing",
+];
+
+/// Clock domain of registers in the elaborated design
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Domain {
+    /// Clock port of the top module
+    Port(ClockDomain),
+    /// Clock generated in the design
+    Generated(NetId),
+}
+
+/// Register, input port of the top module or synchronizer output which a signal comes from
+#[derive(Clone, Copy, Debug)]
+pub struct Origin {
+    pub net: NetId,
+    pub domain: Domain,
+    pub synchronizer: Option<usize>,
+}
+
+/// Check clock domain crossings between registers in the elaborated design
+pub fn check(netlist: &Netlist) -> Vec<AnalyzerError> {
+    let mut checker = CdcChecker::new(netlist);
+    checker.check_crossing();
+    checker.check_synchronizer_input();
+    checker.errors
+}
+
+pub struct CdcChecker<'a> {
+    netlist: &'a Netlist,
+    errors: Vec<AnalyzerError>,
+}
+
+impl<'a> CdcChecker<'a> {
+    pub fn new(netlist: &'a Netlist) -> Self {
+        Self {
+            netlist,
+            errors: Vec::new(),
+        }
+    }
+
+    /// Registers, input ports of the top module and synchronizer outputs which the signal comes from
+    pub fn origins(&self, net: NetId) -> Vec<Origin> {
+        let mut ret = Vec::new();
+        self.trace(net, &mut HashSet::default(), &mut ret);
+        ret
+    }
+
+    fn check_crossing(&mut self) {
+        let mut reported = HashSet::default();
+        let mut reconvergence = HashSet::default();
+
+        for driver in &self.netlist.drivers {
+            let DriverKind::Register { clock, .. } = driver.kind else {
+                continue;
+            };
+            let domain = self.clock_domain(clock);
+
+            let mut origins = Vec::new();
+            let mut visited = HashSet::default();
+            for x in &driver.sources {
+                self.trace(*x, &mut visited, &mut origins);
+            }
+
+            for origin in &origins {
+                if origin.domain == domain || !reported.insert((driver.target, origin.net)) {
+                    continue;
+                }
+                let instances = [driver.target.instance, origin.net.instance];
+                if self.netlist.checked_in_child(&instances) {
+                    continue;
+                }
+
+                let src = self.netlist.name(&origin.net);
+                let src_domain = self.domain_name(&origin.domain);
+                let dst = self.netlist.name(&driver.target);
+                let dst_domain = self.domain_name(&domain);
+                let token = self.netlist.location(driver.target.instance, &driver.token);
+                let error = if self.netlist.width(&origin.net).unwrap_or(1) > 1 {
+                    AnalyzerError::multi_bit_clock_domain_crossing(
+                        &src,
+                        &src_domain,
+                        &dst,
+                        &dst_domain,
+                        &token,
+                    )
+                } else {
+                    AnalyzerError::unsynchronized_clock_domain_crossing(
+                        &src,
+                        &src_domain,
+                        &dst,
+                        &dst_domain,
+                        &token,
+                    )
+                };
+                self.errors.push(error);
+            }
+
+            // Synchronizers from the same domain are reconverged at the register
+            let synchronizers: Vec<_> = origins
+                .iter()
+                .filter_map(|x| x.synchronizer)
+                .filter(|x| self.netlist.instances[*x].blackbox == Some(BlackboxKind::Synchronizer))
+                .collect();
+            let mut checked: Vec<(Domain, usize)> = Vec::new();
+            for synchronizer in synchronizers {
+                for src_domain in self.synchronizer_source_domains(synchronizer) {
+                    let reconverged = checked
+                        .iter()
+                        .find(|(x, y)| *x == src_domain && *y != synchronizer)
+                        .copied();
+                    if let Some((_, other)) = reconverged {
+                        if !reconvergence.insert(driver.target) {
+                            continue;
+                        }
+                        let instances = [driver.target.instance, synchronizer, other];
+                        if self.netlist.checked_in_child(&instances) {
+                            continue;
+                        }
+
+                        let dst = self.netlist.name(&driver.target);
+                        let token = self.netlist.location(driver.target.instance, &driver.token);
+                        self.errors
+                            .push(AnalyzerError::reconvergent_clock_domain_crossing(
+                                &dst,
+                                &self.netlist.instances[other].token.to_string(),
+                                &self.netlist.instances[synchronizer].token.to_string(),
+                                &token,
+                            ));
+                    } else {
+                        checked.push((src_domain, synchronizer));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inputs of synchronizers should be driven by registers directly
+    fn check_synchronizer_input(&mut self) {
+        let netlist = self.netlist;
+        for (index, instance) in netlist.instances.iter().enumerate() {
+            if instance.blackbox != Some(BlackboxKind::Synchronizer) {
+                continue;
+            }
+
+            for port in self.async_inputs(index) {
+                let mut net = NetId {
+                    instance: index,
+                    symbol: port.symbol,
+                };
+                let mut visited = HashSet::default();
+                while visited.insert(net) {
+                    let drivers: Vec<_> = self.netlist.drivers_of(&net).collect();
+                    let [driver] = drivers.as_slice() else {
+                        break;
+                    };
+                    // Constants like the input of reset synchronizer never glitch
+                    if driver.kind != DriverKind::Comb || driver.sources.is_empty() {
+                        break;
+                    }
+                    if !driver.direct {
+                        if !self
+                            .netlist
+                            .checked_in_child(&[index, driver.target.instance])
+                        {
+                            let token = self.netlist.location(index, &instance.token);
+                            self.errors.push(
+                                AnalyzerError::combinational_logic_before_synchronizer(
+                                    &instance.token.to_string(),
+                                    &self.netlist.name(&driver.target),
+                                    &token,
+                                ),
+                            );
+                        }
+                        break;
+                    }
+                    net = driver.sources[0];
+                }
+            }
+        }
+    }
+
+    /// Collect origins of the signal through combinational logic
+    fn trace(&self, net: NetId, visited: &mut HashSet<NetId>, origins: &mut Vec<Origin>) {
+        if !visited.insert(net) {
+            return;
+        }
+
+        let mut driven = false;
+        for driver in self.netlist.drivers_of(&net) {
+            driven = true;
+            match driver.kind {
+                DriverKind::Register { clock, .. } => {
+                    origins.push(Origin {
+                        net,
+                        domain: self.clock_domain(clock),
+                        synchronizer: None,
+                    });
+                    return;
+                }
+                DriverKind::Comb => {
+                    for x in &driver.sources {
+                        self.trace(*x, visited, origins);
+                    }
+                }
+            }
+        }
+        if driven {
+            return;
+        }
+
+        let instance = &self.netlist.instances[net.instance];
+        let Some(port) = instance.ports.iter().find(|x| x.symbol == net.symbol) else {
+            return;
+        };
+        let property = port.property();
+        if instance.blackbox.is_some() && property.direction == Direction::Output {
+            if let Some(clock) = self.blackbox_clock(net.instance, property.clock_domain) {
+                origins.push(Origin {
+                    net,
+                    domain: self.clock_domain(clock),
+                    synchronizer: Some(net.instance),
+                });
+            }
+        } else if net.instance == 0 && property.direction == Direction::Input {
+            origins.push(Origin {
+                net,
+                domain: Domain::Port(port_domain(property.clock_domain)),
+                synchronizer: None,
+            });
+        }
+    }
+
+    /// Trace the clock back to the clock port of the top module through clock gating or buffers
+    pub fn clock_domain(&self, mut net: NetId) -> Domain {
+        let mut visited = HashSet::default();
+        while visited.insert(net) {
+            let drivers: Vec<_> = self.netlist.drivers_of(&net).collect();
+            match drivers.as_slice() {
+                [] => {
+                    let instance = &self.netlist.instances[net.instance];
+                    if net.instance == 0 {
+                        if let Some(port) = instance.ports.iter().find(|x| x.symbol == net.symbol) {
+                            return Domain::Port(port_domain(port.property().clock_domain));
+                        }
+                    }
+                    break;
+                }
+                [driver] if driver.kind == DriverKind::Comb => {
+                    let clocks: Vec<_> = driver
+                        .sources
+                        .iter()
+                        .filter(|x| is_clock(x))
+                        .copied()
+                        .collect();
+                    match (clocks.as_slice(), driver.sources.as_slice()) {
+                        ([x], _) | ([], [x]) => net = *x,
+                        _ => break,
+                    }
+                }
+                _ => break,
+            }
+        }
+        Domain::Generated(net)
+    }
+
+    /// Clock port of the blackbox which the domain belongs to
+    fn blackbox_clock(&self, instance: usize, domain: ClockDomain) -> Option<NetId> {
+        let clocks: Vec<_> = self.netlist.instances[instance]
+            .ports
+            .iter()
+            .filter(|x| x.symbol().evaluate().is_clock())
+            .collect();
+        let clock = if let [x] = clocks.as_slice() {
+            x
+        } else {
+            clocks
+                .iter()
+                .find(|x| x.property().clock_domain == domain)?
+        };
+        Some(NetId {
+            instance,
+            symbol: clock.symbol,
+        })
+    }
+
+    /// Input ports of the blackbox which belong to no clock of the blackbox
+    fn async_inputs(&self, instance: usize) -> Vec<Port> {
+        let ports = &self.netlist.instances[instance].ports;
+        let clock_domains: Vec<_> = ports
+            .iter()
+            .filter(|x| x.symbol().evaluate().is_clock())
+            .map(|x| x.property().clock_domain)
+            .collect();
+        ports
+            .iter()
+            .filter(|x| {
+                let evaluated = x.symbol().evaluate();
+                let property = x.property();
+                property.direction == Direction::Input
+                    && !evaluated.is_clock()
+                    && !evaluated.is_reset()
+                    && !clock_domains.contains(&property.clock_domain)
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn synchronizer_source_domains(&self, instance: usize) -> Vec<Domain> {
+        let mut ret = Vec::new();
+        for port in self.async_inputs(instance) {
+            let mut origins = Vec::new();
+            let net = NetId {
+                instance,
+                symbol: port.symbol,
+            };
+            self.trace(net, &mut HashSet::default(), &mut origins);
+            for x in origins {
+                if !ret.contains(&x.domain) {
+                    ret.push(x.domain);
+                }
+            }
+        }
+        ret
+    }
+
+    pub fn domain_name(&self, domain: &Domain) -> String {
+        match domain {
+            Domain::Port(x) => x.to_string(),
+            Domain::Generated(x) => self.netlist.name(x),
+        }
+    }
+}
+
+fn port_domain(domain: ClockDomain) -> ClockDomain {
+    if domain == ClockDomain::None {
+        ClockDomain::Implicit
+    } else {
+        domain
+    }
+}
+
+fn is_clock(net: &NetId) -> bool {
+    symbol_table::get(net.symbol).is_some_and(|x| x.evaluate().is_clock())
+}
@@ -1,7 +1,9 @@
 use crate::HashMap;
 use std::cell::RefCell;
 use veryl_parser::resource_table::PathId;
-use veryl_parser::veryl_grammar_trait::{InterfaceDeclaration, ModuleDeclaration};
+use veryl_parser::veryl_grammar_trait::{
+    FunctionDeclaration, InterfaceDeclaration, ModuleDeclaration,
+};
 use veryl_parser::veryl_token::TokenSource;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -21,6 +23,7 @@ pub fn new_definition_id() -> DefinitionId {
 pub enum Definition {
     Module(ModuleDeclaration),
     Interface(InterfaceDeclaration),
+    Function(FunctionDeclaration),
 }
 
 impl Definition {
@@ -40,6 +43,13 @@ impl Definition {
                     None
                 }
             }
+            Definition::Function(x) => {
+                if let TokenSource::File { path, .. } = x.function.function_token.token.source {
+                    Some(path)
+                } else {
+                    None
+                }
+            }
         }
     }
 }
@@ -1,5 +1,6 @@
 use crate::symbol::{SymbolId, SymbolKind, Type, TypeKind};
 use crate::symbol_table::{self, ResolveError, ResolveResult};
+use crate::value::Value;
 use veryl_parser::token_range::TokenRange;
 use veryl_parser::veryl_grammar_trait::*;
 use veryl_parser::veryl_token::Token;
@@ -13,16 +14,20 @@ pub struct Evaluated {
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EvaluatedValue {
-    Fixed(isize),
-    FixedArray(Vec<isize>),
+    Fixed(Value),
+    FixedArray(Vec<Value>),
     Unknown,
     UnknownStatic,
 }
 
 impl EvaluatedValue {
     pub fn get_value(&self) -> Option<isize> {
+        self.get_fixed().and_then(|x| x.to_isize())
+    }
+
+    pub fn get_fixed(&self) -> Option<&Value> {
         if let EvaluatedValue::Fixed(x) = self {
-            Some(*x)
+            Some(x)
         } else {
             None
         }
@@ -95,24 +100,7 @@ pub enum EvaluatedError {
     InvalidFactor { kind: String, token: Token },
     CallNonFunction { kind: String, token: Token },
     InvalidSelect { kind: String, range: TokenRange },
-}
-
-fn reduction<T: Fn(isize, isize) -> isize>(
-    value: isize,
-    width: Option<usize>,
-    func: T,
-) -> Option<isize> {
-    if let Some(width) = width {
-        let mut tmp = value;
-        let mut ret = tmp & 1;
-        for _ in 1..width {
-            tmp >>= 1;
-            ret = func(ret, tmp & 1);
-        }
-        Some(ret)
-    } else {
-        None
-    }
+    MixedSignedness { token: Token },
 }
 
 impl Evaluated {
@@ -152,10 +140,22 @@ impl Evaluated {
         matches!(self.r#type, EvaluatedType::Bit(_))
     }
 
+    pub fn is_signed(&self) -> bool {
+        match &self.r#type {
+            EvaluatedType::Bit(x) => x.signed,
+            EvaluatedType::Logic(x) => x.signed,
+            _ => false,
+        }
+    }
+
     pub fn get_value(&self) -> Option<isize> {
         self.value.get_value()
     }
 
+    pub fn get_fixed(&self) -> Option<&Value> {
+        self.value.get_fixed()
+    }
+
     pub fn get_width(&self) -> Option<Vec<usize>> {
         match &self.r#type {
             EvaluatedType::Clock(x) => Some(x.width.clone()),
@@ -199,12 +199,30 @@ impl Evaluated {
         }
     }
 
-    pub fn set_value(&mut self, value: isize) {
+    pub fn set_value(&mut self, value: Value) {
         if let EvaluatedValue::Fixed(x) = &mut self.value {
             *x = value;
         }
     }
 
+    fn shrink_constant(mut self) -> Evaluated {
+        if let Some(value) = self.get_fixed() {
+            let width = value.bit_length().max(1);
+            if !value.has_xz() && self.get_total_width().is_some_and(|x| width < x) {
+                self.set_width(vec![width]);
+            }
+        }
+        self
+    }
+
+    pub fn set_signed(&mut self, signed: bool) {
+        match &mut self.r#type {
+            EvaluatedType::Bit(x) => x.signed = signed,
+            EvaluatedType::Logic(x) => x.signed = signed,
+            _ => (),
+        }
+    }
+
     pub fn set_width(&mut self, width: Vec<usize>) {
         match &mut self.r#type {
             EvaluatedType::Clock(x) => x.width = width,
@@ -266,7 +284,7 @@ impl Evaluated {
     }
 
     pub fn create_fixed(
-        value: isize,
+        value: Value,
         signed: bool,
         width: Vec<usize>,
         array: Vec<usize>,
@@ -276,13 +294,22 @@ impl Evaluated {
         ret
     }
 
-    pub fn set_fixed(&mut self, value: isize, signed: bool, width: Vec<usize>, array: Vec<usize>) {
-        self.value = EvaluatedValue::Fixed(value);
-        self.r#type = EvaluatedType::Bit(EvaluatedTypeBit {
-            signed,
-            width,
-            array,
-        });
+    pub fn set_fixed(&mut self, value: Value, signed: bool, width: Vec<usize>, array: Vec<usize>) {
+        // value including X/Z is 4-state
+        self.r#type = if value.has_xz() {
+            EvaluatedType::Logic(EvaluatedTypeLogic {
+                signed,
+                width,
+                array,
+            })
+        } else {
+            EvaluatedType::Bit(EvaluatedTypeBit {
+                signed,
+                width,
+                array,
+            })
+        };
+        self.value = EvaluatedValue::Fixed(value.with_signed(signed));
     }
 
     pub fn create_undefine_fixed(signed: bool, width: Vec<usize>, array: Vec<usize>) -> Evaluated {
@@ -413,7 +440,7 @@ impl Evaluated {
         single: bool,
         range: TokenRange,
     ) -> Evaluated {
-        let value = self.get_value();
+        let value = self.get_fixed().cloned();
         let width = self.get_width();
         let array = self.get_array();
         if let (Some(width), Some(array)) = (width, array) {
@@ -482,8 +509,8 @@ impl Evaluated {
                         let beg_bit = beg * part_size as isize;
 
                         if let Some(value) = value {
-                            let mask = !(1 << (beg_bit - end_bit + 1));
-                            let new_value = (value >> end_bit) & mask;
+                            let new_value =
+                                value.select(end_bit as usize, (beg_bit - end_bit + 1) as usize);
                             self.set_value(new_value);
                         }
 
@@ -513,7 +540,7 @@ impl Evaluated {
 
     fn binary_op<
         T: Fn(usize, usize, Option<&usize>) -> usize,
-        U: Fn(isize, isize) -> Option<isize>,
+        U: Fn(&Value, &Value, usize) -> Value,
     >(
         mut left: Evaluated,
         mut right: Evaluated,
@@ -526,19 +553,16 @@ impl Evaluated {
         let is_4state = left.is_4state() | right.is_4state();
 
         let mut ret = match (
-            left.get_value(),
-            right.get_value(),
+            left.get_fixed(),
+            right.get_fixed(),
             left.get_total_width(),
             right.get_total_width(),
         ) {
             (Some(value0), Some(value1), Some(width0), Some(width1)) => {
-                let value = calc_value(value0, value1);
                 let width = calc_width(width0, width1, context_width);
-                if let Some(value) = value {
-                    Evaluated::create_fixed(value, false, vec![width], vec![])
-                } else {
-                    Evaluated::create_variable(false, is_4state, vec![width], vec![])
-                }
+                let value = calc_value(value0, value1, width);
+                let signed = value.signed;
+                Evaluated::create_fixed(value, signed, vec![width], vec![])
             }
             (_, _, Some(width0), Some(width1)) => {
                 let width = calc_width(width0, width1, context_width);
@@ -552,7 +576,7 @@ impl Evaluated {
         ret
     }
 
-    fn unary_op<T: Fn(usize) -> usize, U: Fn(isize) -> Option<isize>>(
+    fn unary_op<T: Fn(usize) -> usize, U: Fn(&Value, usize) -> Value>(
         mut left: Evaluated,
         calc_width: T,
         calc_value: U,
@@ -561,15 +585,12 @@ impl Evaluated {
 
         let is_4state = left.is_4state();
 
-        match (left.get_value(), left.get_total_width()) {
+        match (left.get_fixed().cloned(), left.get_total_width()) {
             (Some(value0), Some(width0)) => {
-                let value = calc_value(value0);
                 let width = calc_width(width0);
-                if let Some(value) = value {
-                    left.set_fixed(value, false, vec![width], vec![]);
-                } else {
-                    left.set_variable(false, is_4state, vec![width], vec![]);
-                }
+                let value = calc_value(&value0, width);
+                let signed = value.signed;
+                left.set_fixed(value, signed, vec![width], vec![]);
             }
             (_, Some(width0)) => {
                 let width = calc_width(width0);
@@ -587,7 +608,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| y.try_into().map(|y| x.checked_pow(y)).ok().flatten(),
+            |x, y, w| x.pow(y, w),
         )
     }
 
@@ -597,7 +618,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| x.checked_div(y),
+            |x, y, w| x.div(y, w),
         )
     }
 
@@ -607,7 +628,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| x.checked_rem(y),
+            |x, y, w| x.rem(y, w),
         )
     }
 
@@ -617,7 +638,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| x.checked_mul(y),
+            |x, y, w| x.mul(y, w),
         )
     }
 
@@ -627,7 +648,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| x.checked_add(y),
+            |x, y, w| x.add(y, w),
         )
     }
 
@@ -637,7 +658,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, y, z| x.max(y).max(*z.unwrap_or(&0)),
-            |x, y| x.checked_sub(y),
+            |x, y, w| x.sub(y, w),
         )
     }
 
@@ -647,12 +668,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, _, z| x.max(*z.unwrap_or(&0)),
-            |x, y| {
-                y.try_into()
-                    .map(|y| (x as usize).checked_shl(y).map(|x| x as isize))
-                    .ok()
-                    .flatten()
-            },
+            |x, y, w| x.shl(y, w),
         )
     }
 
@@ -662,12 +678,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, _, z| x.max(*z.unwrap_or(&0)),
-            |x, y| {
-                y.try_into()
-                    .map(|y| (x as usize).checked_shr(y).map(|x| x as isize))
-                    .ok()
-                    .flatten()
-            },
+            |x, y, w| x.shr(y, w),
         )
     }
 
@@ -677,7 +688,7 @@ impl Evaluated {
             exp,
             context_width,
             |x, _, z| x.max(*z.unwrap_or(&0)),
-            |x, y| y.try_into().map(|y| x.checked_shl(y)).ok().flatten(),
+            |x, y, w| x.shl(y, w),
         )
     }
 
@@ -687,174 +698,120 @@ impl Evaluated {
             exp,
             context_width,
             |x, _, z| x.max(*z.unwrap_or(&0)),
-            |x, y| y.try_into().map(|y| x.checked_shr(y)).ok().flatten(),
+            |x, y, w| x.ashr(y, w),
         )
     }
 
     fn le(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_le() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.le(y))
     }
 
     fn ge(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_ge() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.ge(y))
     }
 
     fn lt(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_lt() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.lt(y))
     }
 
     fn gt(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_gt() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.gt(y))
     }
 
     fn eq(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_eq() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.eq(y))
     }
 
     fn ne(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some(x.cmp(&y).is_ne() as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.ne(y))
+    }
+
+    fn case_eq(self, exp: Evaluated) -> Evaluated {
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.case_eq(y))
+    }
+
+    fn case_ne(self, exp: Evaluated) -> Evaluated {
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.case_ne(y))
+    }
+
+    fn wildcard_eq(self, exp: Evaluated) -> Evaluated {
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.wildcard_eq(y))
+    }
+
+    fn wildcard_ne(self, exp: Evaluated) -> Evaluated {
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.wildcard_ne(y))
     }
 
     fn andand(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some((x != 0 && y != 0) as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.logic_and(y))
     }
 
     fn oror(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(
-            self,
-            exp,
-            None,
-            |_, _, _| 1,
-            |x, y| Some((x != 0 || y != 0) as isize),
-        )
+        Self::binary_op(self, exp, None, |_, _, _| 1, |x, y, _| x.logic_or(y))
     }
 
     fn and(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y| Some(x & y))
+        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y, w| x.and(y, w))
     }
 
     fn or(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y| Some(x | y))
+        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y, w| x.or(y, w))
     }
 
     fn xor(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y| Some(x ^ y))
+        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y, w| x.xor(y, w))
     }
 
     fn xnor(self, exp: Evaluated) -> Evaluated {
-        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y| Some(!(x ^ y)))
+        Self::binary_op(self, exp, None, |x, y, _| x.max(y), |x, y, w| x.xnor(y, w))
     }
 
     fn plus(self) -> Evaluated {
-        Self::unary_op(self, |x| x, Some)
+        Self::unary_op(self, |x| x, |x, _| x.clone())
     }
 
     fn minus(self) -> Evaluated {
-        Self::unary_op(self, |x| x, |x| Some(-x))
+        Self::unary_op(self, |x| x, |x, w| x.neg(w))
     }
 
     fn not(self) -> Evaluated {
-        Self::unary_op(self, |_| 1, |x| Some((x == 0) as isize))
+        Self::unary_op(self, |_| 1, |x, _| x.logic_not())
     }
 
     fn inv(self) -> Evaluated {
-        Self::unary_op(self, |x| x, |x| Some(!x))
+        Self::unary_op(self, |x| x, |x, w| x.inv(w))
     }
 
     fn reduction_and(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(self, |_| 1, |x| reduction(x, width, |x, y| x & y))
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_and())
     }
 
     fn reduction_or(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(self, |_| 1, |x| reduction(x, width, |x, y| x | y))
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_or())
     }
 
     fn reduction_nand(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(
-            self,
-            |_| 1,
-            |x| {
-                let ret = reduction(x, width, |x, y| x & y);
-                ret.map(|x| if x == 0 { 1 } else { 0 })
-            },
-        )
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_and().inv(1))
     }
 
     fn reduction_nor(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(
-            self,
-            |_| 1,
-            |x| {
-                let ret = reduction(x, width, |x, y| x | y);
-                ret.map(|x| if x == 0 { 1 } else { 0 })
-            },
-        )
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_or().inv(1))
     }
 
     fn reduction_xor(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(self, |_| 1, |x| reduction(x, width, |x, y| x ^ y))
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_xor())
     }
 
     fn reduction_xnor(self) -> Evaluated {
-        let width = self.get_total_width();
-        Self::unary_op(
-            self,
-            |_| 1,
-            |x| {
-                let ret = reduction(x, width, |x, y| x ^ y);
-                ret.map(|x| if x == 0 { 1 } else { 0 })
-            },
-        )
+        Self::unary_op(self, |_| 1, |x, _| x.reduction_xor().inv(1))
     }
 }
 
+/// Whether the value is a variable which has the signedness
+fn is_signal(x: &Evaluated) -> bool {
+    !x.is_known_static() && matches!(x.r#type, EvaluatedType::Bit(_) | EvaluatedType::Logic(_))
+}
+
 #[derive(Default)]
 pub struct Evaluator {
     pub context_width: Vec<usize>,
@@ -872,12 +829,14 @@ impl Evaluator {
             match x.select_operator.as_ref() {
                 SelectOperator::Colon(_) => (beg, end, false),
                 SelectOperator::PlusColon(_) => {
-                    let one = Evaluated::create_fixed(1, false, vec![32], vec![]);
+                    let one =
+                        Evaluated::create_fixed(Value::new(1, 32, false), false, vec![32], vec![]);
                     let calc = beg.clone().add(end, None).sub(one, None);
                     (calc, beg, false)
                 }
                 SelectOperator::MinusColon(_) => {
-                    let one = Evaluated::create_fixed(1, false, vec![32], vec![]);
+                    let one =
+                        Evaluated::create_fixed(Value::new(1, 32, false), false, vec![32], vec![]);
                     let calc = beg.clone().sub(end, None).add(one, None);
                     (beg, calc, false)
                 }
@@ -892,8 +851,38 @@ impl Evaluator {
         }
     }
 
-    fn binary_operator(&mut self, operator: &str, left: Evaluated, right: Evaluated) -> Evaluated {
-        match operator {
+    fn binary_operator(
+        &mut self,
+        operator: &Token,
+        left: Evaluated,
+        right: Evaluated,
+    ) -> Evaluated {
+        let text = operator.to_string();
+
+        // Constant operand with a signal is counted by the bits required for its value,
+        // so `a + 1` keeps the width of `a` instead of the 32-bit unsized literal
+        let (left, right) = match (left.get_fixed().is_some(), right.get_fixed().is_some()) {
+            (true, false) => (left.shrink_constant(), right),
+            (false, true) => (left, right.shrink_constant()),
+            _ => (left, right),
+        };
+
+        // Signed operand is treated as unsigned if the other operand is unsigned
+        let mixed = matches!(
+            text.as_str(),
+            "/" | "*" | "%" | "+" | "-" | "<=" | ">=" | "<:" | ">:"
+        ) && is_signal(&left)
+            && is_signal(&right)
+            && left.is_signed() != right.is_signed();
+        let signed = match text.as_str() {
+            "**" | "<<<" | ">>>" | "<<" | ">>" => left.is_signed(),
+            "/" | "*" | "%" | "+" | "-" | "&" | "^~" | "^" | "~^" | "|" => {
+                left.is_signed() && right.is_signed()
+            }
+            _ => false,
+        };
+
+        let mut ret = match text.as_str() {
             "**" => left.pow(right, self.context_width.first()),
             "/" => left.div(right, self.context_width.first()),
             "*" => left.mul(right, self.context_width.first()),
@@ -908,10 +897,10 @@ impl Evaluator {
             ">=" => left.ge(right),
             "<:" => left.lt(right),
             ">:" => left.gt(right),
-            "===" => left.eq(right),
-            "==?" => left.eq(right),
-            "!==" => left.ne(right),
-            "!=?" => left.ne(right),
+            "===" => left.case_eq(right),
+            "==?" => left.wildcard_eq(right),
+            "!==" => left.case_ne(right),
+            "!=?" => left.wildcard_ne(right),
             "==" => left.eq(right),
             "!=" => left.ne(right),
             "&&" => left.andand(right),
@@ -922,11 +911,22 @@ impl Evaluator {
             "~^" => left.xnor(right),
             "|" => left.or(right),
             _ => Evaluated::create_unknown(),
+        };
+
+        if ret.get_fixed().is_none() {
+            ret.set_signed(signed);
+        }
+        if mixed {
+            ret.errors
+                .push(EvaluatedError::MixedSignedness { token: *operator });
         }
+        ret
     }
 
     fn unary_operator(&mut self, operator: &str, left: Evaluated) -> Evaluated {
-        match operator {
+        let signed = matches!(operator, "+" | "-" | "~") && left.is_signed();
+
+        let mut ret = match operator {
             "+" => left.plus(),
             "-" => left.minus(),
             "!" => left.not(),
@@ -939,7 +939,12 @@ impl Evaluator {
             "~^" => left.reduction_xnor(),
             "^~" => left.reduction_xnor(),
             _ => Evaluated::create_unknown(),
+        };
+
+        if ret.get_fixed().is_none() {
+            ret.set_signed(signed);
         }
+        ret
     }
 
     pub fn type_width(&mut self, x: Type) -> Option<Vec<usize>> {
@@ -976,7 +981,7 @@ impl Evaluator {
                     let mut ret = Vec::new();
                     for x in &x.width {
                         let width = self.expression(x);
-                        if let EvaluatedValue::Fixed(value) = width.value {
+                        if let Some(value) = width.get_value() {
                             if let Ok(width) = value.try_into() {
                                 ret.push(width);
                             } else {
@@ -1004,7 +1009,7 @@ impl Evaluator {
             let mut ret = Vec::new();
             for x in x {
                 let width = self.expression(x);
-                if let EvaluatedValue::Fixed(value) = width.value {
+                if let Some(value) = width.get_value() {
                     if let Ok(width) = value.try_into() {
                         ret.push(width);
                     } else {
@@ -1027,36 +1032,22 @@ impl Evaluator {
     }
 
     fn based(&mut self, arg: &Based) -> Evaluated {
-        let text = arg.based_token.to_string().replace('_', "");
-        if let Some((width, rest)) = text.split_once('\'') {
-            let signed = &rest[0..1] == "s";
-            let rest = if signed { &rest[1..] } else { rest };
-            let (base, value) = rest.split_at(1);
-            let radix = match base {
-                "b" => 2,
-                "o" => 8,
-                "d" => 10,
-                "h" => 16,
-                _ => unreachable!(),
-            };
-            let width = str::parse::<usize>(width);
-            let value = isize::from_str_radix(value, radix);
-            match (width, value) {
-                (Ok(width), Ok(value)) => {
-                    Evaluated::create_fixed(value, signed, vec![width], vec![])
-                }
-                (Ok(width), _) => Evaluated::create_undefine_fixed(signed, vec![width], vec![]),
-                _ => Evaluated::create_unknown_static(),
-            }
+        let text = arg.based_token.to_string();
+        if let Some(value) = Value::from_literal(&text) {
+            let signed = value.signed;
+            let width = value.width;
+            Evaluated::create_fixed(value, signed, vec![width], vec![])
         } else {
-            unreachable!()
+            Evaluated::create_unknown_static()
         }
     }
 
     fn base_less(&mut self, arg: &BaseLess) -> Evaluated {
-        let text = arg.base_less_token.to_string().replace('_', "");
-        if let Ok(value) = str::parse::<isize>(&text) {
-            Evaluated::create_fixed(value, false, vec![32], vec![])
+        let text = arg.base_less_token.to_string();
+        if let Some(value) = Value::from_digits(&text, 10) {
+            let width = value.width.max(32);
+            let value = value.resize(width);
+            Evaluated::create_fixed(value, false, vec![width], vec![])
         } else {
             Evaluated::create_unknown_static()
         }
@@ -1064,33 +1055,23 @@ impl Evaluator {
 
     fn all_bit(&mut self, arg: &AllBit) -> Evaluated {
         let text = arg.all_bit_token.to_string();
-        let mut unknown = false;
-        let value = match text.as_str() {
-            "'1" => {
-                let mut ret: isize = 0;
-                for _ in 0..*self.context_width.last().unwrap_or(&0) {
-                    if let Some(x) = ret.checked_shl(1) {
-                        ret = x;
-                    } else {
-                        unknown = true;
-                    }
-                    ret |= 1;
-                }
-                ret
-            }
-            "'0" => 0,
-            _ => {
-                unknown = true;
-                0
-            }
-        };
-
-        let width = *self.context_width.last().unwrap_or(&0);
-        if unknown {
-            Evaluated::create_undefine_fixed(false, vec![width], vec![])
+        let (width, bit) = text.split_once('\'').unwrap();
+        let width = if width.is_empty() {
+            *self.context_width.last().unwrap_or(&0)
+        } else if let Ok(width) = width.replace('_', "").parse() {
+            width
         } else {
-            Evaluated::create_fixed(value, false, vec![width], vec![])
-        }
+            return Evaluated::create_unknown_static();
+        };
+        let value = match bit {
+            "1" => Value::ones(width),
+            "0" => Value::zero(width),
+            // X/Z without context width can't be represented as fixed value
+            _ if width == 0 => return Evaluated::create_undefine_fixed(false, vec![0], vec![]),
+            "x" | "X" => Value::x(width),
+            _ => Value::z(width),
+        };
+        Evaluated::create_fixed(value, false, vec![width], vec![])
     }
 
     fn number(&mut self, arg: &Number) -> Evaluated {
@@ -1117,10 +1098,10 @@ impl Evaluator {
 
     fn boolean_literal(&mut self, arg: &BooleanLiteral) -> Evaluated {
         let value = match arg {
-            BooleanLiteral::True(_) => 1,
-            BooleanLiteral::False(_) => 0,
+            BooleanLiteral::True(_) => true,
+            BooleanLiteral::False(_) => false,
         };
-        Evaluated::create_fixed(value, false, vec![1], vec![])
+        Evaluated::create_fixed(Value::from_bool(value), false, vec![1], vec![])
     }
 
     pub fn expression(&mut self, arg: &Expression) -> Evaluated {
@@ -1131,7 +1112,7 @@ impl Evaluator {
         for x in &arg.if_expression_list {
             let cond = self.expression(&x.expression);
 
-            if let EvaluatedValue::Fixed(1) = cond.value {
+            if cond.get_value() == Some(1) {
                 return self.expression(&x.expression0);
             }
         }
@@ -1142,7 +1123,7 @@ impl Evaluator {
     fn expression01(&mut self, arg: &Expression01) -> Evaluated {
         let mut ret = self.expression02(&arg.expression02);
         for x in &arg.expression01_list {
-            let operator = x.operator02.operator02_token.to_string();
+            let operator = x.operator02.operator02_token.token;
             let operand = self.expression02(&x.expression02);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1152,7 +1133,7 @@ impl Evaluator {
     fn expression02(&mut self, arg: &Expression02) -> Evaluated {
         let mut ret = self.expression03(&arg.expression03);
         for x in &arg.expression02_list {
-            let operator = x.operator03.operator03_token.to_string();
+            let operator = x.operator03.operator03_token.token;
             let operand = self.expression03(&x.expression03);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1162,7 +1143,7 @@ impl Evaluator {
     fn expression03(&mut self, arg: &Expression03) -> Evaluated {
         let mut ret = self.expression04(&arg.expression04);
         for x in &arg.expression03_list {
-            let operator = x.operator04.operator04_token.to_string();
+            let operator = x.operator04.operator04_token.token;
             let operand = self.expression04(&x.expression04);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1172,7 +1153,7 @@ impl Evaluator {
     fn expression04(&mut self, arg: &Expression04) -> Evaluated {
         let mut ret = self.expression05(&arg.expression05);
         for x in &arg.expression04_list {
-            let operator = x.operator05.operator05_token.to_string();
+            let operator = x.operator05.operator05_token.token;
             let operand = self.expression05(&x.expression05);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1182,7 +1163,7 @@ impl Evaluator {
     fn expression05(&mut self, arg: &Expression05) -> Evaluated {
         let mut ret = self.expression06(&arg.expression06);
         for x in &arg.expression05_list {
-            let operator = x.operator06.operator06_token.to_string();
+            let operator = x.operator06.operator06_token.token;
             let operand = self.expression06(&x.expression06);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1192,7 +1173,7 @@ impl Evaluator {
     fn expression06(&mut self, arg: &Expression06) -> Evaluated {
         let mut ret = self.expression07(&arg.expression07);
         for x in &arg.expression06_list {
-            let operator = x.operator07.operator07_token.to_string();
+            let operator = x.operator07.operator07_token.token;
             let operand = self.expression07(&x.expression07);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1202,7 +1183,7 @@ impl Evaluator {
     fn expression07(&mut self, arg: &Expression07) -> Evaluated {
         let mut ret = self.expression08(&arg.expression08);
         for x in &arg.expression07_list {
-            let operator = x.operator08.operator08_token.to_string();
+            let operator = x.operator08.operator08_token.token;
             let operand = self.expression08(&x.expression08);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1212,7 +1193,7 @@ impl Evaluator {
     fn expression08(&mut self, arg: &Expression08) -> Evaluated {
         let mut ret = self.expression09(&arg.expression09);
         for x in &arg.expression08_list {
-            let operator = x.operator09.operator09_token.to_string();
+            let operator = x.operator09.operator09_token.token;
             let operand = self.expression09(&x.expression09);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1222,7 +1203,7 @@ impl Evaluator {
     fn expression09(&mut self, arg: &Expression09) -> Evaluated {
         let mut ret = self.expression10(&arg.expression10);
         for x in &arg.expression09_list {
-            let operator = x.operator10.operator10_token.to_string();
+            let operator = x.operator10.operator10_token.token;
             let operand = self.expression10(&x.expression10);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1233,8 +1214,8 @@ impl Evaluator {
         let mut ret = self.expression11(&arg.expression11);
         for x in &arg.expression10_list {
             let operator = match &*x.expression10_list_group {
-                Expression10ListGroup::Operator11(x) => x.operator11.operator11_token.to_string(),
-                Expression10ListGroup::Star(x) => x.star.star_token.to_string(),
+                Expression10ListGroup::Operator11(x) => x.operator11.operator11_token.token,
+                Expression10ListGroup::Star(x) => x.star.star_token.token,
             };
             let operand = self.expression11(&x.expression11);
             ret = self.binary_operator(&operator, ret, operand);
@@ -1245,7 +1226,7 @@ impl Evaluator {
     fn expression11(&mut self, arg: &Expression11) -> Evaluated {
         let mut ret = self.expression12(&arg.expression12);
         for x in &arg.expression11_list {
-            let operator = x.operator12.operator12_token.to_string();
+            let operator = x.operator12.operator12_token.token;
             let operand = self.expression12(&x.expression12);
             ret = self.binary_operator(&operator, ret, operand);
         }
@@ -1255,7 +1236,44 @@ impl Evaluator {
     fn expression12(&mut self, arg: &Expression12) -> Evaluated {
         let mut ret = self.expression13(&arg.expression13);
         if let Some(x) = &arg.expression12_opt {
+            let is_4state = ret.is_4state();
             let new_type = match x.casting_type.as_ref() {
+                CastingType::U32(_) => Some(Evaluated::create_variable(
+                    false,
+                    is_4state,
+                    vec![32],
+                    vec![],
+                )),
+                CastingType::U64(_) => Some(Evaluated::create_variable(
+                    false,
+                    is_4state,
+                    vec![64],
+                    vec![],
+                )),
+                CastingType::I32(_) => Some(Evaluated::create_variable(
+                    true,
+                    is_4state,
+                    vec![32],
+                    vec![],
+                )),
+                CastingType::I64(_) => Some(Evaluated::create_variable(
+                    true,
+                    is_4state,
+                    vec![64],
+                    vec![],
+                )),
+                CastingType::F32(_) => {
+                    Some(Evaluated::create_variable(false, false, vec![32], vec![]))
+                }
+                CastingType::F64(_) => {
+                    Some(Evaluated::create_variable(false, false, vec![64], vec![]))
+                }
+                CastingType::BaseLess(x) => {
+                    let width = self.base_less(&x.base_less).get_value();
+                    width.and_then(|x| x.try_into().ok()).map(|x| {
+                        Evaluated::create_variable(ret.is_signed(), is_4state, vec![x], vec![])
+                    })
+                }
                 CastingType::Bool(_) => {
                     Some(Evaluated::create_variable(false, true, vec![1], vec![]))
                 }
@@ -1446,9 +1464,8 @@ impl Evaluator {
             "$clog2" => {
                 if let Some(arg) = args.first() {
                     let arg = self.expression(&arg.argument_expression.expression);
-                    if let EvaluatedValue::Fixed(x) = arg.value {
-                        let ret = isize::BITS - x.leading_zeros();
-                        Evaluated::create_fixed(ret as isize, false, vec![32], vec![])
+                    if let Some(x) = arg.get_fixed() {
+                        Evaluated::create_fixed(x.clog2(), false, vec![32], vec![])
                     } else {
                         Evaluated::create_unknown()
                     }
@@ -1462,19 +1479,15 @@ impl Evaluator {
 
     fn do_concatenation(&mut self, mut x: Evaluated, mut y: Evaluated) -> Evaluated {
         let mut ret = match (
-            x.get_value(),
-            y.get_value(),
+            x.get_fixed(),
+            y.get_fixed(),
             x.get_total_width(),
             y.get_total_width(),
         ) {
             (Some(value0), Some(value1), Some(width0), Some(width1)) => {
                 let width = width0 + width1;
-                if let Some(x) = value0.checked_shl(width1 as u32) {
-                    let value = x | value1;
-                    Evaluated::create_fixed(value, false, vec![width], vec![])
-                } else {
-                    Evaluated::create_undefine_fixed(false, vec![width], vec![])
-                }
+                let value = value0.resize(width0).concat(&value1.resize(width1));
+                Evaluated::create_fixed(value, false, vec![width], vec![])
             }
             _ => {
                 if x.is_known_static() && y.is_known_static() {
@@ -1494,8 +1507,8 @@ impl Evaluator {
         let e = self.expression(arg.expression.as_ref());
         if let Some(cio) = &arg.concatenation_item_opt {
             let c = self.expression(cio.expression.as_ref());
-            if let EvaluatedValue::Fixed(c) = c.value {
-                let mut tmp = Evaluated::create_fixed(0, false, vec![0], vec![]);
+            if let Some(c) = c.get_value() {
+                let mut tmp = Evaluated::create_fixed(Value::zero(0), false, vec![0], vec![]);
                 for _ in 0..c {
                     tmp = self.do_concatenation(tmp, e.clone());
                 }
@@ -1518,7 +1531,7 @@ impl Evaluator {
         for cll in arg.concatenation_list_list.iter() {
             eval_vec.push(self.concatenation_list_list(cll));
         }
-        let default_value = Evaluated::create_fixed(0, false, vec![0], vec![]);
+        let default_value = Evaluated::create_fixed(Value::zero(0), false, vec![0], vec![]);
         eval_vec.iter().fold(default_value, |acc, x| {
             self.do_concatenation(acc, x.clone())
         })
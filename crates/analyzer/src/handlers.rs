@@ -114,7 +114,7 @@ pub struct Pass2Handlers {
 }
 
 impl Pass2Handlers {
-    pub fn new(_build_opt: &Build, _lint_opt: &Lint) -> Self {
+    pub fn new(_build_opt: &Build, lint_opt: &Lint) -> Self {
         Self {
             check_separator: CheckSeparator::new(),
             check_enum: CheckEnum::new(),
@@ -125,7 +125,7 @@ impl Pass2Handlers {
             check_var_ref: CheckVarRef::new(),
             check_clock_reset: CheckClockReset::new(),
             create_type_dag: CreateTypeDag::new(),
-            check_expression: CheckExpression::new(vec![]),
+            check_expression: CheckExpression::new(lint_opt, vec![]),
             check_clock_domain: CheckClockDomain::new(),
             check_proto: CheckProto::new(),
             check_type: CheckType::new(),
@@ -4,11 +4,14 @@ use crate::connect_operation_table::{self, ConnectOperand};
 use crate::definition_table::{self, Definition};
 use crate::evaluator::{Evaluated, EvaluatedError, EvaluatedType, Evaluator};
 use crate::instance_history::{self, InstanceHistoryError, InstanceSignature};
+use crate::lint;
 use crate::symbol::{
     Direction, GenericBoundKind, ModuleProperty, Symbol, SymbolId, SymbolKind, TypeKind,
 };
 use crate::symbol_table;
+use crate::value::Value;
 use std::collections::{HashMap, HashSet};
+use veryl_metadata::Lint;
 use veryl_parser::ParolError;
 use veryl_parser::resource_table::StrId;
 use veryl_parser::resource_table::TokenId;
@@ -34,18 +37,25 @@ pub struct CheckExpression {
     disable_block_beg: HashSet<TokenId>,
     disable_block_end: HashSet<TokenId>,
     inst_context: Vec<TokenRange>,
+    lint_opt: Lint,
 }
 
 impl CheckExpression {
-    pub fn new(inst_context: Vec<TokenRange>) -> Self {
+    pub fn new(lint_opt: &Lint, inst_context: Vec<TokenRange>) -> Self {
         Self {
             inst_context,
+            lint_opt: lint_opt.clone(),
             ..Default::default()
         }
     }
 
     fn evaluated_error(&mut self, errors: &[EvaluatedError]) {
         for e in errors {
+            if matches!(e, EvaluatedError::MixedSignedness { .. })
+                && !lint::enabled(&self.lint_opt, "mixed_signedness")
+            {
+                continue;
+            }
             self.errors
                 .push(AnalyzerError::evaluated_error(e, &self.inst_context));
         }
@@ -66,7 +76,7 @@ impl CheckExpression {
 
     fn check_compatibility(
         &mut self,
-        _context: Context,
+        context: Context,
         src: &Evaluated,
         dst: &Symbol,
         dst_last_select: &[Select],
@@ -128,11 +138,90 @@ impl CheckExpression {
                     }
                 }
 
+                if context == Context::Assignment {
+                    if let Some(width) = self.dst_width(dst, dst_last_select) {
+                        self.check_width(src, &dst.token.to_string(), width, token);
+                    }
+                }
+
                 // TODO type checks
             }
         }
     }
 
+    fn width_check_enabled(&self) -> bool {
+        lint::enabled(&self.lint_opt, "width_truncation")
+            || lint::enabled(&self.lint_opt, "width_extension")
+    }
+
+    /// Total width of the assignment destination
+    /// It is not evaluated if no width check is enabled.
+    fn dst_width(&mut self, dst: &Symbol, dst_last_select: &[Select]) -> Option<usize> {
+        if !self.width_check_enabled()
+            || !matches!(dst.kind, SymbolKind::Variable(_) | SymbolKind::Port(_))
+        {
+            return None;
+        }
+
+        let mut ret = dst.evaluate();
+        for s in dst_last_select {
+            let (beg, end, single) = self.evaluator.evaluate_select(s);
+            ret = ret.select(beg, end, single, s.into());
+        }
+
+        total_width(&ret)
+    }
+
+    /// Evaluate the expression in the context of the destination width
+    /// like SystemVerilog context-determined expression
+    fn evaluate_in_context(&mut self, exp: &Expression, width: Option<usize>) -> Evaluated {
+        self.evaluator.context_width = width.into_iter().collect();
+        let ret = self.evaluator.expression(exp);
+        self.evaluator.context_width.clear();
+        ret
+    }
+
+    fn check_width(&mut self, src: &Evaluated, dst: &str, dst_width: usize, token: &TokenRange) {
+        if src.get_array().is_none_or(|x| !x.is_empty()) {
+            return;
+        }
+        let Some(src_width) = src.get_total_width() else {
+            return;
+        };
+
+        if let Some(value) = src.get_fixed() {
+            // Constant is checked by whether its value can be represented
+            if lint::enabled(&self.lint_opt, "width_truncation") && !fit_in_width(value, dst_width)
+            {
+                self.errors.push(AnalyzerError::width_truncation(
+                    src_width,
+                    dst,
+                    dst_width,
+                    token,
+                    &self.inst_context,
+                ));
+            }
+        } else if src.is_known_static() {
+            // Constant which can't be evaluated is skipped
+        } else if lint::enabled(&self.lint_opt, "width_truncation") && src_width > dst_width {
+            self.errors.push(AnalyzerError::width_truncation(
+                src_width,
+                dst,
+                dst_width,
+                token,
+                &self.inst_context,
+            ));
+        } else if lint::enabled(&self.lint_opt, "width_extension") && src_width < dst_width {
+            self.errors.push(AnalyzerError::width_extension(
+                src_width,
+                dst,
+                dst_width,
+                token,
+                &self.inst_context,
+            ));
+        }
+    }
+
     fn get_overridden_params(&mut self, arg: &InstDeclaration) -> HashMap<StrId, Evaluated> {
         let mut ret = HashMap::new();
 
@@ -162,7 +251,29 @@ impl CheckExpression {
         ret
     }
 
-    fn check_port_connection(&mut self, arg: &InstDeclaration, module: &ModuleProperty) {
+    /// Evaluate ports with the overridden parameters
+    /// Evaluated ports are used by width check only.
+    fn evaluate_ports(&mut self, module: &ModuleProperty) -> HashMap<StrId, Evaluated> {
+        if !self.width_check_enabled() {
+            return HashMap::new();
+        }
+
+        module
+            .ports
+            .iter()
+            .map(|x| (x.name(), x.symbol().evaluate()))
+            .collect()
+    }
+
+    /// Check port connections with ports evaluated by `evaluate_ports`.
+    /// Connected expressions are evaluated after the overridden parameters are popped
+    /// because the instantiated module may be the module itself.
+    fn check_port_connection(
+        &mut self,
+        arg: &InstDeclaration,
+        module: &ModuleProperty,
+        evaluated_ports: &HashMap<StrId, Evaluated>,
+    ) {
         let connections = if let Some(x) = &arg.inst_declaration_opt2 {
             if let Some(x) = &x.inst_declaration_opt3 {
                 x.inst_port_list.as_ref().into()
@@ -181,23 +292,82 @@ impl CheckExpression {
         }
 
         for connect in connections {
-            let src = if let Some(x) = &connect.inst_port_item_opt {
-                self.evaluator.expression(&x.expression)
+            let token: TokenRange = (&connect).into();
+            let name = connect.identifier.identifier_token.token.text;
+            let port = ports.get(&name);
+            let direction = port.and_then(|x| match &x.kind {
+                SymbolKind::Port(x) => Some(x.direction),
+                _ => None,
+            });
+            let evaluated_port = evaluated_ports.get(&name);
+
+            let width = if matches!(direction, Some(Direction::Input)) {
+                evaluated_port.and_then(total_width)
+            } else {
+                None
+            };
+            let (src, dst) = if let Some(x) = &connect.inst_port_item_opt {
+                let src = self.evaluate_in_context(&x.expression, width);
+                let dst = x.expression.unwrap_identifier().and_then(|x| {
+                    symbol_table::resolve(x)
+                        .ok()
+                        .map(|symbol| (symbol.found, x.last_select()))
+                });
+                (src, dst)
             } else if let Ok(symbol) = symbol_table::resolve(connect.identifier.as_ref()) {
-                symbol.found.evaluate()
+                (symbol.found.evaluate(), Some((symbol.found, Vec::new())))
             } else {
-                Evaluated::create_unknown()
+                (Evaluated::create_unknown(), None)
             };
 
-            let token: TokenRange = (&connect).into();
-            let dst = connect.identifier.identifier_token.token.text;
-            if let Some(dst) = ports.get(&dst) {
-                self.check_compatibility(Context::PortConnection, &src, dst, &[], &token);
+            if let Some(port) = port {
+                self.check_compatibility(Context::PortConnection, &src, port, &[], &token);
+
+                match (direction, evaluated_port) {
+                    (Some(Direction::Input), _) => {
+                        if let Some(width) = width {
+                            self.check_width(&src, &port.token.to_string(), width, &token);
+                        }
+                    }
+                    (Some(Direction::Output), Some(evaluated_port)) => {
+                        // Output port drives the connected variable
+                        if let Some((dst, dst_last_select)) = dst {
+                            if let Some(width) = self.dst_width(&dst, &dst_last_select) {
+                                let name = dst.token.to_string();
+                                self.check_width(evaluated_port, &name, width, &token);
+                            }
+                        }
+                    }
+                    _ => (),
+                }
             }
         }
     }
 }
 
+fn total_width(x: &Evaluated) -> Option<usize> {
+    if x.get_array()?.is_empty() {
+        x.get_total_width()
+    } else {
+        None
+    }
+}
+
+/// Whether the value can be represented by the width as unsigned or signed
+fn fit_in_width(value: &Value, width: usize) -> bool {
+    if value.has_xz() || value.bit_length() <= width {
+        true
+    } else if width == 0 {
+        false
+    } else {
+        // Negative value
+        value
+            .select(width - 1, value.width + 1 - width)
+            .reduction_and()
+            .to_bool()
+    }
+}
+
 impl Handler for CheckExpression {
     fn set_point(&mut self, p: HandlerPoint) {
         self.point = p;
@@ -306,10 +476,15 @@ impl VerylGrammarTrait for CheckExpression {
     fn let_statement(&mut self, arg: &LetStatement) -> Result<(), ParolError> {
         if !self.disable {
             if let HandlerPoint::Before = self.point {
-                let exp = self.evaluator.expression(&arg.expression);
+                let dst = symbol_table::resolve(arg.identifier.as_ref());
+                let width = dst
+                    .as_ref()
+                    .ok()
+                    .and_then(|x| self.dst_width(&x.found, &[]));
+                let exp = self.evaluate_in_context(&arg.expression, width);
                 self.evaluated_error(&exp.errors);
 
-                if let Ok(dst) = symbol_table::resolve(arg.identifier.as_ref()) {
+                if let Ok(dst) = dst {
                     self.check_compatibility(
                         Context::Assignment,
                         &exp,
@@ -345,7 +520,17 @@ impl VerylGrammarTrait for CheckExpression {
                             return Ok(());
                         }
 
-                        let exp = self.evaluator.expression(&x.assignment.expression);
+                        let dst = symbol_table::resolve(arg.expression_identifier.as_ref());
+                        let dst_last_select = arg.expression_identifier.last_select();
+
+                        let width = if is_connect_operation {
+                            None
+                        } else {
+                            dst.as_ref()
+                                .ok()
+                                .and_then(|x| self.dst_width(&x.found, &dst_last_select))
+                        };
+                        let exp = self.evaluate_in_context(&x.assignment.expression, width);
                         self.evaluated_error(&exp.errors);
 
                         if is_connect_operation {
@@ -353,8 +538,7 @@ impl VerylGrammarTrait for CheckExpression {
                             return Ok(());
                         }
 
-                        if let Ok(dst) = symbol_table::resolve(arg.expression_identifier.as_ref()) {
-                            let dst_last_select = arg.expression_identifier.last_select();
+                        if let Ok(dst) = dst {
                             self.check_compatibility(
                                 Context::Assignment,
                                 &exp,
@@ -516,10 +700,15 @@ impl VerylGrammarTrait for CheckExpression {
     fn let_declaration(&mut self, arg: &LetDeclaration) -> Result<(), ParolError> {
         if !self.disable {
             if let HandlerPoint::Before = self.point {
-                let exp = self.evaluator.expression(&arg.expression);
+                let dst = symbol_table::resolve(arg.identifier.as_ref());
+                let width = dst
+                    .as_ref()
+                    .ok()
+                    .and_then(|x| self.dst_width(&x.found, &[]));
+                let exp = self.evaluate_in_context(&arg.expression, width);
                 self.evaluated_error(&exp.errors);
 
-                if let Ok(dst) = symbol_table::resolve(arg.identifier.as_ref()) {
+                if let Ok(dst) = dst {
                     self.check_compatibility(
                         Context::Assignment,
                         &exp,
@@ -558,13 +747,23 @@ impl VerylGrammarTrait for CheckExpression {
     fn assign_declaration(&mut self, arg: &AssignDeclaration) -> Result<(), ParolError> {
         if !self.disable {
             if let HandlerPoint::Before = self.point {
-                let exp = self.evaluator.expression(&arg.expression);
+                let dst = match arg.assign_destination.as_ref() {
+                    AssignDestination::HierarchicalIdentifier(x) => Some((
+                        symbol_table::resolve(x.hierarchical_identifier.as_ref()),
+                        x.hierarchical_identifier.last_select(),
+                    )),
+                    AssignDestination::LBraceAssignConcatenationListRBrace(_) => None,
+                };
+                let width = dst.as_ref().and_then(|(dst, dst_last_select)| {
+                    let dst = dst.as_ref().ok()?;
+                    self.dst_width(&dst.found, dst_last_select)
+                });
+                let exp = self.evaluate_in_context(&arg.expression, width);
                 self.evaluated_error(&exp.errors);
 
-                match arg.assign_destination.as_ref() {
-                    AssignDestination::HierarchicalIdentifier(x) => {
-                        if let Ok(dst) = symbol_table::resolve(x.hierarchical_identifier.as_ref()) {
-                            let dst_last_select = x.hierarchical_identifier.last_select();
+                match dst {
+                    Some((dst, dst_last_select)) => {
+                        if let Ok(dst) = dst {
                             self.check_compatibility(
                                 Context::Assignment,
                                 &exp,
@@ -574,7 +773,7 @@ impl VerylGrammarTrait for CheckExpression {
                             );
                         }
                     }
-                    AssignDestination::LBraceAssignConcatenationListRBrace(_) => {
+                    None => {
                         // TODO check concatenation
                     }
                 }
@@ -626,9 +825,12 @@ impl VerylGrammarTrait for CheckExpression {
 
                             symbol_table::clear_evaluated_cache(&symbol.found.inner_namespace());
 
-                            if let SymbolKind::Module(x) = &symbol.found.kind {
-                                self.check_port_connection(arg, x);
-                            }
+                            let evaluated_ports = if let SymbolKind::Module(x) = &symbol.found.kind
+                            {
+                                self.evaluate_ports(x)
+                            } else {
+                                HashMap::new()
+                            };
 
                             match instance_history::push(sig) {
                                 Ok(true) => {
@@ -638,19 +840,25 @@ impl VerylGrammarTrait for CheckExpression {
                                             Definition::Module(x) => {
                                                 let mut inst_context = self.inst_context.clone();
                                                 inst_context.push(arg.identifier.as_ref().into());
-                                                let mut analyzer =
-                                                    AnalyzerPass2Expression::new(inst_context);
+                                                let mut analyzer = AnalyzerPass2Expression::new(
+                                                    &self.lint_opt,
+                                                    inst_context,
+                                                );
                                                 analyzer.module_declaration(&x);
                                                 self.errors.append(&mut analyzer.get_errors());
                                             }
                                             Definition::Interface(x) => {
                                                 let mut inst_context = self.inst_context.clone();
                                                 inst_context.push(arg.identifier.as_ref().into());
-                                                let mut analyzer =
-                                                    AnalyzerPass2Expression::new(inst_context);
+                                                let mut analyzer = AnalyzerPass2Expression::new(
+                                                    &self.lint_opt,
+                                                    inst_context,
+                                                );
                                                 analyzer.interface_declaration(&x);
                                                 self.errors.append(&mut analyzer.get_errors());
                                             }
+                                            // instance target is module or interface only
+                                            Definition::Function(_) => (),
                                         }
                                     }
                                     instance_history::pop();
@@ -670,6 +878,10 @@ impl VerylGrammarTrait for CheckExpression {
                                     symbol_table::pop_override(x.symbol);
                                 }
                             }
+
+                            if let SymbolKind::Module(x) = &symbol.found.kind {
+                                self.check_port_connection(arg, x, &evaluated_ports);
+                            }
                         }
                     }
                 }
@@ -1,4 +1,5 @@
 use crate::analyzer_error::AnalyzerError;
+use crate::value::Value;
 use veryl_parser::ParolError;
 use veryl_parser::veryl_grammar_trait::*;
 use veryl_parser::veryl_walker::{Handler, HandlerPoint};
@@ -78,19 +79,11 @@ impl VerylGrammarTrait for CheckNumber {
                 _ => unreachable!(),
             };
 
-            let zero = number.trim_start_matches('0').is_empty();
-
-            if let Some(actual_width) = strnum_bitwidth::bitwidth(number, base) {
-                if let Some(width) = width {
-                    if actual_width > width {
-                        self.errors
-                            .push(AnalyzerError::too_large_number(width, &token.into()));
-                    }
+            if let (Some(value), Some(width)) = (Value::from_digits(number, base), width) {
+                if value.width > width {
+                    self.errors
+                        .push(AnalyzerError::too_large_number(width, &token.into()));
                 }
-            } else if width.is_none() && !zero {
-                // bitwidth calculation may be failed over 128bit.
-                self.errors
-                    .push(AnalyzerError::too_large_number(128, &token.into()));
             }
         }
 
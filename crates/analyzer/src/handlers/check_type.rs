@@ -4,6 +4,7 @@ use crate::attribute::Attribute as Attr;
 use crate::attribute_table;
 use crate::namespace::Namespace;
 use crate::namespace_table;
+use crate::suggestion::{Edit, indent_at};
 use crate::symbol::{GenericBoundKind, Symbol, SymbolId, SymbolKind, TypeKind};
 use crate::symbol_path::{GenericSymbolPath, SymbolPathNamespace};
 use crate::symbol_table;
@@ -139,6 +140,46 @@ fn resolve_inst_type(arg: &InstTypeSource) -> Option<Symbol> {
     Some(symbol)
 }
 
+/// Edits to connect the port implicitly like `inst u: Foo (port)`
+fn missing_port_edits(arg: &InstDeclaration, port: &str) -> Vec<Edit> {
+    let Some(x) = &arg.inst_declaration_opt2 else {
+        let token = &arg.semicolon.semicolon_token.token;
+        return Edit::insert(token, token.pos as usize, &format!(" ({port})"))
+            .into_iter()
+            .collect();
+    };
+
+    let token = &x.r_paren.r_paren_token.token;
+    let text = token.source.get_text();
+    let pos = token.pos as usize;
+    let Some(head) = text.get(..pos) else {
+        return vec![];
+    };
+    let last = head.trim_end();
+    let separator = !last.ends_with('(') && !last.ends_with(',');
+
+    let mut ret = Vec::new();
+    if head[last.len()..].contains('\n') {
+        // `)` is placed at its own line
+        if separator {
+            ret.extend(Edit::insert(token, last.len(), ","));
+        }
+        let line = head.rfind('\n').unwrap() + 1;
+        let indent = indent_at(&text, pos);
+        ret.extend(Edit::insert(token, line, &format!("{indent}    {port},\n")));
+    } else {
+        let connection = if separator {
+            format!(", {port}")
+        } else if last.ends_with(',') {
+            format!(" {port}")
+        } else {
+            port.to_string()
+        };
+        ret.extend(Edit::insert(token, last.len(), &connection));
+    }
+    ret
+}
+
 impl VerylGrammarTrait for CheckType {
     fn user_defined_type(&mut self, _arg: &UserDefinedType) -> Result<(), ParolError> {
         match self.point {
@@ -472,6 +513,7 @@ impl VerylGrammarTrait for CheckType {
                                 name,
                                 &port,
                                 &arg.identifier.as_ref().into(),
+                                missing_port_edits(arg, &port),
                             ));
                         }
                     }
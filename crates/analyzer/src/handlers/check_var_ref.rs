@@ -8,14 +8,16 @@ use crate::evaluator::Evaluator;
 use crate::symbol::{Direction, GenericBoundKind, Symbol, SymbolId, SymbolKind};
 use crate::symbol_table;
 use crate::var_ref::{
-    AssignDeclarationType, AssignPosition, AssignPositionType, AssignStatementBranchItemType,
-    AssignStatementBranchType, ExpressionTargetType, VarRef, VarRefAffiliation, VarRefPath,
-    VarRefType,
+    AssignBranchItem, AssignDeclarationType, AssignPosition, AssignPositionType,
+    AssignStatementBranchItemType, AssignStatementBranchType, ExpressionTargetType, VarRef,
+    VarRefAffiliation, VarRefPath, VarRefType,
 };
 use veryl_parser::ParolError;
+use veryl_parser::Stringifier;
+use veryl_parser::token_range::TokenRange;
 use veryl_parser::veryl_grammar_trait::*;
 use veryl_parser::veryl_token::Token;
-use veryl_parser::veryl_walker::{Handler, HandlerPoint};
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
 
 struct FunctionCallContext {
     pub token: Token,
@@ -31,7 +33,7 @@ pub struct CheckVarRef {
     in_expression: Vec<bool>,
     in_if_expression: Vec<()>,
     function_call: Vec<Option<FunctionCallContext>>,
-    branch_index: usize,
+    branch_index: Vec<usize>,
 }
 
 impl CheckVarRef {
@@ -146,6 +148,12 @@ fn has_cond_type(token: &Token) -> bool {
     false
 }
 
+fn stringify(arg: &Expression) -> String {
+    let mut stringifier = Stringifier::new();
+    stringifier.expression(arg);
+    stringifier.as_str().to_string()
+}
+
 fn map_assignable_factor(arg: &Expression) -> Option<VarRefPath> {
     if let Some(Factor::IdentifierFactor(x)) = arg.unwrap_factor() {
         if x.identifier_factor.identifier_factor_opt.is_none() {
@@ -175,18 +183,18 @@ impl VerylGrammarTrait for CheckVarRef {
                     AssignPositionType::StatementBranchItem {
                         token: arg.else_token.token,
                         define_context: arg.else_token.token.into(),
-                        index: self.branch_index,
+                        index: *self.branch_index.last().unwrap(),
                         r#type: AssignStatementBranchItemType::Else,
                     }
                 } else {
                     AssignPositionType::DeclarationBranchItem {
                         token: arg.else_token.token,
                         define_context: arg.else_token.token.into(),
-                        index: self.branch_index,
+                        index: *self.branch_index.last().unwrap(),
                     }
                 };
                 *self.assign_position.0.last_mut().unwrap() = position;
-                self.branch_index += 1;
+                *self.branch_index.last_mut().unwrap() += 1;
             }
         }
         Ok(())
@@ -397,8 +405,24 @@ impl VerylGrammarTrait for CheckVarRef {
     fn if_statement(&mut self, arg: &IfStatement) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
-                self.branch_index = 0;
+                self.branch_index.push(0);
                 let branches = 1 + arg.if_statement_list.len() + arg.if_statement_opt.iter().len();
+                let mut items = vec![AssignBranchItem {
+                    name: format!("`if {}`", stringify(&arg.expression)),
+                    range: arg.r#if.if_token.token.into(),
+                }];
+                for x in &arg.if_statement_list {
+                    items.push(AssignBranchItem {
+                        name: format!("`else if {}`", stringify(&x.expression)),
+                        range: TokenRange::new(&x.r#else.else_token, &x.r#if.if_token),
+                    });
+                }
+                if let Some(ref x) = arg.if_statement_opt {
+                    items.push(AssignBranchItem {
+                        name: "`else`".to_string(),
+                        range: x.r#else.else_token.token.into(),
+                    });
+                }
                 let has_explicit_default = arg.if_statement_opt.is_some();
                 let has_cond_type = has_cond_type(&arg.r#if.if_token.token);
                 let has_default = has_explicit_default | has_cond_type;
@@ -407,6 +431,7 @@ impl VerylGrammarTrait for CheckVarRef {
                         token: arg.r#if.if_token.token,
                         define_context: arg.r#if.if_token.token.into(),
                         branches,
+                        items,
                         has_default,
                         allow_missing_reset_statement: false,
                         r#type: AssignStatementBranchType::If,
@@ -415,14 +440,15 @@ impl VerylGrammarTrait for CheckVarRef {
                     .push(AssignPositionType::StatementBranchItem {
                         token: arg.r#if.if_token.token,
                         define_context: arg.r#if.if_token.token.into(),
-                        index: self.branch_index,
+                        index: 0,
                         r#type: AssignStatementBranchItemType::If,
                     });
-                self.branch_index += 1;
+                *self.branch_index.last_mut().unwrap() += 1;
             }
             HandlerPoint::After => {
                 self.assign_position.pop();
                 self.assign_position.pop();
+                self.branch_index.pop();
             }
         }
         Ok(())
@@ -431,9 +457,25 @@ impl VerylGrammarTrait for CheckVarRef {
     fn if_reset_statement(&mut self, arg: &IfResetStatement) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
-                self.branch_index = 0;
+                self.branch_index.push(0);
                 let branches =
                     1 + arg.if_reset_statement_list.len() + arg.if_reset_statement_opt.iter().len();
+                let mut items = vec![AssignBranchItem {
+                    name: "`if_reset`".to_string(),
+                    range: arg.if_reset.if_reset_token.token.into(),
+                }];
+                for x in &arg.if_reset_statement_list {
+                    items.push(AssignBranchItem {
+                        name: format!("`else if {}`", stringify(&x.expression)),
+                        range: TokenRange::new(&x.r#else.else_token, &x.r#if.if_token),
+                    });
+                }
+                if let Some(ref x) = arg.if_reset_statement_opt {
+                    items.push(AssignBranchItem {
+                        name: "`else`".to_string(),
+                        range: x.r#else.else_token.token.into(),
+                    });
+                }
                 let has_explicit_default = arg.if_reset_statement_opt.is_some();
                 let has_cond_type = has_cond_type(&arg.if_reset.if_reset_token.token);
                 let has_default = has_explicit_default | has_cond_type;
@@ -446,6 +488,7 @@ impl VerylGrammarTrait for CheckVarRef {
                         token: arg.if_reset.if_reset_token.token,
                         define_context: arg.if_reset.if_reset_token.token.into(),
                         branches,
+                        items,
                         has_default,
                         allow_missing_reset_statement,
                         r#type: AssignStatementBranchType::IfReset,
@@ -454,14 +497,15 @@ impl VerylGrammarTrait for CheckVarRef {
                     .push(AssignPositionType::StatementBranchItem {
                         token: arg.if_reset.if_reset_token.token,
                         define_context: arg.if_reset.if_reset_token.token.into(),
-                        index: self.branch_index,
+                        index: 0,
                         r#type: AssignStatementBranchItemType::IfReset,
                     });
-                self.branch_index += 1;
+                *self.branch_index.last_mut().unwrap() += 1;
             }
             HandlerPoint::After => {
                 self.assign_position.pop();
                 self.assign_position.pop();
+                self.branch_index.pop();
             }
         }
         Ok(())
@@ -484,8 +528,31 @@ impl VerylGrammarTrait for CheckVarRef {
     fn case_statement(&mut self, arg: &CaseStatement) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
-                self.branch_index = 0;
+                self.branch_index.push(0);
                 let branches = arg.case_statement_list.len();
+                let items = arg
+                    .case_statement_list
+                    .iter()
+                    .map(|x| {
+                        let (name, range) = match x.case_item.case_item_group.as_ref() {
+                            CaseItemGroup::CaseCondition(x) => {
+                                let mut stringifier = Stringifier::new();
+                                stringifier.case_condition(&x.case_condition);
+                                (
+                                    stringifier.as_str().to_string(),
+                                    x.case_condition.as_ref().into(),
+                                )
+                            }
+                            CaseItemGroup::Defaul(x) => {
+                                ("default".to_string(), x.defaul.as_ref().into())
+                            }
+                        };
+                        AssignBranchItem {
+                            name: format!("`{name}`"),
+                            range,
+                        }
+                    })
+                    .collect();
                 let has_explicit_default = arg.case_statement_list.iter().any(|x| {
                     matches!(
                         x.case_item.case_item_group.as_ref(),
@@ -499,6 +566,7 @@ impl VerylGrammarTrait for CheckVarRef {
                         token: arg.case.case_token.token,
                         define_context: arg.case.case_token.token.into(),
                         branches,
+                        items,
                         has_default,
                         allow_missing_reset_statement: false,
                         r#type: AssignStatementBranchType::Case,
@@ -506,6 +574,7 @@ impl VerylGrammarTrait for CheckVarRef {
             }
             HandlerPoint::After => {
                 self.assign_position.pop();
+                self.branch_index.pop();
             }
         }
         Ok(())
@@ -518,10 +587,84 @@ impl VerylGrammarTrait for CheckVarRef {
                     .push(AssignPositionType::StatementBranchItem {
                         token: arg.colon.colon_token.token,
                         define_context: arg.colon.colon_token.token.into(),
-                        index: self.branch_index,
+                        index: *self.branch_index.last().unwrap(),
                         r#type: AssignStatementBranchItemType::Case,
                     });
-                self.branch_index += 1;
+                *self.branch_index.last_mut().unwrap() += 1;
+            }
+            HandlerPoint::After => {
+                self.assign_position.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn switch_statement(&mut self, arg: &SwitchStatement) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                self.branch_index.push(0);
+                let branches = arg.switch_statement_list.len();
+                let items = arg
+                    .switch_statement_list
+                    .iter()
+                    .map(|x| {
+                        let (name, range) = match x.switch_item.switch_item_group.as_ref() {
+                            SwitchItemGroup::SwitchCondition(x) => {
+                                let mut stringifier = Stringifier::new();
+                                stringifier.switch_condition(&x.switch_condition);
+                                (
+                                    stringifier.as_str().to_string(),
+                                    x.switch_condition.as_ref().into(),
+                                )
+                            }
+                            SwitchItemGroup::Defaul(x) => {
+                                ("default".to_string(), x.defaul.as_ref().into())
+                            }
+                        };
+                        AssignBranchItem {
+                            name: format!("`{name}`"),
+                            range,
+                        }
+                    })
+                    .collect();
+                let has_explicit_default = arg.switch_statement_list.iter().any(|x| {
+                    matches!(
+                        x.switch_item.switch_item_group.as_ref(),
+                        SwitchItemGroup::Defaul(_)
+                    )
+                });
+                let has_cond_type = has_cond_type(&arg.switch.switch_token.token);
+                let has_default = has_explicit_default | has_cond_type;
+                self.assign_position
+                    .push(AssignPositionType::StatementBranch {
+                        token: arg.switch.switch_token.token,
+                        define_context: arg.switch.switch_token.token.into(),
+                        branches,
+                        items,
+                        has_default,
+                        allow_missing_reset_statement: false,
+                        r#type: AssignStatementBranchType::Switch,
+                    });
+            }
+            HandlerPoint::After => {
+                self.assign_position.pop();
+                self.branch_index.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn switch_item(&mut self, arg: &SwitchItem) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                self.assign_position
+                    .push(AssignPositionType::StatementBranchItem {
+                        token: arg.colon.colon_token.token,
+                        define_context: arg.colon.colon_token.token.into(),
+                        index: *self.branch_index.last().unwrap(),
+                        r#type: AssignStatementBranchItemType::Switch,
+                    });
+                *self.branch_index.last_mut().unwrap() += 1;
             }
             HandlerPoint::After => {
                 self.assign_position.pop();
@@ -784,7 +927,7 @@ impl VerylGrammarTrait for CheckVarRef {
     fn generate_if_declaration(&mut self, arg: &GenerateIfDeclaration) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
-                self.branch_index = 0;
+                self.branch_index.push(0);
                 let branches = 1
                     + arg.generate_if_declaration_list.len()
                     + arg.generate_if_declaration_opt.iter().len();
@@ -798,12 +941,13 @@ impl VerylGrammarTrait for CheckVarRef {
                     .push(AssignPositionType::DeclarationBranchItem {
                         token: arg.r#if.if_token.token,
                         define_context: arg.r#if.if_token.token.into(),
-                        index: self.branch_index,
+                        index: 0,
                     });
-                self.branch_index += 1;
+                *self.branch_index.last_mut().unwrap() += 1;
             }
             HandlerPoint::After => {
                 self.assign_position.pop();
+                self.branch_index.pop();
             }
         }
         Ok(())
@@ -3,7 +3,7 @@ use crate::attribute::Attribute as Attr;
 use crate::attribute::{AllowItem, EnumEncodingItem};
 use crate::attribute_table;
 use crate::definition_table::{self, Definition};
-use crate::evaluator::{EvaluatedValue, Evaluator};
+use crate::evaluator::Evaluator;
 use crate::namespace::Namespace;
 use crate::namespace_table;
 use crate::reference_table::{self, ReferenceCandidate};
@@ -86,6 +86,7 @@ pub struct CreateSymbolTable {
     affiliation: Vec<VariableAffiliation>,
     connect_target_identifiers: Vec<ConnectTargetIdentifier>,
     connects: HashMap<Token, ConnectTarget>,
+    inst_type: Option<ScopedIdentifier>,
     parameters: Vec<Vec<Parameter>>,
     ports: Vec<Vec<Port>>,
     needs_default_generic_argument: bool,
@@ -126,6 +127,17 @@ impl CreateSymbolTable {
         }
     }
 
+    /// Port and parameter names in instance declaration refer to the symbols of the component
+    fn add_inst_item_name(&mut self, arg: &Identifier) {
+        if let Some(component) = &self.inst_type {
+            reference_table::add(ReferenceCandidate::InstItemName {
+                arg: arg.clone(),
+                component: component.clone(),
+                namespace: namespace_table::get_default(),
+            });
+        }
+    }
+
     fn get_namespace(&self, token: &Token) -> Namespace {
         let attrs = attribute_table::get(token);
         let mut ret = self.namespace.clone();
@@ -296,7 +308,7 @@ impl CreateSymbolTable {
     fn evaluate_enum_value(&mut self, arg: &EnumItem) -> EnumMemberValue {
         if let Some(ref x) = arg.enum_item_opt {
             let evaluated = Evaluator::new().expression(&x.expression);
-            if let EvaluatedValue::Fixed(value) = evaluated.value {
+            if let Some(value) = evaluated.get_value() {
                 let valid_variant = match self.enum_encoding {
                     EnumEncodingItem::OneHot => value.count_ones() == 1,
                     EnumEncodingItem::Gray => {
@@ -1052,7 +1064,11 @@ impl VerylGrammarTrait for CreateSymbolTable {
     }
 
     fn inst_declaration(&mut self, arg: &InstDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            self.inst_type = Some(arg.scoped_identifier.as_ref().clone());
+        }
         if let HandlerPoint::After = self.point {
+            self.inst_type = None;
             let array: Vec<Expression> = if let Some(x) = &arg.inst_declaration_opt0 {
                 x.array.as_ref().into()
             } else {
@@ -1078,10 +1094,19 @@ impl VerylGrammarTrait for CreateSymbolTable {
         Ok(())
     }
 
+    fn inst_parameter_item(&mut self, arg: &InstParameterItem) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            reference_table::add(arg.into());
+            self.add_inst_item_name(&arg.identifier);
+        }
+        Ok(())
+    }
+
     fn inst_port_item(&mut self, arg: &InstPortItem) -> Result<(), ParolError> {
         match self.point {
             HandlerPoint::Before => {
                 reference_table::add(arg.into());
+                self.add_inst_item_name(&arg.identifier);
                 self.connect_target_identifiers.clear();
             }
             HandlerPoint::After => {
@@ -1332,12 +1357,15 @@ impl VerylGrammarTrait for CreateSymbolTable {
                     &arg.statement_block.r_brace.r_brace_token,
                 );
 
+                let definition = definition_table::insert(Definition::Function(arg.clone()));
+
                 let property = FunctionProperty {
                     range,
                     generic_parameters,
                     generic_references,
                     ports,
                     ret,
+                    definition: Some(definition),
                 };
 
                 if let Some(id) = self.insert_symbol(
@@ -1824,6 +1852,7 @@ impl VerylGrammarTrait for CreateSymbolTable {
                     generic_references,
                     ports,
                     ret,
+                    definition: None,
                 };
 
                 if let Some(id) = self.insert_symbol(
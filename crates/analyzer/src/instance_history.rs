@@ -53,7 +53,7 @@ impl InstanceHistory {
             return Err(InstanceHistoryError::ExceedTotalLimit);
         }
         if self.hierarchy.iter().any(|x| *x == sig)
-            && sig.params.iter().all(|x| x.1.get_value().is_some())
+            && sig.params.iter().all(|x| x.1.get_fixed().is_some())
         {
             return Err(InstanceHistoryError::InfiniteRecursion);
         }
@@ -0,0 +1,283 @@
+use crate::HashMap;
+use crate::definition_table::{self, Definition};
+use crate::evaluator::{Evaluated, Evaluator};
+use crate::namespace_table;
+use crate::symbol::{Symbol, SymbolId, SymbolKind};
+use crate::symbol_path::SymbolPath;
+use crate::symbol_table;
+use crate::value::Value;
+use veryl_parser::resource_table::StrId;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_token::Token;
+use veryl_parser::veryl_walker::{Handler, VerylWalker};
+
+/// Node of the elaborated instance tree
+#[derive(Clone, Debug)]
+pub struct InstanceNode {
+    /// Instance name, or module name for the top
+    pub token: Token,
+    /// Module or interface of the instance
+    pub symbol: SymbolId,
+    /// Parameter values of the instance
+    pub params: Vec<(StrId, Evaluated)>,
+    pub children: Vec<InstanceNode>,
+    /// Children are omitted because of depth limit or recursion
+    pub truncated: bool,
+}
+
+/// Elaborate instances under the top module or interface
+pub fn elaborate(top: &Symbol, depth_limit: usize) -> Option<InstanceNode> {
+    top.kind.get_definition()?;
+
+    let mut builder = InstanceTreeBuilder {
+        depth_limit,
+        path: Vec::new(),
+        children: Vec::new(),
+    };
+    Some(builder.node(top.token, top, &HashMap::default()))
+}
+
+struct InstanceTreeBuilder {
+    depth_limit: usize,
+    /// Module and parameter values from the top to the current instance
+    path: Vec<(SymbolId, Vec<(StrId, Evaluated)>)>,
+    children: Vec<InstanceNode>,
+}
+
+impl InstanceTreeBuilder {
+    fn node(
+        &mut self,
+        token: Token,
+        symbol: &Symbol,
+        overrides: &HashMap<StrId, Evaluated>,
+    ) -> InstanceNode {
+        with_overrides(symbol, overrides, |params| {
+            let recursive = is_recursive(&self.path, symbol, &params);
+            let truncated = recursive || self.path.len() >= self.depth_limit;
+
+            let mut children = Vec::new();
+            if !truncated {
+                if let Some(def) = symbol.kind.get_definition().and_then(definition_table::get) {
+                    self.path.push((symbol.id, params.clone()));
+                    let parent = std::mem::take(&mut self.children);
+                    match def {
+                        Definition::Module(x) => self.module_declaration(&x),
+                        Definition::Interface(x) => self.interface_declaration(&x),
+                        // instance target is module or interface only
+                        Definition::Function(_) => (),
+                    }
+                    children = std::mem::replace(&mut self.children, parent);
+                    self.path.pop();
+                }
+            }
+
+            InstanceNode {
+                token,
+                symbol: symbol.id,
+                params,
+                children,
+                truncated,
+            }
+        })
+    }
+}
+
+/// Evaluate parameters of the module or interface with the override values,
+/// and call `f` while the override values are applied
+pub(crate) fn with_overrides<T>(
+    symbol: &Symbol,
+    overrides: &HashMap<StrId, Evaluated>,
+    f: impl FnOnce(Vec<(StrId, Evaluated)>) -> T,
+) -> T {
+    let parameters = symbol.kind.get_parameters();
+
+    // Push override parameters
+    for x in parameters {
+        if let Some(value) = overrides.get(&x.name) {
+            symbol_table::push_override(x.symbol, value.clone());
+        }
+    }
+    symbol_table::clear_evaluated_cache(&symbol.inner_namespace());
+
+    let params: Vec<_> = parameters
+        .iter()
+        .map(|x| {
+            let value = symbol_table::get(x.symbol)
+                .map(|x| x.evaluate())
+                .unwrap_or_else(Evaluated::create_unknown);
+            (x.name, value)
+        })
+        .collect();
+
+    let ret = f(params);
+
+    symbol_table::clear_evaluated_cache(&symbol.inner_namespace());
+
+    // Pop override parameters
+    for x in parameters {
+        if overrides.contains_key(&x.name) {
+            symbol_table::pop_override(x.symbol);
+        }
+    }
+
+    ret
+}
+
+/// Whether the same module with the same parameter values is already elaborated in the path
+pub(crate) fn is_recursive(
+    path: &[(SymbolId, Vec<(StrId, Evaluated)>)],
+    symbol: &Symbol,
+    params: &[(StrId, Evaluated)],
+) -> bool {
+    path.iter().any(|(id, x)| {
+        *id == symbol.id
+            && x.iter()
+                .zip(params.iter())
+                .all(|(x, y)| x.1.value == y.1.value)
+    })
+}
+
+/// Parameter values given by the instance declaration
+pub(crate) fn inst_overrides(arg: &InstDeclaration) -> HashMap<StrId, Evaluated> {
+    let mut ret = HashMap::default();
+    if let Some(x) = &arg.inst_declaration_opt1 {
+        if let Some(x) = &x.inst_parameter.inst_parameter_opt {
+            let items: Vec<InstParameterItem> = x.inst_parameter_list.as_ref().into();
+            for item in items {
+                let value = Evaluator::new().inst_parameter_item(&item);
+                ret.insert(item.identifier.identifier_token.token.text, value);
+            }
+        }
+    }
+    ret
+}
+
+/// Walk the enabled block of the generate if declaration,
+/// or all blocks if the conditions can't be evaluated
+pub(crate) fn walk_generate_if<T: VerylWalker>(walker: &mut T, arg: &GenerateIfDeclaration) {
+    let mut evaluator = Evaluator::new();
+    let mut conditions = vec![evaluator.expression(&arg.expression).get_value()];
+    for x in &arg.generate_if_declaration_list {
+        conditions.push(evaluator.expression(&x.expression).get_value());
+    }
+    if arg.generate_if_declaration_opt.is_some() {
+        conditions.push(Some(1));
+    }
+
+    let enabled = conditions.iter().position(|x| x != &Some(0));
+    let walk_all = enabled.is_some_and(|x| conditions[x].is_none());
+    let is_enabled = |i: usize| walk_all || enabled == Some(i);
+
+    if is_enabled(0) {
+        walker.generate_named_block(&arg.generate_named_block);
+    }
+    for (i, x) in arg.generate_if_declaration_list.iter().enumerate() {
+        if is_enabled(i + 1) {
+            walker.generate_optional_named_block(&x.generate_optional_named_block);
+        }
+    }
+    if let Some(x) = &arg.generate_if_declaration_opt {
+        if is_enabled(conditions.len() - 1) {
+            walker.generate_optional_named_block(&x.generate_optional_named_block);
+        }
+    }
+}
+
+/// Genvar and its values of the generate for declaration,
+/// or `None` if the range or the step can't be evaluated
+fn generate_for_values(arg: &GenerateForDeclaration) -> Option<(Symbol, Vec<isize>)> {
+    // genvar is declared in the namespace of the named block
+    let genvar = arg.identifier.identifier_token.token;
+    let mut namespace = namespace_table::get(genvar.id)?;
+    namespace.push(
+        arg.generate_named_block
+            .identifier
+            .identifier_token
+            .token
+            .text,
+    );
+    let genvar = symbol_table::resolve((&SymbolPath::new(&[genvar.text]), &namespace))
+        .ok()?
+        .found;
+
+    let mut evaluator = Evaluator::new();
+    let start = evaluator.expression(&arg.range.expression).get_value()?;
+    let (end, inclusive) = match &arg.range.range_opt {
+        Some(x) => (
+            evaluator.expression(&x.expression).get_value()?,
+            matches!(*x.range_operator, RangeOperator::DotDotEqu(_)),
+        ),
+        None => (start, true),
+    };
+    let step = match &arg.generate_for_declaration_opt {
+        Some(x) => Some((
+            x.assignment_operator.assignment_operator_token.to_string(),
+            evaluator.expression(&x.expression).get_value()?,
+        )),
+        None => None,
+    };
+
+    let mut values = Vec::new();
+    let mut value = start;
+    while value < end || (inclusive && value == end) {
+        values.push(value);
+        let next = match &step {
+            None => value.checked_add(1),
+            Some((op, x)) => match op.as_str() {
+                "+=" => value.checked_add(*x),
+                "*=" => value.checked_mul(*x),
+                "<<=" | "<<<=" => value.checked_shl(u32::try_from(*x).ok()?),
+                _ => None,
+            },
+        };
+        // Step which doesn't increase the value never terminates
+        value = next.filter(|x| *x > value)?;
+    }
+    Some((genvar, values))
+}
+
+impl VerylWalker for InstanceTreeBuilder {
+    fn inst_declaration(&mut self, arg: &InstDeclaration) {
+        let Ok(symbol) = symbol_table::resolve(arg.scoped_identifier.as_ref()) else {
+            return;
+        };
+        if !matches!(
+            symbol.found.kind,
+            SymbolKind::Module(_) | SymbolKind::Interface(_)
+        ) {
+            return;
+        }
+
+        let overrides = inst_overrides(arg);
+
+        let token = arg.identifier.identifier_token.token;
+        let node = self.node(token, &symbol.found, &overrides);
+        self.children.push(node);
+    }
+
+    fn generate_if_declaration(&mut self, arg: &GenerateIfDeclaration) {
+        walk_generate_if(self, arg);
+    }
+
+    /// Walk the block for each value of the genvar, or once if the values can't be evaluated
+    fn generate_for_declaration(&mut self, arg: &GenerateForDeclaration) {
+        let Some((genvar, values)) = generate_for_values(arg) else {
+            self.generate_named_block(&arg.generate_named_block);
+            return;
+        };
+
+        for x in values {
+            let value = Value::new(x as u64, 32, false);
+            let value = Evaluated::create_fixed(value, false, vec![32], vec![]);
+            symbol_table::push_override(genvar.id, value);
+            symbol_table::clear_evaluated_cache(&genvar.namespace);
+            self.generate_named_block(&arg.generate_named_block);
+            symbol_table::pop_override(genvar.id);
+        }
+        symbol_table::clear_evaluated_cache(&genvar.namespace);
+    }
+
+    fn get_handlers(&mut self) -> Option<Vec<&mut dyn Handler>> {
+        None
+    }
+}
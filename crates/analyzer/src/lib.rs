@@ -2,17 +2,23 @@ pub mod analyzer;
 pub mod analyzer_error;
 pub mod attribute;
 pub mod attribute_table;
+pub mod cdc;
 pub mod connect_operation_table;
 pub mod definition_table;
 pub mod evaluator;
 pub mod handlers;
 pub mod instance_history;
+pub mod instance_tree;
+pub mod lint;
 pub mod msb_table;
 pub mod multi_sources;
 pub mod namespace;
 pub mod namespace_table;
+pub mod netlist;
 pub mod range_table;
+pub mod rdc;
 pub mod reference_table;
+pub mod suggestion;
 pub mod sv_system_function;
 pub mod symbol;
 pub mod symbol_path;
@@ -20,6 +26,8 @@ pub mod symbol_table;
 pub mod type_dag;
 pub mod r#unsafe;
 pub mod unsafe_table;
+pub mod unused;
+pub mod value;
 pub mod var_ref;
 pub use analyzer::Analyzer;
 pub use analyzer_error::AnalyzerError;
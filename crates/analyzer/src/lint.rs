@@ -0,0 +1,121 @@
+use crate::analyzer_error::{ANALYZER_DEFAULT_ALLOWED, AnalyzerError};
+use crate::attribute::Attribute;
+use crate::attribute_table;
+use miette::{Diagnostic, LabeledSpan, Severity, SourceCode};
+use std::fmt;
+use veryl_metadata::{Lint, LintLevel};
+
+/// Analyzer error with the severity given by lint rules
+#[derive(Debug)]
+pub struct LintError {
+    pub error: AnalyzerError,
+    pub severity: Severity,
+}
+
+impl fmt::Display for LintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.error.fmt(f)
+    }
+}
+
+impl std::error::Error for LintError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.error.source()
+    }
+}
+
+impl Diagnostic for LintError {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.code()
+    }
+
+    fn severity(&self) -> Option<Severity> {
+        Some(self.severity)
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.help()
+    }
+
+    fn url<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.error.url()
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        self.error.source_code()
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        self.error.labels()
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        self.error.related()
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        self.error.diagnostic_source()
+    }
+}
+
+/// Level of the diagnostic code given by lint rules or the default of the analyzer
+pub fn level(lint: &Lint, code: &str) -> Option<LintLevel> {
+    lint.level(code).or_else(|| {
+        ANALYZER_DEFAULT_ALLOWED
+            .contains(&code)
+            .then_some(LintLevel::Allow)
+    })
+}
+
+/// Whether the diagnostic code is not allowed by lint rules
+pub fn enabled(lint: &Lint, code: &str) -> bool {
+    level(lint, code) != Some(LintLevel::Allow)
+}
+
+/// Apply lint rules and `#[allow(...)]` attributes to the errors.
+/// Allowed errors are removed, and the others get the configured severity.
+pub fn apply(errors: Vec<AnalyzerError>, lint: &Lint) -> Vec<LintError> {
+    errors
+        .into_iter()
+        .filter_map(|error| {
+            let code = error.code().map(|x| x.to_string()).unwrap_or_default();
+            let severity = match level(lint, &code) {
+                Some(LintLevel::Allow) => return None,
+                Some(LintLevel::Warn) => Severity::Warning,
+                Some(LintLevel::Deny) => Severity::Error,
+                None => error.severity().unwrap_or(Severity::Error),
+            };
+            if is_allowed(&error, &code) {
+                None
+            } else {
+                Some(LintError { error, severity })
+            }
+        })
+        .collect()
+}
+
+/// Whether the error location is annotated by `#[allow(code)]`
+fn is_allowed(error: &AnalyzerError, code: &str) -> bool {
+    let Some(label) = error.labels().and_then(|mut x| x.next()) else {
+        return false;
+    };
+    let Some(contents) = error
+        .source_code()
+        .and_then(|x| x.read_span(label.inner(), 0, 0).ok())
+    else {
+        return false;
+    };
+    let Some(path) = contents.name() else {
+        return false;
+    };
+
+    let offset = label.offset();
+    attribute_table::get_all().iter().any(|(range, attr)| {
+        let beg = range.beg.pos as usize;
+        let end = (range.end.pos + range.end.length) as usize;
+        matches!(attr, Attribute::Allow(x) if x.to_string() == code)
+            && range.beg.source.to_string() == path
+            && beg <= offset
+            && offset < end
+    })
+}
@@ -0,0 +1,816 @@
+use crate::HashMap;
+use crate::HashSet;
+use crate::definition_table::{self, Definition};
+use crate::evaluator::Evaluated;
+use crate::instance_tree::{inst_overrides, is_recursive, walk_generate_if, with_overrides};
+use crate::symbol::{Direction, GenericBoundKind, Port, Symbol, SymbolId, SymbolKind};
+use crate::symbol_path::SymbolPathNamespace;
+use crate::symbol_table;
+use veryl_parser::ParolError;
+use veryl_parser::resource_table::StrId;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_token::Token;
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
+
+/// Variable or port in an elaborated instance
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct NetId {
+    pub instance: usize,
+    pub symbol: SymbolId,
+}
+
+/// Module which is not elaborated, and is treated by its ports only
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlackboxKind {
+    Synchronizer,
+    AsyncFifo,
+    AsyncHandshake,
+    Unknown,
+}
+
+#[derive(Clone, Debug)]
+pub struct Instance {
+    /// Instance name, or module name for the top
+    pub token: Token,
+    /// Module, proto module or generic parameter of the instance
+    pub symbol: SymbolId,
+    pub parent: Option<usize>,
+    pub blackbox: Option<BlackboxKind>,
+    pub ports: Vec<Port>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DriverKind {
+    Comb,
+    Register { clock: NetId, reset: Option<NetId> },
+}
+
+#[derive(Clone, Debug)]
+pub struct Driver {
+    pub target: NetId,
+    pub sources: Vec<NetId>,
+    pub kind: DriverKind,
+    /// The target is connected to the single source without any logic
+    pub direct: bool,
+    /// Only a part of the target is driven through select or member
+    pub partial: bool,
+    /// Assignment target or connected port
+    pub token: Token,
+}
+
+/// Drivers between variables and ports of the elaborated design
+#[derive(Clone, Debug, Default)]
+pub struct Netlist {
+    pub instances: Vec<Instance>,
+    pub drivers: Vec<Driver>,
+    widths: HashMap<NetId, usize>,
+    driver_map: HashMap<NetId, Vec<usize>>,
+}
+
+impl Netlist {
+    pub fn drivers_of(&self, net: &NetId) -> impl Iterator<Item = &Driver> {
+        self.driver_map
+            .get(net)
+            .into_iter()
+            .flatten()
+            .map(|x| &self.drivers[*x])
+    }
+
+    pub fn width(&self, net: &NetId) -> Option<usize> {
+        self.widths.get(net).copied()
+    }
+
+    /// Hierarchical name from the top instance
+    pub fn name(&self, net: &NetId) -> String {
+        let mut ret = symbol_table::get(net.symbol)
+            .map(|x| x.token.to_string())
+            .unwrap_or_default();
+        let mut instance = net.instance;
+        while let Some(parent) = self.instances[instance].parent {
+            ret = format!("{}.{}", self.instances[instance].token, ret);
+            instance = parent;
+        }
+        ret
+    }
+
+    /// Child instance of the top which includes the instance
+    pub fn top_child(&self, mut instance: usize) -> Option<usize> {
+        while let Some(parent) = self.instances[instance].parent {
+            if parent == 0 {
+                return Some(instance);
+            }
+            instance = parent;
+        }
+        None
+    }
+
+    /// Paths inside a child module are checked when the child module is analyzed
+    pub fn checked_in_child(&self, instances: &[usize]) -> bool {
+        let Some(child) = self.top_child(instances[0]) else {
+            return false;
+        };
+        if instances.iter().any(|x| self.top_child(*x) != Some(child)) {
+            return false;
+        }
+
+        let instance = &self.instances[child];
+        instance.blackbox.is_none()
+            && symbol_table::get(instance.symbol).is_some_and(|x| match &x.kind {
+                SymbolKind::Module(x) => x.generic_parameters.is_empty(),
+                _ => false,
+            })
+    }
+
+    /// Location in the top module
+    pub fn location(&self, instance: usize, token: &Token) -> TokenRange {
+        if let Some(child) = self.top_child(instance) {
+            self.instances[child].token.into()
+        } else {
+            token.into()
+        }
+    }
+}
+
+/// Netlists of modules elaborated without parameter overrides.
+/// They are shared by all instances of the module in the elaborated designs.
+#[derive(Default)]
+pub struct NetlistCache {
+    netlists: HashMap<SymbolId, Netlist>,
+    /// Modules under elaboration to detect recursive instantiation
+    elaborating: HashSet<SymbolId>,
+}
+
+impl NetlistCache {
+    /// Netlist of the module which can be shared, or `None` for generic modules
+    fn get(&mut self, symbol: &Symbol, depth_limit: usize) -> Option<&Netlist> {
+        let SymbolKind::Module(x) = &symbol.kind else {
+            return None;
+        };
+        if !x.generic_parameters.is_empty() || self.elaborating.contains(&symbol.id) {
+            return None;
+        }
+
+        if !self.netlists.contains_key(&symbol.id) {
+            self.elaborating.insert(symbol.id);
+            let netlist = build(symbol, depth_limit, self);
+            self.elaborating.remove(&symbol.id);
+            self.netlists.insert(symbol.id, netlist?);
+        }
+        self.netlists.get(&symbol.id)
+    }
+}
+
+/// Elaborate drivers under the top module.
+/// Sub-hierarchies without parameter overrides are reused from `cache`.
+pub fn elaborate<'a>(
+    top: &Symbol,
+    depth_limit: usize,
+    cache: &'a mut NetlistCache,
+) -> Option<&'a Netlist> {
+    cache.get(top, depth_limit)
+}
+
+fn build(top: &Symbol, depth_limit: usize, cache: &mut NetlistCache) -> Option<Netlist> {
+    let SymbolKind::Module(x) = &top.kind else {
+        return None;
+    };
+
+    let mut builder = NetlistBuilder {
+        depth_limit,
+        path: Vec::new(),
+        netlist: Netlist::default(),
+        cache,
+    };
+    builder.netlist.instances.push(Instance {
+        token: top.token,
+        symbol: top.id,
+        parent: None,
+        blackbox: None,
+        ports: x.ports.clone(),
+    });
+    builder.instance(0, top, &HashMap::default());
+
+    let mut netlist = builder.netlist;
+    for (i, x) in netlist.drivers.iter().enumerate() {
+        netlist.driver_map.entry(x.target).or_default().push(i);
+    }
+    Some(netlist)
+}
+
+/// Whether the module is recognized as a legal clock domain crossing
+pub fn blackbox_kind(symbol: &Symbol) -> Option<BlackboxKind> {
+    match &symbol.kind {
+        SymbolKind::Module(x) => {
+            let proto = x
+                .proto
+                .as_ref()
+                .and_then(|proto| symbol_table::resolve((proto, &symbol.namespace)).ok());
+            if proto.is_some_and(|x| x.found.token.to_string() == "synchronizer") {
+                return Some(BlackboxKind::Synchronizer);
+            }
+            match symbol.token.to_string().as_str() {
+                "async_fifo" => Some(BlackboxKind::AsyncFifo),
+                "async_handshake" => Some(BlackboxKind::AsyncHandshake),
+                _ => None,
+            }
+        }
+        SymbolKind::ProtoModule(_) => {
+            if symbol.token.to_string() == "synchronizer" {
+                Some(BlackboxKind::Synchronizer)
+            } else {
+                Some(BlackboxKind::Unknown)
+            }
+        }
+        _ => None,
+    }
+}
+
+struct PendingInstance {
+    token: Token,
+    symbol: Symbol,
+    overrides: HashMap<StrId, Evaluated>,
+    connections: Vec<Connection>,
+}
+
+struct Connection {
+    port: StrId,
+    sources: Vec<NetId>,
+    /// Variables which can be driven by output port
+    targets: Vec<NetId>,
+    direct: bool,
+    token: Token,
+}
+
+struct NetlistBuilder<'a> {
+    depth_limit: usize,
+    /// Module and parameter values from the top to the current instance
+    path: Vec<(SymbolId, Vec<(StrId, Evaluated)>)>,
+    netlist: Netlist,
+    cache: &'a mut NetlistCache,
+}
+
+impl NetlistBuilder<'_> {
+    fn instance(&mut self, index: usize, symbol: &Symbol, overrides: &HashMap<StrId, Evaluated>) {
+        with_overrides(symbol, overrides, |params| {
+            if is_recursive(&self.path, symbol, &params) || self.path.len() >= self.depth_limit {
+                return;
+            }
+            let Some(Definition::Module(def)) =
+                symbol.kind.get_definition().and_then(definition_table::get)
+            else {
+                return;
+            };
+
+            let (default_clock, default_reset) = match &symbol.kind {
+                SymbolKind::Module(x) => (x.default_clock, x.default_reset),
+                _ => (None, None),
+            };
+            let mut collector = DriverCollector {
+                instance: index,
+                default_clock: default_clock.map(|x| NetId {
+                    instance: index,
+                    symbol: x,
+                }),
+                default_reset: default_reset.map(|x| NetId {
+                    instance: index,
+                    symbol: x,
+                }),
+                ..Default::default()
+            };
+            DriverWalker::new(&mut collector).module_declaration(&def);
+
+            self.netlist.drivers.append(&mut collector.drivers);
+            self.netlist.widths.extend(collector.widths.drain());
+
+            self.path.push((symbol.id, params));
+            for child in collector.children {
+                self.child(index, child);
+            }
+            self.path.pop();
+        });
+    }
+
+    fn child(&mut self, parent: usize, child: PendingInstance) {
+        let (blackbox, ports) = match &child.symbol.kind {
+            SymbolKind::Module(x) => (blackbox_kind(&child.symbol), x.ports.clone()),
+            SymbolKind::ProtoModule(x) => (blackbox_kind(&child.symbol), x.ports.clone()),
+            _ => return,
+        };
+
+        let index = self.netlist.instances.len();
+        self.netlist.instances.push(Instance {
+            token: child.token,
+            symbol: child.symbol.id,
+            parent: Some(parent),
+            blackbox,
+            ports: ports.clone(),
+        });
+
+        for connection in &child.connections {
+            let Some(port) = ports.iter().find(|x| x.name() == connection.port) else {
+                continue;
+            };
+            let net = NetId {
+                instance: index,
+                symbol: port.symbol,
+            };
+            if let Some(width) = port.symbol().evaluate().get_total_width() {
+                self.netlist.widths.insert(net, width);
+            }
+
+            match port.property().direction {
+                Direction::Input => self.netlist.drivers.push(Driver {
+                    target: net,
+                    sources: connection.sources.clone(),
+                    kind: DriverKind::Comb,
+                    direct: connection.direct,
+                    partial: false,
+                    token: connection.token,
+                }),
+                Direction::Output => {
+                    for target in &connection.targets {
+                        self.netlist.drivers.push(Driver {
+                            target: *target,
+                            sources: vec![net],
+                            kind: DriverKind::Comb,
+                            direct: true,
+                            partial: false,
+                            token: connection.token,
+                        });
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if blackbox.is_none() {
+            if child.overrides.is_empty() && self.path.len() < self.depth_limit {
+                if let Some(netlist) = self.cache.get(&child.symbol, self.depth_limit) {
+                    append(&mut self.netlist, netlist, index);
+                    return;
+                }
+            }
+            self.instance(index, &child.symbol, &child.overrides);
+        }
+    }
+}
+
+/// Append the netlist of the module as the instance at `index`
+fn append(dst: &mut Netlist, src: &Netlist, index: usize) {
+    // Top instance of `src` is `index`, and the others follow the existing instances
+    let offset = dst.instances.len() - 1;
+    let map = |x: usize| if x == 0 { index } else { x + offset };
+    let net = |x: NetId| NetId {
+        instance: map(x.instance),
+        symbol: x.symbol,
+    };
+
+    for x in src.instances.iter().skip(1) {
+        let mut x = x.clone();
+        x.parent = x.parent.map(map);
+        dst.instances.push(x);
+    }
+    for x in &src.drivers {
+        let mut x = x.clone();
+        x.target = net(x.target);
+        x.sources = x.sources.iter().map(|x| net(*x)).collect();
+        if let DriverKind::Register { clock, reset } = x.kind {
+            x.kind = DriverKind::Register {
+                clock: net(clock),
+                reset: reset.map(net),
+            };
+        }
+        dst.drivers.push(x);
+    }
+    for (x, width) in &src.widths {
+        dst.widths.insert(net(*x), *width);
+    }
+}
+
+#[derive(Default)]
+struct DriverCollector {
+    instance: usize,
+    point: HandlerPoint,
+    default_clock: Option<NetId>,
+    default_reset: Option<NetId>,
+    kind: Option<DriverKind>,
+    conditions: Vec<Vec<NetId>>,
+    in_function: usize,
+    drivers: Vec<Driver>,
+    widths: HashMap<NetId, usize>,
+    children: Vec<PendingInstance>,
+}
+
+impl DriverCollector {
+    fn net(&mut self, symbol: &Symbol) -> Option<NetId> {
+        match &symbol.kind {
+            // Loop variables are constant in each iteration
+            SymbolKind::Variable(x) if x.loop_variable => return None,
+            SymbolKind::Variable(_) | SymbolKind::Port(_) => (),
+            _ => return None,
+        }
+        let net = NetId {
+            instance: self.instance,
+            symbol: symbol.id,
+        };
+        if let Some(width) = symbol.evaluate().get_total_width() {
+            self.widths.insert(net, width);
+        }
+        Some(net)
+    }
+
+    fn identifier_net<T>(&mut self, arg: T) -> Option<NetId>
+    where
+        T: Into<SymbolPathNamespace>,
+    {
+        let symbol = symbol_table::resolve(arg).ok()?;
+        let symbol = symbol_table::get(*symbol.full_path.first()?)?;
+        self.net(&symbol)
+    }
+
+    fn sources(&mut self, arg: &Expression) -> Vec<NetId> {
+        let mut collector = SourceCollector::default();
+        DriverWalker::new(&mut collector).expression(arg);
+        let mut ret = Vec::new();
+        for symbol in collector.symbols {
+            if let Some(net) = self.net(&symbol) {
+                if !ret.contains(&net) {
+                    ret.push(net);
+                }
+            }
+        }
+        ret
+    }
+
+    fn push_condition(&mut self, expressions: &[&Expression]) {
+        let mut nets = Vec::new();
+        for x in expressions {
+            nets.append(&mut self.sources(x));
+        }
+        self.conditions.push(nets);
+    }
+
+    /// Variables in the index of the assignment target select the driven part like conditions
+    fn push_select(&mut self, selects: &[&Select]) -> bool {
+        let mut nets = Vec::new();
+        for x in selects {
+            nets.append(&mut self.sources(&x.expression));
+            if let Some(x) = &x.select_opt {
+                nets.append(&mut self.sources(&x.expression));
+            }
+        }
+        if nets.is_empty() {
+            false
+        } else {
+            self.conditions.push(nets);
+            true
+        }
+    }
+
+    fn push_driver(
+        &mut self,
+        target: Option<NetId>,
+        expression: &Expression,
+        token: Token,
+        partial: bool,
+    ) {
+        let (Some(target), Some(kind)) = (target, self.kind) else {
+            return;
+        };
+        if self.in_function > 0 {
+            return;
+        }
+
+        let mut sources = self.sources(expression);
+        let direct = kind == DriverKind::Comb
+            && self.conditions.is_empty()
+            && expression.unwrap_identifier().is_some();
+        for x in self.conditions.iter().flatten() {
+            if !sources.contains(x) {
+                sources.push(*x);
+            }
+        }
+
+        self.drivers.push(Driver {
+            target,
+            sources,
+            kind,
+            direct,
+            partial,
+            token,
+        });
+    }
+}
+
+impl Handler for DriverCollector {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+impl VerylGrammarTrait for DriverCollector {
+    fn function_declaration(&mut self, _arg: &FunctionDeclaration) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => self.in_function += 1,
+            HandlerPoint::After => self.in_function -= 1,
+        }
+        Ok(())
+    }
+
+    fn always_ff_declaration(&mut self, arg: &AlwaysFfDeclaration) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                let clock = if let Some(x) = arg.get_explicit_clock() {
+                    self.identifier_net(x.identifier.as_ref())
+                } else {
+                    self.default_clock
+                };
+                let reset = if let Some(x) = arg.get_explicit_reset() {
+                    self.identifier_net(x.identifier.as_ref())
+                } else if arg.has_if_reset() {
+                    self.default_reset
+                } else {
+                    None
+                };
+                self.kind = clock.map(|clock| DriverKind::Register { clock, reset });
+            }
+            HandlerPoint::After => self.kind = None,
+        }
+        Ok(())
+    }
+
+    fn always_comb_declaration(&mut self, _arg: &AlwaysCombDeclaration) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => self.kind = Some(DriverKind::Comb),
+            HandlerPoint::After => self.kind = None,
+        }
+        Ok(())
+    }
+
+    fn if_statement(&mut self, arg: &IfStatement) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                let mut expressions = vec![arg.expression.as_ref()];
+                for x in &arg.if_statement_list {
+                    expressions.push(x.expression.as_ref());
+                }
+                self.push_condition(&expressions);
+            }
+            HandlerPoint::After => {
+                self.conditions.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn if_reset_statement(&mut self, arg: &IfResetStatement) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                let expressions: Vec<_> = arg
+                    .if_reset_statement_list
+                    .iter()
+                    .map(|x| x.expression.as_ref())
+                    .collect();
+                self.push_condition(&expressions);
+            }
+            HandlerPoint::After => {
+                self.conditions.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn case_statement(&mut self, arg: &CaseStatement) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => self.push_condition(&[arg.expression.as_ref()]),
+            HandlerPoint::After => {
+                self.conditions.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn switch_statement(&mut self, arg: &SwitchStatement) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                let mut expressions = Vec::new();
+                for x in &arg.switch_statement_list {
+                    if let SwitchItemGroup::SwitchCondition(x) =
+                        x.switch_item.switch_item_group.as_ref()
+                    {
+                        expressions.push(x.switch_condition.expression.as_ref());
+                        for x in &x.switch_condition.switch_condition_list {
+                            expressions.push(x.expression.as_ref());
+                        }
+                    }
+                }
+                self.push_condition(&expressions);
+            }
+            HandlerPoint::After => {
+                self.conditions.pop();
+            }
+        }
+        Ok(())
+    }
+
+    fn identifier_statement(&mut self, arg: &IdentifierStatement) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            if let IdentifierStatementGroup::Assignment(x) = arg.identifier_statement_group.as_ref()
+            {
+                let ident = arg.expression_identifier.as_ref();
+                let target = self.identifier_net(ident);
+                let token = ident.identifier().token;
+                let partial = !ident.expression_identifier_list.is_empty()
+                    || !ident.expression_identifier_list0.is_empty();
+                let selects: Vec<_> = ident
+                    .expression_identifier_list
+                    .iter()
+                    .map(|x| x.select.as_ref())
+                    .chain(ident.expression_identifier_list0.iter().flat_map(|x| {
+                        x.expression_identifier_list0_list
+                            .iter()
+                            .map(|x| x.select.as_ref())
+                    }))
+                    .collect();
+                let select = self.push_select(&selects);
+                self.push_driver(target, &x.assignment.expression, token, partial);
+                if select {
+                    self.conditions.pop();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn let_statement(&mut self, arg: &LetStatement) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            let target = self.identifier_net(arg.identifier.as_ref());
+            let token = arg.identifier.identifier_token.token;
+            let kind = self.kind.replace(DriverKind::Comb);
+            let conditions = std::mem::take(&mut self.conditions);
+            self.push_driver(target, &arg.expression, token, false);
+            self.conditions = conditions;
+            self.kind = kind;
+        }
+        Ok(())
+    }
+
+    fn let_declaration(&mut self, arg: &LetDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            let target = self.identifier_net(arg.identifier.as_ref());
+            let token = arg.identifier.identifier_token.token;
+            self.kind = Some(DriverKind::Comb);
+            self.push_driver(target, &arg.expression, token, false);
+            self.kind = None;
+        }
+        Ok(())
+    }
+
+    fn assign_declaration(&mut self, arg: &AssignDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            let idents: Vec<HierarchicalIdentifier> = arg.assign_destination.as_ref().into();
+            self.kind = Some(DriverKind::Comb);
+            for ident in idents {
+                let target = self.identifier_net(ident.identifier.as_ref());
+                let token = ident.identifier.identifier_token.token;
+                let partial = !ident.hierarchical_identifier_list.is_empty()
+                    || !ident.hierarchical_identifier_list0.is_empty();
+                let selects: Vec<_> = ident
+                    .hierarchical_identifier_list
+                    .iter()
+                    .map(|x| x.select.as_ref())
+                    .chain(ident.hierarchical_identifier_list0.iter().flat_map(|x| {
+                        x.hierarchical_identifier_list0_list
+                            .iter()
+                            .map(|x| x.select.as_ref())
+                    }))
+                    .collect();
+                let select = self.push_select(&selects);
+                self.push_driver(target, &arg.expression, token, partial);
+                if select {
+                    self.conditions.pop();
+                }
+            }
+            self.kind = None;
+        }
+        Ok(())
+    }
+
+    fn inst_declaration(&mut self, arg: &InstDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            let Ok(symbol) = symbol_table::resolve(arg.scoped_identifier.as_ref()) else {
+                return Ok(());
+            };
+            let Some(symbol) = inst_target(&symbol.found) else {
+                return Ok(());
+            };
+
+            let mut connections = Vec::new();
+            if let Some(x) = &arg.inst_declaration_opt2 {
+                if let Some(x) = &x.inst_declaration_opt3 {
+                    let items: Vec<InstPortItem> = x.inst_port_list.as_ref().into();
+                    for item in items {
+                        let token = item.identifier.identifier_token.token;
+                        let connection = if let Some(x) = &item.inst_port_item_opt {
+                            let sources = self.sources(&x.expression);
+                            let target = x
+                                .expression
+                                .unwrap_identifier()
+                                .and_then(|x| self.identifier_net(x));
+                            Connection {
+                                port: token.text,
+                                sources,
+                                targets: target.into_iter().collect(),
+                                direct: target.is_some(),
+                                token,
+                            }
+                        } else {
+                            let net = self.identifier_net(item.identifier.as_ref());
+                            Connection {
+                                port: token.text,
+                                sources: net.into_iter().collect(),
+                                targets: net.into_iter().collect(),
+                                direct: true,
+                                token,
+                            }
+                        };
+                        connections.push(connection);
+                    }
+                }
+            }
+
+            self.children.push(PendingInstance {
+                token: arg.identifier.identifier_token.token,
+                symbol,
+                overrides: inst_overrides(arg),
+                connections,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Module or proto module which is instantiated
+fn inst_target(symbol: &Symbol) -> Option<Symbol> {
+    match &symbol.kind {
+        SymbolKind::Module(_) | SymbolKind::ProtoModule(_) => Some(symbol.clone()),
+        SymbolKind::GenericInstance(x) => symbol_table::get(x.base),
+        SymbolKind::GenericParameter(x) => {
+            if let GenericBoundKind::Proto(proto) = &x.bound {
+                let proto = symbol_table::resolve((proto, &symbol.namespace)).ok()?;
+                inst_target(&proto.found)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Symbols referred in the expression
+#[derive(Default)]
+struct SourceCollector {
+    point: HandlerPoint,
+    symbols: Vec<Symbol>,
+}
+
+impl Handler for SourceCollector {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+impl VerylGrammarTrait for SourceCollector {
+    fn expression_identifier(&mut self, arg: &ExpressionIdentifier) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            if let Ok(x) = symbol_table::resolve(arg) {
+                if let Some(symbol) = x.full_path.first().and_then(|x| symbol_table::get(*x)) {
+                    self.symbols.push(symbol);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+struct DriverWalker<'a> {
+    handler: &'a mut dyn Handler,
+}
+
+impl<'a> DriverWalker<'a> {
+    fn new(handler: &'a mut dyn Handler) -> Self {
+        Self { handler }
+    }
+}
+
+impl VerylWalker for DriverWalker<'_> {
+    fn get_handlers(&mut self) -> Option<Vec<&mut dyn Handler>> {
+        Some(vec![&mut *self.handler])
+    }
+
+    /// Walk the enabled block only, or all blocks if the conditions can't be evaluated
+    fn generate_if_declaration(&mut self, arg: &GenerateIfDeclaration) {
+        walk_generate_if(self, arg);
+    }
+}
@@ -0,0 +1,313 @@
+use crate::HashMap;
+use crate::HashSet;
+use crate::analyzer_error::AnalyzerError;
+use crate::cdc::CdcChecker;
+use crate::evaluator::EvaluatedTypeResetKind;
+use crate::netlist::{BlackboxKind, DriverKind, NetId, Netlist};
+use crate::symbol::Direction;
+use crate::symbol_table;
+use veryl_metadata::ResetType;
+
+/// Diagnostic codes reported by reset domain crossing check
+pub const CODES: &[&str] = &[
+    "asynchronous_reset_deassertion",
+    "mixed_register_reset",
+    "unsynchronized_reset_domain_crossing",
+];
+
+/// Check reset domain crossings and reset consistency of registers in the elaborated design
+pub fn check(netlist: &Netlist, reset_type: &ResetType) -> Vec<AnalyzerError> {
+    let mut checker = RdcChecker {
+        netlist,
+        cdc: CdcChecker::new(netlist),
+        reset_type: *reset_type,
+        reset_roots: HashMap::default(),
+        synchronizer_stages: HashMap::default(),
+        errors: Vec::new(),
+    };
+    checker.check_crossing();
+    checker.check_deassertion();
+    checker.check_mixed_reset();
+    checker.errors
+}
+
+struct RdcChecker<'a> {
+    netlist: &'a Netlist,
+    cdc: CdcChecker<'a>,
+    reset_type: ResetType,
+    /// Nets which can assert each reset
+    reset_roots: HashMap<NetId, Vec<NetId>>,
+    /// Whether each register is a stage of reset synchronizer
+    synchronizer_stages: HashMap<NetId, bool>,
+    errors: Vec<AnalyzerError>,
+}
+
+impl RdcChecker<'_> {
+    /// Registers reset by an asynchronous reset should be captured by registers
+    /// which are reset whenever the source is reset
+    fn check_crossing(&mut self) {
+        let netlist = self.netlist;
+        let mut reported = HashSet::default();
+
+        for driver in &netlist.drivers {
+            let DriverKind::Register {
+                clock,
+                reset: Some(dst_reset),
+            } = driver.kind
+            else {
+                continue;
+            };
+            let domain = self.cdc.clock_domain(clock);
+            let dst_roots = self.reset_roots(dst_reset);
+
+            for x in &driver.sources {
+                for origin in self.cdc.origins(*x) {
+                    // Crossings between clock domains are checked by CDC check
+                    if origin.domain != domain {
+                        continue;
+                    }
+                    let Some(src_reset) = self.register_reset(&origin.net) else {
+                        continue;
+                    };
+                    if src_reset == dst_reset || !self.is_async(&src_reset) {
+                        continue;
+                    }
+                    if self
+                        .reset_roots(src_reset)
+                        .iter()
+                        .all(|x| dst_roots.contains(x))
+                    {
+                        continue;
+                    }
+                    if !reported.insert((driver.target, origin.net)) {
+                        continue;
+                    }
+                    let instances = [driver.target.instance, origin.net.instance];
+                    if self.netlist.checked_in_child(&instances) {
+                        continue;
+                    }
+
+                    let token = self.netlist.location(driver.target.instance, &driver.token);
+                    self.errors
+                        .push(AnalyzerError::unsynchronized_reset_domain_crossing(
+                            &self.netlist.name(&origin.net),
+                            &self.netlist.name(&src_reset),
+                            &self.netlist.name(&driver.target),
+                            &self.netlist.name(&dst_reset),
+                            &token,
+                        ));
+                }
+            }
+        }
+    }
+
+    /// Asynchronous resets should be deasserted synchronously to the clock of the register
+    fn check_deassertion(&mut self) {
+        let netlist = self.netlist;
+        let mut reported = HashSet::default();
+
+        for driver in &netlist.drivers {
+            let DriverKind::Register {
+                clock,
+                reset: Some(reset),
+            } = driver.kind
+            else {
+                continue;
+            };
+            if !self.is_async(&reset) || !reported.insert(driver.target) {
+                continue;
+            }
+            // Registers of reset synchronizer receive the asynchronous deassertion by design
+            if self.is_synchronizer_stage(&driver.target) {
+                continue;
+            }
+
+            let domain = self.cdc.clock_domain(clock);
+            let origin = self
+                .cdc
+                .origins(reset)
+                .into_iter()
+                .find(|x| x.domain != domain);
+            if let Some(origin) = origin {
+                let instances = [driver.target.instance, origin.net.instance];
+                if self.netlist.checked_in_child(&instances) {
+                    continue;
+                }
+
+                let token = self.netlist.location(driver.target.instance, &driver.token);
+                self.errors
+                    .push(AnalyzerError::asynchronous_reset_deassertion(
+                        &self.netlist.name(&origin.net),
+                        &self.cdc.domain_name(&origin.domain),
+                        &self.netlist.name(&driver.target),
+                        &self.cdc.domain_name(&domain),
+                        &token,
+                    ));
+            }
+        }
+    }
+
+    /// Parts of register should not be driven by both resettable and non-resettable `always_ff`
+    fn check_mixed_reset(&mut self) {
+        let netlist = self.netlist;
+        let mut reported = HashSet::default();
+
+        for driver in &netlist.drivers {
+            let DriverKind::Register { reset: None, .. } = driver.kind else {
+                continue;
+            };
+            if !driver.partial {
+                continue;
+            }
+            let reset = self
+                .netlist
+                .drivers_of(&driver.target)
+                .any(|x| matches!(x.kind, DriverKind::Register { reset: Some(_), .. }));
+            if !reset || !reported.insert(driver.target) {
+                continue;
+            }
+            if self.netlist.checked_in_child(&[driver.target.instance]) {
+                continue;
+            }
+
+            let token = self.netlist.location(driver.target.instance, &driver.token);
+            self.errors.push(AnalyzerError::mixed_register_reset(
+                &self.netlist.name(&driver.target),
+                &token,
+            ));
+        }
+    }
+
+    fn register_reset(&self, net: &NetId) -> Option<NetId> {
+        self.netlist.drivers_of(net).find_map(|x| match x.kind {
+            DriverKind::Register { reset, .. } => reset,
+            DriverKind::Comb => None,
+        })
+    }
+
+    fn is_async(&self, net: &NetId) -> bool {
+        let Some(symbol) = symbol_table::get(net.symbol) else {
+            return false;
+        };
+        match symbol.evaluate().get_reset_kind() {
+            Some(EvaluatedTypeResetKind::AsyncHigh | EvaluatedTypeResetKind::AsyncLow) => true,
+            Some(EvaluatedTypeResetKind::Implicit) => {
+                matches!(self.reset_type, ResetType::AsyncHigh | ResetType::AsyncLow)
+            }
+            _ => false,
+        }
+    }
+
+    /// Nets which can assert the reset
+    fn reset_roots(&mut self, net: NetId) -> Vec<NetId> {
+        if let Some(x) = self.reset_roots.get(&net) {
+            return x.clone();
+        }
+
+        let mut ret = Vec::new();
+        self.trace_reset(net, &mut HashSet::default(), &mut ret);
+        self.reset_roots.insert(net, ret.clone());
+        ret
+    }
+
+    fn trace_reset(&mut self, net: NetId, visited: &mut HashSet<NetId>, roots: &mut Vec<NetId>) {
+        if !visited.insert(net) {
+            return;
+        }
+
+        let netlist = self.netlist;
+        let mut driven = false;
+        for driver in netlist.drivers_of(&net) {
+            driven = true;
+            match driver.kind {
+                DriverKind::Comb => {
+                    for x in &driver.sources {
+                        self.trace_reset(*x, visited, roots);
+                    }
+                }
+                DriverKind::Register { reset, .. } => {
+                    if let Some(reset) = reset {
+                        self.trace_reset(reset, visited, roots);
+                    }
+                    // Register which changes by other signals asserts the reset by itself
+                    if !self.is_synchronizer_stage(&net) && !roots.contains(&net) {
+                        roots.push(net);
+                    }
+                }
+            }
+        }
+        if driven {
+            return;
+        }
+
+        // Output of reset synchronizer is asserted by its reset, and by its input if not constant
+        let instance = &netlist.instances[net.instance];
+        if instance.blackbox == Some(BlackboxKind::Synchronizer) {
+            let mut asserted_by_input = false;
+            for port in &instance.ports {
+                if port.property().direction != Direction::Input {
+                    continue;
+                }
+                let evaluated = port.symbol().evaluate();
+                let input = NetId {
+                    instance: net.instance,
+                    symbol: port.symbol,
+                };
+                if evaluated.is_reset() {
+                    self.trace_reset(input, visited, roots);
+                } else if !evaluated.is_clock() {
+                    asserted_by_input |= netlist.drivers_of(&input).any(|x| !x.sources.is_empty());
+                }
+            }
+            if !asserted_by_input {
+                return;
+            }
+        }
+
+        if !roots.contains(&net) {
+            roots.push(net);
+        }
+    }
+
+    /// Register which is driven by constants through registers with the same clock and reset only,
+    /// like stages of reset synchronizer
+    fn is_synchronizer_stage(&mut self, net: &NetId) -> bool {
+        if let Some(x) = self.synchronizer_stages.get(net) {
+            return *x;
+        }
+
+        let ret = self.trace_synchronizer_stage(net);
+        self.synchronizer_stages.insert(*net, ret);
+        ret
+    }
+
+    fn trace_synchronizer_stage(&self, net: &NetId) -> bool {
+        let mut register = None;
+        let mut visited = HashSet::default();
+        let mut nets = vec![*net];
+        while let Some(net) = nets.pop() {
+            if !visited.insert(net) {
+                continue;
+            }
+            let mut driven = false;
+            for driver in self.netlist.drivers_of(&net) {
+                let DriverKind::Register {
+                    clock,
+                    reset: Some(reset),
+                } = driver.kind
+                else {
+                    return false;
+                };
+                if *register.get_or_insert((clock, reset)) != (clock, reset) {
+                    return false;
+                }
+                nets.extend(driver.sources.iter().copied());
+                driven = true;
+            }
+            if !driven {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -8,8 +8,8 @@ use crate::symbol_table::{ResolveError, ResolveErrorCause};
 use std::cell::RefCell;
 use veryl_parser::token_range::TokenRange;
 use veryl_parser::veryl_grammar_trait::{
-    ExpressionIdentifier, HierarchicalIdentifier, Identifier, InstPortItem, ModportItem,
-    ScopedIdentifier, StructConstructorItem,
+    ExpressionIdentifier, HierarchicalIdentifier, Identifier, InstParameterItem, InstPortItem,
+    ModportItem, ScopedIdentifier, StructConstructorItem,
 };
 use veryl_parser::veryl_token::{Token, TokenSource, is_anonymous_text};
 
@@ -35,10 +35,19 @@ pub enum ReferenceCandidate {
         arg: ModportItem,
         namespace: Namespace,
     },
+    InstParameterItem {
+        arg: InstParameterItem,
+        namespace: Namespace,
+    },
     InstPortItem {
         arg: InstPortItem,
         namespace: Namespace,
     },
+    InstItemName {
+        arg: Identifier,
+        component: ScopedIdentifier,
+        namespace: Namespace,
+    },
     StructConstructorItem {
         arg: StructConstructorItem,
         r#type: ExpressionIdentifier,
@@ -94,6 +103,15 @@ impl From<&ModportItem> for ReferenceCandidate {
     }
 }
 
+impl From<&InstParameterItem> for ReferenceCandidate {
+    fn from(value: &InstParameterItem) -> Self {
+        Self::InstParameterItem {
+            arg: value.clone(),
+            namespace: namespace_table::get_default(),
+        }
+    }
+}
+
 impl From<&InstPortItem> for ReferenceCandidate {
     fn from(value: &InstPortItem) -> Self {
         Self::InstPortItem {
@@ -403,6 +421,31 @@ impl ReferenceTable {
                         }
                     }
                 }
+                ReferenceCandidate::InstParameterItem { arg, namespace } => {
+                    namespace_table::set_default(&namespace.paths);
+
+                    if arg.inst_parameter_item_opt.is_none() {
+                        // implicit parameter override by name
+                        match symbol_table::resolve(arg.identifier.as_ref()) {
+                            Ok(symbol) => {
+                                for id in symbol.full_path {
+                                    symbol_table::add_reference(
+                                        id,
+                                        &arg.identifier.identifier_token.token,
+                                    );
+                                }
+                            }
+                            Err(err) => {
+                                self.push_resolve_error(
+                                    err,
+                                    &arg.identifier.as_ref().into(),
+                                    None,
+                                    None,
+                                );
+                            }
+                        }
+                    }
+                }
                 ReferenceCandidate::InstPortItem { arg, namespace } => {
                     namespace_table::set_default(&namespace.paths);
 
@@ -428,6 +471,38 @@ impl ReferenceTable {
                         }
                     }
                 }
+                ReferenceCandidate::InstItemName {
+                    arg,
+                    component,
+                    namespace,
+                } => {
+                    namespace_table::set_default(&namespace.paths);
+
+                    // Unknown ports and parameters are reported by check_instance
+                    if let Ok(symbol) = symbol_table::resolve(component) {
+                        let component = match &symbol.found.kind {
+                            SymbolKind::GenericInstance(x) => symbol_table::get(x.base),
+                            _ => Some(symbol.found),
+                        };
+                        if let Some(component) = component {
+                            let namespace = component.inner_namespace();
+                            let symbol_path: SymbolPath = arg.into();
+
+                            if let Ok(symbol) = symbol_table::resolve((&symbol_path, &namespace))
+                                && symbol.found.namespace.matched(&namespace)
+                                && matches!(
+                                    symbol.found.kind,
+                                    SymbolKind::Port(_) | SymbolKind::Parameter(_)
+                                )
+                            {
+                                symbol_table::add_reference(
+                                    symbol.found.id,
+                                    &arg.identifier_token.token,
+                                );
+                            }
+                        }
+                    }
+                }
                 ReferenceCandidate::StructConstructorItem { arg, r#type } => {
                     if let Ok(symbol) = symbol_table::resolve(r#type) {
                         let namespace = symbol.found.inner_namespace();
@@ -0,0 +1,97 @@
+use veryl_parser::resource_table::PathId;
+use veryl_parser::veryl_token::{Token, TokenSource};
+
+/// Machine-applicable fix of an analyzer error
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion {
+    pub message: String,
+    pub fix: Fix,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fix {
+    /// Replace ranges of source text
+    Edit(Vec<Edit>),
+    /// Rename the identifier and all references to it
+    Rename { token: Token, name: String },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Edit {
+    pub path: PathId,
+    /// Byte offset of the beginning in the source text
+    pub beg: usize,
+    /// Byte offset of the end in the source text
+    pub end: usize,
+    pub text: String,
+}
+
+impl Edit {
+    /// Replace the token by the text
+    pub fn replace(token: &Token, text: &str) -> Option<Self> {
+        let beg = token.pos as usize;
+        let end = beg + token.length as usize;
+        Self::new(token, beg, end, text)
+    }
+
+    /// Insert the text at the byte offset in the source text of the token
+    pub fn insert(token: &Token, offset: usize, text: &str) -> Option<Self> {
+        Self::new(token, offset, offset, text)
+    }
+
+    fn new(token: &Token, beg: usize, end: usize, text: &str) -> Option<Self> {
+        if let TokenSource::File { path, .. } = token.source {
+            Some(Self {
+                path,
+                beg,
+                end,
+                text: text.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl Suggestion {
+    pub fn edit(message: &str, edits: Vec<Edit>) -> Self {
+        Self {
+            message: message.to_string(),
+            fix: Fix::Edit(edits),
+        }
+    }
+
+    pub fn rename(message: &str, token: &Token, name: &str) -> Self {
+        Self {
+            message: message.to_string(),
+            fix: Fix::Rename {
+                token: *token,
+                name: name.to_string(),
+            },
+        }
+    }
+
+    /// Apply the edits to the source text. Renaming is not supported.
+    pub fn apply(&self, text: &str) -> Option<String> {
+        let Fix::Edit(edits) = &self.fix else {
+            return None;
+        };
+
+        let mut edits: Vec<_> = edits.iter().collect();
+        edits.sort_by_key(|x| x.beg);
+
+        let mut ret = text.to_string();
+        for edit in edits.iter().rev() {
+            ret.replace_range(edit.beg..edit.end, &edit.text);
+        }
+        Some(ret)
+    }
+}
+
+/// Indent of the line including the byte offset
+pub fn indent_at(text: &str, offset: usize) -> &str {
+    let head = &text[..offset];
+    let line = &head[head.rfind('\n').map(|x| x + 1).unwrap_or(0)..];
+    let len = line.len() - line.trim_start().len();
+    &line[..len]
+}
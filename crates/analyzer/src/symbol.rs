@@ -1506,6 +1506,8 @@ pub struct FunctionProperty {
     pub generic_references: Vec<GenericSymbolPath>,
     pub ports: Vec<Port>,
     pub ret: Option<Type>,
+    /// Declaration of the function body, which doesn't exist for proto function
+    pub definition: Option<DefinitionId>,
 }
 
 #[derive(Debug, Clone)]
@@ -588,6 +588,33 @@ impl SymbolTable {
         }
     }
 
+    /// Files which refer symbols declared in the file directly or indirectly.
+    /// They lose their references when the file is dropped, so they should be analyzed again.
+    pub fn dependents(&self, file_path: PathId) -> Vec<PathId> {
+        let mut ret = Vec::new();
+        let mut targets = vec![file_path];
+
+        while let Some(target) = targets.pop() {
+            for symbol in self.symbol_table.values() {
+                if symbol.token.source != target {
+                    continue;
+                }
+                for reference in &symbol.references {
+                    if let TokenSource::File { path, .. } = reference.source
+                        && path != file_path
+                        && !ret.contains(&path)
+                    {
+                        ret.push(path);
+                        targets.push(path);
+                    }
+                }
+            }
+        }
+
+        ret.sort();
+        ret
+    }
+
     pub fn add_reference(&mut self, target: SymbolId, token: &Token) {
         for (_, symbol) in self.symbol_table.iter_mut() {
             if symbol.id == target {
@@ -1146,6 +1173,10 @@ pub fn drop(file_path: PathId) {
     SYMBOL_TABLE.with(|f| f.borrow_mut().drop(file_path))
 }
 
+pub fn dependents(file_path: PathId) -> Vec<PathId> {
+    SYMBOL_TABLE.with(|f| f.borrow().dependents(file_path))
+}
+
 pub fn add_reference(target: SymbolId, token: &Token) {
     SYMBOL_CACHE.with(|f| f.borrow_mut().clear());
     SYMBOL_TABLE.with(|f| f.borrow_mut().add_reference(target, token))
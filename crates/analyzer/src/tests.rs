@@ -1,17 +1,27 @@
+use crate::analyzer_error::{ANALYZER_DEFAULT_ALLOWED, ANALYZER_ERROR_CODES};
+use crate::lint;
 use crate::namespace::Namespace;
+use crate::netlist::{self, NetlistCache};
+use crate::suggestion::{Fix, Suggestion};
 use crate::symbol_path::SymbolPath;
-use crate::{Analyzer, AnalyzerError, symbol_table};
-use veryl_metadata::Metadata;
+use crate::{Analyzer, AnalyzerError, instance_tree, symbol_table};
+use miette::Severity;
+use veryl_metadata::{Lint, LintLevel, Metadata};
 use veryl_parser::Parser;
 
 #[track_caller]
 fn analyze(code: &str) -> Vec<AnalyzerError> {
-    symbol_table::clear();
-
     let metadata: Metadata =
         toml::from_str(&Metadata::create_default_toml("prj").unwrap()).unwrap();
+    analyze_with_metadata(code, &metadata)
+}
+
+#[track_caller]
+fn analyze_with_metadata(code: &str, metadata: &Metadata) -> Vec<AnalyzerError> {
+    symbol_table::clear();
+
     let parser = Parser::parse(&code, &"").unwrap();
-    let analyzer = Analyzer::new(&metadata);
+    let analyzer = Analyzer::new(metadata);
 
     let mut errors = vec![];
     errors.append(&mut analyzer.analyze_pass1(&"prj", &"", &parser.veryl));
@@ -1074,6 +1084,64 @@ fn mismatch_attribute_args() {
     ));
 }
 
+#[test]
+fn lint_rules() {
+    let code = r#"
+    module ModuleA {
+        #[allow(unassign_variable)]
+        var a: logic;
+        let b: logic = a;
+    }
+    "#;
+
+    let mut lint = Lint::default();
+    let errors = lint::apply(analyze(code), &lint);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0].error,
+        AnalyzerError::UnusedVariable { .. }
+    ));
+    assert_eq!(errors[0].severity, Severity::Warning);
+
+    lint.rules
+        .insert("unused_variable".to_string(), LintLevel::Deny);
+    let errors = lint::apply(analyze(code), &lint);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].severity, Severity::Error);
+
+    lint.rules
+        .insert("unused_variable".to_string(), LintLevel::Allow);
+    let errors = lint::apply(analyze(code), &lint);
+    assert!(errors.is_empty());
+
+    // width_extension is allowed by default
+    assert!(lint::enabled(&lint, "unknown_member"));
+    assert!(!lint::enabled(&lint, "width_extension"));
+    lint.rules
+        .insert("width_extension".to_string(), LintLevel::Warn);
+    assert!(lint::enabled(&lint, "width_extension"));
+}
+
+#[test]
+fn analyzer_error_codes() {
+    // Codes are collected from `#[diagnostic(code(...))]` of all variants
+    let source = include_str!("analyzer_error.rs");
+    let mut codes: Vec<_> = source
+        .split("code(")
+        .skip(1)
+        .filter_map(|x| x.split_once(')').map(|x| x.0))
+        .collect();
+    codes.sort();
+    codes.dedup();
+
+    assert_eq!(codes, ANALYZER_ERROR_CODES);
+    assert!(
+        ANALYZER_DEFAULT_ALLOWED
+            .iter()
+            .all(|x| ANALYZER_ERROR_CODES.contains(x))
+    );
+}
+
 #[test]
 fn incompat_proto() {
     let code = r#"
@@ -1889,6 +1957,110 @@ fn mismatch_assignment() {
     ));
 }
 
+#[test]
+fn width_mismatch() {
+    let mut metadata: Metadata =
+        toml::from_str(&Metadata::create_default_toml("prj").unwrap()).unwrap();
+    for code in ["width_truncation", "width_extension", "mixed_signedness"] {
+        metadata
+            .lint
+            .rules
+            .insert(code.to_string(), LintLevel::Warn);
+    }
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic<8>,
+        o_b: output logic<8>,
+        o_c: output logic<4>,
+    ) {
+        assign o_b = i_a + 1;
+        assign o_c = i_a[3:0];
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic<8>,
+        o_b: output logic<4>,
+    ) {
+        assign o_b = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(matches!(errors[0], AnalyzerError::WidthTruncation { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic<4>,
+        o_b: output logic<8>,
+    ) {
+        assign o_b = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(matches!(errors[0], AnalyzerError::WidthExtension { .. }));
+
+    let code = r#"
+    module ModuleA {
+        let _a: logic<2> = 3;
+        let _b: logic<2> = 4;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], AnalyzerError::WidthTruncation { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input logic<8>,
+    ) {
+        inst u: ModuleB (
+            i_a,
+        );
+    }
+    module ModuleB #(
+        param W: u32 = 4,
+    ) (
+        i_a: input logic<W>,
+    ) {}
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(matches!(errors[0], AnalyzerError::WidthTruncation { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  signed logic<8>,
+        i_b: input  logic<8>,
+        o_c: output logic<8>,
+    ) {
+        assign o_c = i_a + i_b;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(matches!(errors[0], AnalyzerError::MixedSignedness { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic<8>,
+        o_b: output logic<4>,
+    ) {
+        assign o_b = i_a;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+}
+
 #[test]
 fn missing_if_reset() {
     let code = r#"
@@ -2245,6 +2417,17 @@ fn too_large_number() {
 
     let errors = analyze(code);
     assert!(matches!(errors[0], AnalyzerError::TooLargeNumber { .. }));
+
+    let code = r#"
+    module ModuleA {
+        const a: bit<256> = 256'hffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff_ffffffff;
+        const b: bit<160> = 160'd1461501637330902918203684832716283019655932542975;
+        const c: bit<512> = 'h1_00000000_00000000_00000000_00000000;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
 }
 
 #[test]
@@ -3533,6 +3716,139 @@ fn uncovered_branch() {
     //assert!(matches!(errors[0], AnalyzerError::UncoveredBranch { .. }));
 }
 
+#[test]
+fn latch_inferred() {
+    let code = r#"
+    module ModuleB {
+        var a: logic;
+        let x: logic = 1;
+
+        always_comb {
+            if x {
+                a = 1;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        &errors[1],
+        AnalyzerError::LatchInferred { branch, .. } if branch == "implicit `else`"
+    ));
+
+    let code = r#"
+    module ModuleC (
+        i_sel: input  logic<2>,
+        i_a  : input  logic<4>,
+        o_b  : output logic<4>,
+        o_c  : output logic<4>,
+        o_d  : output logic<4>,
+    ) {
+        always_comb {
+            o_b = 0;
+            if i_sel == 0 {
+                o_b = i_a;
+            }
+        }
+
+        always_comb {
+            switch {
+                i_sel == 0: o_c = i_a;
+                default   : o_c = 0;
+            }
+        }
+
+        always_comb {
+            for i: u32 in 0..4 {
+                if i_sel == 0 {
+                    o_d[i] = i_a[i];
+                } else {
+                    o_d[i] = 0;
+                }
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleD (
+        i_sel: input  logic<2>,
+        i_a  : input  logic<4>,
+        o_b  : output logic<4>,
+    ) {
+        always_comb {
+            switch {
+                i_sel == 0: o_b = i_a;
+                i_sel == 1: o_b = 0;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        &errors[1],
+        AnalyzerError::LatchInferred { branch, .. } if branch == "implicit `default`"
+    ));
+
+    let code = r#"
+    module ModuleE (
+        i_sel: input  logic<2>,
+        i_a  : input  logic<4>,
+        o_b  : output logic<4>,
+    ) {
+        always_comb {
+            case i_sel {
+                0      : o_b = i_a;
+                1      : {}
+                default: o_b = 0;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        &errors[1],
+        AnalyzerError::LatchInferred { branch, .. } if branch == "`1`"
+    ));
+
+    let code = r#"
+    module ModuleF (
+        i_sel: input  logic<2>,
+        i_a  : input  logic<4>,
+        o_b  : output logic<4>,
+    ) {
+        always_comb {
+            if i_sel == 0 {
+                if i_sel == 1 {
+                    o_b = 0;
+                } else {
+                    o_b = 1;
+                }
+            } else if i_sel == 2 {
+                o_b = 2;
+            } else {
+                if i_sel == 3 {
+                    o_b = i_a;
+                }
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        &errors[1],
+        AnalyzerError::LatchInferred { branch, .. } if branch == "implicit `else`"
+    ));
+}
+
 #[test]
 fn anonymous_identifier() {
     let code = r#"
@@ -4394,41 +4710,717 @@ fn clock_domain() {
 }
 
 #[test]
-fn bit_width() {
-    let code = r#"
-    module t1 (
-    i: input logic<314>,
-    o: output logic,
-    ) {
-        assign o = 1;
-    }
-
-    module t2 (
-        i4: input logic<4>,
-        o4: output logic<-1>,
+fn clock_domain_crossing() {
+    let ports = r#"
+        i_clk_a: input  `a clock,
+        i_rst_a: input  `a reset,
+        i_clk_b: input  `b clock,
+        i_rst_b: input  `b reset,
+        i_dat  : input  `a logic,
+        i_en   : input  `a logic,
+        o_dat  : output `b logic,
+    "#;
+    let sync = r#"
+    proto module synchronizer (
+        i_clk: input  `d clock,
+        i_rst: input  `d reset,
+        i_d  : input  `s logic,
+        o_d  : output `d logic,
+    );
+    module SyncA for synchronizer (
+        i_clk: input  `d clock,
+        i_rst: input  `d reset,
+        i_d  : input  `s logic,
+        o_d  : output `d logic,
     ) {
-        //    inst my_t1: t1 (i: i4, o: o4[0]); // correct type o4[0] is 1 bit wide
-        inst my_t1: t1 (i: i4, o: o4); // incorrect type o4 is 4 bit wide (should be rejected)
+        var ff: `d logic;
+        unsafe (cdc) {
+            always_ff (i_clk, i_rst) {
+                if_reset {
+                    ff  = 0;
+                    o_d = 0;
+                } else {
+                    ff  = i_d;
+                    o_d = ff;
+                }
+            }
+        }
     }
     "#;
+    let cdc_errors = |code: &str| -> Vec<AnalyzerError> {
+        analyze(code)
+            .into_iter()
+            .filter(|x| {
+                matches!(
+                    x,
+                    AnalyzerError::UnsynchronizedClockDomainCrossing { .. }
+                        | AnalyzerError::MultiBitClockDomainCrossing { .. }
+                        | AnalyzerError::ReconvergentClockDomainCrossing { .. }
+                        | AnalyzerError::CombinationalLogicBeforeSynchronizer { .. }
+                )
+            })
+            .collect()
+    };
+
+    let code = format!(
+        r#"
+    module ModuleA ({ports}) {{
+        var r_a: `a logic;
+        var r_b: `b logic;
+        always_ff (i_clk_a, i_rst_a) {{
+            if_reset {{
+                r_a = 0;
+            }} else {{
+                r_a = i_dat;
+            }}
+        }}
+        unsafe (cdc) {{
+            always_ff (i_clk_b, i_rst_b) {{
+                if_reset {{
+                    r_b = 0;
+                }} else {{
+                    r_b = r_a;
+                }}
+            }}
+        }}
+        assign o_dat = r_b;
+    }}
+    "#
+    );
 
-    let errors = analyze(code);
-    println!("errors {:?}", errors);
-    // assert!(matches!(errors[0], AnalyzerError::UnknownUnsafe { .. }));
+    let errors = cdc_errors(&code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::UnsynchronizedClockDomainCrossing { .. }
+    ));
+
+    let code = format!(
+        r#"
+    module ModuleB ({ports}) {{
+        var r_a: `a logic<2>;
+        var r_b: `b logic<2>;
+        always_ff (i_clk_a, i_rst_a) {{
+            if_reset {{
+                r_a = 0;
+            }} else {{
+                r_a = {{i_dat, i_en}};
+            }}
+        }}
+        unsafe (cdc) {{
+            always_ff (i_clk_b, i_rst_b) {{
+                if_reset {{
+                    r_b = 0;
+                }} else {{
+                    r_b = r_a;
+                }}
+            }}
+        }}
+        assign o_dat = r_b[0];
+    }}
+    "#
+    );
+
+    let errors = cdc_errors(&code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::MultiBitClockDomainCrossing { .. }
+    ));
+
+    let code = format!(
+        r#"
+    {sync}
+    module ModuleC ({ports}) {{
+        var r_a: `a logic;
+        always_ff (i_clk_a, i_rst_a) {{
+            if_reset {{
+                r_a = 0;
+            }} else {{
+                r_a = i_dat;
+            }}
+        }}
+        inst u_sync: SyncA (
+            i_clk: i_clk_b,
+            i_rst: i_rst_b,
+            i_d  : r_a    ,
+            o_d  : o_dat  ,
+        );
+    }}
+    "#
+    );
+
+    let errors = cdc_errors(&code);
+    assert!(errors.is_empty());
+
+    let code = format!(
+        r#"
+    {sync}
+    module ModuleD ({ports}) {{
+        var r_a: `a logic;
+        always_ff (i_clk_a, i_rst_a) {{
+            if_reset {{
+                r_a = 0;
+            }} else {{
+                r_a = i_dat;
+            }}
+        }}
+        inst u_sync: SyncA (
+            i_clk: i_clk_b ,
+            i_rst: i_rst_b ,
+            i_d  : r_a & i_en,
+            o_d  : o_dat   ,
+        );
+    }}
+    "#
+    );
+
+    let errors = cdc_errors(&code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::CombinationalLogicBeforeSynchronizer { .. }
+    ));
+
+    let code = format!(
+        r#"
+    {sync}
+    module ModuleE ({ports}) {{
+        var r_a: `a logic;
+        var r_c: `a logic;
+        var s0 : `b logic;
+        var s1 : `b logic;
+        var r_b: `b logic;
+        always_ff (i_clk_a, i_rst_a) {{
+            if_reset {{
+                r_a = 0;
+                r_c = 0;
+            }} else {{
+                r_a = i_dat;
+                r_c = i_en;
+            }}
+        }}
+        inst u_sync0: SyncA (
+            i_clk: i_clk_b,
+            i_rst: i_rst_b,
+            i_d  : r_a    ,
+            o_d  : s0     ,
+        );
+        inst u_sync1: SyncA (
+            i_clk: i_clk_b,
+            i_rst: i_rst_b,
+            i_d  : r_c    ,
+            o_d  : s1     ,
+        );
+        always_ff (i_clk_b, i_rst_b) {{
+            if_reset {{
+                r_b = 0;
+            }} else {{
+                r_b = s0 & s1;
+            }}
+        }}
+        assign o_dat = r_b;
+    }}
+    "#
+    );
+
+    let errors = cdc_errors(&code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::ReconvergentClockDomainCrossing { .. }
+    ));
 }
 
 #[test]
-fn r#unsafe() {
+fn reset_domain_crossing() {
+    let rdc_errors = |code: &str| -> Vec<AnalyzerError> {
+        analyze(code)
+            .into_iter()
+            .filter(|x| {
+                matches!(
+                    x,
+                    AnalyzerError::UnsynchronizedResetDomainCrossing { .. }
+                        | AnalyzerError::AsynchronousResetDeassertion { .. }
+                        | AnalyzerError::MixedRegisterReset { .. }
+                )
+            })
+            .collect()
+    };
+
     let code = r#"
-    module ModuleA {
-        unsafe(x) {
+    module ModuleA (
+        i_clk  : input  clock,
+        i_rst_a: input  reset,
+        i_rst_b: input  reset,
+        i_d    : input  logic,
+        o_d    : output logic,
+    ) {
+        var r_a: logic;
+        always_ff (i_clk, i_rst_a) {
+            if_reset {
+                r_a = 0;
+            } else {
+                r_a = i_d;
+            }
+        }
+        always_ff (i_clk, i_rst_b) {
+            if_reset {
+                o_d = 0;
+            } else {
+                o_d = r_a;
+            }
         }
     }
     "#;
 
-    let errors = analyze(code);
-    assert!(matches!(errors[0], AnalyzerError::UnknownUnsafe { .. }));
-}
+    let errors = rdc_errors(code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::UnsynchronizedResetDomainCrossing { .. }
+    ));
+
+    let code = r#"
+    module ModuleB (
+        i_clk  : input  clock,
+        i_rst_a: input  reset,
+        i_rst_b: input  reset,
+        i_d    : input  logic,
+        o_d    : output logic,
+    ) {
+        var r_a   : logic;
+        var rst_ab: reset;
+        always_comb {
+            rst_ab = i_rst_a & i_rst_b;
+        }
+        always_ff (i_clk, i_rst_a) {
+            if_reset {
+                r_a = 0;
+            } else {
+                r_a = i_d;
+            }
+        }
+        always_ff (i_clk, rst_ab) {
+            if_reset {
+                o_d = 0;
+            } else {
+                o_d = r_a;
+            }
+        }
+    }
+    "#;
+
+    let errors = rdc_errors(code);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleC (
+        i_clk_a: input  `a clock,
+        i_rst_a: input  `a reset,
+        i_clk_b: input  `b clock,
+        i_d    : input  `b logic,
+        o_d    : output `b logic,
+    ) {
+        var rst_b: `b reset;
+        unsafe (cdc) {
+            always_comb {
+                rst_b = i_rst_a;
+            }
+        }
+        always_ff (i_clk_b, rst_b) {
+            if_reset {
+                o_d = 0;
+            } else {
+                o_d = i_d;
+            }
+        }
+    }
+    "#;
+
+    let errors = rdc_errors(code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::AsynchronousResetDeassertion { .. }
+    ));
+
+    let code = r#"
+    proto module synchronizer (
+        i_clk: input  `d clock,
+        i_rst: input  `d reset,
+        i_d  : input  `s logic,
+        o_d  : output `d logic,
+    );
+    module SyncA for synchronizer (
+        i_clk: input  `d clock,
+        i_rst: input  `d reset,
+        i_d  : input  `s logic,
+        o_d  : output `d logic,
+    ) {
+        var ff: `d logic;
+        unsafe (cdc) {
+            always_ff (i_clk, i_rst) {
+                if_reset {
+                    ff  = 0;
+                    o_d = 0;
+                } else {
+                    ff  = i_d;
+                    o_d = ff;
+                }
+            }
+        }
+    }
+    module ModuleD (
+        i_clk_a: input  `a clock,
+        i_rst_a: input  `a reset,
+        i_clk_b: input  `b clock,
+        i_d    : input  `b logic,
+        o_d    : output `b logic,
+    ) {
+        var rst_a: `b reset;
+        var rst_b: `b reset;
+        unsafe (cdc) {
+            always_comb {
+                rst_a = i_rst_a;
+            }
+        }
+        inst u_sync: SyncA (
+            i_clk: i_clk_b,
+            i_rst: rst_a  ,
+            i_d  : '1     ,
+            o_d  : rst_b  ,
+        );
+        always_ff (i_clk_b, rst_b) {
+            if_reset {
+                o_d = 0;
+            } else {
+                o_d = i_d;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleE (
+        i_clk: input  clock   ,
+        i_rst: input  reset   ,
+        i_d  : input  logic<2>,
+        o_d  : output logic<2>,
+    ) {
+        always_ff (i_clk, i_rst) {
+            if_reset {
+                o_d[0] = 0;
+            } else {
+                o_d[0] = i_d[0];
+            }
+        }
+        always_ff (i_clk) {
+            o_d[1] = i_d[1];
+        }
+    }
+    "#;
+
+    let errors = rdc_errors(code);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        errors[0],
+        AnalyzerError::MixedRegisterReset { .. }
+    ));
+}
+
+#[test]
+fn unused_logic() {
+    let mut metadata: Metadata =
+        toml::from_str(&Metadata::create_default_toml("prj").unwrap()).unwrap();
+    for code in [
+        "unused_input_port",
+        "unused_output_port",
+        "dead_logic",
+        "unread_register",
+        "unused_parameter",
+    ] {
+        metadata
+            .lint
+            .rules
+            .insert(code.to_string(), LintLevel::Warn);
+    }
+
+    let code = r#"
+    module ModuleA #(
+        param W: u32 = 8,
+    ) (
+        i_clk: input  clock     ,
+        i_rst: input  reset     ,
+        i_a  : input  logic<W>,
+        o_a  : output logic<W>,
+    ) {
+        var r: logic<W>;
+        always_ff {
+            if_reset {
+                r = 0;
+            } else {
+                r = i_a;
+            }
+        }
+        assign o_a = r;
+    }
+    module ModuleB (
+        i_clk: input  clock   ,
+        i_rst: input  reset   ,
+        i_a  : input  logic<4>,
+        o_a  : output logic<4>,
+    ) {
+        inst u_a: ModuleA #(W: 4) (
+            i_clk   ,
+            i_rst   ,
+            i_a     ,
+            o_a     ,
+        );
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic,
+        i_b: input  logic,
+        o_a: output logic,
+    ) {
+        assign o_a = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], AnalyzerError::UnusedInputPort { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic,
+        o_a: output logic,
+        o_b: output logic,
+    ) {
+        assign o_a = i_a;
+        assign o_b = ~i_a;
+    }
+    module ModuleB (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        inst u_a: ModuleA (
+            i_a     ,
+            o_a     ,
+            o_b: _  ,
+        );
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], AnalyzerError::UnusedOutputPort { .. }));
+
+    let code = r#"
+    module ModuleA #(
+        param W: u32 = 8,
+    ) (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        assign o_a = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], AnalyzerError::UnusedParameter { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_clk: input  clock,
+        i_rst: input  reset,
+        i_a  : input  logic,
+        o_a  : output logic,
+    ) {
+        var r: logic;
+        always_ff {
+            if_reset {
+                r = 0;
+            } else {
+                r = i_a;
+            }
+        }
+        assign o_a = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(errors[0], AnalyzerError::UnreadRegister { .. }));
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        var a: logic;
+        assign a   = ~i_a;
+        assign o_a = a;
+    }
+    module ModuleB (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        var b: logic;
+        inst u_a: ModuleA (
+            i_a     ,
+            o_a: b  ,
+        );
+        assign o_a = i_a;
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert_eq!(errors.len(), 3);
+    assert_eq!(
+        errors
+            .iter()
+            .filter(|x| matches!(x, AnalyzerError::DeadLogic { .. }))
+            .count(),
+        2
+    );
+    // b is connected, but is never read
+    assert!(
+        errors
+            .iter()
+            .any(|x| matches!(x, AnalyzerError::UnusedOutputPort { .. }))
+    );
+
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        assign o_a = ~i_a;
+    }
+    module ModuleB (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        var b: logic;
+        inst u_a: ModuleA (
+            i_a     ,
+            o_a: b  ,
+        );
+        inst u_b: ModuleA (
+            i_a: b  ,
+            o_a     ,
+        );
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    proto module ProtoA (
+        i_a: input  logic,
+        o_a: output logic,
+    );
+    module ModuleA for ProtoA (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        assign o_a = ~i_a;
+    }
+    module ModuleB::<T: ProtoA> (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        inst u_a: T (
+            i_a     ,
+            o_a     ,
+        );
+    }
+    module ModuleC (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        inst u_a: ModuleA (
+            i_a     ,
+            o_a: _  ,
+        );
+        inst u_b: ModuleB::<ModuleA> (
+            i_a     ,
+            o_a     ,
+        );
+    }
+    "#;
+
+    // ModuleA may be instantiated through the generic parameter of ModuleB
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(errors.is_empty());
+
+    let code = r#"
+    module ModuleA (
+        i_clk: input  clock   ,
+        i_a  : input  logic<2>,
+        i_d  : input  logic   ,
+        o_d  : output logic<4>,
+    ) {
+        var a: logic<2>;
+        assign a = i_a;
+        always_ff {
+            o_d[a] = i_d;
+        }
+    }
+    "#;
+
+    let errors = analyze_with_metadata(code, &metadata);
+    assert!(errors.is_empty());
+}
+
+#[test]
+fn bit_width() {
+    let code = r#"
+    module t1 (
+    i: input logic<314>,
+    o: output logic,
+    ) {
+        assign o = 1;
+    }
+
+    module t2 (
+        i4: input logic<4>,
+        o4: output logic<-1>,
+    ) {
+        //    inst my_t1: t1 (i: i4, o: o4[0]); // correct type o4[0] is 1 bit wide
+        inst my_t1: t1 (i: i4, o: o4); // incorrect type o4 is 4 bit wide (should be rejected)
+    }
+    "#;
+
+    let errors = analyze(code);
+    println!("errors {:?}", errors);
+    // assert!(matches!(errors[0], AnalyzerError::UnknownUnsafe { .. }));
+}
+
+#[test]
+fn r#unsafe() {
+    let code = r#"
+    module ModuleA {
+        unsafe(x) {
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(errors[0], AnalyzerError::UnknownUnsafe { .. }));
+}
 
 #[test]
 fn detect_recursive() {
@@ -4656,6 +5648,10 @@ fn evaluator() {
         const I: u32 = if B == 6 ? 10 : 20;
         const J: u32 = $clog2(12);
         const K: u32 = B[2:1];
+        const L: bit<128> = (128'h1 << 100) + 128'hffff_ffff_ffff_ffff;
+        const M: logic<8> = 8'hx0 | 8'h0f;
+        const N: u32 = $clog2(8);
+        const O: u32 = $clog2(1);
     }
     "#;
 
@@ -4674,6 +5670,10 @@ fn evaluator() {
     let i = symbol_table::resolve((&Into::<SymbolPath>::into("I"), &namespace)).unwrap();
     let j = symbol_table::resolve((&Into::<SymbolPath>::into("J"), &namespace)).unwrap();
     let k = symbol_table::resolve((&Into::<SymbolPath>::into("K"), &namespace)).unwrap();
+    let l = symbol_table::resolve((&Into::<SymbolPath>::into("L"), &namespace)).unwrap();
+    let m = symbol_table::resolve((&Into::<SymbolPath>::into("M"), &namespace)).unwrap();
+    let n = symbol_table::resolve((&Into::<SymbolPath>::into("N"), &namespace)).unwrap();
+    let o = symbol_table::resolve((&Into::<SymbolPath>::into("O"), &namespace)).unwrap();
 
     let a = a.found.evaluate();
     let b = b.found.evaluate();
@@ -4686,6 +5686,10 @@ fn evaluator() {
     let i = i.found.evaluate();
     let j = j.found.evaluate();
     let k = k.found.evaluate();
+    let l = l.found.evaluate();
+    let m = m.found.evaluate();
+    let n = n.found.evaluate();
+    let o = o.found.evaluate();
 
     assert_eq!((a.get_value(), a.get_total_width()), (Some(0), Some(32)));
     assert_eq!((b.get_value(), b.get_total_width()), (Some(6), Some(32)));
@@ -4698,6 +5702,16 @@ fn evaluator() {
     assert_eq!((i.get_value(), i.get_total_width()), (Some(10), Some(32)));
     assert_eq!((j.get_value(), j.get_total_width()), (Some(4), Some(32)));
     assert_eq!((k.get_value(), k.get_total_width()), (Some(3), Some(2)));
+    assert_eq!(
+        l.get_fixed().map(|x| x.to_string()),
+        Some("128'h1000000000ffffffffffffffff".to_string())
+    );
+    assert_eq!(
+        m.get_fixed().map(|x| x.to_string()),
+        Some("8'bxxxx1111".to_string())
+    );
+    assert_eq!((n.get_value(), n.get_total_width()), (Some(3), Some(32)));
+    assert_eq!((o.get_value(), o.get_total_width()), (Some(0), Some(32)));
 }
 
 #[test]
@@ -4720,6 +5734,124 @@ fn skip_disabled_generate_block() {
     assert!(errors.is_empty());
 }
 
+#[test]
+fn instance_tree_generate_block() {
+    let code = r#"
+    module ModuleA {
+        const X: u32 = 0;
+
+        inst u0: ModuleB;
+
+        if X == 1 :g_if {
+            inst u1: ModuleB;
+        } else {
+            inst u2: ModuleB #(W: 2);
+        }
+
+        for i in 0..3 :g_for {
+            inst u3: ModuleB #(W: i + 4);
+        }
+    }
+
+    module ModuleB #(
+        param W: u32 = 1,
+    ) {}
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let namespace: Namespace = "prj".into();
+    let top = symbol_table::resolve((&Into::<SymbolPath>::into("ModuleA"), &namespace)).unwrap();
+    let tree = instance_tree::elaborate(&top.found, 8).unwrap();
+
+    let children: Vec<_> = tree
+        .children
+        .iter()
+        .map(|x| (x.token.to_string(), x.params[0].1.get_value()))
+        .collect();
+    assert_eq!(
+        children,
+        vec![
+            ("u0".to_string(), Some(1)),
+            ("u2".to_string(), Some(2)),
+            ("u3".to_string(), Some(4)),
+            ("u3".to_string(), Some(5)),
+            ("u3".to_string(), Some(6)),
+        ]
+    );
+}
+
+#[test]
+fn netlist_cache() {
+    let code = r#"
+    module ModuleA (
+        i_a: input  logic,
+        o_a: output logic,
+        o_b: output logic,
+    ) {
+        inst u0: ModuleB (i_a, o_a);
+        inst u1: ModuleB (i_a, o_a: o_b);
+    }
+
+    module ModuleB (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        inst u: ModuleC (i_a, o_a);
+    }
+
+    module ModuleC (
+        i_a: input  logic,
+        o_a: output logic,
+    ) {
+        assign o_a = ~i_a;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let namespace: Namespace = "prj".into();
+    let resolve = |x: &str| {
+        symbol_table::resolve((&Into::<SymbolPath>::into(x), &namespace))
+            .unwrap()
+            .found
+    };
+
+    // ModuleB and ModuleC are shared through the cache
+    let mut cache = NetlistCache::default();
+    let netlist = netlist::elaborate(&resolve("ModuleB"), 8, &mut cache).unwrap();
+    assert_eq!(netlist.instances.len(), 2);
+    let netlist = netlist::elaborate(&resolve("ModuleA"), 8, &mut cache).unwrap();
+
+    let mut drivers: Vec<_> = netlist
+        .drivers
+        .iter()
+        .map(|x| {
+            let mut sources: Vec<_> = x.sources.iter().map(|x| netlist.name(x)).collect();
+            sources.sort();
+            (netlist.name(&x.target), sources)
+        })
+        .collect();
+    drivers.sort();
+    assert_eq!(
+        drivers,
+        vec![
+            ("o_a".to_string(), vec!["u0.o_a".to_string()]),
+            ("o_b".to_string(), vec!["u1.o_a".to_string()]),
+            ("u0.i_a".to_string(), vec!["i_a".to_string()]),
+            ("u0.o_a".to_string(), vec!["u0.u.o_a".to_string()]),
+            ("u0.u.i_a".to_string(), vec!["u0.i_a".to_string()]),
+            ("u0.u.o_a".to_string(), vec!["u0.u.i_a".to_string()]),
+            ("u1.i_a".to_string(), vec!["i_a".to_string()]),
+            ("u1.o_a".to_string(), vec!["u1.u.o_a".to_string()]),
+            ("u1.u.i_a".to_string(), vec!["u1.i_a".to_string()]),
+            ("u1.u.o_a".to_string(), vec!["u1.u.i_a".to_string()]),
+        ]
+    );
+}
+
 #[test]
 fn parameter_override() {
     let code = r#"
@@ -5055,3 +6187,124 @@ fn mixed_function_argument() {
         AnalyzerError::MixedFunctionArgument { .. }
     ));
 }
+
+#[test]
+fn suggestions() {
+    // apply the first suggestion of each error
+    let fix = |code: &str, errors: &[AnalyzerError]| {
+        let edits: Vec<_> = errors
+            .iter()
+            .filter_map(|x| x.suggestions().first())
+            .flat_map(|x| match &x.fix {
+                Fix::Edit(x) => x.clone(),
+                Fix::Rename { .. } => vec![],
+            })
+            .collect();
+        Suggestion::edit("", edits).apply(code).unwrap()
+    };
+
+    let code = r#"
+    module ModuleA {
+        let a: logic = 1;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert_eq!(errors[0].suggestions().len(), 2);
+    let fixed = fix(code, &errors);
+    assert!(fixed.contains("let _a: logic = 1;"));
+    assert!(analyze(&fixed).is_empty());
+
+    let fixed = errors[0].suggestions()[1].apply(code).unwrap();
+    assert!(fixed.contains("#[allow(unused_variable)]\n        let a: logic = 1;"));
+    assert!(analyze(&fixed).is_empty());
+
+    let code = r#"
+    module ModuleA (
+        i_a: input logic,
+        i_b: input logic,
+    ) {}
+    module ModuleB {
+        let i_a: logic = 1;
+        let i_b: logic = 1;
+        inst u0: ModuleA;
+        inst u1: ModuleA ();
+        inst u2: ModuleA (i_a);
+        inst u3: ModuleA (
+            i_a: 0
+        );
+    }
+    "#;
+
+    let errors = analyze(code);
+    let errors: Vec<_> = errors
+        .into_iter()
+        .filter(|x| matches!(x, AnalyzerError::MissingPort { port, .. } if port == "i_b"))
+        .collect();
+    assert_eq!(errors.len(), 4);
+    let fixed = fix(code, &errors);
+    assert!(fixed.contains("inst u0: ModuleA (i_b);"));
+    assert!(fixed.contains("inst u1: ModuleA (i_b);"));
+    assert!(fixed.contains("inst u2: ModuleA (i_a, i_b);"));
+    assert!(fixed.contains("            i_a: 0,\n            i_b,\n        );"));
+    let errors = analyze(&fixed);
+    assert!(
+        errors
+            .iter()
+            .all(|x| matches!(x, AnalyzerError::MissingPort { port, .. } if port == "i_a"))
+    );
+
+    let code = r#"
+    module ModuleA (
+        clk: input clock,
+        rst: input reset,
+    ) {
+        var a: logic;
+
+        always_ff(clk, rst) {
+            if_reset {
+            } else {
+                a = 1;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    let fixed = fix(code, &errors);
+    assert!(fixed.contains("if_reset {\n                a = '0;\n            }"));
+    assert!(analyze(&fixed).is_empty());
+
+    let code = r#"
+    package A {
+        enum B {
+            C,
+        }
+    }
+    module Module {
+        var _a: A::B;
+
+        always_comb {
+            _a = A.B.C;
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    let fixed = fix(code, &errors);
+    assert!(fixed.contains("_a = A::B::C;"));
+    assert!(analyze(&fixed).is_empty());
+
+    let code = r#"
+    module ModuleA {
+        var always: logic;
+        assign always = 1;
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(matches!(
+        &errors[0].suggestions()[0].fix,
+        Fix::Rename { name, .. } if name == "always_"
+    ));
+}
@@ -0,0 +1,423 @@
+use crate::HashMap;
+use crate::HashSet;
+use crate::analyzer_error::AnalyzerError;
+use crate::lint;
+use crate::netlist::{DriverKind, NetId, Netlist};
+use crate::symbol::{
+    ConnectTarget, Direction, GenericBoundKind, InstanceProperty, ModuleProperty, ParameterKind,
+    Symbol, SymbolId, SymbolKind,
+};
+use crate::symbol_table;
+use crate::var_ref::{VarRef, VarRefAffiliation, VarRefType};
+use veryl_metadata::Lint;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::Token;
+
+/// Usage of symbols collected once per file, or `None` if all unused checks are disabled
+pub struct Usage {
+    input_port: bool,
+    output_port: bool,
+    logic: bool,
+    register: bool,
+    parameter: bool,
+    /// Symbols which are read by any expression
+    reads: HashSet<SymbolId>,
+    /// Symbols which are read in functions, and are not traced by netlist
+    function_reads: HashSet<SymbolId>,
+    /// Instantiations of each module, or proto module for instantiations through generic parameters
+    instances: HashMap<SymbolId, Vec<InstanceProperty>>,
+}
+
+impl Usage {
+    pub fn new(
+        symbols: &[Symbol],
+        var_refs: &HashMap<VarRefAffiliation, Vec<VarRef>>,
+        lint_opt: &Lint,
+    ) -> Option<Self> {
+        let input_port = lint::enabled(lint_opt, "unused_input_port");
+        let output_port = lint::enabled(lint_opt, "unused_output_port");
+        let logic = lint::enabled(lint_opt, "dead_logic");
+        let register = lint::enabled(lint_opt, "unread_register");
+        let parameter = lint::enabled(lint_opt, "unused_parameter");
+        if !(input_port || output_port || logic || register || parameter) {
+            return None;
+        }
+
+        let mut reads = HashSet::default();
+        let mut function_reads = HashSet::default();
+        for (affiliation, list) in var_refs {
+            for x in list {
+                if let VarRefType::ExpressionTarget { .. } = x.r#type {
+                    if let Some(symbol) = x.path.full_path().first() {
+                        reads.insert(*symbol);
+                        if let VarRefAffiliation::Function { .. } = affiliation {
+                            function_reads.insert(*symbol);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut instances: HashMap<_, Vec<_>> = HashMap::default();
+        for symbol in symbols {
+            let SymbolKind::Instance(x) = &symbol.kind else {
+                continue;
+            };
+            let Ok(found) = symbol_table::resolve((&x.type_name.mangled_path(), &symbol.namespace))
+            else {
+                continue;
+            };
+            let id = match &found.found.kind {
+                SymbolKind::Module(_) => found.found.id,
+                SymbolKind::GenericInstance(x) => x.base,
+                SymbolKind::GenericParameter(x) => {
+                    let GenericBoundKind::Proto(proto) = &x.bound else {
+                        continue;
+                    };
+                    let Ok(proto) = symbol_table::resolve((proto, &found.found.namespace)) else {
+                        continue;
+                    };
+                    proto.found.id
+                }
+                _ => continue,
+            };
+            instances.entry(id).or_default().push(x.clone());
+        }
+
+        Some(Self {
+            input_port,
+            output_port,
+            logic,
+            register,
+            parameter,
+            reads,
+            function_reads,
+            instances,
+        })
+    }
+
+    /// Instantiations of the module, or `None` if the module may be instantiated through generic parameters
+    fn instances_of(&self, module: &Symbol) -> Option<&[InstanceProperty]> {
+        if let SymbolKind::Module(x) = &module.kind {
+            if let Some(proto) = &x.proto {
+                let proto = symbol_table::resolve((proto, &module.namespace)).ok()?;
+                if self.instances.contains_key(&proto.found.id) {
+                    return None;
+                }
+            }
+        }
+        self.instances.get(&module.id).map(|x| x.as_slice())
+    }
+}
+
+/// Check unused ports, parameters and logic of the module across the hierarchy
+pub fn check(module: &Symbol, netlist: Option<&Netlist>, usage: &Usage) -> Vec<AnalyzerError> {
+    let SymbolKind::Module(property) = &module.kind else {
+        return Vec::new();
+    };
+
+    let mut checker = UnusedChecker {
+        module,
+        property,
+        usage,
+        errors: Vec::new(),
+    };
+
+    // Ports and parameters of module which implements proto module are decided by the proto
+    if property.proto.is_none() {
+        checker.check_input_port();
+        checker.check_parameter();
+    }
+    if let Some(instances) = usage.instances_of(module) {
+        checker.check_output_port(instances);
+    }
+
+    // Generic and blackbox modules have no netlist, and are checked by their ports only
+    if let Some(netlist) = netlist {
+        checker.check_register(netlist);
+        // Logic is checked from the top module only
+        if module.references.is_empty() {
+            checker.check_logic(netlist);
+        }
+    }
+
+    checker.errors
+}
+
+fn is_ignored(symbol: &Symbol) -> bool {
+    symbol.allow_unused || symbol.token.to_string().starts_with('_')
+}
+
+/// Whether the variable connected with an output port is referenced except the connection
+fn is_read(target: &ConnectTarget) -> bool {
+    if target.expression.is_anonymous_expression() {
+        return false;
+    }
+    let Some(identifier) = target.expression.unwrap_identifier() else {
+        // Concatenation and so on are regarded as read if they include any variable
+        return target.identifiers.iter().any(|x| !x.is_empty());
+    };
+    let Ok(symbol) = symbol_table::resolve(identifier) else {
+        return false;
+    };
+
+    match &symbol.found.kind {
+        // Output port of the parent module is read by the outside
+        SymbolKind::Port(x) => x.direction != Direction::Input,
+        SymbolKind::Variable(_) => {
+            let range: TokenRange = identifier.into();
+            symbol.found.references.iter().any(|x| {
+                x.source
+                    .get_path()
+                    .is_none_or(|path| !range.include(path, x.line, x.column))
+            })
+        }
+        _ => true,
+    }
+}
+
+struct UnusedChecker<'a> {
+    module: &'a Symbol,
+    property: &'a ModuleProperty,
+    usage: &'a Usage,
+    errors: Vec<AnalyzerError>,
+}
+
+impl UnusedChecker<'_> {
+    fn in_module(&self, token: &Token) -> bool {
+        token
+            .source
+            .get_path()
+            .is_some_and(|path| self.property.range.include(path, token.line, token.column))
+    }
+
+    /// Input ports should be referred in the module except port connections of instantiations
+    fn check_input_port(&mut self) {
+        if !self.usage.input_port {
+            return;
+        }
+
+        for port in &self.property.ports {
+            if port.property().direction != Direction::Input {
+                continue;
+            }
+            let symbol = port.symbol();
+            if is_ignored(&symbol) || symbol.references.iter().any(|x| self.in_module(x)) {
+                continue;
+            }
+
+            self.errors.push(AnalyzerError::unused_input_port(
+                &symbol.token.to_string(),
+                &self.module.token.to_string(),
+                &symbol.token.into(),
+            ));
+        }
+    }
+
+    /// Output ports should be connected by any instantiation
+    fn check_output_port(&mut self, instances: &[InstanceProperty]) {
+        if !self.usage.output_port {
+            return;
+        }
+
+        for port in &self.property.ports {
+            if port.property().direction != Direction::Output {
+                continue;
+            }
+            let symbol = port.symbol();
+            if is_ignored(&symbol) {
+                continue;
+            }
+            let read = instances.iter().any(|x| {
+                x.connects
+                    .iter()
+                    .any(|(token, target)| token.text == port.name() && is_read(target))
+            });
+            if read {
+                continue;
+            }
+
+            self.errors.push(AnalyzerError::unused_output_port(
+                &symbol.token.to_string(),
+                &self.module.token.to_string(),
+                &symbol.token.into(),
+            ));
+        }
+    }
+
+    /// Parameters should be overridden by any instantiation or used in the module
+    fn check_parameter(&mut self) {
+        if !self.usage.parameter {
+            return;
+        }
+
+        for x in &self.property.parameters {
+            let Some(symbol) = symbol_table::get(x.symbol) else {
+                continue;
+            };
+            let SymbolKind::Parameter(property) = &symbol.kind else {
+                continue;
+            };
+            // Overriding by instantiation is also counted as reference
+            if !matches!(property.kind, ParameterKind::Param)
+                || is_ignored(&symbol)
+                || !symbol.references.is_empty()
+            {
+                continue;
+            }
+
+            self.errors.push(AnalyzerError::unused_parameter(
+                &symbol.token.to_string(),
+                &symbol.token.into(),
+            ));
+        }
+    }
+
+    /// Symbols which are read by drivers or expressions
+    fn read_symbols(&self, netlist: &Netlist) -> HashSet<SymbolId> {
+        let mut ret = self.usage.reads.clone();
+        for driver in &netlist.drivers {
+            ret.extend(driver.sources.iter().map(|x| x.symbol));
+            if let DriverKind::Register { clock, reset } = driver.kind {
+                ret.insert(clock.symbol);
+                ret.extend(reset.map(|x| x.symbol));
+            }
+        }
+        ret
+    }
+
+    fn unread_registers(&self, netlist: &Netlist) -> Vec<NetId> {
+        let reads = self.read_symbols(netlist);
+        let mut ret: Vec<NetId> = Vec::new();
+        for driver in &netlist.drivers {
+            if !matches!(driver.kind, DriverKind::Register { .. })
+                || reads.contains(&driver.target.symbol)
+                || ret.iter().any(|x| x.symbol == driver.target.symbol)
+            {
+                continue;
+            }
+            let Some(symbol) = symbol_table::get(driver.target.symbol) else {
+                continue;
+            };
+            if matches!(symbol.kind, SymbolKind::Variable(_)) && !is_ignored(&symbol) {
+                ret.push(driver.target);
+            }
+        }
+        ret
+    }
+
+    /// Registers of the module should be read by any logic
+    fn check_register(&mut self, netlist: &Netlist) {
+        if !self.usage.register {
+            return;
+        }
+
+        for net in self.unread_registers(netlist) {
+            if net.instance != 0 {
+                continue;
+            }
+            let Some(symbol) = symbol_table::get(net.symbol) else {
+                continue;
+            };
+
+            self.errors.push(AnalyzerError::unread_register(
+                &symbol.token.to_string(),
+                &symbol.token.into(),
+            ));
+        }
+    }
+
+    /// Variables in the hierarchy should reach any output of the top module
+    fn check_logic(&mut self, netlist: &Netlist) {
+        if !self.usage.logic {
+            return;
+        }
+
+        let top = &netlist.instances[0];
+        let mut nets: Vec<_> = top
+            .ports
+            .iter()
+            .filter(|x| x.property().direction != Direction::Input)
+            .map(|x| NetId {
+                instance: 0,
+                symbol: x.symbol,
+            })
+            .collect();
+        // Top module without output like testbench has no logic to be checked
+        if nets.is_empty() {
+            return;
+        }
+        for driver in &netlist.drivers {
+            if self.usage.function_reads.contains(&driver.target.symbol) {
+                nets.push(driver.target);
+            }
+        }
+
+        let mut live = HashSet::default();
+        while let Some(net) = nets.pop() {
+            if !live.insert(net) {
+                continue;
+            }
+
+            let mut driven = false;
+            for driver in netlist.drivers_of(&net) {
+                driven = true;
+                nets.extend(driver.sources.iter().copied());
+                if let DriverKind::Register { clock, reset } = driver.kind {
+                    nets.push(clock);
+                    nets.extend(reset);
+                }
+            }
+
+            // Outputs of blackbox or truncated instance depend on all its inputs
+            if !driven && net.instance != 0 {
+                let instance = &netlist.instances[net.instance];
+                let output = instance
+                    .ports
+                    .iter()
+                    .any(|x| x.symbol == net.symbol && x.property().direction == Direction::Output);
+                if output {
+                    for port in &instance.ports {
+                        if port.property().direction != Direction::Output {
+                            nets.push(NetId {
+                                instance: net.instance,
+                                symbol: port.symbol,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        // Unread registers are reported by register check
+        let unread = if self.usage.register {
+            self.unread_registers(netlist)
+        } else {
+            Vec::new()
+        };
+        let live: HashSet<_> = live.into_iter().map(|x| x.symbol).collect();
+        let mut reported = HashSet::default();
+        for driver in &netlist.drivers {
+            let net = driver.target;
+            if live.contains(&net.symbol)
+                || unread.iter().any(|x| x.symbol == net.symbol)
+                || !reported.insert(net.symbol)
+            {
+                continue;
+            }
+            let Some(symbol) = symbol_table::get(net.symbol) else {
+                continue;
+            };
+            if !matches!(symbol.kind, SymbolKind::Variable(_)) || is_ignored(&symbol) {
+                continue;
+            }
+
+            let token = netlist.location(net.instance, &symbol.token);
+            self.errors.push(AnalyzerError::dead_logic(
+                &netlist.name(&net),
+                &self.module.token.to_string(),
+                &token,
+            ));
+        }
+    }
+}
@@ -0,0 +1,933 @@
+use smallvec::{SmallVec, smallvec};
+use std::cmp::Ordering;
+use std::fmt;
+
+type Words = SmallVec<[u64; 2]>;
+
+const WORD: usize = u64::BITS as usize;
+
+fn word_count(width: usize) -> usize {
+    width.div_ceil(WORD)
+}
+
+fn mask_top(words: &mut [u64], width: usize) {
+    if let Some(x) = words.last_mut() {
+        if !width.is_multiple_of(WORD) {
+            *x &= (1 << (width % WORD)) - 1;
+        }
+    }
+}
+
+fn bit(words: &[u64], i: usize) -> bool {
+    words
+        .get(i / WORD)
+        .is_some_and(|x| (x >> (i % WORD)) & 1 == 1)
+}
+
+fn set_bit(words: &mut [u64], i: usize, value: bool) {
+    let mask = 1 << (i % WORD);
+    if value {
+        words[i / WORD] |= mask;
+    } else {
+        words[i / WORD] &= !mask;
+    }
+}
+
+fn is_zero(words: &[u64]) -> bool {
+    words.iter().all(|x| *x == 0)
+}
+
+fn bit_length(words: &[u64]) -> usize {
+    for (i, x) in words.iter().enumerate().rev() {
+        if *x != 0 {
+            return i * WORD + (WORD - x.leading_zeros() as usize);
+        }
+    }
+    0
+}
+
+/// Truncate or extend `words` which has `from` bits to `to` bits.
+/// If `sign` is true, MSB of `from` bits is used as fill value.
+fn extend(words: &[u64], from: usize, to: usize, sign: bool) -> Words {
+    let mut ret: Words = (0..word_count(to))
+        .map(|i| words.get(i).copied().unwrap_or(0))
+        .collect();
+    if to > from && sign && from != 0 && bit(words, from - 1) {
+        for (i, x) in ret.iter_mut().enumerate().skip(from / WORD) {
+            *x |= u64::MAX << from.saturating_sub(i * WORD);
+        }
+    }
+    mask_top(&mut ret, to);
+    ret
+}
+
+fn shl_words(words: &[u64], shift: usize) -> Words {
+    let n = words.len();
+    let (ws, bs) = (shift / WORD, shift % WORD);
+    let mut ret: Words = smallvec![0; n];
+    for i in ws..n {
+        let src = i - ws;
+        ret[i] = words[src] << bs;
+        if bs != 0 && src > 0 {
+            ret[i] |= words[src - 1] >> (WORD - bs);
+        }
+    }
+    ret
+}
+
+fn shr_words(words: &[u64], shift: usize) -> Words {
+    let n = words.len();
+    let (ws, bs) = (shift / WORD, shift % WORD);
+    let mut ret: Words = smallvec![0; n];
+    for i in 0..n.saturating_sub(ws) {
+        let src = i + ws;
+        ret[i] = words[src] >> bs;
+        if bs != 0 && src + 1 < n {
+            ret[i] |= words[src + 1] << (WORD - bs);
+        }
+    }
+    ret
+}
+
+/// Arithmetic right shift of `width` bits
+fn sra_words(words: &[u64], shift: usize, width: usize) -> Words {
+    if width != 0 && bit(words, width - 1) {
+        let mut inv: Words = words.iter().map(|x| !x).collect();
+        mask_top(&mut inv, width);
+        let mut ret: Words = shr_words(&inv, shift).iter().map(|x| !x).collect();
+        mask_top(&mut ret, width);
+        ret
+    } else {
+        shr_words(words, shift)
+    }
+}
+
+fn add_words(a: &[u64], b: &[u64]) -> Words {
+    let mut carry = false;
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| {
+            let (x, c0) = a.overflowing_add(*b);
+            let (x, c1) = x.overflowing_add(carry as u64);
+            carry = c0 | c1;
+            x
+        })
+        .collect()
+}
+
+fn sub_words(a: &[u64], b: &[u64]) -> Words {
+    let mut borrow = false;
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| {
+            let (x, b0) = a.overflowing_sub(*b);
+            let (x, b1) = x.overflowing_sub(borrow as u64);
+            borrow = b0 | b1;
+            x
+        })
+        .collect()
+}
+
+fn neg_words(a: &[u64]) -> Words {
+    let zero: Words = smallvec![0; a.len()];
+    sub_words(&zero, a)
+}
+
+fn mul_words(a: &[u64], b: &[u64]) -> Words {
+    let n = a.len();
+    let mut ret: Words = smallvec![0; n];
+    for i in 0..n {
+        let mut carry = 0u128;
+        for j in 0..n - i {
+            let x = a[i] as u128 * b[j] as u128 + ret[i + j] as u128 + carry;
+            ret[i + j] = x as u64;
+            carry = x >> WORD;
+        }
+    }
+    ret
+}
+
+fn cmp_words(a: &[u64], b: &[u64]) -> Ordering {
+    for (a, b) in a.iter().zip(b.iter()).rev() {
+        match a.cmp(b) {
+            Ordering::Equal => (),
+            x => return x,
+        }
+    }
+    Ordering::Equal
+}
+
+/// Unsigned division of `width` bits
+fn divrem_words(a: &[u64], b: &[u64], width: usize) -> (Words, Words) {
+    // remainder has an extra word to hold the shifted out bit
+    let n = a.len() + 1;
+    let b: Words = (0..n).map(|i| b.get(i).copied().unwrap_or(0)).collect();
+    let mut q: Words = smallvec![0; a.len()];
+    let mut r: Words = smallvec![0; n];
+    for i in (0..width).rev() {
+        r = shl_words(&r, 1);
+        r[0] |= bit(a, i) as u64;
+        if cmp_words(&r, &b).is_ge() {
+            r = sub_words(&r, &b);
+            set_bit(&mut q, i, true);
+        }
+    }
+    r.truncate(a.len());
+    (q, r)
+}
+
+/// Arbitrary-width four-state bit vector.
+///
+/// Each bit is represented by a pair of `payload` and `mask_xz` bits:
+/// `(0, 0)` is 0, `(1, 0)` is 1, `(0, 1)` is Z, and `(1, 1)` is X.
+/// `mask_xz` is kept empty while the value has no X/Z bit,
+/// so 2-state values don't pay for it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Value {
+    pub width: usize,
+    pub signed: bool,
+    payload: Words,
+    mask_xz: Words,
+}
+
+impl Value {
+    fn from_planes(payload: Words, mask_xz: Words, width: usize, signed: bool) -> Self {
+        let n = word_count(width);
+        let mut payload = payload;
+        let mut mask_xz = mask_xz;
+        payload.resize(n, 0);
+        mask_top(&mut payload, width);
+        if !mask_xz.is_empty() {
+            mask_xz.resize(n, 0);
+            mask_top(&mut mask_xz, width);
+            if is_zero(&mask_xz) {
+                mask_xz.clear();
+            }
+        }
+        Self {
+            width,
+            signed,
+            payload,
+            mask_xz,
+        }
+    }
+
+    fn unknown(width: usize, signed: bool) -> Self {
+        Self::x(width).with_signed(signed)
+    }
+
+    /// `payload` and `mask_xz` extended to `width`
+    fn planes(&self, width: usize, signed: bool) -> (Words, Words) {
+        let payload = extend(&self.payload, self.width, width, signed);
+        let mask_xz = if self.mask_xz.is_empty() {
+            smallvec![0; word_count(width)]
+        } else {
+            extend(&self.mask_xz, self.width, width, signed)
+        };
+        (payload, mask_xz)
+    }
+
+    /// 2-state operands extended to `width`.
+    /// `None` is returned if any operand has X/Z.
+    fn operands(&self, x: &Value, width: usize) -> Option<(Words, Words, bool)> {
+        if self.has_xz() || x.has_xz() {
+            return None;
+        }
+        let signed = self.signed && x.signed;
+        let a = extend(&self.payload, self.width, width, signed);
+        let b = extend(&x.payload, x.width, width, signed);
+        Some((a, b, signed))
+    }
+
+    pub fn new(payload: u64, width: usize, signed: bool) -> Self {
+        Self::from_planes(smallvec![payload], smallvec![], width, signed)
+    }
+
+    /// Create value from little-endian 64-bit words
+    pub fn from_words(payload: &[u64], width: usize, signed: bool) -> Self {
+        Self::from_planes(payload.into(), smallvec![], width, signed)
+    }
+
+    pub fn zero(width: usize) -> Self {
+        Self::new(0, width, false)
+    }
+
+    pub fn ones(width: usize) -> Self {
+        Self::from_planes(
+            smallvec![u64::MAX; word_count(width)],
+            smallvec![],
+            width,
+            false,
+        )
+    }
+
+    pub fn x(width: usize) -> Self {
+        let all: Words = smallvec![u64::MAX; word_count(width)];
+        Self::from_planes(all.clone(), all, width, false)
+    }
+
+    pub fn z(width: usize) -> Self {
+        let all: Words = smallvec![u64::MAX; word_count(width)];
+        Self::from_planes(smallvec![], all, width, false)
+    }
+
+    pub fn from_bool(x: bool) -> Self {
+        Self::new(x as u64, 1, false)
+    }
+
+    /// Parse digits of `radix` into the value which has the minimum width.
+    /// `x`, `z` and `?` are accepted except decimal which accepts only a single `x` or `z`.
+    pub fn from_digits(digits: &str, radix: u32) -> Option<Self> {
+        let digits = digits.replace('_', "").to_ascii_lowercase();
+        if digits.is_empty() {
+            return None;
+        }
+
+        if radix == 10 {
+            match digits.as_str() {
+                "x" => return Some(Self::x(1)),
+                "z" | "?" => return Some(Self::z(1)),
+                _ => (),
+            }
+
+            let mut payload: Words = smallvec![];
+            for c in digits.chars() {
+                let mut carry = c.to_digit(10)? as u128;
+                for x in payload.iter_mut() {
+                    let y = *x as u128 * 10 + carry;
+                    *x = y as u64;
+                    carry = y >> WORD;
+                }
+                if carry != 0 {
+                    payload.push(carry as u64);
+                }
+            }
+            let width = bit_length(&payload);
+            Some(Self::from_planes(payload, smallvec![], width, false))
+        } else {
+            let bits = match radix {
+                2 => 1,
+                8 => 3,
+                16 => 4,
+                _ => return None,
+            };
+            let n = word_count(digits.len() * bits);
+            let mut payload: Words = smallvec![0; n];
+            let mut mask_xz: Words = smallvec![0; n];
+            for (i, c) in digits.chars().rev().enumerate() {
+                let (value, xz) = match c {
+                    'x' => (u32::MAX, u32::MAX),
+                    'z' | '?' => (0, u32::MAX),
+                    _ => (c.to_digit(radix)?, 0),
+                };
+                for j in 0..bits {
+                    set_bit(&mut payload, i * bits + j, (value >> j) & 1 == 1);
+                    set_bit(&mut mask_xz, i * bits + j, (xz >> j) & 1 == 1);
+                }
+            }
+            let width = bit_length(&payload).max(bit_length(&mask_xz));
+            Some(Self::from_planes(payload, mask_xz, width, false))
+        }
+    }
+
+    /// Parse based literal like `8'hff`, `'sd10` and `4'bx`.
+    /// Unsized literal has 32-bit width at least.
+    pub fn from_literal(text: &str) -> Option<Self> {
+        let text = text.replace('_', "");
+        let (width, rest) = text.split_once('\'')?;
+        let (signed, rest) = match rest.strip_prefix('s') {
+            Some(x) => (true, x),
+            None => (false, rest),
+        };
+        let mut chars = rest.chars();
+        let radix = match chars.next()? {
+            'b' => 2,
+            'o' => 8,
+            'd' => 10,
+            'h' => 16,
+            _ => return None,
+        };
+        let digits = chars.as_str();
+        let value = Self::from_digits(digits, radix)?;
+        let width = if width.is_empty() {
+            value.width.max(32)
+        } else {
+            width.parse().ok()?
+        };
+
+        // X/Z at the leftmost digit is extended to the upper bits
+        let extend_xz = digits.starts_with(['x', 'X', 'z', 'Z', '?']);
+        Some(
+            value
+                .with_signed(extend_xz)
+                .resize(width)
+                .with_signed(signed),
+        )
+    }
+
+    /// Little-endian 64-bit words of the value
+    pub fn payload(&self) -> &[u64] {
+        &self.payload
+    }
+
+    /// Little-endian 64-bit words of X/Z mask.
+    /// It is empty if the value has no X/Z bit.
+    pub fn mask_xz(&self) -> &[u64] {
+        &self.mask_xz
+    }
+
+    pub fn has_xz(&self) -> bool {
+        !self.mask_xz.is_empty()
+    }
+
+    /// Replace X/Z by 0
+    pub fn to_2state(&self) -> Self {
+        if self.has_xz() {
+            let payload = self
+                .payload
+                .iter()
+                .zip(self.mask_xz.iter())
+                .map(|(x, y)| x & !y)
+                .collect();
+            Self::from_planes(payload, smallvec![], self.width, self.signed)
+        } else {
+            self.clone()
+        }
+    }
+
+    /// Unsigned interpretation of the value.
+    /// `None` is returned if it has X/Z or doesn't fit in 64 bits.
+    pub fn to_u64(&self) -> Option<u64> {
+        if self.has_xz() || !is_zero(self.payload.get(1..).unwrap_or(&[])) {
+            None
+        } else {
+            Some(self.payload.first().copied().unwrap_or(0))
+        }
+    }
+
+    pub fn to_usize(&self) -> Option<usize> {
+        self.to_u64().and_then(|x| x.try_into().ok())
+    }
+
+    /// Signed interpretation if the value is signed, otherwise unsigned.
+    /// `None` is returned if it has X/Z or doesn't fit in `isize`.
+    pub fn to_isize(&self) -> Option<isize> {
+        if self.has_xz() {
+            return None;
+        }
+        let n = word_count(self.width) + 1;
+        let words = extend(&self.payload, self.width, n * WORD, self.signed);
+        let x = words[0] as i64;
+        let fill = if x < 0 { u64::MAX } else { 0 };
+        if words[1..].iter().all(|x| *x == fill) {
+            x.try_into().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Whether the value has any bit which is known as 1
+    pub fn to_bool(&self) -> bool {
+        if self.has_xz() {
+            self.payload
+                .iter()
+                .zip(self.mask_xz.iter())
+                .any(|(x, y)| x & !y != 0)
+        } else {
+            !is_zero(&self.payload)
+        }
+    }
+
+    /// Whether all bits are known as 0
+    pub fn is_false(&self) -> bool {
+        !self.has_xz() && is_zero(&self.payload)
+    }
+
+    pub fn msb(&self) -> bool {
+        self.width != 0 && bit(&self.payload, self.width - 1)
+    }
+
+    /// Position of the highest 1, X or Z bit plus one
+    pub fn bit_length(&self) -> usize {
+        bit_length(&self.payload).max(bit_length(&self.mask_xz))
+    }
+
+    /// Change width with sign extension if the value is signed
+    pub fn resize(&self, width: usize) -> Self {
+        let (payload, mask_xz) = self.planes(width, self.signed);
+        Self::from_planes(payload, mask_xz, width, self.signed)
+    }
+
+    pub fn with_signed(mut self, signed: bool) -> Self {
+        self.signed = signed;
+        self
+    }
+
+    pub fn select(&self, offset: usize, width: usize) -> Self {
+        let payload = shr_words(&self.payload, offset);
+        let mask_xz = shr_words(&self.mask_xz, offset);
+        Self::from_planes(payload, mask_xz, width, false)
+    }
+
+    /// Replace `width` bits from `offset` by `value`
+    pub fn replace(&self, offset: usize, width: usize, value: &Value) -> Self {
+        let width = width.min(self.width.saturating_sub(offset));
+        if width == 0 {
+            return self.clone();
+        }
+        let field = shl_words(
+            &extend(&Self::ones(width).payload, width, self.width, false),
+            offset,
+        );
+        let (old_payload, old_mask) = self.planes(self.width, false);
+        let (new_payload, new_mask) = value.planes(width, false);
+        let new_payload = shl_words(&extend(&new_payload, width, self.width, false), offset);
+        let new_mask = shl_words(&extend(&new_mask, width, self.width, false), offset);
+        let merge = |old: &[u64], new: &[u64]| -> Words {
+            old.iter()
+                .zip(new.iter())
+                .zip(field.iter())
+                .map(|((old, new), field)| (old & !field) | (new & field))
+                .collect()
+        };
+        Self::from_planes(
+            merge(&old_payload, &new_payload),
+            merge(&old_mask, &new_mask),
+            self.width,
+            self.signed,
+        )
+    }
+
+    pub fn concat(&self, lower: &Value) -> Self {
+        let width = self.width + lower.width;
+        let (upper_payload, upper_mask) = self.planes(width, false);
+        let (lower_payload, lower_mask) = lower.planes(width, false);
+        let upper_payload = shl_words(&upper_payload, lower.width);
+        let upper_mask = shl_words(&upper_mask, lower.width);
+        let or = |x: &[u64], y: &[u64]| -> Words {
+            x.iter().zip(y.iter()).map(|(x, y)| x | y).collect()
+        };
+        Self::from_planes(
+            or(&upper_payload, &lower_payload),
+            or(&upper_mask, &lower_mask),
+            width,
+            false,
+        )
+    }
+
+    pub fn add(&self, x: &Value, width: usize) -> Self {
+        match self.operands(x, width) {
+            Some((a, b, signed)) => {
+                Self::from_planes(add_words(&a, &b), smallvec![], width, signed)
+            }
+            None => Self::unknown(width, self.signed && x.signed),
+        }
+    }
+
+    pub fn sub(&self, x: &Value, width: usize) -> Self {
+        match self.operands(x, width) {
+            Some((a, b, signed)) => {
+                Self::from_planes(sub_words(&a, &b), smallvec![], width, signed)
+            }
+            None => Self::unknown(width, self.signed && x.signed),
+        }
+    }
+
+    pub fn mul(&self, x: &Value, width: usize) -> Self {
+        match self.operands(x, width) {
+            Some((a, b, signed)) => {
+                Self::from_planes(mul_words(&a, &b), smallvec![], width, signed)
+            }
+            None => Self::unknown(width, self.signed && x.signed),
+        }
+    }
+
+    fn divide(&self, x: &Value, width: usize, remainder: bool) -> Self {
+        let signed = self.signed && x.signed;
+        let Some((a, b, _)) = self.operands(x, width) else {
+            return Self::unknown(width, signed);
+        };
+        // division by zero is X
+        if is_zero(&b) {
+            return Self::unknown(width, signed);
+        }
+
+        let neg_a = signed && bit(&a, width - 1);
+        let neg_b = signed && bit(&b, width - 1);
+        let a = if neg_a { neg_words(&a) } else { a };
+        let b = if neg_b { neg_words(&b) } else { b };
+        let a = extend(&a, width, width, false);
+        let b = extend(&b, width, width, false);
+        let (q, r) = divrem_words(&a, &b, width);
+        let ret = if remainder {
+            if neg_a { neg_words(&r) } else { r }
+        } else if neg_a != neg_b {
+            neg_words(&q)
+        } else {
+            q
+        };
+        Self::from_planes(ret, smallvec![], width, signed)
+    }
+
+    pub fn div(&self, x: &Value, width: usize) -> Self {
+        self.divide(x, width, false)
+    }
+
+    pub fn rem(&self, x: &Value, width: usize) -> Self {
+        self.divide(x, width, true)
+    }
+
+    pub fn pow(&self, x: &Value, width: usize) -> Self {
+        if self.has_xz() || x.has_xz() {
+            return Self::unknown(width, self.signed);
+        }
+
+        let base = extend(&self.payload, self.width, width, self.signed);
+        let one = extend(&[1], 1, width, false);
+
+        let payload = if x.signed && x.msb() {
+            // negative exponent
+            let minus_one =
+                self.signed && self.resize(width) == Self::ones(width).with_signed(true);
+            if base == one {
+                one
+            } else if minus_one {
+                if bit(&x.payload, 0) { base } else { one }
+            } else if is_zero(&base) {
+                return Self::unknown(width, self.signed);
+            } else {
+                smallvec![0; word_count(width)]
+            }
+        } else {
+            let mut ret = one;
+            let mut base = base;
+            for i in 0..bit_length(&x.payload) {
+                if bit(&x.payload, i) {
+                    ret = mul_words(&ret, &base);
+                }
+                base = mul_words(&base, &base);
+            }
+            ret
+        };
+        Self::from_planes(payload, smallvec![], width, self.signed)
+    }
+
+    fn bitwise<T: Fn(u64, u64, u64, u64) -> (u64, u64)>(
+        &self,
+        x: &Value,
+        width: usize,
+        func: T,
+    ) -> Self {
+        let signed = self.signed && x.signed;
+        let (av, am) = self.planes(width, signed);
+        let (bv, bm) = x.planes(width, signed);
+        let (payload, mask_xz) = (0..av.len())
+            .map(|i| func(av[i], am[i], bv[i], bm[i]))
+            .unzip();
+        Self::from_planes(payload, mask_xz, width, signed)
+    }
+
+    pub fn and(&self, x: &Value, width: usize) -> Self {
+        self.bitwise(x, width, |av, am, bv, bm| {
+            let known0 = (!av & !am) | (!bv & !bm);
+            let known1 = (av & !am) & (bv & !bm);
+            let unknown = !(known0 | known1);
+            (known1 | unknown, unknown)
+        })
+    }
+
+    pub fn or(&self, x: &Value, width: usize) -> Self {
+        self.bitwise(x, width, |av, am, bv, bm| {
+            let known0 = (!av & !am) & (!bv & !bm);
+            let known1 = (av & !am) | (bv & !bm);
+            let unknown = !(known0 | known1);
+            (known1 | unknown, unknown)
+        })
+    }
+
+    pub fn xor(&self, x: &Value, width: usize) -> Self {
+        self.bitwise(x, width, |av, am, bv, bm| {
+            let unknown = am | bm;
+            ((av ^ bv) | unknown, unknown)
+        })
+    }
+
+    pub fn xnor(&self, x: &Value, width: usize) -> Self {
+        self.xor(x, width).inv(width)
+    }
+
+    fn shift_amount(x: &Value) -> Option<usize> {
+        if x.has_xz() {
+            None
+        } else {
+            Some(x.to_usize().unwrap_or(usize::MAX))
+        }
+    }
+
+    pub fn shl(&self, x: &Value, width: usize) -> Self {
+        let Some(shift) = Self::shift_amount(x) else {
+            return Self::unknown(width, self.signed);
+        };
+        let (payload, mask_xz) = self.planes(width, self.signed);
+        Self::from_planes(
+            shl_words(&payload, shift),
+            shl_words(&mask_xz, shift),
+            width,
+            self.signed,
+        )
+    }
+
+    pub fn shr(&self, x: &Value, width: usize) -> Self {
+        let Some(shift) = Self::shift_amount(x) else {
+            return Self::unknown(width, self.signed);
+        };
+        let (payload, mask_xz) = self.planes(width, self.signed);
+        Self::from_planes(
+            shr_words(&payload, shift),
+            shr_words(&mask_xz, shift),
+            width,
+            self.signed,
+        )
+    }
+
+    pub fn ashr(&self, x: &Value, width: usize) -> Self {
+        if !self.signed {
+            return self.shr(x, width);
+        }
+        let Some(shift) = Self::shift_amount(x) else {
+            return Self::unknown(width, self.signed);
+        };
+        let (payload, mask_xz) = self.planes(width, self.signed);
+        Self::from_planes(
+            sra_words(&payload, shift, width),
+            sra_words(&mask_xz, shift, width),
+            width,
+            self.signed,
+        )
+    }
+
+    pub fn neg(&self, width: usize) -> Self {
+        if self.has_xz() {
+            return Self::unknown(width, self.signed);
+        }
+        let payload = extend(&self.payload, self.width, width, self.signed);
+        Self::from_planes(neg_words(&payload), smallvec![], width, self.signed)
+    }
+
+    pub fn inv(&self, width: usize) -> Self {
+        let (payload, mask_xz) = self.planes(width, self.signed);
+        let payload = payload
+            .iter()
+            .zip(mask_xz.iter())
+            .map(|(x, y)| !x | y)
+            .collect();
+        Self::from_planes(payload, mask_xz, width, self.signed)
+    }
+
+    fn compare(&self, x: &Value) -> Option<Ordering> {
+        let width = self.width.max(x.width);
+        let (mut a, mut b, signed) = self.operands(x, width)?;
+        if signed && width != 0 {
+            let sign_a = bit(&a, width - 1);
+            let sign_b = bit(&b, width - 1);
+            set_bit(&mut a, width - 1, !sign_a);
+            set_bit(&mut b, width - 1, !sign_b);
+        }
+        Some(cmp_words(&a, &b))
+    }
+
+    fn ordering<T: Fn(Ordering) -> bool>(&self, x: &Value, func: T) -> Self {
+        match self.compare(x) {
+            Some(x) => Self::from_bool(func(x)),
+            None => Self::x(1),
+        }
+    }
+
+    pub fn eq(&self, x: &Value) -> Self {
+        let width = self.width.max(x.width);
+        let signed = self.signed && x.signed;
+        let (av, am) = self.planes(width, signed);
+        let (bv, bm) = x.planes(width, signed);
+        let mut unknown = false;
+        for i in 0..av.len() {
+            let mask = am[i] | bm[i];
+            // known different bit determines the result even if there is X/Z
+            if (av[i] ^ bv[i]) & !mask != 0 {
+                return Self::from_bool(false);
+            }
+            unknown |= mask != 0;
+        }
+        if unknown {
+            Self::x(1)
+        } else {
+            Self::from_bool(true)
+        }
+    }
+
+    pub fn ne(&self, x: &Value) -> Self {
+        self.eq(x).inv(1)
+    }
+
+    /// Equality including X/Z bits (`===`)
+    pub fn case_eq(&self, x: &Value) -> Self {
+        let width = self.width.max(x.width);
+        let signed = self.signed && x.signed;
+        Self::from_bool(self.planes(width, signed) == x.planes(width, signed))
+    }
+
+    pub fn case_ne(&self, x: &Value) -> Self {
+        self.case_eq(x).inv(1)
+    }
+
+    /// Equality which treats X/Z bits of `x` as don't care (`==?`)
+    pub fn wildcard_eq(&self, x: &Value) -> Self {
+        let width = self.width.max(x.width);
+        let signed = self.signed && x.signed;
+        let (av, am) = self.planes(width, signed);
+        let (bv, bm) = x.planes(width, signed);
+        let mut unknown = false;
+        for i in 0..av.len() {
+            let care = !bm[i];
+            if (av[i] ^ bv[i]) & care & !am[i] != 0 {
+                return Self::from_bool(false);
+            }
+            unknown |= am[i] & care != 0;
+        }
+        if unknown {
+            Self::x(1)
+        } else {
+            Self::from_bool(true)
+        }
+    }
+
+    pub fn wildcard_ne(&self, x: &Value) -> Self {
+        self.wildcard_eq(x).inv(1)
+    }
+
+    pub fn lt(&self, x: &Value) -> Self {
+        self.ordering(x, |x| x.is_lt())
+    }
+
+    pub fn le(&self, x: &Value) -> Self {
+        self.ordering(x, |x| x.is_le())
+    }
+
+    pub fn gt(&self, x: &Value) -> Self {
+        self.ordering(x, |x| x.is_gt())
+    }
+
+    pub fn ge(&self, x: &Value) -> Self {
+        self.ordering(x, |x| x.is_ge())
+    }
+
+    pub fn logic_not(&self) -> Self {
+        if self.to_bool() {
+            Self::from_bool(false)
+        } else if self.is_false() {
+            Self::from_bool(true)
+        } else {
+            Self::x(1)
+        }
+    }
+
+    pub fn logic_and(&self, x: &Value) -> Self {
+        if self.is_false() || x.is_false() {
+            Self::from_bool(false)
+        } else if self.to_bool() && x.to_bool() {
+            Self::from_bool(true)
+        } else {
+            Self::x(1)
+        }
+    }
+
+    pub fn logic_or(&self, x: &Value) -> Self {
+        if self.to_bool() || x.to_bool() {
+            Self::from_bool(true)
+        } else if self.is_false() && x.is_false() {
+            Self::from_bool(false)
+        } else {
+            Self::x(1)
+        }
+    }
+
+    pub fn reduction_and(&self) -> Self {
+        let (payload, mask_xz) = self.planes(self.width, false);
+        let mut known0: Words = payload
+            .iter()
+            .zip(mask_xz.iter())
+            .map(|(x, y)| !x & !y)
+            .collect();
+        mask_top(&mut known0, self.width);
+        if !is_zero(&known0) {
+            Self::from_bool(false)
+        } else if self.has_xz() {
+            Self::x(1)
+        } else {
+            Self::from_bool(true)
+        }
+    }
+
+    pub fn reduction_or(&self) -> Self {
+        if self.to_bool() {
+            Self::from_bool(true)
+        } else if self.has_xz() {
+            Self::x(1)
+        } else {
+            Self::from_bool(false)
+        }
+    }
+
+    /// Ceiling of log2 like `$clog2`, which gives 32-bit value
+    pub fn clog2(&self) -> Self {
+        if self.has_xz() {
+            Self::x(32)
+        } else if bit_length(&self.payload) <= 1 {
+            Self::zero(32)
+        } else {
+            let x = self.sub(&Self::new(1, self.width, false), self.width);
+            Self::new(x.bit_length() as u64, 32, false)
+        }
+    }
+
+    pub fn reduction_xor(&self) -> Self {
+        if self.has_xz() {
+            Self::x(1)
+        } else {
+            let ones: u32 = self.payload.iter().map(|x| x.count_ones()).sum();
+            Self::from_bool(ones % 2 == 1)
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = if self.has_xz() {
+            let digits: String = (0..self.width)
+                .rev()
+                .map(|i| match (bit(&self.payload, i), bit(&self.mask_xz, i)) {
+                    (false, false) => '0',
+                    (true, false) => '1',
+                    (false, true) => 'z',
+                    (true, true) => 'x',
+                })
+                .collect();
+            format!("{}'b{}", self.width, digits)
+        } else {
+            let mut digits = String::new();
+            for x in self.payload.iter().rev() {
+                if digits.is_empty() {
+                    if *x != 0 {
+                        digits.push_str(&format!("{x:x}"));
+                    }
+                } else {
+                    digits.push_str(&format!("{x:016x}"));
+                }
+            }
+            if digits.is_empty() {
+                digits.push('0');
+            }
+            format!("{}'h{}", self.width, digits)
+        };
+        text.fmt(f)
+    }
+}
@@ -1,4 +1,4 @@
-use crate::evaluator::{Evaluated, EvaluatedValue, Evaluator};
+use crate::evaluator::{Evaluated, Evaluator};
 use crate::namespace::{DefineContext, Namespace};
 use crate::symbol::{ConnectTargetIdentifier, SymbolId};
 use crate::symbol_table;
@@ -6,6 +6,7 @@ use miette::Result;
 use std::convert::{From, TryFrom};
 use std::fmt;
 use std::ops::RangeInclusive;
+use veryl_parser::token_range::TokenRange;
 use veryl_parser::veryl_grammar_trait::{
     Expression, ExpressionIdentifier, HierarchicalIdentifier, Identifier, Select, SelectOperator,
 };
@@ -119,36 +120,29 @@ impl VarRefPathItem {
 
     fn select_range(&self, x: &VarRefPathItem) -> Option<RangeInclusive<isize>> {
         match x {
-            VarRefPathItem::SelectSingle { index } => match index.value {
-                EvaluatedValue::Fixed(x) => Some(x..=x),
-                _ => None,
-            },
-            VarRefPathItem::SelectColon { msb, lsb } => match (&msb.value, &lsb.value) {
-                (EvaluatedValue::Fixed(msb), EvaluatedValue::Fixed(lsb)) => Some(*lsb..=*msb),
+            VarRefPathItem::SelectSingle { index } => index.get_value().map(|x| x..=x),
+            VarRefPathItem::SelectColon { msb, lsb } => match (msb.get_value(), lsb.get_value()) {
+                (Some(msb), Some(lsb)) => Some(lsb..=msb),
                 _ => None,
             },
             VarRefPathItem::SelectPlusClon { position, width } => {
-                match (&position.value, &width.value) {
-                    (EvaluatedValue::Fixed(position), EvaluatedValue::Fixed(width)) => {
-                        Some(*position..=position + width - 1)
-                    }
+                match (position.get_value(), width.get_value()) {
+                    (Some(position), Some(width)) => Some(position..=position + width - 1),
                     _ => None,
                 }
             }
             VarRefPathItem::SelectMinusColon { position, width } => {
-                match (&position.value, &width.value) {
-                    (EvaluatedValue::Fixed(position), EvaluatedValue::Fixed(width)) => {
-                        Some(position - width + 1..=*position)
-                    }
+                match (position.get_value(), width.get_value()) {
+                    (Some(position), Some(width)) => Some(position - width + 1..=position),
                     _ => None,
                 }
             }
-            VarRefPathItem::SelectStep { index, step } => match (&index.value, &step.value) {
-                (EvaluatedValue::Fixed(index), EvaluatedValue::Fixed(step)) => {
-                    Some(step * index..=step * (index + 1) - 1)
+            VarRefPathItem::SelectStep { index, step } => {
+                match (index.get_value(), step.get_value()) {
+                    (Some(index), Some(step)) => Some(step * index..=step * (index + 1) - 1),
+                    _ => None,
                 }
-                _ => None,
-            },
+            }
             _ => None,
         }
     }
@@ -209,40 +203,34 @@ impl fmt::Display for VarRefPathItem {
                 }
             }
             VarRefPathItem::SelectSingle { index } => {
-                if let EvaluatedValue::Fixed(index) = index.value {
+                if let Some(index) = index.get_value() {
                     format!("[{}]", index)
                 } else {
                     "[]".to_string()
                 }
             }
-            VarRefPathItem::SelectColon { msb, lsb } => match (&msb.value, &lsb.value) {
-                (EvaluatedValue::Fixed(msb), EvaluatedValue::Fixed(lsb)) => {
-                    format!("[{}:{}]", msb, lsb)
-                }
+            VarRefPathItem::SelectColon { msb, lsb } => match (msb.get_value(), lsb.get_value()) {
+                (Some(msb), Some(lsb)) => format!("[{}:{}]", msb, lsb),
                 _ => "[]".to_string(),
             },
             VarRefPathItem::SelectPlusClon { position, width } => {
-                match (&position.value, &width.value) {
-                    (EvaluatedValue::Fixed(position), EvaluatedValue::Fixed(width)) => {
-                        format!("[{}+:{}]", position, width)
-                    }
+                match (position.get_value(), width.get_value()) {
+                    (Some(position), Some(width)) => format!("[{}+:{}]", position, width),
                     _ => "[]".to_string(),
                 }
             }
             VarRefPathItem::SelectMinusColon { position, width } => {
-                match (&position.value, &width.value) {
-                    (EvaluatedValue::Fixed(position), EvaluatedValue::Fixed(width)) => {
-                        format!("[{}-:{}]", position, width)
-                    }
+                match (position.get_value(), width.get_value()) {
+                    (Some(position), Some(width)) => format!("[{}-:{}]", position, width),
                     _ => "[]".to_string(),
                 }
             }
-            VarRefPathItem::SelectStep { index, step } => match (&index.value, &step.value) {
-                (EvaluatedValue::Fixed(index), EvaluatedValue::Fixed(step)) => {
-                    format!("[{} step {}]", index, step)
+            VarRefPathItem::SelectStep { index, step } => {
+                match (index.get_value(), step.get_value()) {
+                    (Some(index), Some(step)) => format!("[{} step {}]", index, step),
+                    _ => "[]".to_string(),
                 }
-                _ => "[]".to_string(),
-            },
+            }
         };
         s.fmt(f)
     }
@@ -516,6 +504,7 @@ pub enum AssignPositionType {
         token: Token,
         define_context: DefineContext,
         branches: usize,
+        items: Vec<AssignBranchItem>,
         has_default: bool,
         allow_missing_reset_statement: bool,
         r#type: AssignStatementBranchType,
@@ -577,6 +566,20 @@ impl AssignPositionType {
     }
 }
 
+/// Branch item of a statement branch, which is used to report the uncovered item
+#[derive(Clone, Debug)]
+pub struct AssignBranchItem {
+    pub name: String,
+    pub range: TokenRange,
+}
+
+/// Uncovered branch item found in `always_comb`, and the statement which has the item
+#[derive(Clone, Debug)]
+pub struct AlwaysCombUncovered {
+    pub token: Token,
+    pub item: AssignBranchItem,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AssignDeclarationType {
     Let,
@@ -592,6 +595,7 @@ pub enum AssignStatementBranchType {
     If,
     IfReset,
     Case,
+    Switch,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -600,6 +604,7 @@ pub enum AssignStatementBranchItemType {
     IfReset,
     Else,
     Case,
+    Switch,
 }
 
 #[derive(Clone, Default, Debug)]
@@ -631,7 +636,7 @@ impl AssignPositionTree {
         self.children.push(node);
     }
 
-    pub fn check_always_comb_uncovered(&self) -> Option<Token> {
+    pub fn check_always_comb_uncovered(&self) -> Option<AlwaysCombUncovered> {
         if let Some(AssignPositionType::Declaration { ref r#type, .. }) = self.r#type {
             if *r#type == AssignDeclarationType::AlwaysComb {
                 let children: Vec<_> = self
@@ -657,23 +662,54 @@ impl AssignPositionTree {
         None
     }
 
-    fn impl_always_comb_uncovered(&self) -> Option<Token> {
-        match self.r#type {
+    fn impl_always_comb_uncovered(&self) -> Option<AlwaysCombUncovered> {
+        match &self.r#type {
             Some(AssignPositionType::StatementBranch {
                 token,
                 branches,
+                items,
                 has_default,
+                r#type,
                 ..
             }) => {
-                if !has_default || self.children.len() != branches {
-                    Some(token)
-                } else {
-                    self.children
-                        .iter()
-                        .map(|x| x.impl_always_comb_uncovered())
-                        .find(|x| x.is_some())
-                        .flatten()
+                for index in 0..*branches {
+                    let assigned = self.children.iter().any(|x| {
+                        matches!(
+                            x.r#type,
+                            Some(AssignPositionType::StatementBranchItem { index: y, .. }) if y == index
+                        )
+                    });
+                    if !assigned {
+                        return items.get(index).map(|item| AlwaysCombUncovered {
+                            token: *token,
+                            item: item.clone(),
+                        });
+                    }
+                }
+
+                if !has_default {
+                    let name = match r#type {
+                        AssignStatementBranchType::If | AssignStatementBranchType::IfReset => {
+                            "implicit `else`"
+                        }
+                        AssignStatementBranchType::Case | AssignStatementBranchType::Switch => {
+                            "implicit `default`"
+                        }
+                    };
+                    return Some(AlwaysCombUncovered {
+                        token: *token,
+                        item: AssignBranchItem {
+                            name: name.to_string(),
+                            range: token.into(),
+                        },
+                    });
                 }
+
+                self.children
+                    .iter()
+                    .map(|x| x.impl_always_comb_uncovered())
+                    .find(|x| x.is_some())
+                    .flatten()
             }
             Some(AssignPositionType::StatementBranchItem { .. }) => {
                 let children: Vec<_> = self
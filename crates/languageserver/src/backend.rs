@@ -1,11 +1,17 @@
 use std::str::FromStr;
 
+use crate::instance_hierarchy;
 use crate::server::{MsgFromServer, MsgToServer, Server, ServerConfigItem, semantic_legend};
+use crate::source_location;
 use async_channel::{Receiver, Sender, unbounded};
 use serde_json::Value;
-use tower_lsp::jsonrpc::Result;
+use tower_lsp::jsonrpc::{Error, Result};
+use tower_lsp::lsp_types::request::{
+    GotoImplementationParams, GotoImplementationResponse, GotoTypeDefinitionParams,
+    GotoTypeDefinitionResponse,
+};
 use tower_lsp::lsp_types::*;
-use tower_lsp::{Client, LanguageServer};
+use tower_lsp::{Client, ClientSocket, LanguageServer, LspService};
 
 const COMPLETION_TRIGGER: &[&str] = &["<", ">", "=", "!", "."];
 
@@ -30,6 +36,59 @@ impl Backend {
         }
     }
 
+    /// Build the service including Veryl specific requests
+    pub fn build_service<F>(init: F) -> (LspService<Self>, ClientSocket)
+    where
+        F: FnOnce(Client) -> Self,
+    {
+        LspService::build(init)
+            .custom_method(instance_hierarchy::METHOD, Self::instance_hierarchy)
+            .custom_method(source_location::METHOD, Self::source_location)
+            .finish()
+    }
+
+    /// Custom request returning the elaborated instance tree like
+    /// `{"textDocument": {"uri": ".."}, "top": "Top"}`
+    pub async fn instance_hierarchy(&self, params: Value) -> Result<Option<Value>> {
+        let url = params["textDocument"]["uri"]
+            .as_str()
+            .and_then(|x| Url::parse(x).ok());
+        let top = params["top"].as_str();
+        let (Some(url), Some(top)) = (url, top) else {
+            return Err(Error::invalid_params(
+                "textDocument.uri and top are required",
+            ));
+        };
+        let top = top.to_string();
+
+        self.send(MsgToServer::InstanceHierarchy { url, top }).await;
+
+        if let Some(MsgFromServer::InstanceHierarchy(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Custom request returning the Veryl location which the position in the generated
+    /// SystemVerilog is mapped from
+    pub async fn source_location(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<Location>> {
+        let url = params.text_document.uri;
+        let position = params.position;
+
+        self.send(MsgToServer::SourceLocation { url, position })
+            .await;
+
+        if let Some(MsgFromServer::SourceLocation(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn send(&self, msg: MsgToServer) {
         if let Err(x) = self.snd.send(msg).await {
             self.client.log_message(MessageType::ERROR, x).await;
@@ -53,7 +112,7 @@ impl LanguageServer for Backend {
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
+                    TextDocumentSyncKind::INCREMENTAL,
                 )),
                 workspace: Some(WorkspaceServerCapabilities {
                     workspace_folders: Some(WorkspaceFoldersServerCapabilities {
@@ -97,10 +156,33 @@ impl LanguageServer for Backend {
                     }),
                 }),
                 definition_provider: Some(OneOf::Left(true)),
+                implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+                type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
                 document_formatting_provider: Some(OneOf::Left(true)),
                 workspace_symbol_provider: Some(OneOf::Left(true)),
                 hover_provider: Some(HoverProviderCapability::Simple(true)),
                 references_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
+                inlay_hint_provider: Some(OneOf::Left(true)),
+                document_symbol_provider: Some(OneOf::Left(true)),
+                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+                signature_help_provider: Some(SignatureHelpOptions {
+                    trigger_characters: Some(vec!["(".to_string(), "<".to_string()]),
+                    retrigger_characters: Some(vec![",".to_string()]),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }),
+                code_action_provider: Some(CodeActionProviderCapability::Options(
+                    CodeActionOptions {
+                        code_action_kinds: Some(vec![CodeActionKind::QUICKFIX]),
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                        resolve_provider: Some(false),
+                    },
+                )),
                 semantic_tokens_provider: Some(
                     SemanticTokensServerCapabilities::SemanticTokensOptions(
                         SemanticTokensOptions {
@@ -150,17 +232,21 @@ impl LanguageServer for Backend {
         self.send(MsgToServer::DidOpen { url, text, version }).await;
     }
 
-    async fn did_change(&self, mut params: DidChangeTextDocumentParams) {
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
         self.client
             .log_message(MessageType::INFO, "did_change")
             .await;
 
         let url = params.text_document.uri;
-        let text = std::mem::take(&mut params.content_changes[0].text);
+        let changes = params.content_changes;
         let version = params.text_document.version;
 
-        self.send(MsgToServer::DidChange { url, text, version })
-            .await;
+        self.send(MsgToServer::DidChange {
+            url,
+            changes,
+            version,
+        })
+        .await;
     }
 
     async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
@@ -265,6 +351,42 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn goto_implementation(
+        &self,
+        params: GotoImplementationParams,
+    ) -> Result<Option<GotoImplementationResponse>> {
+        let url = params.text_document_position_params.text_document.uri;
+        let line = params.text_document_position_params.position.line as usize + 1;
+        let column = params.text_document_position_params.position.character as usize + 1;
+
+        self.send(MsgToServer::GotoImplementation { url, line, column })
+            .await;
+
+        if let Some(MsgFromServer::GotoImplementation(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn goto_type_definition(
+        &self,
+        params: GotoTypeDefinitionParams,
+    ) -> Result<Option<GotoTypeDefinitionResponse>> {
+        let url = params.text_document_position_params.text_document.uri;
+        let line = params.text_document_position_params.position.line as usize + 1;
+        let column = params.text_document_position_params.position.character as usize + 1;
+
+        self.send(MsgToServer::GotoTypeDefinition { url, line, column })
+            .await;
+
+        if let Some(MsgFromServer::GotoTypeDefinition(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn symbol(
         &self,
         params: WorkspaceSymbolParams,
@@ -309,6 +431,177 @@ impl LanguageServer for Backend {
         }
     }
 
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> Result<Option<PrepareRenameResponse>> {
+        let url = params.text_document.uri;
+        let line = params.position.line as usize + 1;
+        let column = params.position.character as usize + 1;
+
+        self.send(MsgToServer::PrepareRename { url, line, column })
+            .await;
+
+        if let Some(MsgFromServer::PrepareRename(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
+        let url = params.text_document_position.text_document.uri;
+        let line = params.text_document_position.position.line as usize + 1;
+        let column = params.text_document_position.position.character as usize + 1;
+        let new_name = params.new_name;
+
+        self.send(MsgToServer::Rename {
+            url,
+            line,
+            column,
+            new_name,
+        })
+        .await;
+
+        match self.recv().await {
+            Some(MsgFromServer::Rename(Ok(x))) => Ok(x),
+            Some(MsgFromServer::Rename(Err(x))) => Err(Error::invalid_params(x)),
+            _ => Ok(None),
+        }
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
+        let diagnostics = params.context.diagnostics;
+
+        self.send(MsgToServer::CodeAction { diagnostics }).await;
+
+        if let Some(MsgFromServer::CodeAction(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn inlay_hint(&self, params: InlayHintParams) -> Result<Option<Vec<InlayHint>>> {
+        let url = params.text_document.uri;
+        let range = params.range;
+
+        self.send(MsgToServer::InlayHint { url, range }).await;
+
+        if let Some(MsgFromServer::InlayHint(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
+        let url = params.text_document_position_params.text_document.uri;
+        let line = params.text_document_position_params.position.line as usize + 1;
+        let column = params.text_document_position_params.position.character as usize + 1;
+
+        self.send(MsgToServer::SignatureHelp { url, line, column })
+            .await;
+
+        if let Some(MsgFromServer::SignatureHelp(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn document_symbol(
+        &self,
+        params: DocumentSymbolParams,
+    ) -> Result<Option<DocumentSymbolResponse>> {
+        let url = params.text_document.uri;
+
+        self.send(MsgToServer::DocumentSymbol { url }).await;
+
+        if let Some(MsgFromServer::DocumentSymbol(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn folding_range(&self, params: FoldingRangeParams) -> Result<Option<Vec<FoldingRange>>> {
+        let url = params.text_document.uri;
+
+        self.send(MsgToServer::FoldingRange { url }).await;
+
+        if let Some(MsgFromServer::FoldingRange(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn selection_range(
+        &self,
+        params: SelectionRangeParams,
+    ) -> Result<Option<Vec<SelectionRange>>> {
+        let url = params.text_document.uri;
+        let positions = params.positions;
+
+        self.send(MsgToServer::SelectionRange { url, positions })
+            .await;
+
+        if let Some(MsgFromServer::SelectionRange(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn prepare_call_hierarchy(
+        &self,
+        params: CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<CallHierarchyItem>>> {
+        let url = params.text_document_position_params.text_document.uri;
+        let line = params.text_document_position_params.position.line as usize + 1;
+        let column = params.text_document_position_params.position.character as usize + 1;
+
+        self.send(MsgToServer::PrepareCallHierarchy { url, line, column })
+            .await;
+
+        if let Some(MsgFromServer::PrepareCallHierarchy(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn incoming_calls(
+        &self,
+        params: CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyIncomingCall>>> {
+        let item = params.item;
+
+        self.send(MsgToServer::IncomingCalls { item }).await;
+
+        if let Some(MsgFromServer::IncomingCalls(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
+    async fn outgoing_calls(
+        &self,
+        params: CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+        let item = params.item;
+
+        self.send(MsgToServer::OutgoingCalls { item }).await;
+
+        if let Some(MsgFromServer::OutgoingCalls(x)) = self.recv().await {
+            Ok(x)
+        } else {
+            Ok(None)
+        }
+    }
+
     async fn semantic_tokens_full(
         &self,
         params: SemanticTokensParams,
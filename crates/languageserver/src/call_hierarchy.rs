@@ -0,0 +1,129 @@
+use crate::walker::to_range;
+use tower_lsp::lsp_types::*;
+use veryl_analyzer::symbol::SymbolKind as VerylSymbolKind;
+use veryl_analyzer::symbol::{Symbol, SymbolId};
+use veryl_analyzer::symbol_table;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_token::Token;
+
+/// Item of the function, or the module, interface and package calling functions
+pub fn to_item(symbol: &Symbol) -> Option<CallHierarchyItem> {
+    let (kind, range) = match &symbol.kind {
+        VerylSymbolKind::Function(x) => (SymbolKind::FUNCTION, &x.range),
+        VerylSymbolKind::Module(x) => (SymbolKind::MODULE, &x.range),
+        VerylSymbolKind::Interface(x) => (SymbolKind::INTERFACE, &x.range),
+        VerylSymbolKind::Package(x) => (SymbolKind::PACKAGE, &x.range),
+        _ => return None,
+    };
+
+    let uri = Url::from_file_path(symbol.token.source.to_string()).ok()?;
+    Some(CallHierarchyItem {
+        name: symbol.token.to_string(),
+        kind,
+        tags: None,
+        detail: Some(symbol.namespace.to_string()),
+        uri,
+        range: to_range(range),
+        selection_range: to_range(&symbol.token.into()),
+        data: None,
+    })
+}
+
+pub fn incoming_calls(item: &CallHierarchyItem) -> Vec<CallHierarchyIncomingCall> {
+    let Some(callee) = find_function(item) else {
+        return Vec::new();
+    };
+
+    let callers: Vec<_> = symbol_table::get_all()
+        .into_iter()
+        .filter(|x| get_range(x).is_some())
+        .collect();
+
+    let mut calls: Vec<(SymbolId, CallHierarchyIncomingCall)> = Vec::new();
+    for reference in &callee.references {
+        let Some(caller) = enclosing(&callers, reference) else {
+            continue;
+        };
+        let range = to_range(&(*reference).into());
+        if let Some((_, call)) = calls.iter_mut().find(|(id, _)| *id == caller.id) {
+            call.from_ranges.push(range);
+        } else if let Some(from) = to_item(caller) {
+            let call = CallHierarchyIncomingCall {
+                from,
+                from_ranges: vec![range],
+            };
+            calls.push((caller.id, call));
+        }
+    }
+
+    calls.into_iter().map(|(_, x)| x).collect()
+}
+
+pub fn outgoing_calls(item: &CallHierarchyItem) -> Vec<CallHierarchyOutgoingCall> {
+    let Some(caller) = find_function(item) else {
+        return Vec::new();
+    };
+    let Some(caller_range) = get_range(&caller) else {
+        return Vec::new();
+    };
+
+    let mut calls = Vec::new();
+    for callee in symbol_table::get_all() {
+        if !matches!(callee.kind, VerylSymbolKind::Function(_)) {
+            continue;
+        }
+
+        let from_ranges: Vec<_> = callee
+            .references
+            .iter()
+            .filter(|x| include(caller_range, x))
+            .map(|x| to_range(&(*x).into()))
+            .collect();
+
+        if !from_ranges.is_empty() {
+            if let Some(to) = to_item(&callee) {
+                calls.push(CallHierarchyOutgoingCall { to, from_ranges });
+            }
+        }
+    }
+
+    calls
+}
+
+/// Function symbol which is pointed by the item
+fn find_function(item: &CallHierarchyItem) -> Option<Symbol> {
+    symbol_table::get_all().into_iter().find(|x| {
+        matches!(x.kind, VerylSymbolKind::Function(_))
+            && to_item(x).is_some_and(|x| {
+                x.uri == item.uri && x.selection_range.start == item.selection_range.start
+            })
+    })
+}
+
+fn get_range(symbol: &Symbol) -> Option<&TokenRange> {
+    match &symbol.kind {
+        VerylSymbolKind::Function(x) => Some(&x.range),
+        VerylSymbolKind::Module(x) => Some(&x.range),
+        VerylSymbolKind::Interface(x) => Some(&x.range),
+        VerylSymbolKind::Package(x) => Some(&x.range),
+        _ => None,
+    }
+}
+
+fn include(range: &TokenRange, token: &Token) -> bool {
+    token
+        .source
+        .get_path()
+        .is_some_and(|path| range.include(path, token.line, token.column))
+}
+
+/// Innermost function, module, interface or package including the token
+fn enclosing<'a>(symbols: &'a [Symbol], token: &Token) -> Option<&'a Symbol> {
+    symbols
+        .iter()
+        .filter(|x| get_range(x).is_some_and(|range| include(range, token)))
+        .max_by_key(|x| {
+            let range = get_range(x).unwrap();
+            (range.beg.line, range.beg.column)
+        })
+}
@@ -0,0 +1,232 @@
+use crate::walker::{Walker, to_range};
+use tower_lsp::lsp_types::{DocumentSymbol, SymbolKind};
+use veryl_parser::ParolError;
+use veryl_parser::stringifier::Stringifier;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_token::Token;
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
+
+/// Hierarchical outline of the syntax tree
+#[derive(Default)]
+pub struct DocumentSymbols {
+    pub symbols: Vec<DocumentSymbol>,
+    point: HandlerPoint,
+    stack: Vec<DocumentSymbol>,
+}
+
+impl DocumentSymbols {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collect(&mut self, veryl: &Veryl) {
+        Walker::new(self).veryl(veryl);
+    }
+
+    fn push<T: Into<TokenRange>>(
+        &mut self,
+        name: &Token,
+        kind: SymbolKind,
+        range: T,
+        detail: Option<String>,
+    ) {
+        match self.point {
+            HandlerPoint::Before => {
+                let range: TokenRange = range.into();
+                let selection_range: TokenRange = name.into();
+                #[allow(deprecated)]
+                self.stack.push(DocumentSymbol {
+                    name: name.to_string(),
+                    detail,
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    range: to_range(&range),
+                    selection_range: to_range(&selection_range),
+                    children: None,
+                });
+            }
+            HandlerPoint::After => {
+                let Some(symbol) = self.stack.pop() else {
+                    return;
+                };
+                if let Some(parent) = self.stack.last_mut() {
+                    parent.children.get_or_insert_with(Vec::new).push(symbol);
+                } else {
+                    self.symbols.push(symbol);
+                }
+            }
+        }
+    }
+}
+
+impl Handler for DocumentSymbols {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+fn stringify<F: FnOnce(&mut Stringifier)>(f: F) -> Option<String> {
+    let mut stringifier = Stringifier::new();
+    f(&mut stringifier);
+    Some(stringifier.as_str().to_string())
+}
+
+impl VerylGrammarTrait for DocumentSymbols {
+    fn module_declaration(&mut self, arg: &ModuleDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::MODULE, arg, None);
+        Ok(())
+    }
+
+    fn interface_declaration(&mut self, arg: &InterfaceDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::INTERFACE, arg, None);
+        Ok(())
+    }
+
+    fn package_declaration(&mut self, arg: &PackageDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::PACKAGE, arg, None);
+        Ok(())
+    }
+
+    fn proto_module_declaration(&mut self, arg: &ProtoModuleDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::MODULE, arg, None);
+        Ok(())
+    }
+
+    fn function_declaration(&mut self, arg: &FunctionDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::FUNCTION, arg, None);
+        Ok(())
+    }
+
+    fn modport_declaration(&mut self, arg: &ModportDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::INTERFACE, arg, None);
+        Ok(())
+    }
+
+    fn struct_union_declaration(&mut self, arg: &StructUnionDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::STRUCT, arg, None);
+        Ok(())
+    }
+
+    fn struct_union_item(&mut self, arg: &StructUnionItem) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| x.scalar_type(&arg.scalar_type));
+        self.push(name, SymbolKind::FIELD, arg, detail);
+        Ok(())
+    }
+
+    fn enum_declaration(&mut self, arg: &EnumDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::ENUM, arg, None);
+        Ok(())
+    }
+
+    fn enum_item(&mut self, arg: &EnumItem) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::ENUM_MEMBER, arg, None);
+        Ok(())
+    }
+
+    fn type_def_declaration(&mut self, arg: &TypeDefDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| x.array_type(&arg.array_type));
+        self.push(name, SymbolKind::TYPE_PARAMETER, arg, detail);
+        Ok(())
+    }
+
+    fn with_parameter_item(&mut self, arg: &WithParameterItem) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::CONSTANT, arg, None);
+        Ok(())
+    }
+
+    fn port_declaration_item(&mut self, arg: &PortDeclarationItem) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| match arg.port_declaration_item_group.as_ref() {
+            PortDeclarationItemGroup::PortTypeConcrete(x_) => {
+                x.port_type_concrete(&x_.port_type_concrete)
+            }
+            PortDeclarationItemGroup::PortTypeAbstract(x_) => {
+                x.port_type_abstract(&x_.port_type_abstract)
+            }
+        });
+        self.push(name, SymbolKind::PROPERTY, arg, detail);
+        Ok(())
+    }
+
+    fn const_declaration(&mut self, arg: &ConstDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::CONSTANT, arg, None);
+        Ok(())
+    }
+
+    fn var_declaration(&mut self, arg: &VarDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| x.array_type(&arg.array_type));
+        self.push(name, SymbolKind::VARIABLE, arg, detail);
+        Ok(())
+    }
+
+    fn let_declaration(&mut self, arg: &LetDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| x.array_type(&arg.array_type));
+        self.push(name, SymbolKind::VARIABLE, arg, detail);
+        Ok(())
+    }
+
+    fn inst_declaration(&mut self, arg: &InstDeclaration) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        let detail = stringify(|x| x.scoped_identifier(&arg.scoped_identifier));
+        self.push(name, SymbolKind::OBJECT, arg, detail);
+        Ok(())
+    }
+
+    fn always_ff_declaration(&mut self, arg: &AlwaysFfDeclaration) -> Result<(), ParolError> {
+        let name = &arg.always_ff.always_ff_token.token;
+        self.push(name, SymbolKind::EVENT, arg, None);
+        Ok(())
+    }
+
+    fn always_comb_declaration(&mut self, arg: &AlwaysCombDeclaration) -> Result<(), ParolError> {
+        let name = &arg.always_comb.always_comb_token.token;
+        self.push(name, SymbolKind::EVENT, arg, None);
+        Ok(())
+    }
+
+    fn initial_declaration(&mut self, arg: &InitialDeclaration) -> Result<(), ParolError> {
+        let name = &arg.initial.initial_token.token;
+        self.push(name, SymbolKind::EVENT, arg, None);
+        Ok(())
+    }
+
+    fn final_declaration(&mut self, arg: &FinalDeclaration) -> Result<(), ParolError> {
+        let name = &arg.r#final.final_token.token;
+        self.push(name, SymbolKind::EVENT, arg, None);
+        Ok(())
+    }
+
+    fn generate_named_block(&mut self, arg: &GenerateNamedBlock) -> Result<(), ParolError> {
+        let name = &arg.identifier.identifier_token.token;
+        self.push(name, SymbolKind::NAMESPACE, arg, None);
+        Ok(())
+    }
+
+    fn generate_optional_named_block(
+        &mut self,
+        arg: &GenerateOptionalNamedBlock,
+    ) -> Result<(), ParolError> {
+        if let Some(ref x) = arg.generate_optional_named_block_opt {
+            let name = &x.identifier.identifier_token.token;
+            self.push(name, SymbolKind::NAMESPACE, arg, None);
+        }
+        Ok(())
+    }
+}
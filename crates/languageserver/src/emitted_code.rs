@@ -0,0 +1,131 @@
+use crate::walker::Walker;
+use std::path::Path;
+use veryl_emitter::Emitter;
+use veryl_metadata::{Metadata, SourceMapTarget};
+use veryl_parser::ParolError;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
+
+/// Token ranges of declarations and statements which are shown with the emitted code
+#[derive(Default)]
+pub struct EmittedCode {
+    ranges: Vec<TokenRange>,
+    point: HandlerPoint,
+}
+
+impl EmittedCode {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collect(&mut self, veryl: &Veryl) {
+        Walker::new(self).veryl(veryl);
+    }
+
+    /// SystemVerilog emitted from the innermost declaration or statement at the position
+    pub fn get(
+        &self,
+        metadata: &Metadata,
+        path: &Path,
+        veryl: &Veryl,
+        line: u32,
+        column: u32,
+    ) -> Option<String> {
+        // Nodes are visited from outer to inner
+        let range = self
+            .ranges
+            .iter()
+            .rev()
+            .find(|x| include(x, line, column))?;
+
+        let dst_path = path.with_extension("sv");
+        let map_path = path.with_extension("sv.map");
+        let mut emitter = Emitter::new(metadata, path, &dst_path, &map_path);
+        emitter.emit(&metadata.project.name, veryl);
+
+        // The emitter builds the source map only if the link to it is emitted
+        if metadata.build.sourcemap_target == SourceMapTarget::None {
+            emitter.source_map().build();
+        }
+
+        let (beg, end) = emitter
+            .source_map()
+            .lookup_dst_lines(range.beg.line, range.end.line)?;
+        let lines: Vec<_> = emitter
+            .as_str()
+            .lines()
+            .skip(beg as usize - 1)
+            .take((end - beg + 1) as usize)
+            .collect();
+
+        let indent = lines
+            .iter()
+            .filter(|x| !x.trim().is_empty())
+            .map(|x| x.len() - x.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        let lines: Vec<_> = lines
+            .iter()
+            .map(|x| x.get(indent..).unwrap_or("").trim_end())
+            .collect();
+
+        Some(lines.join("\n"))
+    }
+}
+
+fn include(range: &TokenRange, line: u32, column: u32) -> bool {
+    let beg = (range.beg.line, range.beg.column);
+    let end = (range.end.line, range.end.column + range.end.length);
+    beg <= (line, column) && (line, column) <= end
+}
+
+impl Handler for EmittedCode {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+macro_rules! emitted_code {
+    ($($name:ident: $type:ty,)*) => {
+        $(
+            fn $name(&mut self, arg: &$type) -> Result<(), ParolError> {
+                if let HandlerPoint::Before = self.point {
+                    self.ranges.push(arg.into());
+                }
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl VerylGrammarTrait for EmittedCode {
+    emitted_code!(
+        let_statement: LetStatement,
+        identifier_statement: IdentifierStatement,
+        if_statement: IfStatement,
+        if_reset_statement: IfResetStatement,
+        return_statement: ReturnStatement,
+        for_statement: ForStatement,
+        case_statement: CaseStatement,
+        switch_statement: SwitchStatement,
+        let_declaration: LetDeclaration,
+        var_declaration: VarDeclaration,
+        const_declaration: ConstDeclaration,
+        type_def_declaration: TypeDefDeclaration,
+        always_ff_declaration: AlwaysFfDeclaration,
+        always_comb_declaration: AlwaysCombDeclaration,
+        assign_declaration: AssignDeclaration,
+        modport_declaration: ModportDeclaration,
+        enum_declaration: EnumDeclaration,
+        struct_union_declaration: StructUnionDeclaration,
+        initial_declaration: InitialDeclaration,
+        final_declaration: FinalDeclaration,
+        inst_declaration: InstDeclaration,
+        with_parameter_item: WithParameterItem,
+        port_declaration_item: PortDeclarationItem,
+        function_declaration: FunctionDeclaration,
+        generate_if_declaration: GenerateIfDeclaration,
+        generate_for_declaration: GenerateForDeclaration,
+    );
+}
@@ -0,0 +1,100 @@
+use tower_lsp::lsp_types::{FoldingRange, FoldingRangeKind};
+use veryl_parser::veryl_grammar_trait::Veryl;
+use veryl_parser::veryl_token::{Token, VerylToken};
+use veryl_parser::veryl_walker::VerylWalker;
+
+/// Folding ranges of blocks, comments and attributes
+#[derive(Default)]
+pub struct FoldingRanges {
+    pub ranges: Vec<FoldingRange>,
+    brackets: Vec<(Token, bool)>,
+    after_hash: bool,
+    attribute: Option<(u32, u32)>,
+    comment: Option<(u32, u32)>,
+}
+
+impl FoldingRanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collect(&mut self, veryl: &Veryl) {
+        self.veryl(veryl);
+        self.flush();
+    }
+
+    fn push(&mut self, beg: u32, end: u32, kind: Option<FoldingRangeKind>) {
+        if beg < end {
+            self.ranges.push(FoldingRange {
+                start_line: beg - 1,
+                start_character: None,
+                end_line: end - 1,
+                end_character: None,
+                kind,
+                collapsed_text: None,
+            });
+        }
+    }
+
+    fn push_attribute(&mut self, beg: u32, end: u32) {
+        if let Some((beg, end)) = merge(&mut self.attribute, beg, end) {
+            self.push(beg, end, Some(FoldingRangeKind::Region));
+        }
+    }
+
+    fn push_comment(&mut self, comment: &Token) {
+        let text = comment.to_string();
+        let lines = text.trim_end().matches('\n').count() as u32;
+        if let Some((beg, end)) = merge(&mut self.comment, comment.line, comment.line + lines) {
+            self.push(beg, end, Some(FoldingRangeKind::Comment));
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some((beg, end)) = self.attribute.take() {
+            self.push(beg, end, Some(FoldingRangeKind::Region));
+        }
+        if let Some((beg, end)) = self.comment.take() {
+            self.push(beg, end, Some(FoldingRangeKind::Comment));
+        }
+    }
+}
+
+/// Merge the lines into the group if they are consecutive,
+/// and return the previous group if not
+fn merge(group: &mut Option<(u32, u32)>, beg: u32, end: u32) -> Option<(u32, u32)> {
+    match group {
+        Some(x) if x.1 + 1 >= beg => {
+            x.1 = end;
+            None
+        }
+        _ => group.replace((beg, end)),
+    }
+}
+
+impl VerylWalker for FoldingRanges {
+    /// Semantic action for non-terminal 'VerylToken'
+    fn veryl_token(&mut self, arg: &VerylToken) {
+        let token = arg.token;
+        let text = token.to_string();
+
+        if text.ends_with(['{', '(', '[']) {
+            let attribute = self.after_hash && text == "[";
+            self.brackets.push((token, attribute));
+        } else if text == "}" || text == ")" || text == "]" {
+            if let Some((beg, attribute)) = self.brackets.pop() {
+                if attribute {
+                    self.push_attribute(beg.line, token.line);
+                } else {
+                    // The closing line is kept visible
+                    self.push(beg.line, token.line - 1, None);
+                }
+            }
+        }
+        self.after_hash = text == "#";
+
+        for comment in &arg.comments {
+            self.push_comment(comment);
+        }
+    }
+}
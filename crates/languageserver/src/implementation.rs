@@ -0,0 +1,103 @@
+use veryl_analyzer::namespace_table;
+use veryl_analyzer::symbol::{Symbol, SymbolKind, TypeKind};
+use veryl_analyzer::symbol_table;
+
+/// Modules and packages implementing the proto, or concrete symbols given to the generic parameter
+pub fn implementations(symbol: &Symbol) -> Vec<Symbol> {
+    match &symbol.kind {
+        SymbolKind::ProtoModule(_) | SymbolKind::ProtoPackage(_) => symbol_table::get_all()
+            .into_iter()
+            .filter(|x| {
+                let proto = match &x.kind {
+                    SymbolKind::Module(x) => &x.proto,
+                    SymbolKind::Package(x) => &x.proto,
+                    _ => return false,
+                };
+                proto
+                    .as_ref()
+                    .and_then(|proto| symbol_table::resolve((proto, &x.namespace)).ok())
+                    .is_some_and(|proto| proto.found.id == symbol.id)
+            })
+            .collect(),
+        SymbolKind::GenericParameter(_) => generic_candidates(symbol),
+        _ => Vec::new(),
+    }
+}
+
+/// Struct, enum, union, typedef or modport which is the type of the variable or port
+pub fn type_definitions(symbol: &Symbol) -> Vec<Symbol> {
+    let r#type = match &symbol.kind {
+        SymbolKind::Variable(x) => &x.r#type,
+        SymbolKind::Port(x) => &x.r#type,
+        SymbolKind::Parameter(x) => &x.r#type,
+        SymbolKind::StructMember(x) => &x.r#type,
+        SymbolKind::UnionMember(x) => &x.r#type,
+        _ => return Vec::new(),
+    };
+    let TypeKind::UserDefined(x) = &r#type.kind else {
+        return Vec::new();
+    };
+
+    let namespace = x
+        .path
+        .paths
+        .first()
+        .and_then(|x| namespace_table::get(x.base.id))
+        .unwrap_or_else(|| symbol.namespace.clone());
+    let Ok(found) = symbol_table::resolve((&x.path.generic_path(), &namespace)) else {
+        return Vec::new();
+    };
+
+    if let SymbolKind::GenericParameter(_) = found.found.kind {
+        generic_candidates(&found.found)
+    } else {
+        vec![found.found]
+    }
+}
+
+/// Concrete symbols given to the generic parameter at instantiation sites
+fn generic_candidates(param: &Symbol) -> Vec<Symbol> {
+    let owner = symbol_table::get_all().into_iter().find(|x| {
+        x.inner_namespace().paths == param.namespace.paths
+            && x.generic_parameters()
+                .iter()
+                .any(|(name, _)| *name == param.token.text)
+    });
+    let Some(owner) = owner else {
+        return Vec::new();
+    };
+    let Some(index) = owner
+        .generic_parameters()
+        .iter()
+        .position(|(name, _)| *name == param.token.text)
+    else {
+        return Vec::new();
+    };
+
+    let mut ret: Vec<Symbol> = Vec::new();
+    for id in &owner.generic_instances {
+        let Some(instance) = symbol_table::get(*id) else {
+            continue;
+        };
+        let SymbolKind::GenericInstance(x) = &instance.kind else {
+            continue;
+        };
+        let Some(arg) = x.arguments.get(index) else {
+            continue;
+        };
+        if !arg.is_resolvable() {
+            continue;
+        }
+
+        // Arguments are resolved at the instantiation sites
+        let Some(namespace) = namespace_table::get(arg.range.beg.id) else {
+            continue;
+        };
+        if let Ok(found) = symbol_table::resolve((&arg.mangled_path(), &namespace)) {
+            if !ret.iter().any(|x| x.id == found.found.id) {
+                ret.push(found.found);
+            }
+        }
+    }
+    ret
+}
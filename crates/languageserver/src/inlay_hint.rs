@@ -0,0 +1,256 @@
+use crate::walker::Walker;
+use tower_lsp::lsp_types::{InlayHint, InlayHintKind, InlayHintLabel, Position};
+use veryl_analyzer::evaluator::{Evaluated, Evaluator};
+use veryl_analyzer::symbol::{SymbolId, SymbolKind};
+use veryl_analyzer::symbol_table;
+use veryl_parser::ParolError;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_token::Token;
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
+
+#[derive(Default)]
+pub struct InlayHints {
+    pub hints: Vec<InlayHint>,
+    point: HandlerPoint,
+    default_clock: Option<SymbolId>,
+    default_reset: Option<SymbolId>,
+}
+
+impl InlayHints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collect(&mut self, veryl: &Veryl) {
+        Walker::new(self).veryl(veryl);
+    }
+
+    fn push(&mut self, token: &Token, label: String, kind: Option<InlayHintKind>) {
+        let line = token.line - 1;
+        let column = token.column - 1 + token.length;
+        self.hints.push(InlayHint {
+            position: Position::new(line, column),
+            label: InlayHintLabel::String(label),
+            kind,
+            text_edits: None,
+            tooltip: None,
+            padding_left: Some(true),
+            padding_right: None,
+            data: None,
+        });
+    }
+
+    fn push_width(&mut self, identifier: &Identifier, array_type: &ArrayType) {
+        let Ok(symbol) = symbol_table::resolve(identifier) else {
+            return;
+        };
+        let SymbolKind::Variable(x) = &symbol.found.kind else {
+            return;
+        };
+
+        // Width written by literals only is obvious
+        if x.r#type.width.iter().chain(&x.r#type.array).all(is_literal) {
+            return;
+        }
+
+        if let Some(label) = width_label(&symbol.found.evaluate()) {
+            let range: TokenRange = array_type.into();
+            self.push(&range.end, label, Some(InlayHintKind::TYPE));
+        }
+    }
+
+    fn push_value(&mut self, identifier: &Identifier, expression: &Expression) {
+        if is_literal(expression) {
+            return;
+        }
+        let Ok(symbol) = symbol_table::resolve(identifier) else {
+            return;
+        };
+        if !matches!(symbol.found.kind, SymbolKind::Parameter(_)) {
+            return;
+        }
+
+        if let Some(value) = value_label(&symbol.found.evaluate()) {
+            let range: TokenRange = expression.into();
+            self.push(&range.end, format!("= {value}"), None);
+        }
+    }
+}
+
+impl Handler for InlayHints {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+impl VerylGrammarTrait for InlayHints {
+    fn module_declaration(&mut self, arg: &ModuleDeclaration) -> Result<(), ParolError> {
+        match self.point {
+            HandlerPoint::Before => {
+                if let Ok(found) = symbol_table::resolve(arg.identifier.as_ref()) {
+                    if let SymbolKind::Module(x) = found.found.kind {
+                        self.default_clock = x.default_clock;
+                        self.default_reset = x.default_reset;
+                    }
+                }
+            }
+            HandlerPoint::After => {
+                self.default_clock = None;
+                self.default_reset = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn var_declaration(&mut self, arg: &VarDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            self.push_width(&arg.identifier, &arg.array_type);
+        }
+        Ok(())
+    }
+
+    fn let_declaration(&mut self, arg: &LetDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            self.push_width(&arg.identifier, &arg.array_type);
+        }
+        Ok(())
+    }
+
+    fn const_declaration(&mut self, arg: &ConstDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            self.push_value(&arg.identifier, &arg.expression);
+        }
+        Ok(())
+    }
+
+    fn with_parameter_item(&mut self, arg: &WithParameterItem) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            self.push_value(&arg.identifier, &arg.expression);
+        }
+        Ok(())
+    }
+
+    fn always_ff_declaration(&mut self, arg: &AlwaysFfDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            if arg.has_explicit_clock() {
+                return Ok(());
+            }
+
+            let mut signals = Vec::new();
+            for (id, used) in [
+                (self.default_clock, true),
+                (self.default_reset, arg.has_if_reset()),
+            ] {
+                if let Some(symbol) = id.and_then(symbol_table::get) {
+                    if used {
+                        signals.push(symbol.token.to_string());
+                    }
+                }
+            }
+
+            if !signals.is_empty() {
+                let label = format!("({})", signals.join(", "));
+                self.push(&arg.always_ff.always_ff_token.token, label, None);
+            }
+        }
+        Ok(())
+    }
+
+    fn inst_declaration(&mut self, arg: &InstDeclaration) -> Result<(), ParolError> {
+        if let HandlerPoint::Before = self.point {
+            let Ok(symbol) = symbol_table::resolve(arg.scoped_identifier.as_ref()) else {
+                return Ok(());
+            };
+            let parameters = match symbol.found.kind {
+                SymbolKind::Module(x) => x.parameters,
+                SymbolKind::Interface(x) => x.parameters,
+                _ => return Ok(()),
+            };
+
+            let mut items: Vec<InstParameterItem> = Vec::new();
+            if let Some(ref x) = arg.inst_declaration_opt1 {
+                if let Some(ref x) = x.inst_parameter.inst_parameter_opt {
+                    items = x.inst_parameter_list.as_ref().into();
+                }
+            }
+
+            let mut defaults = Vec::new();
+            for parameter in &parameters {
+                let item = items
+                    .iter()
+                    .find(|x| x.identifier.identifier_token.token.text == parameter.name);
+
+                if let Some(item) = item {
+                    // Overridden value is shown after the given expression
+                    let token = if let Some(ref x) = item.inst_parameter_item_opt {
+                        if is_literal(&x.expression) {
+                            continue;
+                        }
+                        let range: TokenRange = x.expression.as_ref().into();
+                        range.end
+                    } else {
+                        item.identifier.identifier_token.token
+                    };
+                    if let Some(value) = value_label(&Evaluator::new().inst_parameter_item(item)) {
+                        self.push(&token, format!("= {value}"), None);
+                    }
+                } else if let Some(symbol) = symbol_table::get(parameter.symbol) {
+                    if let Some(value) = value_label(&symbol.evaluate()) {
+                        defaults.push(format!("{}: {value}", symbol.token));
+                    }
+                }
+            }
+
+            // Default values of omitted parameters are shown after the parameter list
+            if !defaults.is_empty() {
+                let token = if let Some(ref x) = arg.inst_declaration_opt1 {
+                    x.inst_parameter.r_paren.r_paren_token.token
+                } else if let Some(ref x) = arg.inst_declaration_opt0 {
+                    let range: TokenRange = x.array.as_ref().into();
+                    range.end
+                } else {
+                    let range: TokenRange = arg.scoped_identifier.as_ref().into();
+                    range.end
+                };
+                let label = format!("#({})", defaults.join(", "));
+                self.push(&token, label, Some(InlayHintKind::PARAMETER));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_literal(x: &Expression) -> bool {
+    let range: TokenRange = x.into();
+    range.beg.id == range.end.id
+        && range
+            .beg
+            .to_string()
+            .starts_with(|x: char| x.is_ascii_digit())
+}
+
+fn width_label(x: &Evaluated) -> Option<String> {
+    let join = |x: Vec<usize>| {
+        x.iter()
+            .map(|x| x.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let width = x.get_width()?;
+    let array = x.get_array()?;
+    let mut ret = format!("<{}>", join(width));
+    if !array.is_empty() {
+        ret.push_str(&format!("[{}]", join(array)));
+    }
+    Some(ret)
+}
+
+fn value_label(x: &Evaluated) -> Option<String> {
+    if let Some(x) = x.get_value() {
+        Some(x.to_string())
+    } else {
+        x.get_fixed().map(|x| x.to_string())
+    }
+}
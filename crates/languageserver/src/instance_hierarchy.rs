@@ -0,0 +1,58 @@
+use crate::walker::to_range;
+use serde_json::{Value, json};
+use tower_lsp::lsp_types::{Location, Url};
+use veryl_analyzer::instance_tree::{self, InstanceNode};
+use veryl_analyzer::symbol::SymbolKind;
+use veryl_analyzer::symbol_table;
+use veryl_parser::resource_table;
+use veryl_parser::veryl_token::Token;
+
+/// Name of the custom request
+pub const METHOD: &str = "veryl/instanceHierarchy";
+
+/// Elaborated instance tree from the top module or interface in the project
+pub fn instance_hierarchy(project: &str, top: &str, depth_limit: usize) -> Option<Value> {
+    let project = resource_table::get_str_id(project.to_string())?;
+    let top = symbol_table::get_all().into_iter().find(|x| {
+        matches!(x.kind, SymbolKind::Module(_) | SymbolKind::Interface(_))
+            && x.namespace.paths.first() == Some(&project)
+            && x.token.to_string() == top
+    })?;
+
+    let node = instance_tree::elaborate(&top, depth_limit)?;
+    Some(to_json(&node))
+}
+
+fn to_json(node: &InstanceNode) -> Value {
+    let module = symbol_table::get(node.symbol).map(|x| x.token.to_string());
+
+    let parameters: Vec<_> = node
+        .params
+        .iter()
+        .map(|(name, value)| {
+            let value = if let Some(x) = value.get_value() {
+                Some(x.to_string())
+            } else {
+                value.get_fixed().map(|x| x.to_string())
+            };
+            json!({ "name": name.to_string(), "value": value })
+        })
+        .collect();
+
+    let children: Vec<_> = node.children.iter().map(to_json).collect();
+
+    json!({
+        "name": node.token.to_string(),
+        "module": module,
+        "location": to_location(&node.token),
+        "parameters": parameters,
+        "children": children,
+        "truncated": node.truncated,
+    })
+}
+
+fn to_location(token: &Token) -> Option<Location> {
+    let uri = Url::from_file_path(token.source.to_string()).ok()?;
+    let range = to_range(&token.into());
+    Some(Location { uri, range })
+}
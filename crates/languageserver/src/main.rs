@@ -1,19 +1,30 @@
 #![recursion_limit = "256"]
 
 mod backend;
+mod call_hierarchy;
+mod document_symbol;
+mod emitted_code;
+mod folding_range;
+mod implementation;
+mod inlay_hint;
+mod instance_hierarchy;
 mod keyword;
+mod selection_range;
 mod server;
+mod signature_help;
+mod source_location;
 #[cfg(test)]
 mod tests;
+mod walker;
 
 use backend::Backend;
-use tower_lsp::{LspService, Server};
+use tower_lsp::Server;
 
 #[tokio::main]
 async fn main() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(Backend::new);
+    let (service, socket) = Backend::build_service(Backend::new);
     Server::new(stdin, stdout, socket).serve(service).await;
 }
@@ -0,0 +1,138 @@
+use crate::walker::{Walker, to_range};
+use tower_lsp::lsp_types::{Position, SelectionRange};
+use veryl_parser::ParolError;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::*;
+use veryl_parser::veryl_walker::{Handler, HandlerPoint, VerylWalker};
+
+/// Token ranges of syntax nodes which are candidates of selection
+#[derive(Default)]
+pub struct SelectionRanges {
+    ranges: Vec<TokenRange>,
+    point: HandlerPoint,
+}
+
+impl SelectionRanges {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collect(&mut self, veryl: &Veryl) {
+        Walker::new(self).veryl(veryl);
+    }
+
+    /// Nested selection ranges from the innermost node at the position
+    pub fn get(&self, position: &Position) -> Option<SelectionRange> {
+        let mut ret: Option<SelectionRange> = None;
+
+        // Nodes are visited from outer to inner
+        for range in &self.ranges {
+            let range = to_range(range);
+            if range.start <= *position && *position <= range.end {
+                let nested = ret.as_ref().map(|x| {
+                    x.range != range && x.range.start <= range.start && range.end <= x.range.end
+                });
+                if nested == Some(false) {
+                    continue;
+                }
+                ret = Some(SelectionRange {
+                    range,
+                    parent: ret.map(Box::new),
+                });
+            }
+        }
+
+        ret
+    }
+}
+
+impl Handler for SelectionRanges {
+    fn set_point(&mut self, p: HandlerPoint) {
+        self.point = p;
+    }
+}
+
+macro_rules! selection_range {
+    ($($name:ident: $type:ty,)*) => {
+        $(
+            fn $name(&mut self, arg: &$type) -> Result<(), ParolError> {
+                if let HandlerPoint::Before = self.point {
+                    self.ranges.push(arg.into());
+                }
+                Ok(())
+            }
+        )*
+    };
+}
+
+impl VerylGrammarTrait for SelectionRanges {
+    selection_range!(
+        identifier: Identifier,
+        number: Number,
+        scoped_identifier: ScopedIdentifier,
+        expression_identifier: ExpressionIdentifier,
+        hierarchical_identifier: HierarchicalIdentifier,
+        select: Select,
+        factor: Factor,
+        function_call: FunctionCall,
+        argument_item: ArgumentItem,
+        expression: Expression,
+        expression01: Expression01,
+        expression02: Expression02,
+        expression03: Expression03,
+        expression04: Expression04,
+        expression05: Expression05,
+        expression06: Expression06,
+        expression07: Expression07,
+        expression08: Expression08,
+        expression09: Expression09,
+        expression10: Expression10,
+        expression11: Expression11,
+        expression12: Expression12,
+        expression13: Expression13,
+        scalar_type: ScalarType,
+        array_type: ArrayType,
+        statement_block: StatementBlock,
+        let_statement: LetStatement,
+        identifier_statement: IdentifierStatement,
+        if_statement: IfStatement,
+        if_reset_statement: IfResetStatement,
+        return_statement: ReturnStatement,
+        for_statement: ForStatement,
+        case_statement: CaseStatement,
+        case_item: CaseItem,
+        switch_statement: SwitchStatement,
+        switch_item: SwitchItem,
+        attribute: Attribute,
+        let_declaration: LetDeclaration,
+        var_declaration: VarDeclaration,
+        const_declaration: ConstDeclaration,
+        type_def_declaration: TypeDefDeclaration,
+        always_ff_declaration: AlwaysFfDeclaration,
+        always_comb_declaration: AlwaysCombDeclaration,
+        assign_declaration: AssignDeclaration,
+        modport_declaration: ModportDeclaration,
+        enum_declaration: EnumDeclaration,
+        enum_item: EnumItem,
+        struct_union_declaration: StructUnionDeclaration,
+        struct_union_item: StructUnionItem,
+        initial_declaration: InitialDeclaration,
+        final_declaration: FinalDeclaration,
+        inst_declaration: InstDeclaration,
+        inst_parameter: InstParameter,
+        inst_parameter_item: InstParameterItem,
+        inst_port_item: InstPortItem,
+        with_parameter: WithParameter,
+        with_parameter_item: WithParameterItem,
+        port_declaration: PortDeclaration,
+        port_declaration_item: PortDeclarationItem,
+        function_declaration: FunctionDeclaration,
+        module_declaration: ModuleDeclaration,
+        interface_declaration: InterfaceDeclaration,
+        generate_if_declaration: GenerateIfDeclaration,
+        generate_for_declaration: GenerateForDeclaration,
+        generate_named_block: GenerateNamedBlock,
+        package_declaration: PackageDeclaration,
+        description_group: DescriptionGroup,
+    );
+}
@@ -1,13 +1,27 @@
+use crate::call_hierarchy;
+use crate::document_symbol::DocumentSymbols;
+use crate::emitted_code::EmittedCode;
+use crate::folding_range::FoldingRanges;
+use crate::implementation::{implementations, type_definitions};
+use crate::inlay_hint::InlayHints;
+use crate::instance_hierarchy::instance_hierarchy;
 use crate::keyword::KEYWORDS;
+use crate::selection_range::SelectionRanges;
+use crate::signature_help::signature_help;
+use crate::source_location::source_location;
 use async_channel::{Receiver, Sender};
 use dashmap::DashMap;
 use futures::executor::block_on;
 use ropey::Rope;
-use std::collections::VecDeque;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
 use std::path::{Path, PathBuf};
 use tower_lsp::Client;
 use tower_lsp::lsp_types::*;
+use veryl_analyzer::analyzer_error::ANALYZER_ERROR_CODES;
+use veryl_analyzer::lint::{self, LintError};
 use veryl_analyzer::namespace::Namespace;
+use veryl_analyzer::suggestion::{Edit, Fix, Suggestion};
 use veryl_analyzer::symbol::SymbolKind as VerylSymbolKind;
 use veryl_analyzer::symbol::{Symbol, TypeKind};
 use veryl_analyzer::symbol_path::SymbolPath;
@@ -16,10 +30,10 @@ use veryl_analyzer::{
     unsafe_table,
 };
 use veryl_formatter::Formatter;
-use veryl_metadata::Metadata;
+use veryl_metadata::{Lint, Metadata};
 use veryl_parser::resource_table::{self, PathId};
 use veryl_parser::text_table;
-use veryl_parser::veryl_token::Token;
+use veryl_parser::veryl_token::{Token, TokenSource};
 use veryl_parser::veryl_walker::VerylWalker;
 use veryl_parser::{Finder, Parser, ParserError};
 use veryl_path::PathSet;
@@ -32,7 +46,7 @@ pub enum MsgToServer {
     },
     DidChange {
         url: Url,
-        text: String,
+        changes: Vec<TextDocumentContentChangeEvent>,
         version: i32,
     },
     DidChangeConfiguration(ServerConfigItem),
@@ -57,6 +71,16 @@ pub enum MsgToServer {
         line: usize,
         column: usize,
     },
+    GotoImplementation {
+        url: Url,
+        line: usize,
+        column: usize,
+    },
+    GotoTypeDefinition {
+        url: Url,
+        line: usize,
+        column: usize,
+    },
     Symbol {
         query: String,
     },
@@ -70,6 +94,58 @@ pub enum MsgToServer {
         line: usize,
         column: usize,
     },
+    PrepareRename {
+        url: Url,
+        line: usize,
+        column: usize,
+    },
+    Rename {
+        url: Url,
+        line: usize,
+        column: usize,
+        new_name: String,
+    },
+    CodeAction {
+        diagnostics: Vec<Diagnostic>,
+    },
+    InlayHint {
+        url: Url,
+        range: Range,
+    },
+    SignatureHelp {
+        url: Url,
+        line: usize,
+        column: usize,
+    },
+    DocumentSymbol {
+        url: Url,
+    },
+    FoldingRange {
+        url: Url,
+    },
+    SelectionRange {
+        url: Url,
+        positions: Vec<Position>,
+    },
+    PrepareCallHierarchy {
+        url: Url,
+        line: usize,
+        column: usize,
+    },
+    IncomingCalls {
+        item: CallHierarchyItem,
+    },
+    OutgoingCalls {
+        item: CallHierarchyItem,
+    },
+    InstanceHierarchy {
+        url: Url,
+        top: String,
+    },
+    SourceLocation {
+        url: Url,
+        position: Position,
+    },
     SemanticTokens {
         url: Url,
     },
@@ -81,13 +157,35 @@ pub enum MsgToServer {
 pub enum MsgFromServer {
     Completion(Option<CompletionResponse>),
     GotoDefinition(Option<Location>),
+    GotoImplementation(Option<GotoDefinitionResponse>),
+    GotoTypeDefinition(Option<GotoDefinitionResponse>),
     Symbol(Vec<SymbolInformation>),
     Hover(Option<Hover>),
     References(Vec<Location>),
+    PrepareRename(Option<PrepareRenameResponse>),
+    Rename(Result<Option<WorkspaceEdit>, String>),
+    CodeAction(Option<CodeActionResponse>),
+    InlayHint(Option<Vec<InlayHint>>),
+    SignatureHelp(Option<SignatureHelp>),
+    DocumentSymbol(Option<DocumentSymbolResponse>),
+    FoldingRange(Option<Vec<FoldingRange>>),
+    SelectionRange(Option<Vec<SelectionRange>>),
+    PrepareCallHierarchy(Option<Vec<CallHierarchyItem>>),
+    IncomingCalls(Option<Vec<CallHierarchyIncomingCall>>),
+    OutgoingCalls(Option<Vec<CallHierarchyOutgoingCall>>),
+    InstanceHierarchy(Option<Value>),
+    SourceLocation(Option<Location>),
     SemanticTokens(Option<SemanticTokensResult>),
     Formatting(Option<Vec<TextEdit>>),
 }
 
+/// File which is analyzed again because it refers the changed file
+struct Dependent {
+    path: PathBuf,
+    parser: Parser,
+    errors: Vec<AnalyzerError>,
+}
+
 pub struct BackgroundTask {
     metadata: Metadata,
     paths: Vec<PathSet>,
@@ -153,9 +251,15 @@ impl Server {
                         self.did_open(&url, &text, version);
                         self.latest_change = Some((url, text, version));
                     }
-                    MsgToServer::DidChange { url, text, version } => {
-                        self.did_change(&url, &text, version);
-                        self.latest_change = Some((url, text, version));
+                    MsgToServer::DidChange {
+                        url,
+                        changes,
+                        version,
+                    } => {
+                        if let Some(text) = self.apply_changes(&url, &changes) {
+                            self.did_change(&url, &text, version);
+                            self.latest_change = Some((url, text, version));
+                        }
                     }
                     MsgToServer::DidChangeConfiguration(x) => self.config.set(x),
                     MsgToServer::WillRenameFile { old_url } => self.on_remove(old_url),
@@ -170,11 +274,47 @@ impl Server {
                     MsgToServer::GotoDefinition { url, line, column } => {
                         self.goto_definition(&url, line, column)
                     }
+                    MsgToServer::GotoImplementation { url, line, column } => {
+                        self.goto_implementation(&url, line, column)
+                    }
+                    MsgToServer::GotoTypeDefinition { url, line, column } => {
+                        self.goto_type_definition(&url, line, column)
+                    }
                     MsgToServer::Symbol { query } => self.symbol(&query),
                     MsgToServer::Hover { url, line, column } => self.hover(&url, line, column),
                     MsgToServer::References { url, line, column } => {
                         self.references(&url, line, column)
                     }
+                    MsgToServer::PrepareRename { url, line, column } => {
+                        self.prepare_rename(&url, line, column)
+                    }
+                    MsgToServer::Rename {
+                        url,
+                        line,
+                        column,
+                        new_name,
+                    } => self.rename(&url, line, column, &new_name),
+                    MsgToServer::CodeAction { diagnostics } => self.code_action(diagnostics),
+                    MsgToServer::InlayHint { url, range } => self.inlay_hint(&url, &range),
+                    MsgToServer::SignatureHelp { url, line, column } => {
+                        self.signature_help(&url, line, column)
+                    }
+                    MsgToServer::DocumentSymbol { url } => self.document_symbol(&url),
+                    MsgToServer::FoldingRange { url } => self.folding_range(&url),
+                    MsgToServer::SelectionRange { url, positions } => {
+                        self.selection_range(&url, &positions)
+                    }
+                    MsgToServer::PrepareCallHierarchy { url, line, column } => {
+                        self.prepare_call_hierarchy(&url, line, column)
+                    }
+                    MsgToServer::IncomingCalls { item } => self.incoming_calls(&item),
+                    MsgToServer::OutgoingCalls { item } => self.outgoing_calls(&item),
+                    MsgToServer::InstanceHierarchy { url, top } => {
+                        self.instance_hierarchy(&url, &top)
+                    }
+                    MsgToServer::SourceLocation { url, position } => {
+                        self.source_location(&url, &position)
+                    }
                     MsgToServer::SemanticTokens { url } => self.semantic_tokens(&url),
                     MsgToServer::Formatting { url } => self.formatting(&url),
                 }
@@ -253,6 +393,31 @@ impl Server {
         }
     }
 
+    /// Apply incremental changes to the current document
+    fn apply_changes(
+        &self,
+        url: &Url,
+        changes: &[TextDocumentContentChangeEvent],
+    ) -> Option<String> {
+        let path = url.to_file_path().ok()?;
+        let mut rope = self
+            .document_map
+            .get(&path)
+            .map(|x| x.clone())
+            .unwrap_or_default();
+        for change in changes {
+            if let Some(range) = change.range {
+                let start = to_char_idx(&rope, range.start);
+                let end = to_char_idx(&rope, range.end);
+                rope.remove(start..end);
+                rope.insert(start, &change.text);
+            } else {
+                rope = Rope::from_str(&change.text);
+            }
+        }
+        Some(rope.to_string())
+    }
+
     fn did_rename_files(&mut self, new_path: Url) {
         // Do not dispatch if there's already a pending analysis
         if !self.background_done {
@@ -427,6 +592,8 @@ impl Server {
     }
 
     fn hover(&mut self, url: &Url, line: usize, column: usize) {
+        let metadata = self.get_metadata(url);
+        let mut contents = Vec::new();
         if let Ok(path) = url.to_file_path() {
             if let Some(parser) = self.parser_map.get(&path) {
                 let mut finder = Finder::new();
@@ -442,20 +609,36 @@ impl Server {
                         };
                         if let Ok(symbol) = symbol_table::resolve((&path, &namespace)) {
                             let text = symbol.found.kind.to_string();
-                            let hover = Hover {
-                                contents: HoverContents::Scalar(MarkedString::String(text)),
-                                range: None,
-                            };
-                            self.snd
-                                .send_blocking(MsgFromServer::Hover(Some(hover)))
-                                .unwrap();
-                            return;
+                            contents.push(MarkedString::String(text));
                         }
                     }
                 }
+
+                if let Some(metadata) = &metadata {
+                    let mut emitted_code = EmittedCode::new();
+                    emitted_code.collect(&parser.veryl);
+                    if let Some(value) =
+                        emitted_code.get(metadata, &path, &parser.veryl, line as u32, column as u32)
+                    {
+                        contents.push(MarkedString::LanguageString(LanguageString {
+                            language: "systemverilog".to_string(),
+                            value,
+                        }));
+                    }
+                }
             }
         }
-        self.snd.send_blocking(MsgFromServer::Hover(None)).unwrap();
+
+        let hover = match contents.len() {
+            0 => None,
+            1 => Some(HoverContents::Scalar(contents.pop().unwrap())),
+            _ => Some(HoverContents::Array(contents)),
+        };
+        let hover = hover.map(|contents| Hover {
+            contents,
+            range: None,
+        });
+        self.snd.send_blocking(MsgFromServer::Hover(hover)).unwrap();
     }
 
     fn references(&mut self, url: &Url, line: usize, column: usize) {
@@ -488,6 +671,408 @@ impl Server {
             .unwrap();
     }
 
+    fn prepare_rename(&mut self, url: &Url, line: usize, column: usize) {
+        let ret = self
+            .rename_target(url, line, column)
+            .map(|(token, _)| PrepareRenameResponse::Range(to_location(&token).range));
+        self.snd
+            .send_blocking(MsgFromServer::PrepareRename(ret))
+            .unwrap();
+    }
+
+    fn rename(&mut self, url: &Url, line: usize, column: usize, new_name: &str) {
+        let ret = check_new_name(new_name).map(|_| {
+            self.rename_target(url, line, column)
+                .map(|(_, symbol)| self.rename_edit(&symbol, new_name))
+        });
+        self.snd.send_blocking(MsgFromServer::Rename(ret)).unwrap();
+    }
+
+    /// Find the symbol which has the token at the position as its declaration or a reference.
+    /// Symbols outside of the project like dependencies can't be renamed.
+    fn rename_target(&self, url: &Url, line: usize, column: usize) -> Option<(Token, Symbol)> {
+        let path = url.to_file_path().ok()?;
+        let parser = self.parser_map.get(&path)?;
+        let mut finder = Finder::new();
+        finder.line = line as u32;
+        finder.column = column as u32;
+        finder.veryl(&parser.veryl);
+
+        let token = finder.token?;
+        let namespace = namespace_table::get(token.id)?;
+        let path = if finder.token_group.is_empty() {
+            SymbolPath::new(&[token.text])
+        } else {
+            SymbolPath::from(finder.token_group.as_slice())
+        };
+
+        let contains = |x: &Symbol| {
+            x.token.text == token.text
+                && (x.token.id == token.id || x.references.iter().any(|y| y.id == token.id))
+        };
+
+        // Port and parameter names of instances are not resolved in the current namespace,
+        // so the symbol which refers the token is searched instead
+        let symbol = match symbol_table::resolve((&path, &namespace)) {
+            Ok(x) if contains(&x.found) => x.found,
+            _ => symbol_table::get_all().into_iter().find(contains)?,
+        };
+
+        let renamable = !matches!(
+            symbol.kind,
+            VerylSymbolKind::SystemVerilog
+                | VerylSymbolKind::Namespace
+                | VerylSymbolKind::SystemFunction(_)
+                | VerylSymbolKind::EnumMemberMangled
+        );
+        let in_project = match symbol.token.source {
+            TokenSource::File { path, .. } => {
+                !PathBuf::from(path.to_string()).starts_with(&self.cache_dir)
+            }
+            _ => false,
+        };
+
+        if renamable && in_project {
+            Some((token, symbol))
+        } else {
+            None
+        }
+    }
+
+    fn rename_edit(&self, symbol: &Symbol, new_name: &str) -> WorkspaceEdit {
+        let connects: Vec<_> = symbol_table::get_all()
+            .into_iter()
+            .filter_map(|x| match x.kind {
+                VerylSymbolKind::Instance(x) => Some(x.connects.into_keys()),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+
+        let mut tokens = vec![symbol.token];
+        tokens.extend(
+            symbol
+                .references
+                .iter()
+                .filter(|x| x.text == symbol.token.text),
+        );
+        tokens.sort_by_key(|x| (x.source.to_string(), x.line, x.column));
+        tokens.dedup_by_key(|x| (x.source, x.line, x.column));
+
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        for token in tokens {
+            let location = to_location(&token);
+            let implicit =
+                connects.iter().any(|x| x.id == token.id) && !self.is_followed_by_colon(&token);
+
+            // Implicit connection like `inst u: Foo (a)` is expanded to keep the other side
+            let new_text = if implicit {
+                let old_name = token.to_string();
+                let is_port = namespace_table::get(token.id)
+                    .map(|x| !x.included(&symbol.namespace))
+                    .unwrap_or(false);
+                if is_port {
+                    format!("{new_name}: {old_name}")
+                } else {
+                    format!("{old_name}: {new_name}")
+                }
+            } else {
+                new_name.to_string()
+            };
+
+            changes.entry(location.uri).or_default().push(TextEdit {
+                range: location.range,
+                new_text,
+            });
+        }
+
+        WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }
+    }
+
+    /// Code actions are attached to diagnostics when they are published,
+    /// so they are taken from the diagnostics in the request
+    fn code_action(&mut self, diagnostics: Vec<Diagnostic>) {
+        let mut ret = Vec::new();
+        for diag in diagnostics {
+            let Some(data) = diag.data.clone() else {
+                continue;
+            };
+            let Ok(actions) = serde_json::from_value::<Vec<CodeAction>>(data) else {
+                continue;
+            };
+            for mut action in actions {
+                action.diagnostics = Some(vec![diag.clone()]);
+                ret.push(CodeActionOrCommand::CodeAction(action));
+            }
+        }
+
+        let ret = if ret.is_empty() { None } else { Some(ret) };
+        self.snd
+            .send_blocking(MsgFromServer::CodeAction(ret))
+            .unwrap();
+    }
+
+    fn to_code_actions(&self, suggestions: &[Suggestion]) -> Vec<CodeAction> {
+        suggestions
+            .iter()
+            .filter_map(|x| {
+                let edit = match &x.fix {
+                    Fix::Edit(edits) => self.to_workspace_edit(edits)?,
+                    Fix::Rename { token, name } => {
+                        let symbol = symbol_table::get_all()
+                            .into_iter()
+                            .find(|x| x.token.id == token.id)?;
+                        self.rename_edit(&symbol, name)
+                    }
+                };
+                Some(CodeAction {
+                    title: x.message.clone(),
+                    kind: Some(CodeActionKind::QUICKFIX),
+                    edit: Some(edit),
+                    is_preferred: Some(true),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    fn to_workspace_edit(&self, edits: &[Edit]) -> Option<WorkspaceEdit> {
+        let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+        for edit in edits {
+            let path = resource_table::get_path_value(edit.path)?;
+            let rope = if let Some(rope) = self.document_map.get(&path) {
+                rope.clone()
+            } else {
+                Rope::from_str(&std::fs::read_to_string(&path).ok()?)
+            };
+            let range = Range::new(to_position(&rope, edit.beg), to_position(&rope, edit.end));
+            let url = Url::from_file_path(&path).ok()?;
+            changes.entry(url).or_default().push(TextEdit {
+                range,
+                new_text: edit.text.clone(),
+            });
+        }
+
+        Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        })
+    }
+
+    fn inlay_hint(&mut self, url: &Url, range: &Range) {
+        let mut ret = None;
+
+        if let Ok(path) = url.to_file_path() {
+            if let Some(parser) = self.parser_map.get(&path) {
+                let mut hints = InlayHints::new();
+                hints.collect(&parser.veryl);
+                ret = Some(
+                    hints
+                        .hints
+                        .into_iter()
+                        .filter(|x| range.start <= x.position && x.position <= range.end)
+                        .collect(),
+                );
+            }
+        }
+
+        self.snd
+            .send_blocking(MsgFromServer::InlayHint(ret))
+            .unwrap();
+    }
+
+    /// The text is scanned instead of the syntax tree because it can't be parsed while typing
+    fn signature_help(&mut self, url: &Url, line: usize, column: usize) {
+        let mut ret = None;
+
+        if let Ok(path) = url.to_file_path() {
+            if let Some(rope) = self.document_map.get(&path) {
+                let position = Position::new(line as u32 - 1, column as u32 - 1);
+                let text = rope.slice(..to_char_idx(&rope, position)).to_string();
+                if let Some(namespace) = current_namespace(url, line, column) {
+                    ret = signature_help(&text, &namespace);
+                }
+            }
+        }
+
+        self.snd
+            .send_blocking(MsgFromServer::SignatureHelp(ret))
+            .unwrap();
+    }
+
+    fn document_symbol(&mut self, url: &Url) {
+        let mut ret = None;
+
+        if let Ok(path) = url.to_file_path() {
+            if let Some(parser) = self.parser_map.get(&path) {
+                let mut symbols = DocumentSymbols::new();
+                symbols.collect(&parser.veryl);
+                ret = Some(DocumentSymbolResponse::Nested(symbols.symbols));
+            }
+        }
+
+        self.snd
+            .send_blocking(MsgFromServer::DocumentSymbol(ret))
+            .unwrap();
+    }
+
+    fn folding_range(&mut self, url: &Url) {
+        let mut ret = None;
+
+        if let Ok(path) = url.to_file_path() {
+            if let Some(parser) = self.parser_map.get(&path) {
+                let mut ranges = FoldingRanges::new();
+                ranges.collect(&parser.veryl);
+                ret = Some(ranges.ranges);
+            }
+        }
+
+        self.snd
+            .send_blocking(MsgFromServer::FoldingRange(ret))
+            .unwrap();
+    }
+
+    fn selection_range(&mut self, url: &Url, positions: &[Position]) {
+        let mut ret = None;
+
+        if let Ok(path) = url.to_file_path() {
+            if let Some(parser) = self.parser_map.get(&path) {
+                let mut ranges = SelectionRanges::new();
+                ranges.collect(&parser.veryl);
+
+                // The result must have the same length as positions
+                let selections = positions
+                    .iter()
+                    .map(|x| {
+                        ranges.get(x).unwrap_or(SelectionRange {
+                            range: Range::new(*x, *x),
+                            parent: None,
+                        })
+                    })
+                    .collect();
+                ret = Some(selections);
+            }
+        }
+
+        self.snd
+            .send_blocking(MsgFromServer::SelectionRange(ret))
+            .unwrap();
+    }
+
+    fn goto_implementation(&mut self, url: &Url, line: usize, column: usize) {
+        let ret = self.resolve_at(url, line, column).map(|symbol| {
+            let locations = implementations(&symbol)
+                .iter()
+                .map(|x| to_location(&x.token))
+                .collect();
+            GotoDefinitionResponse::Array(locations)
+        });
+
+        self.snd
+            .send_blocking(MsgFromServer::GotoImplementation(ret))
+            .unwrap();
+    }
+
+    fn goto_type_definition(&mut self, url: &Url, line: usize, column: usize) {
+        let ret = self.resolve_at(url, line, column).map(|symbol| {
+            let locations = type_definitions(&symbol)
+                .iter()
+                .map(|x| to_location(&x.token))
+                .collect();
+            GotoDefinitionResponse::Array(locations)
+        });
+
+        self.snd
+            .send_blocking(MsgFromServer::GotoTypeDefinition(ret))
+            .unwrap();
+    }
+
+    /// Symbol referred by the identifier at the position
+    fn resolve_at(&self, url: &Url, line: usize, column: usize) -> Option<Symbol> {
+        let path = url.to_file_path().ok()?;
+        let parser = self.parser_map.get(&path)?;
+        let mut finder = Finder::new();
+        finder.line = line as u32;
+        finder.column = column as u32;
+        finder.veryl(&parser.veryl);
+
+        let token = finder.token?;
+        let namespace = namespace_table::get(token.id)?;
+        let path = if finder.token_group.is_empty() {
+            SymbolPath::new(&[token.text])
+        } else {
+            SymbolPath::from(finder.token_group.as_slice())
+        };
+        symbol_table::resolve((&path, &namespace))
+            .ok()
+            .map(|x| x.found)
+    }
+
+    fn prepare_call_hierarchy(&mut self, url: &Url, line: usize, column: usize) {
+        let ret = self
+            .resolve_at(url, line, column)
+            .filter(|x| matches!(x.kind, VerylSymbolKind::Function(_)))
+            .and_then(|x| call_hierarchy::to_item(&x))
+            .map(|x| vec![x]);
+
+        self.snd
+            .send_blocking(MsgFromServer::PrepareCallHierarchy(ret))
+            .unwrap();
+    }
+
+    fn incoming_calls(&mut self, item: &CallHierarchyItem) {
+        let ret = call_hierarchy::incoming_calls(item);
+        self.snd
+            .send_blocking(MsgFromServer::IncomingCalls(Some(ret)))
+            .unwrap();
+    }
+
+    fn outgoing_calls(&mut self, item: &CallHierarchyItem) {
+        let ret = call_hierarchy::outgoing_calls(item);
+        self.snd
+            .send_blocking(MsgFromServer::OutgoingCalls(Some(ret)))
+            .unwrap();
+    }
+
+    fn instance_hierarchy(&mut self, url: &Url, top: &str) {
+        let ret = self.get_metadata(url).and_then(|metadata| {
+            instance_hierarchy(
+                &metadata.project.name,
+                top,
+                metadata.build.instance_depth_limit,
+            )
+        });
+        self.snd
+            .send_blocking(MsgFromServer::InstanceHierarchy(ret))
+            .unwrap();
+    }
+
+    fn source_location(&mut self, url: &Url, position: &Position) {
+        let ret = url
+            .to_file_path()
+            .ok()
+            .and_then(|path| source_location(&path, position));
+        self.snd
+            .send_blocking(MsgFromServer::SourceLocation(ret))
+            .unwrap();
+    }
+
+    fn is_followed_by_colon(&self, token: &Token) -> bool {
+        let path = PathBuf::from(token.source.to_string());
+        let text = if let Some(rope) = self.document_map.get(&path) {
+            rope.to_string()
+        } else {
+            token.source.get_text()
+        };
+        let pos = (token.pos + token.length) as usize;
+        text.get(pos..)
+            .map(|x| x.trim_start().starts_with(':'))
+            .unwrap_or(false)
+    }
+
     fn semantic_tokens(&mut self, url: &Url) {
         let mut ret = None;
 
@@ -670,6 +1255,12 @@ impl Server {
                 return Some(metadata.to_owned());
             } else if let Ok(metadata_path) = Metadata::search_from(&path) {
                 if let Ok(metadata) = Metadata::load(metadata_path) {
+                    if let Err(x) = metadata.lint.check_rules(ANALYZER_ERROR_CODES) {
+                        block_on(
+                            self.client
+                                .show_message(MessageType::WARNING, x.to_string()),
+                        );
+                    }
                     self.metadata_map.insert(path, metadata.clone());
                     return Some(metadata);
                 }
@@ -686,40 +1277,50 @@ impl Server {
                 return;
             }
 
+            // Code actions refer the latest text to convert offsets of suggestions
+            self.document_map.insert(path.clone(), rope.clone());
+
             if let Some(metadata) = self.get_metadata(url) {
                 let diag = match Parser::parse(text, &path) {
                     Ok(x) => {
-                        if let Some(path) =
+                        let analyzer = Analyzer::new(&metadata);
+
+                        // Files referring symbols of the changed file lose their references by
+                        // drop_tables, so only they are analyzed again with the changed file
+                        let dependents = if let Some(path) =
                             resource_table::get_path_id(Path::new(&path).to_path_buf())
                         {
+                            let dependents = symbol_table::dependents(path);
                             drop_tables(path);
-                        }
-                        let analyzer = Analyzer::new(&metadata);
+                            dependents
+                        } else {
+                            Vec::new()
+                        };
+
                         let mut errors = analyzer.analyze_pass1(prj, &path, &x.veryl);
-                        errors.append(&mut Analyzer::analyze_post_pass1());
+                        let mut dependents: Vec<_> = dependents
+                            .into_iter()
+                            .filter_map(|x| self.reanalyze_pass1(prj, &analyzer, x))
+                            .collect();
+
+                        for error in Analyzer::analyze_post_pass1() {
+                            let dependent = error_path(&error)
+                                .and_then(|x| dependents.iter_mut().find(|y| y.path == x));
+                            if let Some(dependent) = dependent {
+                                dependent.errors.push(error);
+                            } else {
+                                errors.push(error);
+                            }
+                        }
+
                         errors.append(&mut analyzer.analyze_pass2(prj, &path, &x.veryl));
                         errors.append(&mut analyzer.analyze_pass3(prj, &path, &x.veryl));
-                        let ret: Vec<_> = errors
-                            .drain(0..)
-                            .filter(|x| {
-                                // Filter errors caused by unresolve error until background completion
-                                if self.background_done {
-                                    true
-                                } else {
-                                    !matches!(
-                                        x,
-                                        AnalyzerError::UndefinedIdentifier { .. }
-                                            | AnalyzerError::UnknownMember { .. }
-                                            | AnalyzerError::UnassignVariable { .. }
-                                    )
-                                }
-                            })
-                            .map(|x| {
-                                let x: miette::ErrReport = x.into();
-                                to_diag(x, &rope)
-                            })
-                            .collect();
+                        let ret = self.to_diags(errors, &rope, &metadata.lint);
                         self.parser_map.insert(path.clone(), x);
+
+                        for dependent in dependents {
+                            self.update_dependent(prj, &analyzer, &metadata.lint, dependent);
+                        }
                         ret
                     }
                     Err(x) => {
@@ -738,11 +1339,86 @@ impl Server {
                         .log_message(MessageType::INFO, format!("failed to load metadata: {url}")),
                 );
             }
-
-            self.document_map.insert(path.clone(), rope);
         }
     }
 
+    fn reanalyze_pass1(&self, prj: &str, analyzer: &Analyzer, id: PathId) -> Option<Dependent> {
+        let path = resource_table::get_path_value(id)?;
+        let text = if let Some(rope) = self.document_map.get(&path) {
+            rope.to_string()
+        } else {
+            std::fs::read_to_string(&path).ok()?
+        };
+        let parser = Parser::parse(&text, &path).ok()?;
+        drop_tables(id);
+        let errors = analyzer.analyze_pass1(prj, &path, &parser.veryl);
+        Some(Dependent {
+            path,
+            parser,
+            errors,
+        })
+    }
+
+    /// Re-analyze the dependent file, and update its diagnostics if it is opened
+    fn update_dependent(
+        &mut self,
+        prj: &str,
+        analyzer: &Analyzer,
+        lint: &Lint,
+        dependent: Dependent,
+    ) {
+        // Pass2 and pass3 should be executed even if the file is not opened
+        // because tables dropped by pass1 are rebuilt through them
+        let veryl = &dependent.parser.veryl;
+        let mut errors = dependent.errors;
+        errors.append(&mut analyzer.analyze_pass2(prj, &dependent.path, veryl));
+        errors.append(&mut analyzer.analyze_pass3(prj, &dependent.path, veryl));
+
+        let Some(rope) = self.document_map.get(&dependent.path).map(|x| x.clone()) else {
+            return;
+        };
+        let Ok(url) = Url::from_file_path(&dependent.path) else {
+            return;
+        };
+
+        let diag = self.to_diags(errors, &rope, lint);
+        self.parser_map.insert(dependent.path, dependent.parser);
+
+        block_on(self.client.publish_diagnostics(url, diag, None));
+    }
+
+    fn to_diags(&self, errors: Vec<AnalyzerError>, rope: &Rope, lint: &Lint) -> Vec<Diagnostic> {
+        let errors = errors
+            .into_iter()
+            .filter(|x| {
+                // Filter errors caused by unresolve error until background completion
+                if self.background_done {
+                    true
+                } else {
+                    !matches!(
+                        x,
+                        AnalyzerError::UndefinedIdentifier { .. }
+                            | AnalyzerError::UnknownMember { .. }
+                            | AnalyzerError::UnassignVariable { .. }
+                    )
+                }
+            })
+            .collect();
+
+        lint::apply(errors, lint)
+            .into_iter()
+            .map(|x| {
+                let actions = self.to_code_actions(x.error.suggestions());
+                let x: miette::ErrReport = x.into();
+                let mut diag = to_diag(x, rope);
+                if !actions.is_empty() {
+                    diag.data = serde_json::to_value(actions).ok();
+                }
+                diag
+            })
+            .collect()
+    }
+
     fn on_remove(&mut self, path: Url) {
         if let Ok(path) = path.to_file_path() {
             if let Some(path_id) = resource_table::get_path_id(Path::new(&path).to_path_buf()) {
@@ -752,6 +1428,38 @@ impl Server {
     }
 }
 
+/// Path of the file where the error is reported
+fn error_path(err: &AnalyzerError) -> Option<PathBuf> {
+    use miette::Diagnostic;
+    let label = err.labels()?.next()?;
+    let source = err.source_code()?.read_span(label.inner(), 0, 0).ok()?;
+    source.name().map(PathBuf::from)
+}
+
+/// Convert byte offset to position counted by UTF-16 code units
+fn to_position(rope: &Rope, offset: usize) -> Position {
+    let char_idx = rope.byte_to_char(offset.min(rope.len_bytes()));
+    let line = rope.char_to_line(char_idx);
+    let character =
+        rope.char_to_utf16_cu(char_idx) - rope.char_to_utf16_cu(rope.line_to_char(line));
+    Position::new(line as u32, character as u32)
+}
+
+/// Convert position counted by UTF-16 code units to char index
+fn to_char_idx(rope: &Rope, position: Position) -> usize {
+    let line = position.line as usize;
+    if line >= rope.len_lines() {
+        return rope.len_chars();
+    }
+    let end = if line + 1 < rope.len_lines() {
+        rope.line_to_char(line + 1)
+    } else {
+        rope.len_chars()
+    };
+    let cu = rope.char_to_utf16_cu(rope.line_to_char(line)) + position.character as usize;
+    rope.utf16_cu_to_char(cu.min(rope.len_utf16_cu())).min(end)
+}
+
 fn to_diag(err: miette::ErrReport, rope: &Rope) -> Diagnostic {
     let miette_diag: &dyn miette::Diagnostic = err.as_ref();
 
@@ -788,7 +1496,7 @@ fn to_diag(err: miette::ErrReport, rope: &Rope) -> Diagnostic {
             }
         };
         (DiagnosticSeverity::ERROR, msg)
-    } else if let Some(x) = err.downcast_ref::<AnalyzerError>() {
+    } else if let Some(x) = err.downcast_ref::<LintError>() {
         use miette::Diagnostic;
         let (severity, text) = match x.severity() {
             Some(miette::Severity::Error) => (DiagnosticSeverity::ERROR, "Error"),
@@ -824,6 +1532,33 @@ fn to_location(token: &Token) -> Location {
     Location { uri, range }
 }
 
+fn check_new_name(name: &str) -> Result<(), String> {
+    let (raw, identifier) = match name.strip_prefix("r#") {
+        Some(x) => (true, x),
+        None => (false, name),
+    };
+
+    let mut chars = identifier.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|x| x.is_ascii_alphabetic() || x == '_')
+        && chars.all(|x| x.is_ascii_alphanumeric() || x == '_' || x == '$');
+
+    if !valid {
+        Err(format!("{name} is not a valid identifier"))
+    } else if !raw && KEYWORDS.contains(&identifier) {
+        Err(format!("{name} is a keyword"))
+    } else if identifier.starts_with("__") {
+        Err(format!("{name} is reserved for compiler usage"))
+    } else if symbol_table::is_sv_keyword(identifier) {
+        Err(format!(
+            "{name} is a SystemVerilog keyword which may not be used as identifier"
+        ))
+    } else {
+        Ok(())
+    }
+}
+
 fn completion_item_operator(
     line: usize,
     column: usize,
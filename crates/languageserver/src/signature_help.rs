@@ -0,0 +1,285 @@
+use tower_lsp::lsp_types::*;
+use veryl_analyzer::namespace::Namespace;
+use veryl_analyzer::symbol::{
+    ClockDomain, Parameter, ParameterKind, Port, Symbol, SymbolId, SymbolKind,
+};
+use veryl_analyzer::symbol_table;
+use veryl_parser::resource_table;
+use veryl_parser::stringifier::Stringifier;
+use veryl_parser::veryl_walker::VerylWalker;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ListKind {
+    /// `#(` of module/interface instance
+    Parameter,
+    /// `(` of module instance or function call
+    Port,
+    /// `::<` of generic arguments
+    Generic,
+}
+
+struct Call {
+    path: Vec<String>,
+    kind: ListKind,
+    /// Index of the item where the cursor is
+    index: usize,
+    /// Name of the item if it is a named connection like `a: x`
+    name: Option<String>,
+}
+
+/// Signature of the list which includes the end of the text
+pub fn signature_help(text: &str, namespace: &Namespace) -> Option<SignatureHelp> {
+    let call = find_call(text)?;
+
+    let mut path = Vec::new();
+    for x in &call.path {
+        path.push(resource_table::get_str_id(x.clone())?);
+    }
+    let symbol = symbol_table::resolve((&path, namespace)).ok()?.found;
+
+    let name = symbol.token.to_string();
+    let (items, ret): (Vec<(String, String)>, _) = match (call.kind, &symbol.kind) {
+        (ListKind::Parameter, SymbolKind::Module(x)) => (params(&x.parameters), None),
+        (ListKind::Parameter, SymbolKind::ProtoModule(x)) => (params(&x.parameters), None),
+        (ListKind::Parameter, SymbolKind::Interface(x)) => (params(&x.parameters), None),
+        (ListKind::Port, SymbolKind::Module(x)) => (ports(&x.ports), None),
+        (ListKind::Port, SymbolKind::ProtoModule(x)) => (ports(&x.ports), None),
+        (ListKind::Port, SymbolKind::Function(x)) => (ports(&x.ports), x.ret.clone()),
+        (ListKind::Port, SymbolKind::ProtoFunction(x)) => (ports(&x.ports), x.ret.clone()),
+        (ListKind::Generic, SymbolKind::Module(x)) => (generics(&x.generic_parameters), None),
+        (ListKind::Generic, SymbolKind::Interface(x)) => (generics(&x.generic_parameters), None),
+        (ListKind::Generic, SymbolKind::Package(x)) => (generics(&x.generic_parameters), None),
+        (ListKind::Generic, SymbolKind::Function(x)) => (generics(&x.generic_parameters), None),
+        _ => return None,
+    };
+
+    let (open, close) = match call.kind {
+        ListKind::Parameter => (" #(", ")"),
+        ListKind::Port => (" (", ")"),
+        ListKind::Generic => ("::<", ">"),
+    };
+
+    let mut label = format!("{name}{open}");
+    let mut parameters = Vec::new();
+    for (i, (_, item)) in items.iter().enumerate() {
+        if i != 0 {
+            label.push_str(", ");
+        }
+        let beg = label.encode_utf16().count() as u32;
+        label.push_str(item);
+        let end = label.encode_utf16().count() as u32;
+        parameters.push(ParameterInformation {
+            label: ParameterLabel::LabelOffsets([beg, end]),
+            documentation: None,
+        });
+    }
+    label.push_str(close);
+    if let Some(ret) = ret {
+        label.push_str(&format!(" -> {ret}"));
+    }
+
+    let active = call
+        .name
+        .and_then(|x| items.iter().position(|(y, _)| *y == x))
+        .unwrap_or(call.index);
+
+    let documentation = if !symbol.doc_comment.is_empty() {
+        let content = MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: symbol.doc_comment.format(false),
+        };
+        Some(Documentation::MarkupContent(content))
+    } else {
+        None
+    };
+
+    let signature = SignatureInformation {
+        label,
+        documentation,
+        parameters: Some(parameters),
+        active_parameter: None,
+    };
+
+    Some(SignatureHelp {
+        signatures: vec![signature],
+        active_signature: Some(0),
+        active_parameter: Some(active as u32),
+    })
+}
+
+fn params(x: &[Parameter]) -> Vec<(String, String)> {
+    x.iter()
+        .filter_map(|x| symbol_table::get(x.symbol))
+        .filter_map(|symbol| {
+            let SymbolKind::Parameter(x) = &symbol.kind else {
+                return None;
+            };
+            let kind = match x.kind {
+                ParameterKind::Param => "param",
+                ParameterKind::Const => "const",
+            };
+            let mut stringifier = Stringifier::new();
+            stringifier.expression(&x.value);
+            let name = symbol.token.to_string();
+            let label = format!("{kind} {name}: {} = {}", x.r#type, stringifier.as_str());
+            Some((name, label))
+        })
+        .collect()
+}
+
+fn ports(x: &[Port]) -> Vec<(String, String)> {
+    x.iter()
+        .map(|x| {
+            let property = x.property();
+            let clock_domain = match property.clock_domain {
+                ClockDomain::Explicit(_) => format!("{} ", property.clock_domain),
+                _ => String::new(),
+            };
+            let name = x.name().to_string();
+            let mut label = format!(
+                "{name}: {} {clock_domain}{}",
+                property.direction, property.r#type
+            );
+            if let Some(x) = &property.default_value {
+                let mut stringifier = Stringifier::new();
+                stringifier.expression(x);
+                label.push_str(&format!(" = {}", stringifier.as_str()));
+            }
+            (name, label)
+        })
+        .collect()
+}
+
+fn generics(x: &[SymbolId]) -> Vec<(String, String)> {
+    x.iter()
+        .filter_map(|x| symbol_table::get(*x))
+        .filter_map(|symbol: Symbol| {
+            let SymbolKind::GenericParameter(x) = &symbol.kind else {
+                return None;
+            };
+            let name = symbol.token.to_string();
+            let mut label = format!("{name}: {}", x.bound);
+            if let Some(x) = &x.default_value {
+                label.push_str(&format!(" = {x}"));
+            }
+            Some((name, label))
+        })
+        .collect()
+}
+
+/// Find the innermost list which is not closed at the end of the text
+fn find_call(text: &str) -> Option<Call> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut depth = 0;
+    let mut index = 0;
+    let mut item_head = chars.len();
+
+    let mut i = chars.len();
+    while i > 0 {
+        i -= 1;
+        match chars[i] {
+            ')' | ']' | '}' => depth += 1,
+            '(' | '[' | '{' if depth > 0 => depth -= 1,
+            ',' if depth == 0 => {
+                if index == 0 {
+                    item_head = i + 1;
+                }
+                index += 1;
+            }
+            '(' => {
+                if index == 0 {
+                    item_head = i + 1;
+                }
+                let name = item_name(&chars[item_head..]);
+                let head = trim_end(&chars[..i]);
+                return if let Some(head) = head.strip_suffix(&['#']) {
+                    callee(head, ListKind::Parameter, index, name)
+                } else {
+                    callee(skip_instance_suffix(head)?, ListKind::Port, index, name)
+                };
+            }
+            '<' if depth == 0 && chars[..i].ends_with(&[':', ':']) => {
+                if index == 0 {
+                    item_head = i + 1;
+                }
+                let name = item_name(&chars[item_head..]);
+                return callee(&chars[..i - 2], ListKind::Generic, index, name);
+            }
+            ';' | '[' | '{' => return None,
+            _ => (),
+        }
+    }
+
+    None
+}
+
+fn callee(head: &[char], kind: ListKind, index: usize, name: Option<String>) -> Option<Call> {
+    let head = trim_end(head);
+    let len = head
+        .iter()
+        .rev()
+        .take_while(|x| x.is_ascii_alphanumeric() || matches!(x, '_' | '$' | ':'))
+        .count();
+    let text: String = head[head.len() - len..].iter().collect();
+    let path: Vec<_> = text
+        .split("::")
+        .filter(|x| !x.is_empty())
+        .map(|x| x.to_string())
+        .collect();
+
+    if path.is_empty() {
+        None
+    } else {
+        Some(Call {
+            path,
+            kind,
+            index,
+            name,
+        })
+    }
+}
+
+/// Skip array and parameter list like `inst u: Foo [2] #(N: 1) (`
+fn skip_instance_suffix(head: &[char]) -> Option<&[char]> {
+    let mut head = head;
+    loop {
+        head = trim_end(head);
+        match head.last() {
+            Some(')') => head = skip_group(head, '(', ')')?.strip_suffix(&['#'])?,
+            Some(']') => head = skip_group(head, '[', ']')?,
+            _ => return Some(head),
+        }
+    }
+}
+
+/// Skip the group closed at the end of the text
+fn skip_group(head: &[char], open: char, close: char) -> Option<&[char]> {
+    let mut depth = 0;
+    for (i, x) in head.iter().enumerate().rev() {
+        if *x == close {
+            depth += 1;
+        } else if *x == open {
+            depth -= 1;
+            if depth == 0 {
+                return Some(trim_end(&head[..i]));
+            }
+        }
+    }
+    None
+}
+
+fn trim_end(x: &[char]) -> &[char] {
+    let len = x.iter().rev().take_while(|x| x.is_whitespace()).count();
+    &x[..x.len() - len]
+}
+
+/// Name of the named connection like `a: x`
+fn item_name(item: &[char]) -> Option<String> {
+    let item: String = item.iter().collect();
+    let (name, rest) = item.split_once(':')?;
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name.chars().all(|x| x.is_ascii_alphanumeric() || x == '_')
+        && !rest.starts_with(':');
+    valid.then(|| name.to_string())
+}
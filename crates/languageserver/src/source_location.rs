@@ -0,0 +1,17 @@
+use std::path::Path;
+use tower_lsp::lsp_types::{Location, Position, Range, Url};
+use veryl_sourcemap::SourceMap;
+
+/// Name of the custom request
+pub const METHOD: &str = "veryl/sourceLocation";
+
+/// Location in Veryl source which the position in the generated SystemVerilog is mapped from
+pub fn source_location(path: &Path, position: &Position) -> Option<Location> {
+    let source_map = SourceMap::from_src(path).ok()?;
+    let (path, line, column) = source_map.lookup(position.line + 1, position.character + 1)?;
+
+    let uri = Url::from_file_path(path).ok()?;
+    let position = Position::new(line - 1, column - 1);
+    let range = Range::new(position, position);
+    Some(Location { uri, range })
+}
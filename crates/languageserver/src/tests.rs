@@ -9,24 +9,24 @@ use std::path::PathBuf;
 use tokio::io::{AsyncReadExt, AsyncWriteExt, DuplexStream};
 use tower_lsp::jsonrpc::{Id, Request, Response};
 use tower_lsp::lsp_types::*;
-use tower_lsp::{Client, LanguageServer, LspService, Server};
+use tower_lsp::{Client, Server};
 
 struct TestServer {
     req_stream: DuplexStream,
     res_stream: DuplexStream,
     responses: VecDeque<String>,
+    buffer: String,
 }
 
 impl TestServer {
-    fn new<F, S>(init: F) -> Self
+    fn new<F>(init: F) -> Self
     where
-        F: FnOnce(Client) -> S,
-        S: LanguageServer,
+        F: FnOnce(Client) -> Backend,
     {
         let (req_client, req_server) = tokio::io::duplex(1024);
         let (res_server, res_client) = tokio::io::duplex(1024);
 
-        let (service, socket) = LspService::new(init);
+        let (service, socket) = Backend::build_service(init);
 
         tokio::spawn(Server::new(req_server, res_server, socket).serve(service));
 
@@ -34,6 +34,7 @@ impl TestServer {
             req_stream: req_client,
             res_stream: res_client,
             responses: VecDeque::new(),
+            buffer: String::new(),
         }
     }
 
@@ -41,12 +42,12 @@ impl TestServer {
         format!("Content-Length: {}\r\n\r\n{}", payload.len(), payload)
     }
 
-    fn decode(text: &str) -> Vec<String> {
+    /// Split complete messages and the rest which is not received yet
+    fn decode(text: &str) -> (Vec<String>, String) {
         let mut ret = Vec::new();
         let mut temp = text;
 
-        while !temp.is_empty() {
-            let p = temp.find("\r\n\r\n").unwrap();
+        while let Some(p) = temp.find("\r\n\r\n") {
             let (header, body) = temp.split_at(p + 4);
             let len = header
                 .strip_prefix("Content-Length: ")
@@ -54,12 +55,15 @@ impl TestServer {
                 .strip_suffix("\r\n\r\n")
                 .unwrap();
             let len: usize = len.parse().unwrap();
+            if body.len() < len {
+                break;
+            }
             let (body, rest) = body.split_at(len);
             ret.push(body.to_string());
             temp = rest;
         }
 
-        ret
+        (ret, temp.to_string())
     }
 
     async fn send_request(&mut self, req: Request) {
@@ -75,30 +79,44 @@ impl TestServer {
         self.req_stream.write_all(req.as_bytes()).await.unwrap();
     }
 
-    async fn recv_response(&mut self) -> Response {
-        if self.responses.is_empty() {
+    async fn recv(&mut self) -> String {
+        while self.responses.is_empty() {
             let mut buf = vec![0; 1024];
             let n = self.res_stream.read(&mut buf).await.unwrap();
-            let ret = String::from_utf8(buf[..n].to_vec()).unwrap();
-            for x in Self::decode(&ret) {
+            self.buffer
+                .push_str(std::str::from_utf8(&buf[..n]).unwrap());
+            let (messages, rest) = Self::decode(&self.buffer);
+            for x in messages {
                 self.responses.push_front(x);
             }
+            self.buffer = rest;
         }
-        let res = self.responses.pop_back().unwrap();
+        self.responses.pop_back().unwrap()
+    }
+
+    async fn recv_response(&mut self) -> Response {
+        let res = self.recv().await;
         serde_json::from_str(&res).unwrap()
     }
 
     async fn recv_notification(&mut self) -> Request {
-        if self.responses.is_empty() {
-            let mut buf = vec![0; 1024];
-            let n = self.res_stream.read(&mut buf).await.unwrap();
-            let ret = String::from_utf8(buf[..n].to_vec()).unwrap();
-            for x in Self::decode(&ret) {
-                self.responses.push_front(x);
+        let res = self.recv().await;
+        serde_json::from_str(&res).unwrap()
+    }
+
+    /// Receive the response of the request while acknowledging requests from the server
+    async fn recv_response_of(&mut self, id: i64) -> Value {
+        loop {
+            let res: Value = serde_json::from_str(&self.recv().await).unwrap();
+            if res.get("method").is_some() {
+                if let Some(x) = res.get("id") {
+                    let id: Id = serde_json::from_value(x.clone()).unwrap();
+                    self.send_ack(&id).await;
+                }
+            } else if res["id"] == id {
+                return res;
             }
         }
-        let res = self.responses.pop_back().unwrap();
-        serde_json::from_str(&res).unwrap()
     }
 }
 
@@ -115,12 +133,23 @@ fn build_initialized() -> Request {
     Request::build("initialized").params(json!(params)).finish()
 }
 
-fn build_did_open(text: &str) -> Request {
+fn file_url(name: &str) -> Url {
     let mut path = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
     path.pop();
     path.pop();
-    path.push("test.veryl");
-    let uri = Url::from_file_path(path).unwrap();
+    path.push(name);
+    Url::from_file_path(path).unwrap()
+}
+
+fn test_url() -> Url {
+    file_url("test.veryl")
+}
+
+fn build_did_open(text: &str) -> Request {
+    build_did_open_file(test_url(), text)
+}
+
+fn build_did_open_file(uri: Url, text: &str) -> Request {
     let text_document = TextDocumentItem {
         uri,
         language_id: "veryl".to_string(),
@@ -169,6 +198,65 @@ async fn did_open() {
     );
 }
 
+fn build_did_change(uri: Url, version: i32, range: Range, text: &str) -> Request {
+    let params = DidChangeTextDocumentParams {
+        text_document: VersionedTextDocumentIdentifier { uri, version },
+        content_changes: vec![TextDocumentContentChangeEvent {
+            range: Some(range),
+            range_length: None,
+            text: text.to_string(),
+        }],
+    };
+
+    Request::build("textDocument/didChange")
+        .params(json!(params))
+        .finish()
+}
+
+fn build_references(id: i64, uri: Url, line: u32, character: u32) -> Request {
+    let params = ReferenceParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position { line, character },
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+        context: ReferenceContext {
+            include_declaration: false,
+        },
+    };
+    Request::build("textDocument/references")
+        .params(json!(params))
+        .id(id)
+        .finish()
+}
+
+fn build_rename(id: i64, line: u32, character: u32, new_name: &str) -> Request {
+    let params = RenameParams {
+        text_document_position: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: test_url() },
+            position: Position { line, character },
+        },
+        new_name: new_name.to_string(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    Request::build("textDocument/rename")
+        .params(json!(params))
+        .id(id)
+        .finish()
+}
+
+fn build_prepare_rename(id: i64, line: u32, character: u32) -> Request {
+    let params = TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier { uri: test_url() },
+        position: Position { line, character },
+    };
+    Request::build("textDocument/prepareRename")
+        .params(json!(params))
+        .id(id)
+        .finish()
+}
+
 #[tokio::test]
 #[ntest::timeout(60000)]
 async fn diagnostics() {
@@ -253,3 +341,989 @@ async fn progress() {
     }
     assert_eq!(percentage, 100);
 }
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn rename() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let code = r#"module Foo #(
+    param N: u32 = 1,
+) (
+    i_a: input  logic,
+    o_b: output logic,
+) {
+    assign o_b = i_a;
+}
+module Top (
+    i_a: input  logic,
+    o_b: output logic,
+) {
+    inst u: Foo #(N: 2) (i_a, o_b: o_b);
+}
+"#;
+    let req = build_did_open(code);
+    server.send_request(req).await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let edits = |res: &Value| {
+        let mut ret: Vec<_> = res["result"]["changes"][test_url().as_str()]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| {
+                (
+                    x["range"]["start"]["line"].as_u64().unwrap(),
+                    x["range"]["start"]["character"].as_u64().unwrap(),
+                    x["newText"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect();
+        ret.sort();
+        ret
+    };
+
+    // port name in instance
+    server.send_request(build_prepare_rename(2, 12, 31)).await;
+    let res = server.recv_response_of(2).await;
+    assert_eq!(res["result"]["start"]["line"], Value::from(12));
+    assert_eq!(res["result"]["start"]["character"], Value::from(30));
+
+    server.send_request(build_rename(3, 12, 31, "o_c")).await;
+    let res = server.recv_response_of(3).await;
+    assert_eq!(
+        edits(&res),
+        vec![
+            (4, 4, "o_c".to_string()),
+            (6, 11, "o_c".to_string()),
+            (12, 30, "o_c".to_string()),
+        ]
+    );
+
+    // implicit connection is expanded
+    server.send_request(build_rename(4, 3, 4, "i_x")).await;
+    let res = server.recv_response_of(4).await;
+    assert_eq!(
+        edits(&res),
+        vec![
+            (3, 4, "i_x".to_string()),
+            (6, 17, "i_x".to_string()),
+            (12, 25, "i_x: i_a".to_string()),
+        ]
+    );
+
+    server.send_request(build_rename(5, 9, 4, "i_y")).await;
+    let res = server.recv_response_of(5).await;
+    assert_eq!(
+        edits(&res),
+        vec![(9, 4, "i_y".to_string()), (12, 25, "i_a: i_y".to_string())]
+    );
+
+    // parameter name in instance
+    server.send_request(build_rename(6, 12, 18, "M")).await;
+    let res = server.recv_response_of(6).await;
+    assert_eq!(
+        edits(&res),
+        vec![(1, 10, "M".to_string()), (12, 18, "M".to_string())]
+    );
+
+    // reserved identifier and keywords
+    for (id, name) in [(7, "__x"), (8, "always"), (9, "module"), (10, "0a")] {
+        server.send_request(build_rename(id, 10, 4, name)).await;
+        let res = server.recv_response_of(id).await;
+        assert_eq!(res["error"]["code"], Value::from(-32602));
+    }
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn incremental_change() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url_a = file_url("test_a.veryl");
+    let url_b = file_url("test_b.veryl");
+    let code_a = "module A (o: output logic) {\n    assign o = 1;\n}\n";
+    let code_b = "module B (o: output logic) {\n    inst u: A (o);\n}\n";
+
+    let diagnostics = |res: &Request, url: &Url| {
+        res.method() == "textDocument/publishDiagnostics"
+            && res.params().unwrap()["uri"] == url.as_str()
+    };
+
+    server
+        .send_request(build_did_open_file(url_a.clone(), code_a))
+        .await;
+    while !diagnostics(&server.recv_notification().await, &url_a) {}
+    server
+        .send_request(build_did_open_file(url_b.clone(), code_b))
+        .await;
+    while !diagnostics(&server.recv_notification().await, &url_b) {}
+
+    // `assign o = 1;` -> `assign o = 0;`
+    let range = Range::new(Position::new(1, 15), Position::new(1, 16));
+    server
+        .send_request(build_did_change(url_a.clone(), 1, range, "0"))
+        .await;
+
+    // test_b.veryl refers test_a.veryl, so it is analyzed again
+    let mut updated = (false, false);
+    while updated != (true, true) {
+        let res = server.recv_notification().await;
+        updated.0 |= diagnostics(&res, &url_a);
+        updated.1 |= diagnostics(&res, &url_b);
+    }
+
+    // insert a line at the beginning of test_a.veryl
+    let range = Range::new(Position::new(0, 0), Position::new(0, 0));
+    server
+        .send_request(build_did_change(url_a.clone(), 2, range, "// A\n"))
+        .await;
+    while !diagnostics(&server.recv_notification().await, &url_b) {}
+
+    // references from test_b.veryl are kept after test_a.veryl is changed
+    server
+        .send_request(build_references(2, url_a.clone(), 1, 10))
+        .await;
+    let res = server.recv_response_of(2).await;
+    let mut locations: Vec<_> = res["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| {
+            (
+                x["uri"].as_str().unwrap().to_string(),
+                x["range"]["start"]["line"].as_u64().unwrap(),
+                x["range"]["start"]["character"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    locations.sort();
+    assert_eq!(
+        locations,
+        vec![(url_a.to_string(), 2, 11), (url_b.to_string(), 1, 15),]
+    );
+}
+
+fn build_code_action(id: i64, uri: Url, diagnostics: Vec<Diagnostic>) -> Request {
+    let params = CodeActionParams {
+        text_document: TextDocumentIdentifier { uri },
+        range: diagnostics[0].range,
+        context: CodeActionContext {
+            diagnostics,
+            only: None,
+            trigger_kind: None,
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    Request::build("textDocument/codeAction")
+        .params(json!(params))
+        .id(id)
+        .finish()
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn code_action() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_code_action.veryl");
+    let code = "module A {\n    var a: logic;\n}\n";
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    let params = loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break res.params().unwrap().clone();
+        }
+    };
+    let params: PublishDiagnosticsParams = serde_json::from_value(params).unwrap();
+    assert_eq!(params.diagnostics.len(), 1);
+
+    server
+        .send_request(build_code_action(2, url.clone(), params.diagnostics))
+        .await;
+    let res = server.recv_response_of(2).await;
+    let actions: Vec<CodeAction> = serde_json::from_value(res["result"].clone()).unwrap();
+    assert_eq!(actions.len(), 2);
+
+    let edits =
+        |action: &CodeAction| action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&url].clone();
+
+    // `var a` -> `var _a`
+    assert_eq!(actions[0].kind, Some(CodeActionKind::QUICKFIX));
+    assert_eq!(
+        edits(&actions[0]),
+        vec![TextEdit {
+            range: Range::new(Position::new(1, 8), Position::new(1, 9)),
+            new_text: "_a".to_string(),
+        }]
+    );
+
+    // `#[allow(unused_variable)]` is inserted before `var`
+    assert_eq!(
+        edits(&actions[1]),
+        vec![TextEdit {
+            range: Range::new(Position::new(1, 4), Position::new(1, 4)),
+            new_text: "#[allow(unused_variable)]\n    ".to_string(),
+        }]
+    );
+    assert!(actions[1].diagnostics.is_some());
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn inlay_hint() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_inlay_hint.veryl");
+    let code = r#"module Sub #(
+    param W: u32 = 8,
+    param D: u32 = W * 2,
+) (
+    i_clk: input clock,
+    i_rst: input reset,
+) {
+    var a: logic<W>;
+    always_ff {
+        if_reset {
+            a = 0;
+        } else {
+            a = 1;
+        }
+    }
+}
+module Top {
+    const N: u32 = 2 * 3;
+    inst u: Sub #(W: N);
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let params = InlayHintParams {
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        text_document: TextDocumentIdentifier { uri: url },
+        range: Range::new(Position::new(0, 0), Position::new(20, 0)),
+    };
+    let req = Request::build("textDocument/inlayHint")
+        .params(json!(params))
+        .id(2)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(2).await;
+    let hints: Vec<InlayHint> = serde_json::from_value(res["result"].clone()).unwrap();
+    let mut hints: Vec<_> = hints
+        .into_iter()
+        .map(|x| {
+            let InlayHintLabel::String(label) = x.label else {
+                unreachable!()
+            };
+            (x.position.line, x.position.character, label)
+        })
+        .collect();
+    hints.sort();
+
+    assert_eq!(
+        hints,
+        vec![
+            (2, 24, "= 16".to_string()),
+            (7, 19, "<8>".to_string()),
+            (8, 13, "(i_clk, i_rst)".to_string()),
+            (17, 24, "= 6".to_string()),
+            (18, 22, "= 6".to_string()),
+            (18, 23, "#(D: 16)".to_string()),
+        ]
+    );
+}
+
+fn build_signature_help(id: i64, uri: Url, line: u32, character: u32) -> Request {
+    let params = SignatureHelpParams {
+        context: None,
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position { line, character },
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    Request::build("textDocument/signatureHelp")
+        .params(json!(params))
+        .id(id)
+        .finish()
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn signature_help() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_signature_help.veryl");
+    let code = r#"module Sub #(
+    param W: u32 = 8,
+    param D: u32 = 2,
+) (
+    i_a: input  logic<W>,
+    o_b: output logic   ,
+) {
+    assign o_b = i_a[0];
+}
+module Top {
+    function f (a: input logic, b: input logic) -> logic { return a & b; }
+    
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    // type at the end of line 11 and request signature help at the cursor
+    let mut column = 4;
+    let mut signature = async |id: i64, input: &str| {
+        let range = Range::new(Position::new(11, column), Position::new(11, column));
+        server
+            .send_request(build_did_change(url.clone(), id as i32, range, input))
+            .await;
+        column += input.len() as u32;
+
+        server
+            .send_request(build_signature_help(id, url.clone(), 11, column))
+            .await;
+        let res = server.recv_response_of(id).await;
+        let help: SignatureHelp = serde_json::from_value(res["result"].clone()).unwrap();
+        (
+            help.signatures[0].label.clone(),
+            help.active_parameter.unwrap(),
+        )
+    };
+
+    assert_eq!(
+        signature(2, "inst u: Sub #(W: 1, ").await,
+        ("Sub #(param W: u32 = 8, param D: u32 = 2)".to_string(), 1)
+    );
+    assert_eq!(
+        signature(3, "D: 2) (o_b: ").await,
+        (
+            "Sub (i_a: input logic<W>, o_b: output logic)".to_string(),
+            1
+        )
+    );
+    assert_eq!(
+        signature(4, "x); let c: logic = f(1, ").await,
+        ("f (a: input logic, b: input logic) -> logic".to_string(), 1)
+    );
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn outline() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_outline.veryl");
+    let code = r#"module Top (
+    i_clk: input clock,
+) {
+    // comment 1
+    // comment 2
+    #[allow(unused_variable)]
+    #[allow(unused_variable)]
+    var a: logic;
+    always_ff {
+        a = 1;
+    }
+    :g {
+        let b: logic = a + 1;
+    }
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let text_document = TextDocumentIdentifier { uri: url };
+
+    // document symbols
+    let params = DocumentSymbolParams {
+        text_document: text_document.clone(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let req = Request::build("textDocument/documentSymbol")
+        .params(json!(params))
+        .id(2)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(2).await;
+    let symbols: Vec<DocumentSymbol> = serde_json::from_value(res["result"].clone()).unwrap();
+
+    fn flatten(symbols: &[DocumentSymbol], depth: usize, ret: &mut Vec<String>) {
+        for x in symbols {
+            ret.push(format!("{}{} {:?}", "  ".repeat(depth), x.name, x.kind));
+            if let Some(children) = &x.children {
+                flatten(children, depth + 1, ret);
+            }
+        }
+    }
+    let mut outline = Vec::new();
+    flatten(&symbols, 0, &mut outline);
+    assert_eq!(
+        outline,
+        vec![
+            "Top Module",
+            "  i_clk Property",
+            "  a Variable",
+            "  always_ff Event",
+            "  g Namespace",
+            "    b Variable",
+        ]
+    );
+
+    // folding ranges
+    let params = FoldingRangeParams {
+        text_document: text_document.clone(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let req = Request::build("textDocument/foldingRange")
+        .params(json!(params))
+        .id(3)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(3).await;
+    let ranges: Vec<FoldingRange> = serde_json::from_value(res["result"].clone()).unwrap();
+    let mut ranges: Vec<_> = ranges
+        .into_iter()
+        .map(|x| (x.start_line, x.end_line, x.kind))
+        .collect();
+    ranges.sort_by_key(|x| (x.0, x.1));
+    assert_eq!(
+        ranges,
+        vec![
+            (0, 1, None),
+            (2, 13, None),
+            (3, 4, Some(FoldingRangeKind::Comment)),
+            (5, 6, Some(FoldingRangeKind::Region)),
+            (8, 9, None),
+            (11, 12, None),
+        ]
+    );
+
+    // selection ranges from `a` of `a + 1`
+    let params = SelectionRangeParams {
+        text_document,
+        positions: vec![Position::new(12, 23)],
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let req = Request::build("textDocument/selectionRange")
+        .params(json!(params))
+        .id(4)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(4).await;
+    let selections: Vec<SelectionRange> = serde_json::from_value(res["result"].clone()).unwrap();
+    let mut ranges = Vec::new();
+    let mut selection = selections.first();
+    while let Some(x) = selection {
+        let range = x.range;
+        ranges.push((
+            range.start.line,
+            range.start.character,
+            range.end.line,
+            range.end.character,
+        ));
+        selection = x.parent.as_deref();
+    }
+    assert_eq!(
+        ranges,
+        vec![
+            (12, 23, 12, 24),
+            (12, 23, 12, 28),
+            (12, 8, 12, 29),
+            (11, 4, 13, 5),
+            (0, 0, 14, 1),
+        ]
+    );
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn call_hierarchy() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_call_hierarchy.veryl");
+    let code = r#"package CallPkg {
+    function f (a: input logic) -> logic {
+        return a;
+    }
+    function g (a: input logic) -> logic {
+        return f(a) | f(~a);
+    }
+}
+module CallTop {
+    let x: logic = CallPkg::g(1);
+    let y: logic = CallPkg::f(0);
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    // prepare from the call of `g`
+    let params = CallHierarchyPrepareParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: url.clone() },
+            position: Position::new(9, 28),
+        },
+        work_done_progress_params: WorkDoneProgressParams::default(),
+    };
+    let req = Request::build("textDocument/prepareCallHierarchy")
+        .params(json!(params))
+        .id(2)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(2).await;
+    let items: Vec<CallHierarchyItem> = serde_json::from_value(res["result"].clone()).unwrap();
+    assert_eq!(items.len(), 1);
+    let g = items[0].clone();
+    assert_eq!(g.name, "g");
+    assert_eq!(g.kind, SymbolKind::FUNCTION);
+    assert_eq!(
+        g.range,
+        Range::new(Position::new(4, 4), Position::new(6, 5))
+    );
+    assert_eq!(
+        g.selection_range,
+        Range::new(Position::new(4, 13), Position::new(4, 14))
+    );
+
+    // outgoing calls of `g`
+    let params = CallHierarchyOutgoingCallsParams {
+        item: g.clone(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let req = Request::build("callHierarchy/outgoingCalls")
+        .params(json!(params))
+        .id(3)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(3).await;
+    let calls: Vec<CallHierarchyOutgoingCall> =
+        serde_json::from_value(res["result"].clone()).unwrap();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].to.name, "f");
+    assert_eq!(
+        calls[0].from_ranges,
+        vec![
+            Range::new(Position::new(5, 15), Position::new(5, 16)),
+            Range::new(Position::new(5, 22), Position::new(5, 23)),
+        ]
+    );
+
+    // incoming calls of `f`
+    let params = CallHierarchyIncomingCallsParams {
+        item: calls[0].to.clone(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    };
+    let req = Request::build("callHierarchy/incomingCalls")
+        .params(json!(params))
+        .id(4)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(4).await;
+    let calls: Vec<CallHierarchyIncomingCall> =
+        serde_json::from_value(res["result"].clone()).unwrap();
+    let mut callers: Vec<_> = calls
+        .iter()
+        .map(|x| (x.from.name.clone(), x.from.kind, x.from_ranges.len()))
+        .collect();
+    callers.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        callers,
+        vec![
+            ("CallTop".to_string(), SymbolKind::MODULE, 1),
+            ("g".to_string(), SymbolKind::FUNCTION, 2),
+        ]
+    );
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn instance_hierarchy() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_instance_hierarchy.veryl");
+    let code = r#"module InstTop {
+    inst u0: InstSub #(W: 4);
+    inst u1: InstSub;
+}
+module InstSub #(
+    param W: u32 = 8,
+    param D: u32 = W * 2,
+) {
+    inst u: InstLeaf;
+}
+module InstLeaf {}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let req = Request::build("veryl/instanceHierarchy")
+        .params(json!({"textDocument": {"uri": url}, "top": "InstTop"}))
+        .id(2)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(2).await;
+    let tree = &res["result"];
+
+    fn flatten(node: &Value, depth: usize, ret: &mut Vec<String>) {
+        let params: Vec<_> = node["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|x| format!("{}={}", x["name"].as_str().unwrap(), x["value"]))
+            .collect();
+        ret.push(format!(
+            "{}{}: {} [{}]",
+            "  ".repeat(depth),
+            node["name"].as_str().unwrap(),
+            node["module"].as_str().unwrap(),
+            params.join(", ")
+        ));
+        for x in node["children"].as_array().unwrap() {
+            flatten(x, depth + 1, ret);
+        }
+    }
+    let mut hierarchy = Vec::new();
+    flatten(tree, 0, &mut hierarchy);
+    assert_eq!(
+        hierarchy,
+        vec![
+            "InstTop: InstTop []",
+            "  u0: InstSub [W=\"4\", D=\"8\"]",
+            "    u: InstLeaf []",
+            "  u1: InstSub [W=\"8\", D=\"16\"]",
+            "    u: InstLeaf []",
+        ]
+    );
+    assert_eq!(
+        tree["children"][0]["location"]["range"]["start"],
+        json!({"line": 1, "character": 9})
+    );
+    assert_eq!(tree["truncated"], json!(false));
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn implementation() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_implementation.veryl");
+    let code = r#"proto module ImplProto;
+module ImplA for ImplProto {}
+module ImplB for ImplProto {}
+package ImplPkg {
+    struct ImplStruct {
+        a: logic,
+    }
+}
+module ImplWrap::<M: ImplProto> {
+    inst u: M;
+}
+module ImplTop {
+    var s: ImplPkg::ImplStruct;
+    assign s = 0;
+    inst u0: ImplWrap::<ImplA>;
+    inst u1: ImplWrap::<ImplB>;
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let request = |id: i64, method: &str, line: u32, character: u32| {
+        let params = TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: url.clone() },
+            position: Position { line, character },
+        };
+        Request::build(method.to_string())
+            .params(json!(params))
+            .id(id)
+            .finish()
+    };
+    let lines = |res: &Value| {
+        let locations: Vec<Location> = serde_json::from_value(res["result"].clone()).unwrap();
+        let mut lines: Vec<_> = locations.iter().map(|x| x.range.start.line).collect();
+        lines.sort();
+        lines
+    };
+
+    // implementations of the proto
+    server
+        .send_request(request(2, "textDocument/implementation", 0, 15))
+        .await;
+    let res = server.recv_response_of(2).await;
+    assert_eq!(lines(&res), vec![1, 2]);
+
+    // concrete modules given to the generic parameter
+    server
+        .send_request(request(3, "textDocument/implementation", 9, 12))
+        .await;
+    let res = server.recv_response_of(3).await;
+    assert_eq!(lines(&res), vec![1, 2]);
+
+    // type of the variable
+    server
+        .send_request(request(4, "textDocument/typeDefinition", 12, 8))
+        .await;
+    let res = server.recv_response_of(4).await;
+    assert_eq!(lines(&res), vec![4]);
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn hover_emitted_code() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let url = file_url("test_hover_emitted_code.veryl");
+    let code = r#"module HoverTop (
+    i_a: input logic,
+) {
+    var b: logic;
+    always_comb {
+        b = i_a;
+    }
+}
+"#;
+    server
+        .send_request(build_did_open_file(url.clone(), code))
+        .await;
+
+    loop {
+        let res = server.recv_notification().await;
+        if res.method() == "textDocument/publishDiagnostics" {
+            break;
+        }
+    }
+
+    let request = |id: i64, line: u32, character: u32| {
+        let params = TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri: url.clone() },
+            position: Position { line, character },
+        };
+        Request::build("textDocument/hover")
+            .params(json!(params))
+            .id(id)
+            .finish()
+    };
+
+    // variable declaration
+    server.send_request(request(2, 3, 8)).await;
+    let res = server.recv_response_of(2).await;
+    let hover: Hover = serde_json::from_value(res["result"].clone()).unwrap();
+    let HoverContents::Array(contents) = hover.contents else {
+        panic!("unexpected hover: {:?}", hover.contents);
+    };
+    assert_eq!(
+        contents[1],
+        MarkedString::LanguageString(LanguageString {
+            language: "systemverilog".to_string(),
+            value: "logic b;".to_string(),
+        })
+    );
+
+    // operator in always_comb which is not a symbol
+    server.send_request(request(3, 5, 10)).await;
+    let res = server.recv_response_of(3).await;
+    let hover: Hover = serde_json::from_value(res["result"].clone()).unwrap();
+    assert_eq!(
+        hover.contents,
+        HoverContents::Scalar(MarkedString::LanguageString(LanguageString {
+            language: "systemverilog".to_string(),
+            value: "b = i_a;".to_string(),
+        }))
+    );
+}
+
+#[tokio::test]
+#[ntest::timeout(60000)]
+async fn source_location() {
+    let mut server = TestServer::new(Backend::new);
+
+    let req = build_initialize(1);
+    server.send_request(req).await;
+    let res = server.recv_response().await;
+    assert!(res.is_ok());
+
+    let req = build_initialized();
+    server.send_request(req).await;
+
+    let dir = env::temp_dir().join("veryl_ls_source_location");
+    std::fs::create_dir_all(&dir).unwrap();
+    let src_path = dir.join("top.veryl");
+    let dst_path = dir.join("top.sv");
+    let map_path = dir.join("top.sv.map");
+    std::fs::write(&src_path, "module Top {\n    var a: logic;\n}\n").unwrap();
+
+    let mut source_map = veryl_sourcemap::SourceMap::new(&src_path, &dst_path, &map_path);
+    source_map.add(1, 8, 1, 8, "Top");
+    source_map.add(2, 11, 2, 9, "a");
+    source_map.build();
+    std::fs::write(&map_path, source_map.to_bytes().unwrap()).unwrap();
+    let dst = format!(
+        "module prj_Top;\n    logic a;\nendmodule\n{}\n",
+        source_map.get_link()
+    );
+    std::fs::write(&dst_path, dst).unwrap();
+
+    let params = TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier {
+            uri: Url::from_file_path(&dst_path).unwrap(),
+        },
+        position: Position::new(1, 10),
+    };
+    let req = Request::build("veryl/sourceLocation")
+        .params(json!(params))
+        .id(2)
+        .finish();
+    server.send_request(req).await;
+    let res = server.recv_response_of(2).await;
+    let location: Location = serde_json::from_value(res["result"].clone()).unwrap();
+
+    let src_path = std::fs::canonicalize(&src_path).unwrap();
+    assert_eq!(location.uri, Url::from_file_path(src_path).unwrap());
+    assert_eq!(location.range.start, Position::new(1, 8));
+}
@@ -0,0 +1,27 @@
+use tower_lsp::lsp_types::{Position, Range};
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_walker::{Handler, VerylWalker};
+
+/// Walker which calls the handler at each node of the syntax tree
+pub struct Walker<'a> {
+    handler: &'a mut dyn Handler,
+}
+
+impl<'a> Walker<'a> {
+    pub fn new(handler: &'a mut dyn Handler) -> Self {
+        Self { handler }
+    }
+}
+
+impl VerylWalker for Walker<'_> {
+    fn get_handlers(&mut self) -> Option<Vec<&mut dyn Handler>> {
+        Some(vec![&mut *self.handler])
+    }
+}
+
+pub fn to_range(range: &TokenRange) -> Range {
+    Range::new(
+        Position::new(range.beg.line - 1, range.beg.column - 1),
+        Position::new(range.end.line - 1, range.end.column - 1 + range.end.length),
+    )
+}
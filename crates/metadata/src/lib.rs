@@ -19,7 +19,7 @@ pub use build_info::BuildInfo;
 pub use doc::Doc;
 pub use format::Format;
 pub use git::Git;
-pub use lint::{Case, Lint};
+pub use lint::{Case, Lint, LintLevel};
 pub use lockfile::{LockSource, Lockfile};
 pub use metadata::{BumpKind, Metadata, UrlPath};
 pub use metadata_error::MetadataError;
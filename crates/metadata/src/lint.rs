@@ -1,5 +1,7 @@
+use crate::MetadataError;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -7,6 +9,53 @@ use std::fmt;
 pub struct Lint {
     #[serde(default)]
     pub naming: LintNaming,
+    /// Level of each diagnostic code like `unused_variable = "deny"`
+    #[serde(default)]
+    pub rules: HashMap<String, LintLevel>,
+}
+
+impl Lint {
+    /// Level of the diagnostic code overridden by `rules`
+    pub fn level(&self, code: &str) -> Option<LintLevel> {
+        self.rules.get(code).copied()
+    }
+
+    /// Check that all codes of `rules` are included in the known diagnostic `codes`
+    pub fn check_rules(&self, codes: &[&str]) -> Result<(), MetadataError> {
+        let mut unknown: Vec<_> = self
+            .rules
+            .keys()
+            .filter(|x| !codes.contains(&x.as_str()))
+            .cloned()
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            unknown.sort();
+            Err(MetadataError::UnknownLintRule(unknown))
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LintLevel {
+    #[serde(rename = "allow")]
+    Allow,
+    #[serde(rename = "warn")]
+    Warn,
+    #[serde(rename = "deny")]
+    Deny,
+}
+
+impl fmt::Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            LintLevel::Allow => "allow",
+            LintLevel::Warn => "warn",
+            LintLevel::Deny => "deny",
+        };
+        text.fmt(f)
+    }
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
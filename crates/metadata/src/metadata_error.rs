@@ -74,6 +74,13 @@ pub enum MetadataError {
     #[error("project name \"{0}\" is used multiply in dependencies")]
     NameConflict(String),
 
+    #[diagnostic(
+        code(MetadataError::UnknownLintRule),
+        help("diagnostic code of the analyzer can be used as lint rule")
+    )]
+    #[error("unknown lint rules: {}", .0.join(", "))]
+    UnknownLintRule(Vec<String>),
+
     #[diagnostic(code(MetadataError::Path), help(""))]
     #[error("path error")]
     Path(#[from] PathError),
@@ -27,6 +27,8 @@ pub enum SimType {
     Vcs,
     #[serde(rename = "vivado")]
     Vivado,
+    #[serde(rename = "native")]
+    Native,
 }
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
@@ -23,6 +23,12 @@ target = {type = "source"}
 
 [format]
 indent_width = 4
+
+[lint.rules]
+unused_variable = "deny"
+missing_port = "allow"
+width_truncation = "warn"
+unused_output_port = "warn"
 "#;
 
 const MAIN_TOML: &'static str = r#"
@@ -141,6 +147,17 @@ fn check_toml() {
     assert!(metadata.build.reset_low_prefix.is_none());
     assert_eq!(metadata.build.reset_low_suffix.unwrap(), "_n");
     assert_eq!(metadata.format.indent_width, 4);
+    assert_eq!(
+        metadata.lint.level("unused_variable"),
+        Some(LintLevel::Deny)
+    );
+    assert_eq!(metadata.lint.level("missing_port"), Some(LintLevel::Allow));
+    assert_eq!(metadata.lint.level("unknown_member"), None);
+    assert_eq!(
+        metadata.lint.level("width_truncation"),
+        Some(LintLevel::Warn)
+    );
+    assert_eq!(metadata.lint.level("width_extension"), None);
 }
 
 #[test]
@@ -167,6 +184,22 @@ fn check() {
     assert!(metadata.check().is_err());
 }
 
+#[test]
+fn check_rules() {
+    let mut lint = Lint::default();
+    lint.rules
+        .insert("unused_variable".to_string(), LintLevel::Deny);
+    lint.rules
+        .insert("missing_port".to_string(), LintLevel::Allow);
+    assert!(
+        lint.check_rules(&["missing_port", "unused_variable"])
+            .is_ok()
+    );
+
+    let err = lint.check_rules(&["unused_variable"]).unwrap_err();
+    assert!(matches!(err, MetadataError::UnknownLintRule(x) if x == ["missing_port"]));
+}
+
 #[test]
 fn publish() {
     let (mut metadata, tempdir) = create_metadata_simple();
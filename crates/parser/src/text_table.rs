@@ -43,8 +43,10 @@ impl TextTable {
         self.table.get(&id).cloned()
     }
 
+    /// Drop texts of the path except the current one which may be set by parsing before dropping
     pub fn drop(&mut self, id: PathId) {
-        self.table.retain(|_, x| x.path != id);
+        let current = self.current_text;
+        self.table.retain(|k, x| x.path != id || *k == current);
     }
 }
 
@@ -0,0 +1,693 @@
+use crate::HashMap;
+use crate::evaluator::{self, Flow, extend};
+use crate::ir::{
+    BinaryOp, Expression, Ir, ProcessKind, Statement, SystemFunctionCall, UnaryOp, VarId, Variable,
+};
+use veryl_analyzer::value::Value;
+
+/// Index of temporary value
+type Reg = usize;
+
+/// Instruction of compiled program.
+/// Width and signedness of each operation are decided at compile time,
+/// so the execution doesn't need to walk expression trees.
+#[derive(Clone, Debug)]
+enum Op {
+    Const {
+        dst: Reg,
+        value: Value,
+    },
+    Load {
+        dst: Reg,
+        var: VarId,
+        w: usize,
+        s: bool,
+    },
+    Select {
+        dst: Reg,
+        var: VarId,
+        offset: Reg,
+        width: usize,
+        w: usize,
+        s: bool,
+    },
+    /// Context-determined unary operation like `-` and `~`
+    Unary {
+        dst: Reg,
+        op: UnaryOp,
+        src: Reg,
+        w: usize,
+    },
+    /// Self-determined unary operation like reduction
+    Reduce {
+        dst: Reg,
+        op: UnaryOp,
+        src: Reg,
+        w: usize,
+    },
+    Binary {
+        dst: Reg,
+        op: BinaryOp,
+        x: Reg,
+        y: Reg,
+        w: usize,
+    },
+    Mux {
+        dst: Reg,
+        cond: Reg,
+        x: Reg,
+        y: Reg,
+        w: usize,
+        s: bool,
+    },
+    Concat {
+        dst: Reg,
+        items: Vec<(Reg, usize)>,
+        w: usize,
+    },
+    Resize {
+        dst: Reg,
+        src: Reg,
+        width: usize,
+        signed: bool,
+        w: usize,
+        s: bool,
+    },
+    /// Assign `width` bits from `lsb` of the source to the variable.
+    /// Non-blocking assignment is deferred to `next`.
+    Store {
+        var: VarId,
+        src: Reg,
+        lsb: usize,
+        width: usize,
+        select: Option<(Reg, usize)>,
+        blocking: bool,
+    },
+    Jump(usize),
+    JumpIfNot {
+        cond: Reg,
+        target: usize,
+    },
+    System(SystemFunctionCall),
+}
+
+/// Linear program compiled from processes of IR.
+/// Combinational processes are concatenated in the levelised order,
+/// so a single pass of it settles all variables.
+#[derive(Clone, Debug, Default)]
+pub struct Program {
+    comb: Vec<Op>,
+    ff: Vec<Vec<Op>>,
+    /// Asynchronous reset condition of each register and the temporary of the result
+    reset: Vec<Option<(Vec<Op>, Reg)>>,
+    initial: Vec<Vec<Op>>,
+    temps: Vec<Value>,
+}
+
+impl Program {
+    pub fn compile(ir: &Ir) -> Self {
+        let mut compiler = Compiler {
+            variables: &ir.variables,
+            ops: Vec::new(),
+            temps: 0,
+            max_temps: 1,
+            breaks: Vec::new(),
+            returns: Vec::new(),
+        };
+
+        for x in &ir.comb {
+            compiler.statements(&x.statements, true);
+        }
+        let comb = compiler.take();
+
+        let mut ff = Vec::new();
+        let mut reset = Vec::new();
+        for x in &ir.ff {
+            compiler.statements(&x.statements, false);
+            ff.push(compiler.take());
+
+            let cond = match &x.kind {
+                ProcessKind::Ff(event) => event
+                    .reset
+                    .as_ref()
+                    .filter(|x| x.asynchronous)
+                    .map(|x| &x.cond),
+                _ => None,
+            };
+            reset.push(cond.map(|x| {
+                compiler.temps = 0;
+                let cond = compiler.expression(x, 0);
+                (compiler.take(), cond)
+            }));
+        }
+
+        let mut initial = Vec::new();
+        for x in &ir.initial {
+            compiler.statements(&x.statements, true);
+            initial.push(compiler.take());
+        }
+
+        Self {
+            comb,
+            ff,
+            reset,
+            initial,
+            temps: vec![Value::zero(0); compiler.max_temps],
+        }
+    }
+
+    /// Execute all combinational processes
+    pub fn settle(&mut self, variables: &mut [Variable]) {
+        run(&self.comb, &mut self.temps, variables, None, &mut |_, _| {
+            Flow::Next
+        });
+    }
+
+    /// Whether the asynchronous reset of the register is asserted
+    pub fn reset(&mut self, index: usize, variables: &mut [Variable]) -> bool {
+        let Some((ops, cond)) = &self.reset[index] else {
+            return false;
+        };
+        run(ops, &mut self.temps, variables, None, &mut |_, _| {
+            Flow::Next
+        });
+        self.temps[*cond].to_bool()
+    }
+
+    /// Execute the register process with non-blocking assignments to `next`
+    pub fn ff(
+        &mut self,
+        index: usize,
+        variables: &mut [Variable],
+        next: &mut HashMap<VarId, Value>,
+    ) {
+        run(
+            &self.ff[index],
+            &mut self.temps,
+            variables,
+            Some(next),
+            &mut |_, _| Flow::Next,
+        );
+    }
+
+    /// Execute the initial process with the handler of system functions
+    pub fn initial(
+        &mut self,
+        index: usize,
+        variables: &mut [Variable],
+        system: &mut dyn FnMut(&SystemFunctionCall, &[Variable]) -> Flow,
+    ) -> Flow {
+        run(
+            &self.initial[index],
+            &mut self.temps,
+            variables,
+            None,
+            system,
+        )
+    }
+}
+
+struct Compiler<'a> {
+    variables: &'a [Variable],
+    ops: Vec<Op>,
+    /// Temporaries are reused by each statement
+    temps: usize,
+    max_temps: usize,
+    /// Jumps to be patched to the end of loop or function
+    breaks: Vec<Vec<usize>>,
+    returns: Vec<Vec<usize>>,
+}
+
+impl Compiler<'_> {
+    fn take(&mut self) -> Vec<Op> {
+        std::mem::take(&mut self.ops)
+    }
+
+    fn temp(&mut self) -> Reg {
+        let ret = self.temps;
+        self.temps += 1;
+        self.max_temps = self.max_temps.max(self.temps);
+        ret
+    }
+
+    fn push(&mut self, op: Op) -> usize {
+        self.ops.push(op);
+        self.ops.len() - 1
+    }
+
+    fn patch(&mut self, jumps: Vec<usize>) {
+        let target = self.ops.len();
+        for x in jumps {
+            match &mut self.ops[x] {
+                Op::Jump(x) => *x = target,
+                Op::JumpIfNot { target: x, .. } => *x = target,
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Compile top-level statements of a process.
+    /// `break` and `return` outside loop and function exit the statement like the evaluator.
+    fn statements(&mut self, statements: &[Statement], blocking: bool) {
+        for x in statements {
+            self.breaks.push(Vec::new());
+            self.returns.push(Vec::new());
+            self.statement(x, blocking);
+            let breaks = self.breaks.pop().unwrap();
+            let returns = self.returns.pop().unwrap();
+            self.patch(breaks);
+            self.patch(returns);
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement, blocking: bool) {
+        self.temps = 0;
+        match statement {
+            Statement::Assign(x) => {
+                let widths: Vec<_> = x
+                    .dst
+                    .iter()
+                    .map(|x| {
+                        if let Some((_, width)) = &x.select {
+                            *width
+                        } else {
+                            self.variables[x.var.0].width
+                        }
+                    })
+                    .collect();
+                let total: usize = widths.iter().sum();
+                let src = self.expression(&x.expr, total);
+
+                // The first destination is assigned from MSB
+                let mut lsb = total;
+                for (dst, width) in x.dst.iter().zip(widths) {
+                    lsb -= width;
+                    let select = dst
+                        .select
+                        .as_ref()
+                        .map(|(x, width)| (self.expression(x, 0), *width));
+                    self.push(Op::Store {
+                        var: dst.var,
+                        src,
+                        lsb,
+                        width,
+                        select,
+                        blocking,
+                    });
+                }
+            }
+            Statement::If(x) => {
+                let cond = self.expression(&x.cond, 0);
+                let jump_false = self.push(Op::JumpIfNot { cond, target: 0 });
+                for x in &x.true_side {
+                    self.statement(x, blocking);
+                }
+                if x.false_side.is_empty() {
+                    self.patch(vec![jump_false]);
+                } else {
+                    let jump_end = self.push(Op::Jump(0));
+                    self.patch(vec![jump_false]);
+                    for x in &x.false_side {
+                        self.statement(x, blocking);
+                    }
+                    self.patch(vec![jump_end]);
+                }
+            }
+            Statement::For(x) => {
+                self.loop_var(x.var, &x.start);
+                let head = self.ops.len();
+                self.temps = 0;
+                let cond = self.expression(&x.cond, 0);
+                let jump_exit = self.push(Op::JumpIfNot { cond, target: 0 });
+                self.breaks.push(vec![jump_exit]);
+                for x in &x.body {
+                    self.statement(x, blocking);
+                }
+                self.loop_var(x.var, &x.step);
+                self.push(Op::Jump(head));
+                let breaks = self.breaks.pop().unwrap();
+                self.patch(breaks);
+            }
+            Statement::Break => {
+                let jump = self.push(Op::Jump(0));
+                self.breaks.last_mut().unwrap().push(jump);
+            }
+            Statement::Return => {
+                let jump = self.push(Op::Jump(0));
+                self.returns.last_mut().unwrap().push(jump);
+            }
+            // variables in function are assigned immediately even in always_ff
+            Statement::FunctionBody(x) => {
+                self.breaks.push(Vec::new());
+                self.returns.push(Vec::new());
+                for x in x {
+                    self.statement(x, true);
+                }
+                let breaks = self.breaks.pop().unwrap();
+                let returns = self.returns.pop().unwrap();
+                self.patch(breaks);
+                self.patch(returns);
+            }
+            Statement::SystemFunctionCall(x) => {
+                self.push(Op::System(x.clone()));
+            }
+        }
+    }
+
+    /// Loop variable is assigned immediately
+    fn loop_var(&mut self, var: VarId, expr: &Expression) {
+        self.temps = 0;
+        let width = self.variables[var.0].width;
+        let src = self.expression(expr, width);
+        self.push(Op::Store {
+            var,
+            src,
+            lsb: 0,
+            width,
+            select: None,
+            blocking: true,
+        });
+    }
+
+    fn expression(&mut self, expr: &Expression, context_width: usize) -> Reg {
+        let w = evaluator::width(expr, self.variables).max(context_width);
+        let s = evaluator::signed(expr, self.variables);
+        self.context(expr, w, s)
+    }
+
+    fn self_determined(&self, expr: &Expression) -> (usize, bool) {
+        (
+            evaluator::width(expr, self.variables),
+            evaluator::signed(expr, self.variables),
+        )
+    }
+
+    /// Compile expression in the same manner as `evaluator::evaluate_context`
+    fn context(&mut self, expr: &Expression, w: usize, s: bool) -> Reg {
+        if expr.is_const() {
+            let value = evaluator::evaluate_context(expr, self.variables, w, s);
+            let dst = self.temp();
+            self.push(Op::Const { dst, value });
+            return dst;
+        }
+
+        match expr {
+            Expression::Value(_) | Expression::Fill(_) => unreachable!(),
+            Expression::Variable(var) => {
+                let dst = self.temp();
+                self.push(Op::Load {
+                    dst,
+                    var: *var,
+                    w,
+                    s,
+                });
+                dst
+            }
+            Expression::Select { var, offset, width } => {
+                let offset = self.expression(offset, 0);
+                let dst = self.temp();
+                self.push(Op::Select {
+                    dst,
+                    var: *var,
+                    offset,
+                    width: *width,
+                    w,
+                    s,
+                });
+                dst
+            }
+            Expression::Unary { op, operand } => match op {
+                UnaryOp::Plus => self.context(operand, w, s),
+                UnaryOp::Minus | UnaryOp::Inv => {
+                    let src = self.context(operand, w, s);
+                    let dst = self.temp();
+                    self.push(Op::Unary {
+                        dst,
+                        op: *op,
+                        src,
+                        w,
+                    });
+                    dst
+                }
+                _ => {
+                    let src = self.expression(operand, 0);
+                    let dst = self.temp();
+                    self.push(Op::Reduce {
+                        dst,
+                        op: *op,
+                        src,
+                        w,
+                    });
+                    dst
+                }
+            },
+            Expression::Binary { op, left, right } => {
+                let (x, y) = if op.is_comparison() {
+                    let (lw, ls) = self.self_determined(left);
+                    let (rw, rs) = self.self_determined(right);
+                    let cw = lw.max(rw);
+                    let cs = ls && rs;
+                    (self.context(left, cw, cs), self.context(right, cw, cs))
+                } else if op.is_logical() {
+                    (self.expression(left, 0), self.expression(right, 0))
+                } else if op.is_shift() || *op == BinaryOp::Pow {
+                    (self.context(left, w, s), self.expression(right, 0))
+                } else {
+                    (self.context(left, w, s), self.context(right, w, s))
+                };
+                let dst = self.temp();
+                self.push(Op::Binary {
+                    dst,
+                    op: *op,
+                    x,
+                    y,
+                    w,
+                });
+                dst
+            }
+            Expression::Ternary {
+                cond,
+                true_expr,
+                false_expr,
+            } => {
+                let cond = self.expression(cond, 0);
+                let x = self.context(true_expr, w, s);
+                let y = self.context(false_expr, w, s);
+                let dst = self.temp();
+                self.push(Op::Mux {
+                    dst,
+                    cond,
+                    x,
+                    y,
+                    w,
+                    s,
+                });
+                dst
+            }
+            Expression::Concatenation(x) => {
+                let items = x
+                    .iter()
+                    .map(|(x, repeat)| (self.expression(x, 0), *repeat))
+                    .collect();
+                let dst = self.temp();
+                self.push(Op::Concat { dst, items, w });
+                dst
+            }
+            Expression::Resize {
+                expr,
+                width,
+                signed,
+            } => {
+                let src = self.expression(expr, 0);
+                let dst = self.temp();
+                self.push(Op::Resize {
+                    dst,
+                    src,
+                    width: *width,
+                    signed: *signed,
+                    w,
+                    s,
+                });
+                dst
+            }
+        }
+    }
+}
+
+fn reduce(op: UnaryOp, x: &Value) -> Value {
+    match op {
+        UnaryOp::Not => x.logic_not(),
+        UnaryOp::And => x.reduction_and(),
+        UnaryOp::Or => x.reduction_or(),
+        UnaryOp::Xor => x.reduction_xor(),
+        UnaryOp::Nand => x.reduction_and().inv(1),
+        UnaryOp::Nor => x.reduction_or().inv(1),
+        UnaryOp::Xnor => x.reduction_xor().inv(1),
+        UnaryOp::Clog2 => x.clog2(),
+        _ => unreachable!(),
+    }
+}
+
+fn binary(op: BinaryOp, x: &Value, y: &Value, w: usize) -> Value {
+    match op {
+        BinaryOp::Le => extend(x.le(y), w, false),
+        BinaryOp::Ge => extend(x.ge(y), w, false),
+        BinaryOp::Lt => extend(x.lt(y), w, false),
+        BinaryOp::Gt => extend(x.gt(y), w, false),
+        BinaryOp::Eq => extend(x.eq(y), w, false),
+        BinaryOp::Ne => extend(x.ne(y), w, false),
+        BinaryOp::CaseEq => extend(x.case_eq(y), w, false),
+        BinaryOp::CaseNe => extend(x.case_ne(y), w, false),
+        BinaryOp::WildcardEq => extend(x.wildcard_eq(y), w, false),
+        BinaryOp::WildcardNe => extend(x.wildcard_ne(y), w, false),
+        BinaryOp::LogicAnd => extend(x.logic_and(y), w, false),
+        BinaryOp::LogicOr => extend(x.logic_or(y), w, false),
+        BinaryOp::Shl | BinaryOp::AShl => x.shl(y, w),
+        BinaryOp::Shr => x.shr(y, w),
+        BinaryOp::AShr => x.ashr(y, w),
+        BinaryOp::Pow => x.pow(y, w),
+        BinaryOp::Div => x.div(y, w),
+        BinaryOp::Rem => x.rem(y, w),
+        BinaryOp::Mul => x.mul(y, w),
+        BinaryOp::Add => x.add(y, w),
+        BinaryOp::Sub => x.sub(y, w),
+        BinaryOp::And => x.and(y, w),
+        BinaryOp::Or => x.or(y, w),
+        BinaryOp::Xor => x.xor(y, w),
+        BinaryOp::Xnor => x.xnor(y, w),
+    }
+}
+
+/// `None` is returned if it has X/Z, and too large offset is saturated.
+fn offset(value: &Value) -> Option<usize> {
+    if value.has_xz() {
+        None
+    } else {
+        Some(value.to_usize().unwrap_or(usize::MAX))
+    }
+}
+
+fn run(
+    ops: &[Op],
+    temps: &mut [Value],
+    variables: &mut [Variable],
+    mut next: Option<&mut HashMap<VarId, Value>>,
+    system: &mut dyn FnMut(&SystemFunctionCall, &[Variable]) -> Flow,
+) -> Flow {
+    let mut pc = 0;
+    while let Some(op) = ops.get(pc) {
+        pc += 1;
+        match op {
+            Op::Const { dst, value } => temps[*dst] = value.clone(),
+            Op::Load { dst, var, w, s } => {
+                temps[*dst] = extend(variables[var.0].value.clone(), *w, *s);
+            }
+            Op::Select {
+                dst,
+                var,
+                offset: x,
+                width,
+                w,
+                s,
+            } => {
+                let value = match offset(&temps[*x]) {
+                    Some(x) => variables[var.0].value.select(x, *width),
+                    None => Value::x(*width),
+                };
+                temps[*dst] = extend(value, *w, *s);
+            }
+            Op::Unary { dst, op, src, w } => {
+                temps[*dst] = match op {
+                    UnaryOp::Minus => temps[*src].neg(*w),
+                    UnaryOp::Inv => temps[*src].inv(*w),
+                    _ => unreachable!(),
+                };
+            }
+            Op::Reduce { dst, op, src, w } => {
+                temps[*dst] = extend(reduce(*op, &temps[*src]), *w, false);
+            }
+            Op::Binary { dst, op, x, y, w } => {
+                temps[*dst] = binary(*op, &temps[*x], &temps[*y], *w);
+            }
+            Op::Mux {
+                dst,
+                cond,
+                x,
+                y,
+                w,
+                s,
+            } => {
+                let cond = &temps[*cond];
+                temps[*dst] = if cond.to_bool() {
+                    temps[*x].clone()
+                } else if cond.is_false() {
+                    temps[*y].clone()
+                } else if temps[*x].case_eq(&temps[*y]).to_bool() {
+                    // unknown condition gives X except the case both sides are the same
+                    temps[*x].clone()
+                } else {
+                    Value::x(*w).with_signed(*s)
+                };
+            }
+            Op::Concat { dst, items, w } => {
+                let mut ret = Value::zero(0);
+                for (x, repeat) in items {
+                    for _ in 0..*repeat {
+                        ret = ret.concat(&temps[*x]);
+                    }
+                }
+                temps[*dst] = extend(ret, *w, false);
+            }
+            Op::Resize {
+                dst,
+                src,
+                width,
+                signed,
+                w,
+                s,
+            } => {
+                let ret = temps[*src].resize(*width).with_signed(*signed);
+                temps[*dst] = extend(ret, *w, *s);
+            }
+            Op::Store {
+                var: id,
+                src,
+                lsb,
+                width,
+                select,
+                blocking,
+            } => {
+                let next = if *blocking { None } else { next.as_deref_mut() };
+                let part = temps[*src].select(*lsb, *width);
+                let var = &variables[id.0];
+                let current = next.as_ref().and_then(|x| x.get(id)).unwrap_or(&var.value);
+                let value = if let Some((x, width)) = select {
+                    // write to unknown position is ignored
+                    let Some(x) = offset(&temps[*x]) else {
+                        continue;
+                    };
+                    var.fit(&current.replace(x, *width, &part))
+                } else {
+                    var.fit(&part)
+                };
+                if let Some(next) = next {
+                    next.insert(*id, value);
+                } else {
+                    variables[id.0].value = value;
+                }
+            }
+            Op::Jump(x) => pc = *x,
+            Op::JumpIfNot { cond, target } => {
+                if !temps[*cond].to_bool() {
+                    pc = *target;
+                }
+            }
+            Op::System(x) => {
+                if system(x, variables) == Flow::Finish {
+                    return Flow::Finish;
+                }
+            }
+        }
+    }
+    Flow::Next
+}
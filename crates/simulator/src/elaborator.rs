@@ -0,0 +1,2371 @@
+use crate::HashMap;
+use crate::evaluator;
+use crate::ir::{
+    AssignStatement, BinaryOp, Destination, Expression, FfEvent, FfReset, ForStatement,
+    IfStatement, Ir, Process, ProcessKind, Statement, SystemFunctionCall, SystemFunctionKind,
+    UnaryOp, VarId, Variable,
+};
+use crate::simulator_error::SimulatorError;
+use std::mem;
+use veryl_analyzer::definition_table::{self, Definition};
+use veryl_analyzer::namespace::Namespace;
+use veryl_analyzer::namespace_table;
+use veryl_analyzer::symbol::{
+    Direction, FunctionProperty, ModuleProperty, Port, Symbol, SymbolId, SymbolKind, Type, TypeKind,
+};
+use veryl_analyzer::symbol_path::{GenericSymbolPath, GenericSymbolPathKind, SymbolPath};
+use veryl_analyzer::symbol_table::{self, ResolveResult};
+use veryl_analyzer::value::Value;
+use veryl_metadata::{Build, ClockType, ResetType};
+use veryl_parser::resource_table;
+use veryl_parser::token_range::TokenRange;
+use veryl_parser::veryl_grammar_trait::{self as syntax_tree, *};
+use veryl_parser::veryl_token::{Token, VerylToken};
+
+/// Width of index calculation in select
+const INDEX_WIDTH: usize = 64;
+
+#[derive(Clone, Debug)]
+struct TypeInfo {
+    /// Dimensions from outermost array to innermost width
+    dims: Vec<usize>,
+    signed: bool,
+    is_4state: bool,
+}
+
+impl TypeInfo {
+    fn width(&self) -> usize {
+        self.dims.iter().product()
+    }
+}
+
+/// Generic argument of function call
+#[derive(Clone, Debug)]
+enum Generic {
+    Value(Value),
+    Type(SymbolId),
+}
+
+/// Elaboration context of a module or interface instance
+#[derive(Default)]
+struct Scope {
+    /// Prefix of hierarchical names like `u_fifo.`
+    prefix: String,
+    default_clock: Option<SymbolId>,
+    default_reset: Option<SymbolId>,
+    variables: HashMap<SymbolId, VarId>,
+    parameters: HashMap<SymbolId, Value>,
+    /// Parameter values given by the parent instance
+    overrides: HashMap<SymbolId, Value>,
+    /// Members of interface instances and interface ports
+    interfaces: HashMap<SymbolId, HashMap<SymbolId, VarId>>,
+    /// Generic arguments of the function which is being inlined
+    generics: HashMap<SymbolId, Generic>,
+}
+
+/// Port connection which is evaluated in the parent scope
+enum Connection {
+    Input(Expression),
+    Output(Vec<Destination>),
+    Inout(VarId),
+    Interface(HashMap<SymbolId, VarId>),
+}
+
+#[derive(Default)]
+pub struct Elaborator {
+    ir: Ir,
+    clock_type: ClockType,
+    reset_type: ResetType,
+    depth_limit: usize,
+    total_limit: usize,
+    depth: usize,
+    total: usize,
+    scope: Scope,
+    dims: HashMap<VarId, Vec<usize>>,
+    msb: Vec<usize>,
+    /// Reset condition of the current always_ff
+    reset: Option<Expression>,
+    /// Inlined function calls which must be executed before the current statement
+    hoisted: Vec<Statement>,
+    /// Variables which receive the value of `return` in the inlined functions
+    returns: Vec<Option<VarId>>,
+    /// Number of inlined function calls to make unique names
+    calls: usize,
+}
+
+type Result<T> = std::result::Result<T, SimulatorError>;
+
+impl Elaborator {
+    pub fn elaborate(top: &str, namespace: &Namespace, build: &Build) -> Result<Ir> {
+        let path = SymbolPath::new(&[resource_table::insert_str(top)]);
+        let symbol = symbol_table::resolve((&path, namespace))
+            .map_err(|_| SimulatorError::top_not_found(top))?;
+        let SymbolKind::Module(property) = &symbol.found.kind else {
+            return Err(SimulatorError::top_not_found(top));
+        };
+        let Some(Definition::Module(declaration)) = definition_table::get(property.definition)
+        else {
+            return Err(SimulatorError::top_not_found(top));
+        };
+
+        let mut elaborator = Elaborator {
+            clock_type: build.clock_type,
+            reset_type: build.reset_type,
+            depth_limit: build.instance_depth_limit,
+            total_limit: build.instance_total_limit,
+            scope: Scope {
+                default_clock: property.default_clock,
+                default_reset: property.default_reset,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        elaborator.module(property, &declaration)?;
+        elaborator.ir.sort_comb();
+        elaborator.check_drivers()?;
+        Ok(elaborator.ir)
+    }
+
+    /// Reject designs whose combinational logic can't be settled by a single pass
+    fn check_drivers(&self) -> Result<()> {
+        let ir = &self.ir;
+        let processes: Vec<_> = ir.comb.iter().chain(ir.ff.iter()).collect();
+
+        if let Some((var, drivers)) = ir.multiple_drivers() {
+            let tokens: Vec<_> = drivers.iter().map(|x| processes[*x].token).collect();
+            return Err(SimulatorError::multiple_driver(
+                &ir.variables[var.0].name,
+                &tokens[0],
+                &tokens[1..],
+            ));
+        }
+
+        if let Some(processes) = ir.comb_loop() {
+            // a variable which is driven by the last process and read by the first one
+            let last = &ir.comb[processes[processes.len() - 1]];
+            let first = &ir.comb[processes[0]];
+            let var = last
+                .outputs
+                .iter()
+                .find(|x| first.inputs.contains(x))
+                .unwrap_or(&last.outputs[0]);
+            let tokens: Vec<_> = processes.iter().map(|x| ir.comb[*x].token).collect();
+            return Err(SimulatorError::combinational_loop(
+                &ir.variables[var.0].name,
+                &tokens[0],
+                &tokens[1..],
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn module(&mut self, property: &ModuleProperty, declaration: &ModuleDeclaration) -> Result<()> {
+        // Interface ports are bound by the parent instance
+        for port in &property.ports {
+            if !self.scope.interfaces.contains_key(&port.symbol) {
+                self.variable(&port.symbol())?;
+            }
+        }
+
+        let items: Vec<ModuleItem> = declaration
+            .module_declaration_list
+            .iter()
+            .flat_map(|x| Vec::<ModuleItem>::from(x.module_group.as_ref()))
+            .collect();
+        let items: Vec<_> = items.iter().map(|x| x.generate_item.as_ref()).collect();
+        self.generate_items(&items)
+    }
+
+    fn interface(&mut self, declaration: &InterfaceDeclaration) -> Result<()> {
+        let items: Vec<InterfaceItem> = declaration
+            .interface_declaration_list
+            .iter()
+            .flat_map(|x| Vec::<InterfaceItem>::from(x.interface_group.as_ref()))
+            .collect();
+        let items: Vec<_> = items
+            .iter()
+            .filter_map(|x| match x {
+                InterfaceItem::GenerateItem(x) => Some(x.generate_item.as_ref()),
+                InterfaceItem::ModportDeclaration(_) => None,
+            })
+            .collect();
+        self.generate_items(&items)
+    }
+
+    fn generate_items(&mut self, items: &[&GenerateItem]) -> Result<()> {
+        // Declare module-level variables and interface instances before local variables in
+        // statement blocks and references through interfaces
+        for item in items {
+            match item {
+                GenerateItem::VarDeclaration(x) => {
+                    self.declare(&x.var_declaration.identifier)?;
+                }
+                GenerateItem::LetDeclaration(x) => {
+                    self.declare(&x.let_declaration.identifier)?;
+                }
+                GenerateItem::InstDeclaration(x) => {
+                    let x = &x.inst_declaration;
+                    if let SymbolKind::Interface(_) = self.inst_target(x)?.kind {
+                        self.inst_declaration(x)?;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        for item in items {
+            self.generate_item(item)?;
+        }
+        Ok(())
+    }
+
+    fn generate_item(&mut self, arg: &GenerateItem) -> Result<()> {
+        match arg {
+            GenerateItem::VarDeclaration(_)
+            | GenerateItem::ConstDeclaration(_)
+            | GenerateItem::TypeDefDeclaration(_)
+            | GenerateItem::EnumDeclaration(_)
+            | GenerateItem::StructUnionDeclaration(_)
+            | GenerateItem::ImportDeclaration(_) => Ok(()),
+            GenerateItem::LetDeclaration(x) => {
+                let x = &x.let_declaration;
+                let statement = self.let_assign(&x.identifier, &x.expression)?;
+                let mut statements = mem::take(&mut self.hoisted);
+                statements.push(statement);
+                self.add_comb(statements, x.as_ref().into());
+                Ok(())
+            }
+            GenerateItem::AssignDeclaration(x) => {
+                let x = &x.assign_declaration;
+                let idents: Vec<HierarchicalIdentifier> = x.assign_destination.as_ref().into();
+                let mut dst = Vec::new();
+                for ident in &idents {
+                    dst.push(self.hierarchical_destination(ident)?);
+                }
+                let expr = self.expression(&x.expression)?;
+                let mut statements = mem::take(&mut self.hoisted);
+                statements.push(Statement::Assign(AssignStatement { dst, expr }));
+                self.add_comb(statements, x.as_ref().into());
+                Ok(())
+            }
+            GenerateItem::AlwaysCombDeclaration(x) => {
+                let x = &x.always_comb_declaration;
+                let statements = self.statement_block(&x.statement_block)?;
+                self.add_comb(statements, x.as_ref().into());
+                Ok(())
+            }
+            GenerateItem::InstDeclaration(x) => {
+                let x = &x.inst_declaration;
+                if let SymbolKind::Interface(_) = self.inst_target(x)?.kind {
+                    // interface instances are elaborated in advance
+                    Ok(())
+                } else {
+                    self.inst_declaration(x)
+                }
+            }
+            GenerateItem::AlwaysFfDeclaration(x) => self.always_ff(&x.always_ff_declaration),
+            GenerateItem::ConnectDeclaration(x) => Err(unsupported(
+                "connect",
+                x.connect_declaration.as_ref().into(),
+            )),
+            // functions are inlined at each call
+            GenerateItem::FunctionDeclaration(_) => Ok(()),
+            GenerateItem::GenerateIfDeclaration(x) => self.generate_if(&x.generate_if_declaration),
+            GenerateItem::GenerateForDeclaration(x) => {
+                self.generate_for(&x.generate_for_declaration)
+            }
+            GenerateItem::GenerateBlockDeclaration(x) => {
+                let x = &x.generate_block_declaration.generate_named_block;
+                let groups: Vec<_> = x
+                    .generate_named_block_list
+                    .iter()
+                    .map(|x| x.generate_group.as_ref())
+                    .collect();
+                let label = x.identifier.identifier_token.to_string();
+                self.generate_block(Some(&label), &groups)
+            }
+            GenerateItem::AliasDeclaration(x) => {
+                Err(unsupported("alias", x.alias_declaration.as_ref().into()))
+            }
+            GenerateItem::InitialDeclaration(x) => {
+                let x = &x.initial_declaration;
+                let statements = self.statement_block(&x.statement_block)?;
+                self.ir.initial.push(Process::new(
+                    ProcessKind::Initial,
+                    statements,
+                    x.as_ref().into(),
+                ));
+                Ok(())
+            }
+            GenerateItem::FinalDeclaration(x) => {
+                Err(unsupported("final", x.final_declaration.as_ref().into()))
+            }
+            GenerateItem::UnsafeBlock(x) => {
+                Err(unsupported("unsafe", x.unsafe_block.as_ref().into()))
+            }
+        }
+    }
+
+    fn generate_if(&mut self, arg: &GenerateIfDeclaration) -> Result<()> {
+        if self.const_value(&arg.expression)?.to_bool() {
+            let x = &arg.generate_named_block;
+            let groups: Vec<_> = x
+                .generate_named_block_list
+                .iter()
+                .map(|x| x.generate_group.as_ref())
+                .collect();
+            let label = x.identifier.identifier_token.to_string();
+            return self.generate_block(Some(&label), &groups);
+        }
+
+        for x in &arg.generate_if_declaration_list {
+            if self.const_value(&x.expression)?.to_bool() {
+                return self.generate_optional_block(&x.generate_optional_named_block);
+            }
+        }
+
+        if let Some(x) = &arg.generate_if_declaration_opt {
+            self.generate_optional_block(&x.generate_optional_named_block)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn generate_optional_block(&mut self, arg: &GenerateOptionalNamedBlock) -> Result<()> {
+        let groups: Vec<_> = arg
+            .generate_optional_named_block_list
+            .iter()
+            .map(|x| x.generate_group.as_ref())
+            .collect();
+        let label = arg
+            .generate_optional_named_block_opt
+            .as_ref()
+            .map(|x| x.identifier.identifier_token.to_string());
+        self.generate_block(label.as_deref(), &groups)
+    }
+
+    fn generate_for(&mut self, arg: &GenerateForDeclaration) -> Result<()> {
+        let token: TokenRange = arg.into();
+        let block = &arg.generate_named_block;
+        let label = block.identifier.identifier_token.token.text;
+
+        // genvar is declared in the namespace of the named block
+        let genvar = arg.identifier.identifier_token.token;
+        let mut namespace = namespace_table::get(genvar.id).unwrap_or_default();
+        namespace.push(label);
+        let genvar = symbol_table::resolve((&SymbolPath::new(&[genvar.text]), &namespace))
+            .map_err(|_| {
+                SimulatorError::unresolvable_identifier(
+                    &arg.identifier.identifier_token.to_string(),
+                    &token,
+                )
+            })?
+            .found;
+
+        let start = self.const_value(&arg.range.expression)?;
+        let (op, end) = match &arg.range.range_opt {
+            Some(x) => (
+                range_operator(&x.range_operator),
+                self.const_value(&x.expression)?,
+            ),
+            None => (BinaryOp::Le, start.clone()),
+        };
+        let step = match &arg.generate_for_declaration_opt {
+            Some(x) => Some((assignment_operator(&x.assignment_operator)?, &x.expression)),
+            None => None,
+        };
+
+        let groups: Vec<_> = block
+            .generate_named_block_list
+            .iter()
+            .map(|x| x.generate_group.as_ref())
+            .collect();
+
+        let mut index = start;
+        loop {
+            let cond = binary(
+                op,
+                Expression::Value(index.clone()),
+                Expression::Value(end.clone()),
+            );
+            if !evaluator::evaluate(&cond, &self.ir.variables, 0).to_bool() {
+                break;
+            }
+            let Some(i) = index.to_usize() else {
+                return Err(SimulatorError::unevaluatable_expression(&token));
+            };
+
+            self.scope.parameters.insert(genvar.id, index.clone());
+            self.generate_block(Some(&format!("{label}[{i}]")), &groups)?;
+
+            let next = if let Some((op, expr)) = &step {
+                let value = self.const_value(expr)?;
+                binary(
+                    *op,
+                    Expression::Value(index.clone()),
+                    Expression::Value(value),
+                )
+            } else {
+                binary(
+                    BinaryOp::Add,
+                    Expression::Value(index.clone()),
+                    Expression::Value(Value::new(1, 1, false)),
+                )
+            };
+            let next = evaluator::evaluate(&next, &self.ir.variables, index.width)
+                .resize(index.width)
+                .with_signed(index.signed);
+            // step which doesn't change the index never terminates
+            if next.case_eq(&index).to_bool() {
+                return Err(SimulatorError::unevaluatable_expression(&token));
+            }
+            index = next;
+        }
+        self.scope.parameters.remove(&genvar.id);
+        Ok(())
+    }
+
+    /// Elaborate items in the generate block.
+    /// Declarations in the block are discarded after the block
+    /// because the same symbols are elaborated again in each iteration of generate for.
+    fn generate_block(&mut self, label: Option<&str>, groups: &[&GenerateGroup]) -> Result<()> {
+        let items: Vec<GenerateItem> = groups
+            .iter()
+            .flat_map(|x| Vec::<GenerateItem>::from(*x))
+            .collect();
+        let items: Vec<_> = items.iter().collect();
+
+        let prefix = if let Some(label) = label {
+            format!("{}{}.", self.scope.prefix, label)
+        } else {
+            self.scope.prefix.clone()
+        };
+        let prefix = mem::replace(&mut self.scope.prefix, prefix);
+        let variables = self.scope.variables.clone();
+        let parameters = self.scope.parameters.clone();
+        let interfaces = self.scope.interfaces.clone();
+
+        let ret = self.generate_items(&items);
+
+        self.scope.prefix = prefix;
+        self.scope.variables = variables;
+        self.scope.parameters = parameters;
+        self.scope.interfaces = interfaces;
+        ret
+    }
+
+    /// Resolve the module or interface which is instantiated
+    fn inst_target(&mut self, arg: &InstDeclaration) -> Result<Symbol> {
+        let symbol = symbol_table::resolve(arg.scoped_identifier.as_ref()).map_err(|_| {
+            SimulatorError::unresolvable_identifier(
+                &arg.scoped_identifier.identifier().to_string(),
+                &arg.scoped_identifier.as_ref().into(),
+            )
+        })?;
+        Ok(symbol.found)
+    }
+
+    fn inst_declaration(&mut self, arg: &InstDeclaration) -> Result<()> {
+        let token: TokenRange = arg.into();
+        if arg.inst_declaration_opt0.is_some() {
+            return Err(unsupported("instance array", token));
+        }
+
+        let target = self.inst_target(arg)?;
+        let (generic_parameters, parameters, definition) = match &target.kind {
+            SymbolKind::Module(x) => (&x.generic_parameters, &x.parameters, x.definition),
+            SymbolKind::Interface(x) => (&x.generic_parameters, &x.parameters, x.definition),
+            x => {
+                return Err(unsupported(
+                    &format!("{} instance", x.to_kind_name()),
+                    token,
+                ));
+            }
+        };
+        if !generic_parameters.is_empty() {
+            return Err(unsupported("generic instance", token));
+        }
+
+        self.total += 1;
+        if self.depth >= self.depth_limit {
+            return Err(SimulatorError::exceed_limit("hierarchy depth", &token));
+        }
+        if self.total > self.total_limit {
+            return Err(SimulatorError::exceed_limit("total instance", &token));
+        }
+
+        let mut overrides = HashMap::default();
+        if let Some(x) = &arg.inst_declaration_opt1
+            && let Some(x) = &x.inst_parameter.inst_parameter_opt
+        {
+            let items: Vec<InstParameterItem> = x.inst_parameter_list.as_ref().into();
+            for item in &items {
+                let name = item.identifier.identifier_token.token.text;
+                let Some(param) = parameters.iter().find(|x| x.name == name) else {
+                    return Err(SimulatorError::unresolvable_identifier(
+                        &item.identifier.identifier_token.to_string(),
+                        &item.identifier.as_ref().into(),
+                    ));
+                };
+                let value = if let Some(x) = &item.inst_parameter_item_opt {
+                    self.const_value(&x.expression)?
+                } else {
+                    self.const_value(&item.identifier.as_ref().into())?
+                };
+                overrides.insert(param.symbol, value);
+            }
+        }
+
+        let scope = Scope {
+            prefix: format!("{}{}.", self.scope.prefix, arg.identifier.identifier_token),
+            overrides,
+            ..Default::default()
+        };
+
+        match (&target.kind, definition_table::get(definition)) {
+            (SymbolKind::Module(property), Some(Definition::Module(declaration))) => {
+                let connections = self.connections(arg, &property.ports)?;
+                let hoisted = mem::take(&mut self.hoisted);
+                if !hoisted.is_empty() {
+                    self.add_comb(hoisted, token);
+                }
+                let scope = Scope {
+                    default_clock: property.default_clock,
+                    default_reset: property.default_reset,
+                    ..scope
+                };
+                self.child_scope(scope, |x| {
+                    x.bind_ports(connections)?;
+                    x.module(property, &declaration)
+                })?;
+            }
+            (SymbolKind::Interface(_), Some(Definition::Interface(declaration))) => {
+                let scope = self.child_scope(scope, |x| x.interface(&declaration))?;
+                let instance = symbol_table::resolve(arg.identifier.as_ref()).map_err(|_| {
+                    SimulatorError::unresolvable_identifier(
+                        &arg.identifier.identifier_token.to_string(),
+                        &token,
+                    )
+                })?;
+                self.scope
+                    .interfaces
+                    .insert(instance.found.id, scope.variables);
+            }
+            _ => return Err(unsupported("instance", token)),
+        }
+        Ok(())
+    }
+
+    /// Elaborate in the scope of a child instance, and return the scope
+    fn child_scope(
+        &mut self,
+        scope: Scope,
+        f: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<Scope> {
+        let parent = std::mem::replace(&mut self.scope, scope);
+        self.depth += 1;
+        let ret = f(self);
+        self.depth -= 1;
+        let scope = std::mem::replace(&mut self.scope, parent);
+        ret.map(|_| scope)
+    }
+
+    /// Evaluate port connections of the instance in the parent scope
+    fn connections(
+        &mut self,
+        arg: &InstDeclaration,
+        ports: &[Port],
+    ) -> Result<Vec<(Symbol, Connection)>> {
+        let mut ret = Vec::new();
+        let Some(x) = arg
+            .inst_declaration_opt2
+            .as_ref()
+            .and_then(|x| x.inst_declaration_opt3.as_ref())
+        else {
+            return Ok(ret);
+        };
+
+        let items: Vec<InstPortItem> = x.inst_port_list.as_ref().into();
+        for item in &items {
+            let token: TokenRange = item.into();
+            let name = item.identifier.identifier_token.token.text;
+            let Some(port) = ports.iter().find(|x| x.token.token.text == name) else {
+                return Err(SimulatorError::unresolvable_identifier(
+                    &item.identifier.identifier_token.to_string(),
+                    &token,
+                ));
+            };
+            let port = port.symbol();
+            let SymbolKind::Port(property) = &port.kind else {
+                unreachable!();
+            };
+
+            // `.a` is a shorthand of `.a(a)`
+            let implicit: syntax_tree::Expression;
+            let expr = if let Some(x) = &item.inst_port_item_opt {
+                x.expression.as_ref()
+            } else {
+                implicit = item.identifier.as_ref().into();
+                &implicit
+            };
+
+            let connection = match property.direction {
+                Direction::Input => Connection::Input(self.expression(expr)?),
+                Direction::Output => Connection::Output(self.connection_destination(expr)?),
+                Direction::Inout => match self.expression(expr)? {
+                    Expression::Variable(x) => Connection::Inout(x),
+                    _ => return Err(unsupported("inout connection to expression", token)),
+                },
+                Direction::Interface | Direction::Modport => {
+                    Connection::Interface(self.interface_connection(expr, &token)?)
+                }
+                Direction::Import => return Err(unsupported("import port", token)),
+            };
+            ret.push((port, connection));
+        }
+        Ok(ret)
+    }
+
+    fn connection_destination(
+        &mut self,
+        arg: &syntax_tree::Expression,
+    ) -> Result<Vec<Destination>> {
+        let items = match self.expression(arg)? {
+            Expression::Concatenation(x) => x,
+            x => vec![(x, 1)],
+        };
+
+        let mut ret = Vec::new();
+        for (x, repeat) in items {
+            match x {
+                Expression::Variable(var) if repeat == 1 => {
+                    ret.push(Destination { var, select: None });
+                }
+                Expression::Select { var, offset, width } if repeat == 1 => {
+                    ret.push(Destination {
+                        var,
+                        select: Some((*offset, width)),
+                    });
+                }
+                _ => return Err(unsupported("output connection to expression", arg.into())),
+            }
+        }
+        Ok(ret)
+    }
+
+    fn interface_connection(
+        &mut self,
+        arg: &syntax_tree::Expression,
+        token: &TokenRange,
+    ) -> Result<HashMap<SymbolId, VarId>> {
+        arg.unwrap_identifier()
+            .filter(|x| {
+                x.expression_identifier_list.is_empty() && x.expression_identifier_list0.is_empty()
+            })
+            .and_then(|x| symbol_table::resolve(x).ok())
+            .and_then(|x| self.scope.interfaces.get(&x.found.id))
+            .cloned()
+            .ok_or_else(|| unsupported("interface connection", *token))
+    }
+
+    /// Bind ports of the current scope to the connections given by the parent.
+    /// The port shares the connected variable if it has the same type,
+    /// otherwise it is connected through combinational assignment.
+    fn bind_ports(&mut self, connections: Vec<(Symbol, Connection)>) -> Result<()> {
+        for (port, connection) in connections {
+            let token: TokenRange = (&port.token).into();
+            match connection {
+                Connection::Input(Expression::Variable(var)) | Connection::Inout(var)
+                    if self.aliasable(&port, var)? =>
+                {
+                    self.alias(&port, var);
+                }
+                Connection::Input(expr) => {
+                    let var = self.variable(&port)?;
+                    let statement = Statement::Assign(AssignStatement {
+                        dst: vec![Destination { var, select: None }],
+                        expr,
+                    });
+                    self.add_comb(vec![statement], token);
+                }
+                Connection::Output(dst) => {
+                    if let [Destination { var, select: None }] = dst.as_slice()
+                        && self.aliasable(&port, *var)?
+                    {
+                        self.alias(&port, *var);
+                    } else {
+                        let var = self.variable(&port)?;
+                        let statement = Statement::Assign(AssignStatement {
+                            dst,
+                            expr: Expression::Variable(var),
+                        });
+                        self.add_comb(vec![statement], token);
+                    }
+                }
+                Connection::Inout(_) => {
+                    return Err(unsupported("inout connection to different type", token));
+                }
+                Connection::Interface(members) => {
+                    self.scope.interfaces.insert(port.id, members);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the port can share the variable connected by the parent
+    fn aliasable(&mut self, port: &Symbol, var: VarId) -> Result<bool> {
+        let SymbolKind::Port(x) = &port.kind else {
+            unreachable!();
+        };
+        let info = self.type_info(&x.r#type, &port.namespace, &(&port.token).into())?;
+        let variable = &self.ir.variables[var.0];
+        Ok(info.dims == self.dims[&var]
+            && info.signed == variable.signed
+            && info.is_4state == variable.is_4state)
+    }
+
+    fn alias(&mut self, port: &Symbol, var: VarId) {
+        let name = format!("{}{}", self.scope.prefix, port.token);
+        self.ir.add_alias(&name, var);
+        self.scope.variables.insert(port.id, var);
+    }
+
+    fn always_ff(&mut self, arg: &AlwaysFfDeclaration) -> Result<()> {
+        let token: TokenRange = arg.into();
+
+        let clock = if let Some(x) = arg.get_explicit_clock() {
+            self.hierarchical_symbol(&x)?
+        } else if let Some(x) = self.scope.default_clock.and_then(symbol_table::get) {
+            x
+        } else {
+            return Err(unsupported("always_ff without clock", token));
+        };
+        let posedge = match type_kind(&clock) {
+            Some(TypeKind::ClockPosedge) => true,
+            Some(TypeKind::ClockNegedge) => false,
+            _ => self.clock_type == ClockType::PosEdge,
+        };
+        let clock = self.variable(&clock)?;
+
+        let reset = if arg.has_if_reset() {
+            let reset = if let Some(x) = arg.get_explicit_reset() {
+                self.hierarchical_symbol(&x)?
+            } else if let Some(x) = self.scope.default_reset.and_then(symbol_table::get) {
+                x
+            } else {
+                return Err(unsupported("if_reset without reset", token));
+            };
+            let reset_type = match type_kind(&reset) {
+                Some(TypeKind::ResetAsyncHigh) => ResetType::AsyncHigh,
+                Some(TypeKind::ResetAsyncLow) => ResetType::AsyncLow,
+                Some(TypeKind::ResetSyncHigh) => ResetType::SyncHigh,
+                Some(TypeKind::ResetSyncLow) => ResetType::SyncLow,
+                _ => self.reset_type,
+            };
+            let var = Expression::Variable(self.variable(&reset)?);
+            let cond = match reset_type {
+                ResetType::AsyncHigh | ResetType::SyncHigh => var,
+                ResetType::AsyncLow | ResetType::SyncLow => Expression::Unary {
+                    op: UnaryOp::Not,
+                    operand: Box::new(var),
+                },
+            };
+            let asynchronous = matches!(reset_type, ResetType::AsyncHigh | ResetType::AsyncLow);
+            Some(FfReset { cond, asynchronous })
+        } else {
+            None
+        };
+
+        self.reset = reset.as_ref().map(|x| x.cond.clone());
+        let statements = self.statement_block(&arg.statement_block);
+        self.reset = None;
+
+        let event = FfEvent {
+            clock,
+            posedge,
+            reset,
+        };
+        self.ir
+            .ff
+            .push(Process::new(ProcessKind::Ff(event), statements?, token));
+        Ok(())
+    }
+
+    fn hierarchical_symbol(&mut self, arg: &HierarchicalIdentifier) -> Result<Symbol> {
+        let token: TokenRange = arg.into();
+        if !arg.hierarchical_identifier_list.is_empty()
+            || !arg.hierarchical_identifier_list0.is_empty()
+        {
+            return Err(unsupported("select of clock or reset", token));
+        }
+        let symbol = symbol_table::resolve(arg).map_err(|_| {
+            SimulatorError::unresolvable_identifier(
+                &arg.identifier.identifier_token.to_string(),
+                &token,
+            )
+        })?;
+        Ok(symbol.found)
+    }
+
+    fn add_comb(&mut self, statements: Vec<Statement>, token: TokenRange) {
+        self.ir
+            .comb
+            .push(Process::new(ProcessKind::Comb, statements, token));
+    }
+
+    fn declare(&mut self, arg: &Identifier) -> Result<VarId> {
+        let symbol = symbol_table::resolve(arg).map_err(|_| {
+            SimulatorError::unresolvable_identifier(&arg.identifier_token.to_string(), &arg.into())
+        })?;
+        self.variable(&symbol.found)
+    }
+
+    /// Get the variable corresponding to the symbol, and declare it if it doesn't exist yet
+    fn variable(&mut self, symbol: &Symbol) -> Result<VarId> {
+        if let Some(x) = self.scope.variables.get(&symbol.id) {
+            return Ok(*x);
+        }
+
+        let token: TokenRange = (&symbol.token).into();
+        let r#type = match &symbol.kind {
+            SymbolKind::Port(x) => {
+                if matches!(x.direction, Direction::Interface | Direction::Modport) {
+                    return Err(unsupported("interface port", token));
+                }
+                &x.r#type
+            }
+            SymbolKind::Variable(x) => &x.r#type,
+            _ => return Err(unsupported(&symbol.kind.to_kind_name(), token)),
+        };
+
+        let info = self.type_info(r#type, &symbol.namespace, &token)?;
+        let name = format!("{}{}", self.scope.prefix, symbol.token);
+        let variable = Variable::new(&name, info.width(), info.signed, info.is_4state, token);
+        let id = self.ir.add_variable(variable);
+        self.scope.variables.insert(symbol.id, id);
+        self.dims.insert(id, info.dims);
+        Ok(id)
+    }
+
+    fn type_info(
+        &mut self,
+        r#type: &Type,
+        namespace: &Namespace,
+        token: &TokenRange,
+    ) -> Result<TypeInfo> {
+        let mut dims = Vec::new();
+        for x in r#type.array.iter().chain(r#type.width.iter()) {
+            dims.push(self.const_usize(x)?);
+        }
+
+        let (base, signed) = match &r#type.kind {
+            TypeKind::Clock
+            | TypeKind::ClockPosedge
+            | TypeKind::ClockNegedge
+            | TypeKind::Reset
+            | TypeKind::ResetAsyncHigh
+            | TypeKind::ResetAsyncLow
+            | TypeKind::ResetSyncHigh
+            | TypeKind::ResetSyncLow
+            | TypeKind::Bit
+            | TypeKind::Logic => {
+                if r#type.width.is_empty() {
+                    (Some(1), false)
+                } else {
+                    (None, false)
+                }
+            }
+            TypeKind::Bool => (Some(1), false),
+            TypeKind::U32 => (Some(32), false),
+            TypeKind::I32 => (Some(32), true),
+            TypeKind::U64 => (Some(64), false),
+            TypeKind::I64 => (Some(64), true),
+            TypeKind::UserDefined(x) => {
+                let symbol =
+                    symbol_table::resolve((&x.path.generic_path(), namespace)).map_err(|_| {
+                        SimulatorError::unresolvable_identifier(&x.path.to_string(), token)
+                    })?;
+                let symbol = match &symbol.found.kind {
+                    SymbolKind::GenericParameter(_) => {
+                        match self.scope.generics.get(&symbol.found.id) {
+                            Some(Generic::Type(x)) => symbol_table::get(*x)
+                                .ok_or_else(|| unsupported("generic parameter", *token))?,
+                            _ => return Err(unsupported("generic parameter", *token)),
+                        }
+                    }
+                    _ => symbol.found,
+                };
+                let width = self.user_defined_width(&symbol, token)?;
+                (Some(width), false)
+            }
+            _ => return Err(unsupported(&format!("type {}", r#type), *token)),
+        };
+
+        dims.extend(base);
+
+        Ok(TypeInfo {
+            dims,
+            signed: signed | r#type.is_signed(),
+            is_4state: !r#type.kind.is_2state(),
+        })
+    }
+
+    fn user_defined_width(&mut self, symbol: &Symbol, token: &TokenRange) -> Result<usize> {
+        match &symbol.kind {
+            SymbolKind::Enum(x) => {
+                if let Some(r#type) = &x.r#type {
+                    Ok(self.type_info(r#type, &symbol.namespace, token)?.width())
+                } else {
+                    Ok(x.width)
+                }
+            }
+            SymbolKind::Struct(x) => {
+                let mut ret = 0;
+                for member in &x.members {
+                    ret += self.member_width(*member, token)?;
+                }
+                Ok(ret)
+            }
+            SymbolKind::Union(x) => {
+                let mut ret = 0;
+                for member in &x.members {
+                    ret = ret.max(self.member_width(*member, token)?);
+                }
+                Ok(ret)
+            }
+            SymbolKind::TypeDef(x) => {
+                Ok(self.type_info(&x.r#type, &symbol.namespace, token)?.width())
+            }
+            _ => Err(unsupported(&symbol.kind.to_kind_name(), *token)),
+        }
+    }
+
+    fn member_width(&mut self, id: SymbolId, token: &TokenRange) -> Result<usize> {
+        let Some(symbol) = symbol_table::get(id) else {
+            return Err(unsupported("member", *token));
+        };
+        let r#type = match &symbol.kind {
+            SymbolKind::StructMember(x) => &x.r#type,
+            SymbolKind::UnionMember(x) => &x.r#type,
+            _ => return Err(unsupported(&symbol.kind.to_kind_name(), *token)),
+        };
+        Ok(self.type_info(r#type, &symbol.namespace, token)?.width())
+    }
+
+    fn const_value(&mut self, arg: &syntax_tree::Expression) -> Result<Value> {
+        let expr = self.const_expression(arg)?;
+        Ok(evaluator::evaluate(&expr, &self.ir.variables, 0))
+    }
+
+    /// Lower the expression which must be constant.
+    /// Function calls in it are executed at elaboration, and their variables are discarded.
+    fn const_expression(&mut self, arg: &syntax_tree::Expression) -> Result<Expression> {
+        let first = self.ir.variables.len();
+        let outer = mem::take(&mut self.hoisted);
+        let ret = self.expression(arg);
+        let hoisted = mem::replace(&mut self.hoisted, outer);
+        let expr = ret?;
+
+        if hoisted.is_empty() {
+            return if expr.is_const() {
+                Ok(expr)
+            } else {
+                Err(SimulatorError::unevaluatable_expression(&arg.into()))
+            };
+        }
+
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        expr.gather_variables(&mut inputs);
+        for x in &hoisted {
+            x.gather_variables(&mut inputs, &mut outputs);
+        }
+        if inputs.iter().any(|x| x.0 < first) {
+            return Err(SimulatorError::unevaluatable_expression(&arg.into()));
+        }
+
+        for x in &hoisted {
+            evaluator::execute(x, &mut self.ir.variables, None);
+        }
+        let value = evaluator::evaluate(&expr, &self.ir.variables, 0);
+
+        self.ir.variables.truncate(first);
+        self.ir.names.retain(|_, x| x.0 < first);
+        self.dims.retain(|x, _| x.0 < first);
+        Ok(Expression::Value(value))
+    }
+
+    fn const_usize(&mut self, arg: &syntax_tree::Expression) -> Result<usize> {
+        self.const_value(arg)?
+            .to_usize()
+            .ok_or_else(|| SimulatorError::unevaluatable_expression(&arg.into()))
+    }
+
+    fn parameter(&mut self, symbol: &Symbol, token: &TokenRange) -> Result<Value> {
+        if let Some(x) = self.scope.parameters.get(&symbol.id) {
+            return Ok(x.clone());
+        }
+
+        let SymbolKind::Parameter(x) = &symbol.kind else {
+            unreachable!();
+        };
+
+        let expr = if let Some(x) = self.scope.overrides.get(&symbol.id) {
+            Expression::Value(x.clone())
+        } else {
+            self.const_expression(&x.value)?
+        };
+
+        let info = self.type_info(&x.r#type, &symbol.namespace, token)?;
+        let width = info.width();
+        let value = evaluator::evaluate(&expr, &self.ir.variables, width)
+            .resize(width)
+            .with_signed(info.signed);
+        self.scope.parameters.insert(symbol.id, value.clone());
+        Ok(value)
+    }
+
+    fn enum_member(&mut self, symbol: &Symbol, token: &TokenRange) -> Result<Value> {
+        let SymbolKind::EnumMember(x) = &symbol.kind else {
+            unreachable!();
+        };
+        let Some(value) = x.value.value() else {
+            return Err(SimulatorError::unevaluatable_expression(token));
+        };
+        let width = if let Some(parent) = symbol.get_parent() {
+            self.user_defined_width(&parent, token)?
+        } else {
+            INDEX_WIDTH
+        };
+        Ok(Value::new(value as u64, width, false))
+    }
+
+    fn reference(
+        &mut self,
+        symbol: &ResolveResult,
+        selects: &[Select],
+        token: &TokenRange,
+    ) -> Result<Expression> {
+        match &symbol.found.kind {
+            SymbolKind::Port(_) | SymbolKind::Variable(_) => {
+                let var = self.variable(&symbol.found)?;
+                self.variable_reference(var, selects, token)
+            }
+            SymbolKind::Parameter(_) => {
+                let value = self.parameter(&symbol.found, token)?;
+                self.const_select(value, selects, token)
+            }
+            SymbolKind::EnumMember(_) => {
+                let value = self.enum_member(&symbol.found, token)?;
+                self.const_select(value, selects, token)
+            }
+            SymbolKind::Genvar => {
+                let Some(value) = self.scope.parameters.get(&symbol.found.id).cloned() else {
+                    return Err(SimulatorError::unevaluatable_expression(token));
+                };
+                self.const_select(value, selects, token)
+            }
+            SymbolKind::GenericParameter(_) => match self.scope.generics.get(&symbol.found.id) {
+                Some(Generic::Value(x)) => {
+                    let value = x.clone();
+                    self.const_select(value, selects, token)
+                }
+                _ => Err(unsupported("generic parameter", *token)),
+            },
+            x => Err(unsupported(&x.to_kind_name(), *token)),
+        }
+    }
+
+    fn variable_reference(
+        &mut self,
+        var: VarId,
+        selects: &[Select],
+        token: &TokenRange,
+    ) -> Result<Expression> {
+        if selects.is_empty() {
+            Ok(Expression::Variable(var))
+        } else {
+            let dims = self.dims[&var].clone();
+            let (offset, width) = self.select(&dims, selects, token)?;
+            Ok(Expression::Select {
+                var,
+                offset: Box::new(offset),
+                width,
+            })
+        }
+    }
+
+    /// Get the variable of member access through interface instance or interface port
+    fn interface_member(&mut self, symbol: &ResolveResult, token: &TokenRange) -> Result<VarId> {
+        let id = match &symbol.found.kind {
+            SymbolKind::Variable(_) => symbol.found.id,
+            SymbolKind::ModportVariableMember(x) => x.variable,
+            _ => return Err(unsupported("member access", *token)),
+        };
+        symbol
+            .full_path
+            .first()
+            .and_then(|x| self.scope.interfaces.get(x))
+            .and_then(|x| x.get(&id))
+            .copied()
+            .ok_or_else(|| unsupported("member access", *token))
+    }
+
+    fn const_select(
+        &mut self,
+        value: Value,
+        selects: &[Select],
+        token: &TokenRange,
+    ) -> Result<Expression> {
+        if selects.is_empty() {
+            return Ok(Expression::Value(value));
+        }
+
+        let (offset, width) = self.select(&[value.width], selects, token)?;
+        let offset = if offset.is_const() {
+            evaluator::evaluate(&offset, &self.ir.variables, 0).to_usize()
+        } else {
+            None
+        };
+        if let Some(offset) = offset {
+            Ok(Expression::Value(value.select(offset, width)))
+        } else {
+            Err(SimulatorError::unevaluatable_expression(token))
+        }
+    }
+
+    /// Convert selects to bit offset and width
+    fn select(
+        &mut self,
+        dims: &[usize],
+        selects: &[Select],
+        token: &TokenRange,
+    ) -> Result<(Expression, usize)> {
+        let mut dims = dims.to_vec();
+        let mut offset = index_value(0);
+        let mut width = dims.iter().product();
+
+        for s in selects {
+            if dims.is_empty() {
+                return Err(unsupported("select of scalar", *token));
+            }
+            let dim = dims.remove(0);
+            let stride: usize = dims.iter().product();
+
+            self.msb.push(dim.saturating_sub(1));
+            let ret = self.select_range(s);
+            self.msb.pop();
+            let (lsb, select_width, is_range) = ret?;
+
+            offset = binary(
+                BinaryOp::Add,
+                offset,
+                binary(BinaryOp::Mul, lsb, index_value(stride)),
+            );
+            width = select_width * stride;
+            if is_range {
+                dims.insert(0, select_width);
+            }
+        }
+
+        Ok((offset, width))
+    }
+
+    /// Get LSB, width and whether it is range select
+    fn select_range(&mut self, arg: &Select) -> Result<(Expression, usize, bool)> {
+        let Some(opt) = &arg.select_opt else {
+            return Ok((self.expression(&arg.expression)?, 1, false));
+        };
+
+        match opt.select_operator.as_ref() {
+            SelectOperator::Colon(_) => {
+                let msb = self.const_usize(&arg.expression)?;
+                let lsb = self.const_usize(&opt.expression)?;
+                if msb < lsb {
+                    return Err(unsupported("descending range select", arg.into()));
+                }
+                Ok((index_value(lsb), msb - lsb + 1, true))
+            }
+            SelectOperator::PlusColon(_) => {
+                let beg = self.expression(&arg.expression)?;
+                let width = self.const_usize(&opt.expression)?;
+                Ok((beg, width, true))
+            }
+            SelectOperator::MinusColon(_) => {
+                let beg = self.expression(&arg.expression)?;
+                let width = self.const_usize(&opt.expression)?;
+                let lsb = binary(BinaryOp::Sub, beg, index_value(width.saturating_sub(1)));
+                Ok((lsb, width, true))
+            }
+            SelectOperator::Step(_) => {
+                let beg = self.expression(&arg.expression)?;
+                let width = self.const_usize(&opt.expression)?;
+                let lsb = binary(BinaryOp::Mul, beg, index_value(width));
+                Ok((lsb, width, true))
+            }
+        }
+    }
+
+    fn hierarchical_destination(&mut self, arg: &HierarchicalIdentifier) -> Result<Destination> {
+        let token: TokenRange = arg.into();
+        let symbol = symbol_table::resolve(arg).map_err(|_| {
+            SimulatorError::unresolvable_identifier(
+                &arg.identifier.identifier_token.to_string(),
+                &token,
+            )
+        })?;
+        let selects: Vec<_> = arg
+            .hierarchical_identifier_list
+            .iter()
+            .map(|x| x.select.as_ref().clone())
+            .collect();
+        let members: Vec<Vec<_>> = arg
+            .hierarchical_identifier_list0
+            .iter()
+            .map(|x| {
+                x.hierarchical_identifier_list0_list
+                    .iter()
+                    .map(|x| x.select.as_ref().clone())
+                    .collect()
+            })
+            .collect();
+        let expr = self.identifier_reference(&symbol, &selects, &members, &token)?;
+        destination(expr, &token)
+    }
+
+    fn expression_destination(&mut self, arg: &ExpressionIdentifier) -> Result<Destination> {
+        let token: TokenRange = arg.into();
+        let expr = self.expression_identifier(arg)?;
+        destination(expr, &token)
+    }
+
+    /// Lower identifier with selects and member access through interface
+    fn identifier_reference(
+        &mut self,
+        symbol: &ResolveResult,
+        selects: &[Select],
+        members: &[Vec<Select>],
+        token: &TokenRange,
+    ) -> Result<Expression> {
+        match members {
+            [] => self.reference(symbol, selects, token),
+            [member] if selects.is_empty() => {
+                let var = self.interface_member(symbol, token)?;
+                self.variable_reference(var, member, token)
+            }
+            _ => Err(unsupported("member access", *token)),
+        }
+    }
+
+    fn let_assign(
+        &mut self,
+        identifier: &Identifier,
+        expression: &syntax_tree::Expression,
+    ) -> Result<Statement> {
+        let var = self.declare(identifier)?;
+        let expr = self.expression(expression)?;
+        Ok(Statement::Assign(AssignStatement {
+            dst: vec![Destination { var, select: None }],
+            expr,
+        }))
+    }
+
+    fn statement_block(&mut self, arg: &StatementBlock) -> Result<Vec<Statement>> {
+        let outer = mem::take(&mut self.hoisted);
+        let ret = self.statement_block_items(arg);
+        self.hoisted = outer;
+        ret
+    }
+
+    fn statement_block_items(&mut self, arg: &StatementBlock) -> Result<Vec<Statement>> {
+        let mut ret = Vec::new();
+        for x in &arg.statement_block_list {
+            let items: Vec<StatementBlockItem> = x.statement_block_group.as_ref().into();
+            for item in &items {
+                match item {
+                    StatementBlockItem::VarDeclaration(x) => {
+                        self.declare(&x.var_declaration.identifier)?;
+                    }
+                    StatementBlockItem::LetStatement(x) => {
+                        let x = &x.let_statement;
+                        let statement = self.let_assign(&x.identifier, &x.expression)?;
+                        ret.append(&mut self.hoisted);
+                        ret.push(statement);
+                    }
+                    StatementBlockItem::Statement(x) => {
+                        let mut statements = self.statement(&x.statement)?;
+                        ret.append(&mut self.hoisted);
+                        ret.append(&mut statements);
+                    }
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Lower a statement which is not enclosed by a statement block like a case item
+    fn single_statement(&mut self, arg: &syntax_tree::Statement) -> Result<Vec<Statement>> {
+        let outer = mem::take(&mut self.hoisted);
+        let ret = self.statement(arg);
+        let mut hoisted = mem::replace(&mut self.hoisted, outer);
+        hoisted.append(&mut ret?);
+        Ok(hoisted)
+    }
+
+    fn statement(&mut self, arg: &syntax_tree::Statement) -> Result<Vec<Statement>> {
+        match arg {
+            syntax_tree::Statement::IdentifierStatement(x) => {
+                self.identifier_statement(&x.identifier_statement)
+            }
+            syntax_tree::Statement::IfStatement(x) => Ok(vec![self.if_statement(&x.if_statement)?]),
+            syntax_tree::Statement::IfResetStatement(x) => {
+                Ok(vec![self.if_reset_statement(&x.if_reset_statement)?])
+            }
+            syntax_tree::Statement::ReturnStatement(x) => {
+                self.return_statement(&x.return_statement)
+            }
+            syntax_tree::Statement::BreakStatement(_) => Ok(vec![Statement::Break]),
+            syntax_tree::Statement::ForStatement(x) => {
+                Ok(vec![self.for_statement(&x.for_statement)?])
+            }
+            syntax_tree::Statement::CaseStatement(x) => self.case_statement(&x.case_statement),
+            syntax_tree::Statement::SwitchStatement(x) => {
+                self.switch_statement(&x.switch_statement)
+            }
+        }
+    }
+
+    fn identifier_statement(&mut self, arg: &IdentifierStatement) -> Result<Vec<Statement>> {
+        match arg.identifier_statement_group.as_ref() {
+            IdentifierStatementGroup::Assignment(x) => {
+                let dst = self.expression_destination(&arg.expression_identifier)?;
+                let expr = self.expression(&x.assignment.expression)?;
+                let expr = match x.assignment.assignment_group.as_ref() {
+                    AssignmentGroup::Equ(_) => expr,
+                    AssignmentGroup::AssignmentOperator(op) => {
+                        let op = assignment_operator(&op.assignment_operator)?;
+                        let left = self.expression_identifier(&arg.expression_identifier)?;
+                        binary(op, left, expr)
+                    }
+                    AssignmentGroup::DiamondOperator(op) => {
+                        return Err(unsupported("<>", op.diamond_operator.as_ref().into()));
+                    }
+                };
+                Ok(vec![Statement::Assign(AssignStatement {
+                    dst: vec![dst],
+                    expr,
+                })])
+            }
+            IdentifierStatementGroup::FunctionCall(x) => {
+                let identifier = &arg.expression_identifier;
+                let symbol = self.function_symbol(identifier)?;
+                if let SymbolKind::SystemFunction(_) = symbol.found.kind {
+                    let statement =
+                        self.system_function_call(&symbol, identifier, &x.function_call)?;
+                    Ok(vec![statement])
+                } else {
+                    // the return value is discarded
+                    self.function_call(&symbol, identifier, &x.function_call)?;
+                    Ok(mem::take(&mut self.hoisted))
+                }
+            }
+        }
+    }
+
+    fn function_symbol(&mut self, identifier: &ExpressionIdentifier) -> Result<ResolveResult> {
+        // generic function is resolved by the path without generic arguments
+        let path: GenericSymbolPath = identifier.scoped_identifier.as_ref().into();
+        let namespace = namespace_table::get(identifier.identifier().token.id).unwrap_or_default();
+        symbol_table::resolve((&path.generic_path(), &namespace)).map_err(|_| {
+            SimulatorError::unresolvable_identifier(
+                &identifier.identifier().to_string(),
+                &identifier.into(),
+            )
+        })
+    }
+
+    fn system_function_call(
+        &mut self,
+        symbol: &ResolveResult,
+        identifier: &ExpressionIdentifier,
+        arg: &FunctionCall,
+    ) -> Result<Statement> {
+        let token: TokenRange = identifier.into();
+        let Some(kind) = SystemFunctionKind::from_name(&symbol.found.token.to_string()) else {
+            return Err(unsupported(
+                &format!("system function {}", symbol.found.token),
+                token,
+            ));
+        };
+
+        let items = argument_items(arg);
+        let mut format = None;
+        let mut args = Vec::new();
+        for (i, item) in items.iter().enumerate() {
+            let expression = &item.argument_expression.expression;
+            // the first argument of $fatal is finish number
+            if kind == SystemFunctionKind::Fatal && i == 0 {
+                continue;
+            }
+            if let Some(Factor::StringLiteral(x)) = expression.unwrap_factor() {
+                if format.is_none() && args.is_empty() {
+                    let text = x.string_literal.string_literal_token.to_string();
+                    format = Some(unescape(&text[1..text.len() - 1]));
+                    continue;
+                } else {
+                    return Err(unsupported("string literal", expression.as_ref().into()));
+                }
+            }
+            args.push(self.expression(expression)?);
+        }
+
+        Ok(Statement::SystemFunctionCall(SystemFunctionCall {
+            kind,
+            format,
+            args,
+        }))
+    }
+
+    /// System function in expression like `$clog2`
+    fn system_function_expression(
+        &mut self,
+        symbol: &ResolveResult,
+        identifier: &ExpressionIdentifier,
+        arg: &FunctionCall,
+    ) -> Result<Expression> {
+        let token: TokenRange = identifier.into();
+        let name = symbol.found.token.to_string();
+        let items = argument_items(arg);
+        let [item] = items.as_slice() else {
+            return Err(unsupported(&format!("system function {name}"), token));
+        };
+        let expression = &item.argument_expression.expression;
+
+        match name.as_str() {
+            "$clog2" => Ok(Expression::Unary {
+                op: UnaryOp::Clog2,
+                operand: Box::new(self.expression(expression)?),
+            }),
+            "$signed" | "$unsigned" => {
+                let expr = self.expression(expression)?;
+                let width = evaluator::width(&expr, &self.ir.variables);
+                Ok(Expression::Resize {
+                    expr: Box::new(expr),
+                    width,
+                    signed: name == "$signed",
+                })
+            }
+            _ => Err(unsupported(&format!("system function {name}"), token)),
+        }
+    }
+
+    /// Inline the function call.
+    /// The function body is hoisted before the current statement,
+    /// and the variable which has the return value is returned.
+    fn function_call(
+        &mut self,
+        symbol: &ResolveResult,
+        identifier: &ExpressionIdentifier,
+        arg: &FunctionCall,
+    ) -> Result<Option<VarId>> {
+        let token: TokenRange = identifier.into();
+        let function = &symbol.found;
+        let SymbolKind::Function(property) = &function.kind else {
+            return Err(unsupported(
+                &format!("{} call", function.kind.to_kind_name()),
+                token,
+            ));
+        };
+        let Some(Definition::Function(declaration)) =
+            property.definition.and_then(definition_table::get)
+        else {
+            return Err(unsupported("function call", token));
+        };
+
+        if self.depth >= self.depth_limit {
+            return Err(SimulatorError::exceed_limit("function call depth", &token));
+        }
+
+        // Generic arguments and arguments are evaluated in the caller
+        let path: GenericSymbolPath = identifier.scoped_identifier.as_ref().into();
+        let arguments = path
+            .paths
+            .last()
+            .map(|x| x.arguments.clone())
+            .unwrap_or_default();
+        let table = function.generic_table(&arguments);
+        let mut generics = HashMap::default();
+        for id in &property.generic_parameters {
+            if let Some(param) = symbol_table::get(*id)
+                && let Some(path) = table.get(&param.token.text)
+            {
+                generics.insert(*id, self.generic_argument(path)?);
+            }
+        }
+
+        let connections = self.function_arguments(property, arg, &token)?;
+
+        self.calls += 1;
+        let prefix = format!("{}{}@{}.", self.scope.prefix, function.token, self.calls);
+        let prefix = mem::replace(&mut self.scope.prefix, prefix);
+        let generics = mem::replace(&mut self.scope.generics, generics);
+        let variables = self.scope.variables.clone();
+        let parameters = self.scope.parameters.clone();
+        self.depth += 1;
+
+        let ret = self.function_body(function, property, &declaration, connections);
+
+        self.depth -= 1;
+        self.scope.prefix = prefix;
+        self.scope.generics = generics;
+        self.scope.variables = variables;
+        self.scope.parameters = parameters;
+
+        let (body, mut outputs, ret) = ret?;
+        self.hoisted.push(Statement::FunctionBody(body));
+        self.hoisted.append(&mut outputs);
+        Ok(ret)
+    }
+
+    /// Evaluate arguments of function call in the order of ports
+    fn function_arguments(
+        &mut self,
+        property: &FunctionProperty,
+        arg: &FunctionCall,
+        token: &TokenRange,
+    ) -> Result<Vec<(Symbol, Connection)>> {
+        let items = argument_items(arg);
+        let mut ret = Vec::new();
+        for (i, port) in property.ports.iter().enumerate() {
+            let name = port.token.token.text;
+            // named argument like `a: x` or positional argument
+            let named = items.iter().find_map(|x| {
+                let opt = x.argument_item_opt.as_ref()?;
+                let ident = x.argument_expression.expression.unwrap_identifier()?;
+                (ident.identifier().token.text == name).then_some(opt.expression.as_ref())
+            });
+            let expr = if let Some(x) = named {
+                x
+            } else if let Some(x) = items.get(i).filter(|x| x.argument_item_opt.is_none()) {
+                x.argument_expression.expression.as_ref()
+            } else {
+                return Err(unsupported("function call without argument", *token));
+            };
+
+            let port = port.symbol();
+            let SymbolKind::Port(x) = &port.kind else {
+                unreachable!();
+            };
+            let connection = match x.direction {
+                Direction::Input => Connection::Input(self.expression(expr)?),
+                Direction::Output => Connection::Output(self.connection_destination(expr)?),
+                _ => return Err(unsupported("function port", (&port.token).into())),
+            };
+            ret.push((port, connection));
+        }
+        Ok(ret)
+    }
+
+    /// Lower the function body in the function scope, and return the body,
+    /// assignments to output arguments and the variable of return value
+    #[allow(clippy::type_complexity)]
+    fn function_body(
+        &mut self,
+        function: &Symbol,
+        property: &FunctionProperty,
+        declaration: &FunctionDeclaration,
+        connections: Vec<(Symbol, Connection)>,
+    ) -> Result<(Vec<Statement>, Vec<Statement>, Option<VarId>)> {
+        let mut body = Vec::new();
+        let mut outputs = Vec::new();
+        for (port, connection) in connections {
+            let var = self.variable(&port)?;
+            match connection {
+                Connection::Input(expr) => body.push(Statement::Assign(AssignStatement {
+                    dst: vec![Destination { var, select: None }],
+                    expr,
+                })),
+                Connection::Output(dst) => outputs.push(Statement::Assign(AssignStatement {
+                    dst,
+                    expr: Expression::Variable(var),
+                })),
+                _ => unreachable!(),
+            }
+        }
+
+        let ret = if let Some(r#type) = &property.ret {
+            let token: TokenRange = (&function.token).into();
+            let info = self.type_info(r#type, &function.inner_namespace(), &token)?;
+            let name = format!("{}return", self.scope.prefix);
+            let variable = Variable::new(&name, info.width(), info.signed, info.is_4state, token);
+            let var = self.ir.add_variable(variable);
+            self.dims.insert(var, info.dims);
+            Some(var)
+        } else {
+            None
+        };
+
+        self.returns.push(ret);
+        let statements = self.statement_block(&declaration.statement_block);
+        self.returns.pop();
+        body.append(&mut statements?);
+
+        Ok((body, outputs, ret))
+    }
+
+    fn generic_argument(&mut self, arg: &GenericSymbolPath) -> Result<Generic> {
+        let token = arg.range;
+        let Some(base) = arg.paths.first().map(|x| &x.base) else {
+            return Err(SimulatorError::unevaluatable_expression(&token));
+        };
+        match arg.kind {
+            GenericSymbolPathKind::IntegerBased => Ok(Generic::Value(based(base)?)),
+            GenericSymbolPathKind::IntegerBaseLess => Ok(Generic::Value(base_less(base)?)),
+            GenericSymbolPathKind::IntegerAllBit => match all_bit(base)? {
+                Expression::Value(x) => Ok(Generic::Value(x)),
+                _ => Err(SimulatorError::unevaluatable_expression(&token)),
+            },
+            GenericSymbolPathKind::RealExponent | GenericSymbolPathKind::RealFixedPoint => {
+                Err(unsupported("real number", token))
+            }
+            GenericSymbolPathKind::Identifier => {
+                let namespace = namespace_table::get(base.id).unwrap_or_default();
+                let symbol =
+                    symbol_table::resolve((&arg.generic_path(), &namespace)).map_err(|_| {
+                        SimulatorError::unresolvable_identifier(&base.to_string(), &token)
+                    })?;
+                match &symbol.found.kind {
+                    SymbolKind::Parameter(_) => {
+                        Ok(Generic::Value(self.parameter(&symbol.found, &token)?))
+                    }
+                    SymbolKind::EnumMember(_) => {
+                        Ok(Generic::Value(self.enum_member(&symbol.found, &token)?))
+                    }
+                    SymbolKind::GenericParameter(_) => self
+                        .scope
+                        .generics
+                        .get(&symbol.found.id)
+                        .cloned()
+                        .ok_or_else(|| unsupported("generic parameter", token)),
+                    SymbolKind::Enum(_)
+                    | SymbolKind::Struct(_)
+                    | SymbolKind::Union(_)
+                    | SymbolKind::TypeDef(_) => Ok(Generic::Type(symbol.found.id)),
+                    x => Err(unsupported(
+                        &format!("{} as generic argument", x.to_kind_name()),
+                        token,
+                    )),
+                }
+            }
+        }
+    }
+
+    fn return_statement(&mut self, arg: &ReturnStatement) -> Result<Vec<Statement>> {
+        let Some(Some(var)) = self.returns.last().copied() else {
+            return Err(unsupported("return", arg.into()));
+        };
+        let expr = self.expression(&arg.expression)?;
+        Ok(vec![
+            Statement::Assign(AssignStatement {
+                dst: vec![Destination { var, select: None }],
+                expr,
+            }),
+            Statement::Return,
+        ])
+    }
+
+    fn for_statement(&mut self, arg: &syntax_tree::ForStatement) -> Result<Statement> {
+        let var = self.declare(&arg.identifier)?;
+        let start = self.expression(&arg.range.expression)?;
+        let (op, end) = match &arg.range.range_opt {
+            Some(x) => (
+                range_operator(&x.range_operator),
+                self.expression(&x.expression)?,
+            ),
+            None => (BinaryOp::Le, start.clone()),
+        };
+        let cond = binary(op, Expression::Variable(var), end);
+        let step = if let Some(x) = &arg.for_statement_opt {
+            let op = assignment_operator(&x.assignment_operator)?;
+            binary(
+                op,
+                Expression::Variable(var),
+                self.expression(&x.expression)?,
+            )
+        } else {
+            binary(
+                BinaryOp::Add,
+                Expression::Variable(var),
+                Expression::Value(Value::new(1, 1, false)),
+            )
+        };
+        let body = self.statement_block(&arg.statement_block)?;
+        Ok(Statement::For(ForStatement {
+            var,
+            start,
+            cond,
+            step,
+            body,
+        }))
+    }
+
+    fn case_statement(&mut self, arg: &CaseStatement) -> Result<Vec<Statement>> {
+        let target = self.expression(&arg.expression)?;
+
+        let mut false_side = Vec::new();
+        let mut items = Vec::new();
+        for x in &arg.case_statement_list {
+            let x = &x.case_item;
+            let body = match x.case_item_group0.as_ref() {
+                CaseItemGroup0::Statement(x) => self.single_statement(&x.statement)?,
+                CaseItemGroup0::StatementBlock(x) => self.statement_block(&x.statement_block)?,
+            };
+            match x.case_item_group.as_ref() {
+                CaseItemGroup::CaseCondition(x) => {
+                    let cond = self.case_condition(&target, &x.case_condition)?;
+                    items.push((cond, body));
+                }
+                CaseItemGroup::Defaul(_) => false_side = body,
+            }
+        }
+
+        Ok(if_chain(items, false_side))
+    }
+
+    fn switch_statement(&mut self, arg: &SwitchStatement) -> Result<Vec<Statement>> {
+        let mut false_side = Vec::new();
+        let mut items = Vec::new();
+        for x in &arg.switch_statement_list {
+            let x = &x.switch_item;
+            let body = match x.switch_item_group0.as_ref() {
+                SwitchItemGroup0::Statement(x) => self.single_statement(&x.statement)?,
+                SwitchItemGroup0::StatementBlock(x) => self.statement_block(&x.statement_block)?,
+            };
+            match x.switch_item_group.as_ref() {
+                SwitchItemGroup::SwitchCondition(x) => {
+                    let cond = self.switch_condition(&x.switch_condition)?;
+                    items.push((cond, body));
+                }
+                SwitchItemGroup::Defaul(_) => false_side = body,
+            }
+        }
+
+        Ok(if_chain(items, false_side))
+    }
+
+    /// Condition of case item which matches if any item matches like `inside`
+    fn case_condition(&mut self, target: &Expression, arg: &CaseCondition) -> Result<Expression> {
+        let items: Vec<RangeItem> = arg.into();
+        self.range_items(target, &items)
+    }
+
+    fn switch_condition(&mut self, arg: &SwitchCondition) -> Result<Expression> {
+        let items: Vec<syntax_tree::Expression> = arg.into();
+        let mut ret: Option<Expression> = None;
+        for item in &items {
+            let cond = self.expression(item)?;
+            ret = Some(match ret {
+                Some(x) => binary(BinaryOp::LogicOr, x, cond),
+                None => cond,
+            });
+        }
+        Ok(ret.unwrap_or_else(|| Expression::Value(Value::from_bool(false))))
+    }
+
+    /// Whether the target matches any item.
+    /// Single value is compared by wildcard equality, and range is compared by its bounds.
+    fn range_items(&mut self, target: &Expression, items: &[RangeItem]) -> Result<Expression> {
+        let mut ret: Option<Expression> = None;
+        for item in items {
+            let range = &item.range;
+            let beg = self.expression(&range.expression)?;
+            let cond = if let Some(x) = &range.range_opt {
+                let end = self.expression(&x.expression)?;
+                binary(
+                    BinaryOp::LogicAnd,
+                    binary(BinaryOp::Ge, target.clone(), beg),
+                    binary(range_operator(&x.range_operator), target.clone(), end),
+                )
+            } else {
+                binary(BinaryOp::WildcardEq, target.clone(), beg)
+            };
+            ret = Some(match ret {
+                Some(x) => binary(BinaryOp::LogicOr, x, cond),
+                None => cond,
+            });
+        }
+        Ok(ret.unwrap_or_else(|| Expression::Value(Value::from_bool(false))))
+    }
+
+    fn if_statement(&mut self, arg: &syntax_tree::IfStatement) -> Result<Statement> {
+        let mut false_side = if let Some(x) = &arg.if_statement_opt {
+            self.statement_block(&x.statement_block)?
+        } else {
+            Vec::new()
+        };
+
+        for x in arg.if_statement_list.iter().rev() {
+            let cond = self.expression(&x.expression)?;
+            let true_side = self.statement_block(&x.statement_block)?;
+            false_side = vec![Statement::If(IfStatement {
+                cond,
+                true_side,
+                false_side,
+            })];
+        }
+
+        let cond = self.expression(&arg.expression)?;
+        let true_side = self.statement_block(&arg.statement_block)?;
+        Ok(Statement::If(IfStatement {
+            cond,
+            true_side,
+            false_side,
+        }))
+    }
+
+    fn if_reset_statement(&mut self, arg: &IfResetStatement) -> Result<Statement> {
+        let Some(cond) = self.reset.clone() else {
+            return Err(unsupported("if_reset outside always_ff", arg.into()));
+        };
+
+        let mut false_side = if let Some(x) = &arg.if_reset_statement_opt {
+            self.statement_block(&x.statement_block)?
+        } else {
+            Vec::new()
+        };
+
+        for x in arg.if_reset_statement_list.iter().rev() {
+            let cond = self.expression(&x.expression)?;
+            let true_side = self.statement_block(&x.statement_block)?;
+            false_side = vec![Statement::If(IfStatement {
+                cond,
+                true_side,
+                false_side,
+            })];
+        }
+
+        let true_side = self.statement_block(&arg.statement_block)?;
+        Ok(Statement::If(IfStatement {
+            cond,
+            true_side,
+            false_side,
+        }))
+    }
+
+    fn expression(&mut self, arg: &syntax_tree::Expression) -> Result<Expression> {
+        self.if_expression(&arg.if_expression)
+    }
+
+    fn if_expression(&mut self, arg: &IfExpression) -> Result<Expression> {
+        let mut ret = self.expression01(&arg.expression01)?;
+        for x in arg.if_expression_list.iter().rev() {
+            let cond = self.expression(&x.expression)?;
+            let true_expr = self.expression(&x.expression0)?;
+            ret = Expression::Ternary {
+                cond: Box::new(cond),
+                true_expr: Box::new(true_expr),
+                false_expr: Box::new(ret),
+            };
+        }
+        Ok(ret)
+    }
+
+    fn expression01(&mut self, arg: &Expression01) -> Result<Expression> {
+        let mut ret = self.expression02(&arg.expression02)?;
+        for x in &arg.expression01_list {
+            let operand = self.expression02(&x.expression02)?;
+            ret = binary_operator(&x.operator02.operator02_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression02(&mut self, arg: &Expression02) -> Result<Expression> {
+        let mut ret = self.expression03(&arg.expression03)?;
+        for x in &arg.expression02_list {
+            let operand = self.expression03(&x.expression03)?;
+            ret = binary_operator(&x.operator03.operator03_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression03(&mut self, arg: &Expression03) -> Result<Expression> {
+        let mut ret = self.expression04(&arg.expression04)?;
+        for x in &arg.expression03_list {
+            let operand = self.expression04(&x.expression04)?;
+            ret = binary_operator(&x.operator04.operator04_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression04(&mut self, arg: &Expression04) -> Result<Expression> {
+        let mut ret = self.expression05(&arg.expression05)?;
+        for x in &arg.expression04_list {
+            let operand = self.expression05(&x.expression05)?;
+            ret = binary_operator(&x.operator05.operator05_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression05(&mut self, arg: &Expression05) -> Result<Expression> {
+        let mut ret = self.expression06(&arg.expression06)?;
+        for x in &arg.expression05_list {
+            let operand = self.expression06(&x.expression06)?;
+            ret = binary_operator(&x.operator06.operator06_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression06(&mut self, arg: &Expression06) -> Result<Expression> {
+        let mut ret = self.expression07(&arg.expression07)?;
+        for x in &arg.expression06_list {
+            let operand = self.expression07(&x.expression07)?;
+            ret = binary_operator(&x.operator07.operator07_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression07(&mut self, arg: &Expression07) -> Result<Expression> {
+        let mut ret = self.expression08(&arg.expression08)?;
+        for x in &arg.expression07_list {
+            let operand = self.expression08(&x.expression08)?;
+            ret = binary_operator(&x.operator08.operator08_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression08(&mut self, arg: &Expression08) -> Result<Expression> {
+        let mut ret = self.expression09(&arg.expression09)?;
+        for x in &arg.expression08_list {
+            let operand = self.expression09(&x.expression09)?;
+            ret = binary_operator(&x.operator09.operator09_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression09(&mut self, arg: &Expression09) -> Result<Expression> {
+        let mut ret = self.expression10(&arg.expression10)?;
+        for x in &arg.expression09_list {
+            let operand = self.expression10(&x.expression10)?;
+            ret = binary_operator(&x.operator10.operator10_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression10(&mut self, arg: &Expression10) -> Result<Expression> {
+        let mut ret = self.expression11(&arg.expression11)?;
+        for x in &arg.expression10_list {
+            let operator = match x.expression10_list_group.as_ref() {
+                Expression10ListGroup::Operator11(x) => &x.operator11.operator11_token,
+                Expression10ListGroup::Star(x) => &x.star.star_token,
+            };
+            let operand = self.expression11(&x.expression11)?;
+            ret = binary_operator(operator, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression11(&mut self, arg: &Expression11) -> Result<Expression> {
+        let mut ret = self.expression12(&arg.expression12)?;
+        for x in &arg.expression11_list {
+            let operand = self.expression12(&x.expression12)?;
+            ret = binary_operator(&x.operator12.operator12_token, ret, operand)?;
+        }
+        Ok(ret)
+    }
+
+    fn expression12(&mut self, arg: &Expression12) -> Result<Expression> {
+        let ret = self.expression13(&arg.expression13)?;
+        let Some(x) = &arg.expression12_opt else {
+            return Ok(ret);
+        };
+
+        let token: TokenRange = x.casting_type.as_ref().into();
+        let (width, signed) = match x.casting_type.as_ref() {
+            CastingType::U32(_) => (32, false),
+            CastingType::I32(_) => (32, true),
+            CastingType::U64(_) => (64, false),
+            CastingType::I64(_) => (64, true),
+            CastingType::Bool(_) => (1, false),
+            CastingType::Clock(_)
+            | CastingType::ClockPosedge(_)
+            | CastingType::ClockNegedge(_)
+            | CastingType::Reset(_)
+            | CastingType::ResetAsyncHigh(_)
+            | CastingType::ResetAsyncLow(_)
+            | CastingType::ResetSyncHigh(_)
+            | CastingType::ResetSyncLow(_) => return Ok(ret),
+            CastingType::Based(x) => {
+                let token = &x.based.based_token;
+                let Some(value) = based(&token.token)?.to_usize() else {
+                    return Err(SimulatorError::unevaluatable_expression(
+                        &(&token.token).into(),
+                    ));
+                };
+                (value, false)
+            }
+            CastingType::BaseLess(x) => {
+                let token = &x.base_less.base_less_token;
+                let Some(value) = base_less(&token.token)?.to_usize() else {
+                    return Err(SimulatorError::unevaluatable_expression(
+                        &(&token.token).into(),
+                    ));
+                };
+                (value, false)
+            }
+            CastingType::UserDefinedType(x) => {
+                let symbol = symbol_table::resolve(x.user_defined_type.scoped_identifier.as_ref())
+                    .map_err(|_| {
+                        SimulatorError::unresolvable_identifier(
+                            &x.user_defined_type
+                                .scoped_identifier
+                                .identifier()
+                                .to_string(),
+                            &token,
+                        )
+                    })?;
+                (self.user_defined_width(&symbol.found, &token)?, false)
+            }
+            CastingType::F32(_) | CastingType::F64(_) => {
+                return Err(unsupported("floating point", token));
+            }
+        };
+
+        let signed = match x.casting_type.as_ref() {
+            CastingType::Based(_) | CastingType::BaseLess(_) => {
+                evaluator::signed(&ret, &self.ir.variables)
+            }
+            _ => signed,
+        };
+
+        Ok(Expression::Resize {
+            expr: Box::new(ret),
+            width,
+            signed,
+        })
+    }
+
+    fn expression13(&mut self, arg: &Expression13) -> Result<Expression> {
+        let mut ret = self.factor(&arg.factor)?;
+        for x in arg.expression13_list.iter().rev() {
+            let operator = match x.expression13_list_group.as_ref() {
+                Expression13ListGroup::UnaryOperator(x) => &x.unary_operator.unary_operator_token,
+                Expression13ListGroup::Operator04(x) => &x.operator04.operator04_token,
+                Expression13ListGroup::Operator05(x) => &x.operator05.operator05_token,
+                Expression13ListGroup::Operator06(x) => &x.operator06.operator06_token,
+                Expression13ListGroup::Operator10(x) => &x.operator10.operator10_token,
+            };
+            let Some(op) = UnaryOp::from_operator(&operator.to_string()) else {
+                return Err(unsupported(&operator.to_string(), (&operator.token).into()));
+            };
+            ret = Expression::Unary {
+                op,
+                operand: Box::new(ret),
+            };
+        }
+        Ok(ret)
+    }
+
+    fn factor(&mut self, arg: &Factor) -> Result<Expression> {
+        match arg {
+            Factor::Number(x) => number(&x.number),
+            Factor::BooleanLiteral(x) => {
+                let value = matches!(x.boolean_literal.as_ref(), BooleanLiteral::True(_));
+                Ok(Expression::Value(Value::from_bool(value)))
+            }
+            Factor::IdentifierFactor(x) => {
+                let x = &x.identifier_factor;
+                let Some(opt) = &x.identifier_factor_opt else {
+                    return self.expression_identifier(&x.expression_identifier);
+                };
+                match opt.identifier_factor_opt_group.as_ref() {
+                    IdentifierFactorOptGroup::FunctionCall(call) => {
+                        let identifier = &x.expression_identifier;
+                        let call = &call.function_call;
+                        let symbol = self.function_symbol(identifier)?;
+                        if let SymbolKind::SystemFunction(_) = symbol.found.kind {
+                            self.system_function_expression(&symbol, identifier, call)
+                        } else if let Some(var) = self.function_call(&symbol, identifier, call)? {
+                            Ok(Expression::Variable(var))
+                        } else {
+                            Err(unsupported(
+                                "function without return value",
+                                x.as_ref().into(),
+                            ))
+                        }
+                    }
+                    IdentifierFactorOptGroup::StructConstructor(_) => {
+                        Err(unsupported("struct constructor", x.as_ref().into()))
+                    }
+                }
+            }
+            Factor::LParenExpressionRParen(x) => self.expression(&x.expression),
+            Factor::LBraceConcatenationListRBrace(x) => {
+                let items: Vec<ConcatenationItem> = x.concatenation_list.as_ref().into();
+                let mut ret = Vec::new();
+                for item in &items {
+                    let expr = self.expression(&item.expression)?;
+                    let repeat = if let Some(x) = &item.concatenation_item_opt {
+                        self.const_usize(&x.expression)?
+                    } else {
+                        1
+                    };
+                    ret.push((expr, repeat));
+                }
+                Ok(Expression::Concatenation(ret))
+            }
+            Factor::FactorGroup(x) => match x.factor_group.as_ref() {
+                FactorGroup::Msb(_) => {
+                    if let Some(msb) = self.msb.last() {
+                        Ok(index_value(*msb))
+                    } else {
+                        Err(unsupported("msb", arg.into()))
+                    }
+                }
+                FactorGroup::Lsb(_) => Ok(index_value(0)),
+            },
+            Factor::QuoteLBraceArrayLiteralListRBrace(_) => {
+                Err(unsupported("array literal", arg.into()))
+            }
+            Factor::CaseExpression(x) => self.case_expression(&x.case_expression),
+            Factor::SwitchExpression(x) => self.switch_expression(&x.switch_expression),
+            Factor::StringLiteral(_) => Err(unsupported("string literal", arg.into())),
+            Factor::InsideExpression(x) => {
+                let x = &x.inside_expression;
+                let target = self.expression(&x.expression)?;
+                self.range_items(&target, &range_list(&x.range_list))
+            }
+            Factor::OutsideExpression(x) => {
+                let x = &x.outside_expression;
+                let target = self.expression(&x.expression)?;
+                let inside = self.range_items(&target, &range_list(&x.range_list))?;
+                Ok(Expression::Unary {
+                    op: UnaryOp::Not,
+                    operand: Box::new(inside),
+                })
+            }
+            Factor::TypeExpression(_) => Err(unsupported("type expression", arg.into())),
+            Factor::FactorTypeFactor(_) => Err(unsupported("type", arg.into())),
+        }
+    }
+
+    fn case_expression(&mut self, arg: &CaseExpression) -> Result<Expression> {
+        let target = self.expression(&arg.expression)?;
+        let mut items = vec![(arg.case_condition.as_ref(), arg.expression0.as_ref())];
+        for x in &arg.case_expression_list {
+            items.push((x.case_condition.as_ref(), x.expression.as_ref()));
+        }
+
+        let mut ret = self.expression(&arg.expression1)?;
+        for (cond, expr) in items.into_iter().rev() {
+            let cond = self.case_condition(&target, cond)?;
+            let true_expr = self.expression(expr)?;
+            ret = Expression::Ternary {
+                cond: Box::new(cond),
+                true_expr: Box::new(true_expr),
+                false_expr: Box::new(ret),
+            };
+        }
+        Ok(ret)
+    }
+
+    fn switch_expression(&mut self, arg: &SwitchExpression) -> Result<Expression> {
+        let mut items = vec![(arg.switch_condition.as_ref(), arg.expression.as_ref())];
+        for x in &arg.switch_expression_list {
+            items.push((x.switch_condition.as_ref(), x.expression.as_ref()));
+        }
+
+        let mut ret = self.expression(&arg.expression0)?;
+        for (cond, expr) in items.into_iter().rev() {
+            let cond = self.switch_condition(cond)?;
+            let true_expr = self.expression(expr)?;
+            ret = Expression::Ternary {
+                cond: Box::new(cond),
+                true_expr: Box::new(true_expr),
+                false_expr: Box::new(ret),
+            };
+        }
+        Ok(ret)
+    }
+
+    fn expression_identifier(&mut self, arg: &ExpressionIdentifier) -> Result<Expression> {
+        let token: TokenRange = arg.into();
+        let symbol = symbol_table::resolve(arg).map_err(|_| {
+            SimulatorError::unresolvable_identifier(&arg.identifier().to_string(), &token)
+        })?;
+        let selects: Vec<_> = arg
+            .expression_identifier_list
+            .iter()
+            .map(|x| x.select.as_ref().clone())
+            .collect();
+        let members: Vec<Vec<_>> = arg
+            .expression_identifier_list0
+            .iter()
+            .map(|x| {
+                x.expression_identifier_list0_list
+                    .iter()
+                    .map(|x| x.select.as_ref().clone())
+                    .collect()
+            })
+            .collect();
+        self.identifier_reference(&symbol, &selects, &members, &token)
+    }
+}
+
+fn type_kind(symbol: &Symbol) -> Option<TypeKind> {
+    match &symbol.kind {
+        SymbolKind::Port(x) => Some(x.r#type.kind.clone()),
+        SymbolKind::Variable(x) => Some(x.r#type.kind.clone()),
+        _ => None,
+    }
+}
+
+fn destination(expr: Expression, token: &TokenRange) -> Result<Destination> {
+    match expr {
+        Expression::Variable(var) => Ok(Destination { var, select: None }),
+        Expression::Select { var, offset, width } => Ok(Destination {
+            var,
+            select: Some((*offset, width)),
+        }),
+        _ => Err(unsupported("assignment to constant", *token)),
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut ret = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => ret.push('\n'),
+                Some('t') => ret.push('\t'),
+                Some(x) => ret.push(x),
+                None => (),
+            }
+        } else {
+            ret.push(c);
+        }
+    }
+    ret
+}
+
+fn argument_items(arg: &FunctionCall) -> Vec<ArgumentItem> {
+    if let Some(x) = &arg.function_call_opt {
+        x.argument_list.as_ref().into()
+    } else {
+        Vec::new()
+    }
+}
+
+fn range_list(arg: &RangeList) -> Vec<RangeItem> {
+    let mut ret = vec![arg.range_item.as_ref().clone()];
+    for x in &arg.range_list_list {
+        ret.push(x.range_item.as_ref().clone());
+    }
+    ret
+}
+
+/// Comparison of the upper bound of range
+fn range_operator(arg: &RangeOperator) -> BinaryOp {
+    match arg {
+        RangeOperator::DotDot(_) => BinaryOp::Lt,
+        RangeOperator::DotDotEqu(_) => BinaryOp::Le,
+    }
+}
+
+fn assignment_operator(arg: &AssignmentOperator) -> Result<BinaryOp> {
+    let token = &arg.assignment_operator_token;
+    BinaryOp::from_assignment_operator(&token.to_string())
+        .ok_or_else(|| unsupported(&token.to_string(), (&token.token).into()))
+}
+
+/// Convert conditional items to nested if statements
+fn if_chain(items: Vec<(Expression, Vec<Statement>)>, default: Vec<Statement>) -> Vec<Statement> {
+    let mut ret = default;
+    for (cond, true_side) in items.into_iter().rev() {
+        ret = vec![Statement::If(IfStatement {
+            cond,
+            true_side,
+            false_side: ret,
+        })];
+    }
+    ret
+}
+
+fn unsupported(kind: &str, token: TokenRange) -> SimulatorError {
+    SimulatorError::unsupported_syntax(kind, &token)
+}
+
+fn index_value(x: usize) -> Expression {
+    Expression::Value(Value::new(x as u64, INDEX_WIDTH, false))
+}
+
+fn binary(op: BinaryOp, left: Expression, right: Expression) -> Expression {
+    Expression::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
+
+fn binary_operator(
+    operator: &VerylToken,
+    left: Expression,
+    right: Expression,
+) -> Result<Expression> {
+    if let Some(op) = BinaryOp::from_operator(&operator.to_string()) {
+        Ok(binary(op, left, right))
+    } else {
+        Err(unsupported(&operator.to_string(), (&operator.token).into()))
+    }
+}
+
+fn number(arg: &Number) -> Result<Expression> {
+    match arg {
+        Number::IntegralNumber(x) => match x.integral_number.as_ref() {
+            IntegralNumber::Based(x) => Ok(Expression::Value(based(&x.based.based_token.token)?)),
+            IntegralNumber::BaseLess(x) => Ok(Expression::Value(base_less(
+                &x.base_less.base_less_token.token,
+            )?)),
+            IntegralNumber::AllBit(x) => all_bit(&x.all_bit.all_bit_token.token),
+        },
+        Number::RealNumber(_) => Err(unsupported("real number", arg.into())),
+    }
+}
+
+fn based(token: &Token) -> Result<Value> {
+    Value::from_literal(&token.to_string())
+        .ok_or_else(|| SimulatorError::unevaluatable_expression(&token.into()))
+}
+
+fn base_less(token: &Token) -> Result<Value> {
+    let Some(value) = Value::from_digits(&token.to_string(), 10) else {
+        return Err(SimulatorError::unevaluatable_expression(&token.into()));
+    };
+    let width = value.width.max(32);
+    Ok(value.resize(width))
+}
+
+fn all_bit(token: &Token) -> Result<Expression> {
+    let text = token.to_string().replace('_', "");
+    let Some((width, bit)) = text.split_once('\'') else {
+        unreachable!();
+    };
+
+    let bit = match bit {
+        "1" => Value::from_bool(true),
+        "0" => Value::from_bool(false),
+        "x" | "X" => Value::x(1),
+        _ => Value::z(1),
+    };
+    if width.is_empty() {
+        Ok(Expression::Fill(bit))
+    } else {
+        let width = width.parse().unwrap_or(0);
+        Ok(Expression::Value(
+            bit.with_signed(true).resize(width).with_signed(false),
+        ))
+    }
+}
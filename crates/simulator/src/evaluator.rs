@@ -0,0 +1,361 @@
+use crate::HashMap;
+use crate::ir::{BinaryOp, Expression, Statement, SystemFunctionCall, UnaryOp, VarId, Variable};
+use veryl_analyzer::value::Value;
+
+/// Self-determined width of expression
+pub fn width(expr: &Expression, variables: &[Variable]) -> usize {
+    match expr {
+        Expression::Value(x) => x.width,
+        Expression::Fill(_) => 1,
+        Expression::Variable(x) => variables[x.0].width,
+        Expression::Select { width, .. } => *width,
+        Expression::Unary { op, operand } => {
+            if op.is_context_determined() {
+                width(operand, variables)
+            } else if *op == UnaryOp::Clog2 {
+                32
+            } else {
+                1
+            }
+        }
+        Expression::Binary { op, left, right } => {
+            if op.is_comparison() || op.is_logical() {
+                1
+            } else if op.is_shift() || *op == BinaryOp::Pow {
+                width(left, variables)
+            } else {
+                width(left, variables).max(width(right, variables))
+            }
+        }
+        Expression::Ternary {
+            true_expr,
+            false_expr,
+            ..
+        } => width(true_expr, variables).max(width(false_expr, variables)),
+        Expression::Concatenation(x) => x
+            .iter()
+            .map(|(x, repeat)| width(x, variables) * repeat)
+            .sum(),
+        Expression::Resize { width, .. } => *width,
+    }
+}
+
+/// Self-determined signedness of expression
+pub fn signed(expr: &Expression, variables: &[Variable]) -> bool {
+    match expr {
+        Expression::Value(x) => x.signed,
+        Expression::Fill(_) => false,
+        Expression::Variable(x) => variables[x.0].signed,
+        Expression::Select { .. } => false,
+        Expression::Unary { op, operand } => {
+            op.is_context_determined() && signed(operand, variables)
+        }
+        Expression::Binary { op, left, right } => {
+            if op.is_comparison() || op.is_logical() {
+                false
+            } else if op.is_shift() || *op == BinaryOp::Pow {
+                signed(left, variables)
+            } else {
+                signed(left, variables) && signed(right, variables)
+            }
+        }
+        Expression::Ternary {
+            true_expr,
+            false_expr,
+            ..
+        } => signed(true_expr, variables) && signed(false_expr, variables),
+        Expression::Concatenation(_) => false,
+        Expression::Resize { signed, .. } => *signed,
+    }
+}
+
+pub(crate) fn extend(value: Value, width: usize, signed: bool) -> Value {
+    if value.width == width && value.signed == signed {
+        value
+    } else {
+        let sign_extend = value.signed && signed;
+        value
+            .with_signed(sign_extend)
+            .resize(width)
+            .with_signed(signed)
+    }
+}
+
+/// Evaluate offset of select.
+/// `None` is returned if it has X/Z, and too large offset is saturated.
+fn offset(expr: &Expression, variables: &[Variable]) -> Option<usize> {
+    let value = evaluate(expr, variables, 0);
+    if value.has_xz() {
+        None
+    } else {
+        Some(value.to_usize().unwrap_or(usize::MAX))
+    }
+}
+
+/// Evaluate expression in the context which has `context_width`
+pub fn evaluate(expr: &Expression, variables: &[Variable], context_width: usize) -> Value {
+    let w = width(expr, variables).max(context_width);
+    let s = signed(expr, variables);
+    evaluate_context(expr, variables, w, s)
+}
+
+pub(crate) fn evaluate_context(
+    expr: &Expression,
+    variables: &[Variable],
+    w: usize,
+    s: bool,
+) -> Value {
+    match expr {
+        Expression::Value(x) => extend(x.clone(), w, s),
+        // 1-bit value is replicated by sign extension
+        Expression::Fill(x) => x.clone().with_signed(true).resize(w).with_signed(false),
+        Expression::Variable(x) => extend(variables[x.0].value.clone(), w, s),
+        Expression::Select {
+            var,
+            offset: x,
+            width,
+        } => {
+            let value = match offset(x, variables) {
+                Some(x) => variables[var.0].value.select(x, *width),
+                None => Value::x(*width),
+            };
+            extend(value, w, s)
+        }
+        Expression::Unary { op, operand } => match op {
+            UnaryOp::Plus => evaluate_context(operand, variables, w, s),
+            UnaryOp::Minus => evaluate_context(operand, variables, w, s).neg(w),
+            UnaryOp::Inv => evaluate_context(operand, variables, w, s).inv(w),
+            _ => {
+                let x = evaluate(operand, variables, 0);
+                let ret = match op {
+                    UnaryOp::Not => x.logic_not(),
+                    UnaryOp::And => x.reduction_and(),
+                    UnaryOp::Or => x.reduction_or(),
+                    UnaryOp::Xor => x.reduction_xor(),
+                    UnaryOp::Nand => x.reduction_and().inv(1),
+                    UnaryOp::Nor => x.reduction_or().inv(1),
+                    UnaryOp::Xnor => x.reduction_xor().inv(1),
+                    UnaryOp::Clog2 => x.clog2(),
+                    _ => unreachable!(),
+                };
+                extend(ret, w, false)
+            }
+        },
+        Expression::Binary { op, left, right } => {
+            if op.is_comparison() {
+                let cw = width(left, variables).max(width(right, variables));
+                let cs = signed(left, variables) && signed(right, variables);
+                let x = evaluate_context(left, variables, cw, cs);
+                let y = evaluate_context(right, variables, cw, cs);
+                let ret = match op {
+                    BinaryOp::Le => x.le(&y),
+                    BinaryOp::Ge => x.ge(&y),
+                    BinaryOp::Lt => x.lt(&y),
+                    BinaryOp::Gt => x.gt(&y),
+                    BinaryOp::Eq => x.eq(&y),
+                    BinaryOp::Ne => x.ne(&y),
+                    BinaryOp::CaseEq => x.case_eq(&y),
+                    BinaryOp::CaseNe => x.case_ne(&y),
+                    BinaryOp::WildcardEq => x.wildcard_eq(&y),
+                    BinaryOp::WildcardNe => x.wildcard_ne(&y),
+                    _ => unreachable!(),
+                };
+                extend(ret, w, false)
+            } else if op.is_logical() {
+                let x = evaluate(left, variables, 0);
+                let y = evaluate(right, variables, 0);
+                let ret = match op {
+                    BinaryOp::LogicAnd => x.logic_and(&y),
+                    BinaryOp::LogicOr => x.logic_or(&y),
+                    _ => unreachable!(),
+                };
+                extend(ret, w, false)
+            } else if op.is_shift() || *op == BinaryOp::Pow {
+                let x = evaluate_context(left, variables, w, s);
+                let y = evaluate(right, variables, 0);
+                match op {
+                    BinaryOp::Shl | BinaryOp::AShl => x.shl(&y, w),
+                    BinaryOp::Shr => x.shr(&y, w),
+                    BinaryOp::AShr => x.ashr(&y, w),
+                    BinaryOp::Pow => x.pow(&y, w),
+                    _ => unreachable!(),
+                }
+            } else {
+                let x = evaluate_context(left, variables, w, s);
+                let y = evaluate_context(right, variables, w, s);
+                match op {
+                    BinaryOp::Div => x.div(&y, w),
+                    BinaryOp::Rem => x.rem(&y, w),
+                    BinaryOp::Mul => x.mul(&y, w),
+                    BinaryOp::Add => x.add(&y, w),
+                    BinaryOp::Sub => x.sub(&y, w),
+                    BinaryOp::And => x.and(&y, w),
+                    BinaryOp::Or => x.or(&y, w),
+                    BinaryOp::Xor => x.xor(&y, w),
+                    BinaryOp::Xnor => x.xnor(&y, w),
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Expression::Ternary {
+            cond,
+            true_expr,
+            false_expr,
+        } => {
+            let cond = evaluate(cond, variables, 0);
+            if cond.to_bool() {
+                evaluate_context(true_expr, variables, w, s)
+            } else if cond.is_false() {
+                evaluate_context(false_expr, variables, w, s)
+            } else {
+                // unknown condition gives X except the case both sides are the same
+                let x = evaluate_context(true_expr, variables, w, s);
+                let y = evaluate_context(false_expr, variables, w, s);
+                if x.case_eq(&y).to_bool() {
+                    x
+                } else {
+                    Value::x(w).with_signed(s)
+                }
+            }
+        }
+        Expression::Concatenation(x) => {
+            let mut ret = Value::zero(0);
+            for (x, repeat) in x {
+                let x = evaluate(x, variables, 0);
+                for _ in 0..*repeat {
+                    ret = ret.concat(&x);
+                }
+            }
+            extend(ret, w, false)
+        }
+        Expression::Resize {
+            expr,
+            width,
+            signed,
+        } => {
+            let ret = evaluate(expr, variables, 0)
+                .resize(*width)
+                .with_signed(*signed);
+            extend(ret, w, s)
+        }
+    }
+}
+
+/// Control flow after executing a statement
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    Next,
+    Break,
+    Return,
+    /// Simulation is finished by `$finish` or `$fatal`
+    Finish,
+}
+
+/// Execute statement.
+/// If `next` is given, assignments are deferred to it as non-blocking assignments.
+pub fn execute(
+    statement: &Statement,
+    variables: &mut [Variable],
+    next: Option<&mut HashMap<VarId, Value>>,
+) -> Flow {
+    execute_with(statement, variables, next, &mut |_, _| Flow::Next)
+}
+
+/// Execute statement with the handler of system functions like `$display`
+pub fn execute_with(
+    statement: &Statement,
+    variables: &mut [Variable],
+    mut next: Option<&mut HashMap<VarId, Value>>,
+    system: &mut dyn FnMut(&SystemFunctionCall, &[Variable]) -> Flow,
+) -> Flow {
+    match statement {
+        Statement::Assign(x) => {
+            let widths: Vec<_> = x
+                .dst
+                .iter()
+                .map(|x| {
+                    if let Some((_, width)) = &x.select {
+                        *width
+                    } else {
+                        variables[x.var.0].width
+                    }
+                })
+                .collect();
+            let total: usize = widths.iter().sum();
+            let value = evaluate(&x.expr, variables, total);
+
+            // The first destination is assigned from MSB
+            let mut lsb = total;
+            for (dst, width) in x.dst.iter().zip(widths.iter()) {
+                lsb -= width;
+                let part = value.select(lsb, *width);
+                let var = &variables[dst.var.0];
+                let current = next
+                    .as_ref()
+                    .and_then(|x| x.get(&dst.var))
+                    .unwrap_or(&var.value);
+                let value = if let Some((x, width)) = &dst.select {
+                    // write to unknown position is ignored
+                    let Some(x) = offset(x, variables) else {
+                        continue;
+                    };
+                    var.fit(&current.replace(x, *width, &part))
+                } else {
+                    var.fit(&part)
+                };
+                if let Some(next) = next.as_deref_mut() {
+                    next.insert(dst.var, value);
+                } else {
+                    variables[dst.var.0].value = value;
+                }
+            }
+            Flow::Next
+        }
+        Statement::If(x) => {
+            let statements = if evaluate(&x.cond, variables, 0).to_bool() {
+                &x.true_side
+            } else {
+                &x.false_side
+            };
+            execute_block(statements, variables, next, system)
+        }
+        Statement::For(x) => {
+            let var = x.var.0;
+            let value = evaluate(&x.start, variables, variables[var].width);
+            variables[var].value = variables[var].fit(&value);
+            while evaluate(&x.cond, variables, 0).to_bool() {
+                match execute_block(&x.body, variables, next.as_deref_mut(), system) {
+                    Flow::Next => (),
+                    Flow::Break => break,
+                    x => return x,
+                }
+                let value = evaluate(&x.step, variables, variables[var].width);
+                variables[var].value = variables[var].fit(&value);
+            }
+            Flow::Next
+        }
+        Statement::Break => Flow::Break,
+        Statement::Return => Flow::Return,
+        // variables in function are assigned immediately even in always_ff
+        Statement::FunctionBody(x) => match execute_block(x, variables, None, system) {
+            Flow::Finish => Flow::Finish,
+            _ => Flow::Next,
+        },
+        Statement::SystemFunctionCall(x) => system(x, variables),
+    }
+}
+
+fn execute_block(
+    statements: &[Statement],
+    variables: &mut [Variable],
+    mut next: Option<&mut HashMap<VarId, Value>>,
+    system: &mut dyn FnMut(&SystemFunctionCall, &[Variable]) -> Flow,
+) -> Flow {
+    for x in statements {
+        let flow = execute_with(x, variables, next.as_deref_mut(), system);
+        if flow != Flow::Next {
+            return flow;
+        }
+    }
+    Flow::Next
+}
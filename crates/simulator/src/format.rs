@@ -0,0 +1,176 @@
+use veryl_analyzer::value::Value;
+
+/// Bits of the value from MSB as `0`, `1`, `x` and `z`
+pub fn bits(value: &Value) -> Vec<u8> {
+    let bit = |words: &[u64], i: usize| {
+        words
+            .get(i / u64::BITS as usize)
+            .is_some_and(|x| (x >> (i % u64::BITS as usize)) & 1 == 1)
+    };
+    (0..value.width)
+        .rev()
+        .map(
+            |i| match (bit(value.payload(), i), bit(value.mask_xz(), i)) {
+                (false, false) => b'0',
+                (true, false) => b'1',
+                (false, true) => b'z',
+                (true, true) => b'x',
+            },
+        )
+        .collect()
+}
+
+/// Format arguments like `$display` of SystemVerilog.
+/// Arguments without format specifier are shown as decimal.
+pub fn format(text: Option<&str>, args: &[Value]) -> String {
+    let mut ret = String::new();
+    let mut args = args.iter();
+
+    if let Some(text) = text {
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                ret.push(c);
+                continue;
+            }
+
+            let mut width = String::new();
+            while let Some(x) = chars.peek().filter(|x| x.is_ascii_digit()) {
+                width.push(*x);
+                chars.next();
+            }
+            // `%0d` suppresses padding
+            let pad = width.is_empty();
+
+            let Some(spec) = chars.next() else {
+                ret.push('%');
+                break;
+            };
+            let radix = match spec.to_ascii_lowercase() {
+                'd' => 10,
+                'b' => 2,
+                'o' => 8,
+                'h' | 'x' => 16,
+                '%' => {
+                    ret.push('%');
+                    continue;
+                }
+                _ => {
+                    ret.push('%');
+                    ret.push_str(&width);
+                    ret.push(spec);
+                    continue;
+                }
+            };
+            if let Some(arg) = args.next() {
+                ret.push_str(&radix_string(arg, radix, pad));
+            }
+        }
+    }
+
+    for arg in args {
+        ret.push_str(&radix_string(arg, 10, true));
+    }
+    ret
+}
+
+fn radix_string(value: &Value, radix: u32, pad: bool) -> String {
+    if radix == 10 {
+        return decimal(value, pad);
+    }
+
+    let group = radix.trailing_zeros() as usize;
+    let mut bits = bits(value);
+    let len = bits.len().div_ceil(group) * group;
+    let mut padded = vec![b'0'; len - bits.len()];
+    padded.append(&mut bits);
+
+    let digits: String = padded
+        .chunks(group)
+        .map(|x| {
+            if x.iter().all(|x| *x == b'x') {
+                'x'
+            } else if x.iter().all(|x| *x == b'z') {
+                'z'
+            } else if x.contains(&b'x') {
+                'X'
+            } else if x.contains(&b'z') {
+                'Z'
+            } else {
+                let digit = x.iter().fold(0, |acc, x| (acc << 1) | (x - b'0') as u32);
+                char::from_digit(digit, radix).unwrap()
+            }
+        })
+        .collect();
+
+    if pad {
+        digits
+    } else {
+        let trimmed = digits.trim_start_matches('0');
+        if trimmed.is_empty() {
+            "0".to_string()
+        } else {
+            trimmed.to_string()
+        }
+    }
+}
+
+fn decimal(value: &Value, pad: bool) -> String {
+    let negative = value.signed && !value.has_xz() && value.msb();
+
+    let digits = if value.has_xz() {
+        let bits = bits(value);
+        if bits.iter().all(|x| *x == b'z') {
+            "z".to_string()
+        } else if bits.iter().all(|x| *x == b'x') {
+            "x".to_string()
+        } else {
+            "X".to_string()
+        }
+    } else {
+        let value = if negative {
+            value.neg(value.width)
+        } else {
+            value.clone()
+        };
+        unsigned_decimal(value.payload())
+    };
+
+    let digits = if negative {
+        format!("-{digits}")
+    } else {
+        digits
+    };
+
+    if pad {
+        // pad to the length of the maximum value
+        let max = if value.signed {
+            Value::ones(value.width.saturating_sub(1))
+        } else {
+            Value::ones(value.width)
+        };
+        let len = unsigned_decimal(max.payload()).len() + value.signed as usize;
+        format!("{digits:>len$}")
+    } else {
+        digits
+    }
+}
+
+fn unsigned_decimal(words: &[u64]) -> String {
+    let mut words = words.to_vec();
+    let mut ret = Vec::new();
+    loop {
+        let mut rem = 0u128;
+        for word in words.iter_mut().rev() {
+            let x = (rem << 64) | *word as u128;
+            *word = (x / 10) as u64;
+            rem = x % 10;
+        }
+        ret.push(b'0' + rem as u8);
+        if words.iter().all(|x| *x == 0) {
+            break;
+        }
+    }
+    ret.reverse();
+    String::from_utf8(ret).unwrap()
+}
@@ -0,0 +1,732 @@
+use crate::HashMap;
+use crate::evaluator;
+use std::fmt;
+use veryl_analyzer::value::Value;
+use veryl_parser::token_range::TokenRange;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VarId(pub usize);
+
+/// Bit offset and width of partial access
+pub type BitRange = (usize, usize);
+
+/// Variable and the bit range accessed by a statement.
+/// The range is `None` if the whole variable or an unknown position is accessed.
+pub type Access = (VarId, Option<BitRange>);
+
+/// Whether two accesses to the same variable may touch a common bit
+fn overlap(x: Option<BitRange>, y: Option<BitRange>) -> bool {
+    match (x, y) {
+        (Some((x, xw)), Some((y, yw))) => x < y + yw && y < x + xw,
+        _ => true,
+    }
+}
+
+fn const_range(offset: &Expression, width: usize) -> Option<BitRange> {
+    if !offset.is_const() {
+        return None;
+    }
+    let offset = evaluator::evaluate(offset, &[], 0);
+    if offset.has_xz() {
+        None
+    } else {
+        Some((offset.to_usize()?, width))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Variable {
+    pub name: String,
+    pub width: usize,
+    pub signed: bool,
+    pub is_4state: bool,
+    pub value: Value,
+    pub token: TokenRange,
+}
+
+impl Variable {
+    /// 4-state variable is initialized by X, and 2-state one is initialized by 0
+    pub fn new(name: &str, width: usize, signed: bool, is_4state: bool, token: TokenRange) -> Self {
+        let value = if is_4state {
+            Value::x(width)
+        } else {
+            Value::zero(width)
+        };
+        Self {
+            name: name.to_string(),
+            width,
+            signed,
+            is_4state,
+            value: value.with_signed(signed),
+            token,
+        }
+    }
+
+    /// Convert value to the type of the variable.
+    /// X/Z is replaced by 0 if the variable is 2-state.
+    pub fn fit(&self, value: &Value) -> Value {
+        let ret = value.resize(self.width).with_signed(self.signed);
+        if self.is_4state { ret } else { ret.to_2state() }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,
+    Minus,
+    Not,
+    Inv,
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+    /// `$clog2` system function
+    Clog2,
+}
+
+impl UnaryOp {
+    pub fn from_operator(x: &str) -> Option<Self> {
+        match x {
+            "+" => Some(UnaryOp::Plus),
+            "-" => Some(UnaryOp::Minus),
+            "!" => Some(UnaryOp::Not),
+            "~" => Some(UnaryOp::Inv),
+            "&" => Some(UnaryOp::And),
+            "|" => Some(UnaryOp::Or),
+            "^" => Some(UnaryOp::Xor),
+            "~&" => Some(UnaryOp::Nand),
+            "~|" => Some(UnaryOp::Nor),
+            "~^" | "^~" => Some(UnaryOp::Xnor),
+            _ => None,
+        }
+    }
+
+    /// Whether the operand width follows the context width
+    pub fn is_context_determined(&self) -> bool {
+        matches!(self, UnaryOp::Plus | UnaryOp::Minus | UnaryOp::Inv)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOp {
+    Pow,
+    Div,
+    Rem,
+    Mul,
+    Add,
+    Sub,
+    Shl,
+    Shr,
+    AShl,
+    AShr,
+    Le,
+    Ge,
+    Lt,
+    Gt,
+    Eq,
+    Ne,
+    CaseEq,
+    CaseNe,
+    WildcardEq,
+    WildcardNe,
+    LogicAnd,
+    LogicOr,
+    And,
+    Or,
+    Xor,
+    Xnor,
+}
+
+impl BinaryOp {
+    pub fn from_operator(x: &str) -> Option<Self> {
+        match x {
+            "**" => Some(BinaryOp::Pow),
+            "/" => Some(BinaryOp::Div),
+            "%" => Some(BinaryOp::Rem),
+            "*" => Some(BinaryOp::Mul),
+            "+" => Some(BinaryOp::Add),
+            "-" => Some(BinaryOp::Sub),
+            "<<" => Some(BinaryOp::Shl),
+            ">>" => Some(BinaryOp::Shr),
+            "<<<" => Some(BinaryOp::AShl),
+            ">>>" => Some(BinaryOp::AShr),
+            "<=" => Some(BinaryOp::Le),
+            ">=" => Some(BinaryOp::Ge),
+            "<:" => Some(BinaryOp::Lt),
+            ">:" => Some(BinaryOp::Gt),
+            "==" => Some(BinaryOp::Eq),
+            "!=" => Some(BinaryOp::Ne),
+            "===" => Some(BinaryOp::CaseEq),
+            "!==" => Some(BinaryOp::CaseNe),
+            "==?" => Some(BinaryOp::WildcardEq),
+            "!=?" => Some(BinaryOp::WildcardNe),
+            "&&" => Some(BinaryOp::LogicAnd),
+            "||" => Some(BinaryOp::LogicOr),
+            "&" => Some(BinaryOp::And),
+            "|" => Some(BinaryOp::Or),
+            "^" => Some(BinaryOp::Xor),
+            "~^" | "^~" => Some(BinaryOp::Xnor),
+            _ => None,
+        }
+    }
+
+    /// Assignment operator like `+=` to the corresponding binary operator
+    pub fn from_assignment_operator(x: &str) -> Option<Self> {
+        x.strip_suffix('=').and_then(Self::from_operator)
+    }
+
+    pub fn is_shift(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Shl | BinaryOp::Shr | BinaryOp::AShl | BinaryOp::AShr
+        )
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Le
+                | BinaryOp::Ge
+                | BinaryOp::Lt
+                | BinaryOp::Gt
+                | BinaryOp::Eq
+                | BinaryOp::Ne
+                | BinaryOp::CaseEq
+                | BinaryOp::CaseNe
+                | BinaryOp::WildcardEq
+                | BinaryOp::WildcardNe
+        )
+    }
+
+    pub fn is_logical(&self) -> bool {
+        matches!(self, BinaryOp::LogicAnd | BinaryOp::LogicOr)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Expression {
+    Value(Value),
+    /// Unsized fill literal like `'1` which follows the context width
+    Fill(Value),
+    Variable(VarId),
+    Select {
+        var: VarId,
+        offset: Box<Expression>,
+        width: usize,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: Box<Expression>,
+    },
+    Binary {
+        op: BinaryOp,
+        left: Box<Expression>,
+        right: Box<Expression>,
+    },
+    Ternary {
+        cond: Box<Expression>,
+        true_expr: Box<Expression>,
+        false_expr: Box<Expression>,
+    },
+    Concatenation(Vec<(Expression, usize)>),
+    Resize {
+        expr: Box<Expression>,
+        width: usize,
+        signed: bool,
+    },
+}
+
+impl Expression {
+    pub fn is_const(&self) -> bool {
+        match self {
+            Expression::Value(_) | Expression::Fill(_) => true,
+            Expression::Variable(_) | Expression::Select { .. } => false,
+            Expression::Unary { operand, .. } => operand.is_const(),
+            Expression::Binary { left, right, .. } => left.is_const() && right.is_const(),
+            Expression::Ternary {
+                cond,
+                true_expr,
+                false_expr,
+            } => cond.is_const() && true_expr.is_const() && false_expr.is_const(),
+            Expression::Concatenation(x) => x.iter().all(|(x, _)| x.is_const()),
+            Expression::Resize { expr, .. } => expr.is_const(),
+        }
+    }
+
+    /// Collect variables referred by the expression
+    pub fn gather_variables(&self, ret: &mut Vec<VarId>) {
+        match self {
+            Expression::Value(_) | Expression::Fill(_) => (),
+            Expression::Variable(x) => ret.push(*x),
+            Expression::Select { var, offset, .. } => {
+                ret.push(*var);
+                offset.gather_variables(ret);
+            }
+            Expression::Unary { operand, .. } => operand.gather_variables(ret),
+            Expression::Binary { left, right, .. } => {
+                left.gather_variables(ret);
+                right.gather_variables(ret);
+            }
+            Expression::Ternary {
+                cond,
+                true_expr,
+                false_expr,
+            } => {
+                cond.gather_variables(ret);
+                true_expr.gather_variables(ret);
+                false_expr.gather_variables(ret);
+            }
+            Expression::Concatenation(x) => {
+                for (x, _) in x {
+                    x.gather_variables(ret);
+                }
+            }
+            Expression::Resize { expr, .. } => expr.gather_variables(ret),
+        }
+    }
+
+    /// Collect variables referred by the expression with the constant bit range of select
+    pub fn gather_accesses(&self, ret: &mut Vec<Access>) {
+        match self {
+            Expression::Value(_) | Expression::Fill(_) => (),
+            Expression::Variable(x) => ret.push((*x, None)),
+            Expression::Select { var, offset, width } => {
+                ret.push((*var, const_range(offset, *width)));
+                offset.gather_accesses(ret);
+            }
+            Expression::Unary { operand, .. } => operand.gather_accesses(ret),
+            Expression::Binary { left, right, .. } => {
+                left.gather_accesses(ret);
+                right.gather_accesses(ret);
+            }
+            Expression::Ternary {
+                cond,
+                true_expr,
+                false_expr,
+            } => {
+                cond.gather_accesses(ret);
+                true_expr.gather_accesses(ret);
+                false_expr.gather_accesses(ret);
+            }
+            Expression::Concatenation(x) => {
+                for (x, _) in x {
+                    x.gather_accesses(ret);
+                }
+            }
+            Expression::Resize { expr, .. } => expr.gather_accesses(ret),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Destination {
+    pub var: VarId,
+    /// Bit offset and width of partial assignment
+    pub select: Option<(Expression, usize)>,
+}
+
+#[derive(Clone, Debug)]
+pub struct AssignStatement {
+    pub dst: Vec<Destination>,
+    pub expr: Expression,
+}
+
+#[derive(Clone, Debug)]
+pub struct IfStatement {
+    pub cond: Expression,
+    pub true_side: Vec<Statement>,
+    pub false_side: Vec<Statement>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ForStatement {
+    /// Loop variable which is always assigned immediately
+    pub var: VarId,
+    pub start: Expression,
+    /// Condition to continue the loop
+    pub cond: Expression,
+    /// Next value of the loop variable like `i + 1`
+    pub step: Expression,
+    pub body: Vec<Statement>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SystemFunctionKind {
+    Display,
+    Info,
+    Warning,
+    Error,
+    Fatal,
+    Finish,
+}
+
+impl SystemFunctionKind {
+    pub fn from_name(x: &str) -> Option<Self> {
+        match x {
+            "$display" => Some(SystemFunctionKind::Display),
+            "$info" => Some(SystemFunctionKind::Info),
+            "$warning" => Some(SystemFunctionKind::Warning),
+            "$error" => Some(SystemFunctionKind::Error),
+            "$fatal" => Some(SystemFunctionKind::Fatal),
+            "$finish" => Some(SystemFunctionKind::Finish),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SystemFunctionCall {
+    pub kind: SystemFunctionKind,
+    /// Format string without quotes and escapes
+    pub format: Option<String>,
+    pub args: Vec<Expression>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Statement {
+    Assign(AssignStatement),
+    If(IfStatement),
+    For(ForStatement),
+    Break,
+    Return,
+    /// Inlined body of function call which is exited by `return`
+    FunctionBody(Vec<Statement>),
+    SystemFunctionCall(SystemFunctionCall),
+}
+
+impl Statement {
+    pub fn gather_variables(&self, inputs: &mut Vec<VarId>, outputs: &mut Vec<VarId>) {
+        match self {
+            Statement::Assign(x) => {
+                for dst in &x.dst {
+                    outputs.push(dst.var);
+                    if let Some((offset, _)) = &dst.select {
+                        offset.gather_variables(inputs);
+                    }
+                }
+                x.expr.gather_variables(inputs);
+            }
+            Statement::If(x) => {
+                x.cond.gather_variables(inputs);
+                for x in x.true_side.iter().chain(x.false_side.iter()) {
+                    x.gather_variables(inputs, outputs);
+                }
+            }
+            Statement::For(x) => {
+                outputs.push(x.var);
+                x.start.gather_variables(inputs);
+                x.cond.gather_variables(inputs);
+                x.step.gather_variables(inputs);
+                for x in &x.body {
+                    x.gather_variables(inputs, outputs);
+                }
+            }
+            Statement::Break | Statement::Return => (),
+            Statement::FunctionBody(x) => {
+                for x in x {
+                    x.gather_variables(inputs, outputs);
+                }
+            }
+            Statement::SystemFunctionCall(x) => {
+                for x in &x.args {
+                    x.gather_variables(inputs);
+                }
+            }
+        }
+    }
+
+    /// Collect accessed variables like `gather_variables` with the constant bit range of select
+    pub fn gather_accesses(&self, inputs: &mut Vec<Access>, outputs: &mut Vec<Access>) {
+        match self {
+            Statement::Assign(x) => {
+                for dst in &x.dst {
+                    if let Some((offset, width)) = &dst.select {
+                        outputs.push((dst.var, const_range(offset, *width)));
+                        offset.gather_accesses(inputs);
+                    } else {
+                        outputs.push((dst.var, None));
+                    }
+                }
+                x.expr.gather_accesses(inputs);
+            }
+            Statement::If(x) => {
+                x.cond.gather_accesses(inputs);
+                for x in x.true_side.iter().chain(x.false_side.iter()) {
+                    x.gather_accesses(inputs, outputs);
+                }
+            }
+            Statement::For(x) => {
+                outputs.push((x.var, None));
+                x.start.gather_accesses(inputs);
+                x.cond.gather_accesses(inputs);
+                x.step.gather_accesses(inputs);
+                for x in &x.body {
+                    x.gather_accesses(inputs, outputs);
+                }
+            }
+            Statement::Break | Statement::Return => (),
+            Statement::FunctionBody(x) => {
+                for x in x {
+                    x.gather_accesses(inputs, outputs);
+                }
+            }
+            Statement::SystemFunctionCall(x) => {
+                for x in &x.args {
+                    x.gather_accesses(inputs);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct FfReset {
+    /// Expression which becomes true while reset is asserted
+    pub cond: Expression,
+    pub asynchronous: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct FfEvent {
+    pub clock: VarId,
+    pub posedge: bool,
+    pub reset: Option<FfReset>,
+}
+
+#[derive(Clone, Debug)]
+pub enum ProcessKind {
+    Comb,
+    Ff(FfEvent),
+    Initial,
+}
+
+#[derive(Clone, Debug)]
+pub struct Process {
+    pub kind: ProcessKind,
+    pub statements: Vec<Statement>,
+    pub inputs: Vec<VarId>,
+    pub outputs: Vec<VarId>,
+    pub token: TokenRange,
+}
+
+impl Process {
+    pub fn new(kind: ProcessKind, statements: Vec<Statement>, token: TokenRange) -> Self {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for x in &statements {
+            x.gather_variables(&mut inputs, &mut outputs);
+        }
+        inputs.sort();
+        inputs.dedup();
+        outputs.sort();
+        outputs.dedup();
+
+        Self {
+            kind,
+            statements,
+            inputs,
+            outputs,
+            token,
+        }
+    }
+
+    /// Inputs and outputs with the constant bit range of select
+    pub fn accesses(&self) -> (Vec<Access>, Vec<Access>) {
+        let mut inputs = Vec::new();
+        let mut outputs = Vec::new();
+        for x in &self.statements {
+            x.gather_accesses(&mut inputs, &mut outputs);
+        }
+        (inputs, outputs)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Ir {
+    pub variables: Vec<Variable>,
+    pub comb: Vec<Process>,
+    pub ff: Vec<Process>,
+    pub initial: Vec<Process>,
+    pub names: HashMap<String, VarId>,
+}
+
+impl Ir {
+    pub fn add_variable(&mut self, variable: Variable) -> VarId {
+        let id = VarId(self.variables.len());
+        self.names.insert(variable.name.clone(), id);
+        self.variables.push(variable);
+        id
+    }
+
+    /// Register another hierarchical name of the variable like a port connected to it
+    pub fn add_alias(&mut self, name: &str, id: VarId) {
+        self.names.insert(name.to_string(), id);
+    }
+
+    pub fn get_variable(&self, name: &str) -> Option<VarId> {
+        self.names.get(name).copied()
+    }
+
+    /// Drivers of each combinational process.
+    /// Accesses to disjoint constant bit ranges of the same variable are not dependencies.
+    fn comb_sources(&self) -> Vec<Vec<usize>> {
+        let accesses: Vec<_> = self.comb.iter().map(|x| x.accesses()).collect();
+
+        let mut drivers: HashMap<VarId, Vec<(usize, Option<BitRange>)>> = HashMap::default();
+        for (i, (_, outputs)) in accesses.iter().enumerate() {
+            for (var, range) in outputs {
+                drivers.entry(*var).or_default().push((i, *range));
+            }
+        }
+
+        accesses
+            .iter()
+            .enumerate()
+            .map(|(i, (inputs, _))| {
+                let mut sources: Vec<usize> = inputs
+                    .iter()
+                    .filter_map(|(var, range)| {
+                        drivers.get(var).map(|x| {
+                            x.iter()
+                                .filter(|(_, x)| overlap(*range, *x))
+                                .map(|(x, _)| *x)
+                        })
+                    })
+                    .flatten()
+                    .filter(|x| *x != i)
+                    .collect();
+                sources.sort();
+                sources.dedup();
+                sources
+            })
+            .collect()
+    }
+
+    /// Topological order of combinational processes,
+    /// and fan-in counts which remain non-zero for processes in or after combinational loop
+    fn comb_order(&self, sources: &[Vec<usize>]) -> (Vec<usize>, Vec<usize>) {
+        let len = self.comb.len();
+        let mut fanin = vec![0; len];
+        let mut fanout: Vec<Vec<usize>> = vec![Vec::new(); len];
+        for (i, x) in sources.iter().enumerate() {
+            fanin[i] = x.len();
+            for s in x {
+                fanout[*s].push(i);
+            }
+        }
+
+        let mut order = Vec::new();
+        let mut ready: Vec<usize> = (0..len).rev().filter(|x| fanin[*x] == 0).collect();
+        while let Some(i) = ready.pop() {
+            order.push(i);
+            for x in fanout[i].iter().rev() {
+                fanin[*x] -= 1;
+                if fanin[*x] == 0 {
+                    ready.push(*x);
+                }
+            }
+        }
+        (order, fanin)
+    }
+
+    /// Sort combinational processes so that each process is executed after its drivers
+    pub fn sort_comb(&mut self) {
+        let sources = self.comb_sources();
+        let (mut order, fanin) = self.comb_order(&sources);
+
+        // Processes in combinational loop are executed in declaration order
+        for (i, x) in fanin.iter().enumerate() {
+            if *x != 0 {
+                order.push(i);
+            }
+        }
+
+        let mut comb: Vec<_> = self.comb.drain(..).map(Some).collect();
+        self.comb = order.into_iter().map(|x| comb[x].take().unwrap()).collect();
+    }
+
+    /// Find a combinational loop, and return the processes in it in the order of data flow
+    pub fn comb_loop(&self) -> Option<Vec<usize>> {
+        let sources = self.comb_sources();
+        let (_, fanin) = self.comb_order(&sources);
+
+        // A process which is not sorted has a driver which is not sorted too,
+        // so tracing drivers from it reaches a loop eventually.
+        let mut i = fanin.iter().position(|x| *x != 0)?;
+        let mut visited = vec![None; self.comb.len()];
+        let mut path = Vec::new();
+        while visited[i].is_none() {
+            visited[i] = Some(path.len());
+            path.push(i);
+            i = *sources[i].iter().find(|x| fanin[**x] != 0).unwrap();
+        }
+
+        let mut ret = path.split_off(visited[i].unwrap());
+        ret.reverse();
+        Some(ret)
+    }
+
+    /// Find processes which drive the same bits of a variable.
+    /// The variable and indices of processes in `comb` followed by `ff` are returned.
+    pub fn multiple_drivers(&self) -> Option<(VarId, Vec<usize>)> {
+        let mut drivers: HashMap<VarId, Vec<(usize, Option<BitRange>)>> = HashMap::default();
+        let mut order = Vec::new();
+        for (i, x) in self.comb.iter().chain(self.ff.iter()).enumerate() {
+            let (_, mut outputs) = x.accesses();
+            outputs.sort();
+            outputs.dedup();
+            for (var, range) in outputs {
+                let entry = drivers.entry(var).or_default();
+                if entry.is_empty() {
+                    order.push(var);
+                }
+                entry.push((i, range));
+            }
+        }
+
+        for var in order {
+            let drivers = &drivers[&var];
+            for (i, (x, x_range)) in drivers.iter().enumerate() {
+                let mut ret: Vec<usize> = drivers[i..]
+                    .iter()
+                    .filter(|(_, y_range)| overlap(*x_range, *y_range))
+                    .map(|(y, _)| *y)
+                    .collect();
+                ret.dedup();
+                if ret.iter().any(|y| y != x) {
+                    return Some((var, ret));
+                }
+            }
+        }
+        None
+    }
+}
+
+impl fmt::Display for Ir {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut ret = String::new();
+        for x in &self.variables {
+            ret.push_str(&format!("var {}: {}\n", x.name, x.value));
+        }
+        for x in &self.comb {
+            ret.push_str(&format!(
+                "comb inputs: {:?} outputs: {:?}\n",
+                x.inputs, x.outputs
+            ));
+        }
+        for x in &self.ff {
+            if let ProcessKind::Ff(event) = &x.kind {
+                ret.push_str(&format!(
+                    "ff clock: {:?} inputs: {:?} outputs: {:?}\n",
+                    event.clock, x.inputs, x.outputs
+                ));
+            }
+        }
+        for x in &self.initial {
+            ret.push_str(&format!("initial inputs: {:?}\n", x.inputs));
+        }
+        ret.fmt(f)
+    }
+}
@@ -1,18 +1,17 @@
-pub struct Simulator;
-
-impl Simulator {
-    pub fn new(_top: &str) -> Self {
-        Self
-    }
-
-    pub fn set(&mut self, _port: &str, _value: usize) {}
-
-    pub fn get(&mut self, _port: &str) -> usize {
-        0
-    }
-
-    pub fn step(&mut self) {}
-}
+pub mod compiler;
+pub mod elaborator;
+pub mod evaluator;
+pub mod format;
+pub mod ir;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod simulator;
+pub mod simulator_error;
+pub mod wave;
+pub use simulator::{Message, Simulator};
+pub use simulator_error::SimulatorError;
 
 #[cfg(test)]
 mod tests;
+
+type HashMap<K, V> = fxhash::FxHashMap<K, V>;
@@ -0,0 +1,173 @@
+use crate::Simulator;
+use crate::ir::SystemFunctionKind;
+use crate::simulator::Message;
+use pyo3::prelude::*;
+use std::ffi::CString;
+use veryl_analyzer::value::Value;
+
+const COCOTB: &str = include_str!("python/cocotb.py");
+
+/// Python handle of the simulator which is used by the cocotb-compatible scheduler.
+/// Values are exchanged as binary strings which may contain `x` and `z`.
+#[pyclass(name = "Simulator", unsendable)]
+pub struct PySimulator {
+    sim: Simulator,
+}
+
+#[pymethods]
+impl PySimulator {
+    fn get(&self, name: &str) -> Option<String> {
+        self.sim.get(name).map(|x| binary_string(&x))
+    }
+
+    fn set(&mut self, name: &str, value: &str) -> PyResult<()> {
+        let value = Value::from_digits(value, 2).ok_or_else(|| {
+            pyo3::exceptions::PyValueError::new_err(format!("invalid value: {value}"))
+        })?;
+        self.sim.drive(name, &value);
+        Ok(())
+    }
+
+    fn width(&self, name: &str) -> Option<usize> {
+        let ir = self.sim.ir();
+        ir.get_variable(name).map(|x| ir.variables[x.0].width)
+    }
+
+    /// Whether the name is a prefix of hierarchical names like an instance
+    fn has_scope(&self, name: &str) -> bool {
+        let prefix = format!("{name}.");
+        self.sim.ir().names.keys().any(|x| x.starts_with(&prefix))
+    }
+
+    fn advance(&mut self, time: u64) {
+        self.sim.advance(time);
+    }
+
+    fn time(&self) -> u64 {
+        self.sim.time()
+    }
+}
+
+fn binary_string(value: &Value) -> String {
+    let bit = |words: &[u64], i: usize| words.get(i / 64).is_some_and(|x| (x >> (i % 64)) & 1 == 1);
+    (0..value.width)
+        .rev()
+        .map(
+            |i| match (bit(value.payload(), i), bit(value.mask_xz(), i)) {
+                (false, false) => '0',
+                (true, false) => '1',
+                (false, true) => 'z',
+                (true, true) => 'x',
+            },
+        )
+        .collect()
+}
+
+/// Result of a test function decorated by `cocotb.test`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CocotbTest {
+    pub name: String,
+    pub passed: bool,
+    /// Traceback of the failure or the reason of unexpected pass
+    pub message: Option<String>,
+    /// Elapsed simulation time in picoseconds
+    pub time: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CocotbResult {
+    pub tests: Vec<CocotbTest>,
+    /// Log records of `dut._log` and `cocotb.log`
+    pub messages: Vec<Message>,
+}
+
+impl CocotbResult {
+    pub fn success(&self) -> bool {
+        self.tests.iter().all(|x| x.passed)
+    }
+}
+
+fn level(name: &str) -> SystemFunctionKind {
+    match name {
+        "INFO" => SystemFunctionKind::Info,
+        "WARNING" => SystemFunctionKind::Warning,
+        "ERROR" => SystemFunctionKind::Error,
+        "CRITICAL" => SystemFunctionKind::Fatal,
+        _ => SystemFunctionKind::Display,
+    }
+}
+
+/// Execute cocotb tests in `source` against the simulator through the embedded Python interpreter.
+/// `toplevel` is the name of the handle passed to the tests as `dut`.
+/// The simulator keeps the final state, so waveform can be written after the tests.
+/// Errors of the test module itself like syntax errors are returned as `Err`.
+pub fn run_cocotb(
+    sim: &mut Simulator,
+    toplevel: &str,
+    module: &str,
+    source: &str,
+) -> Result<CocotbResult, String> {
+    Python::with_gil(|py| {
+        let format = |e: PyErr| {
+            let traceback = e
+                .traceback(py)
+                .and_then(|x| x.format().ok())
+                .unwrap_or_default();
+            format!("{traceback}{e}")
+        };
+        run(py, sim, toplevel, module, source).map_err(format)
+    })
+}
+
+/// Name, pass/fail, failure message and elapsed time returned by the shim
+type TestRecord = (String, bool, Option<String>, u64);
+
+fn run(
+    py: Python,
+    sim: &mut Simulator,
+    toplevel: &str,
+    module: &str,
+    source: &str,
+) -> PyResult<CocotbResult> {
+    let cstr = |x: &str| {
+        CString::new(x).map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    };
+
+    // The shim is loaded for each run to reset registered tests
+    let cocotb = PyModule::from_code(py, &cstr(COCOTB)?, c"cocotb.py", c"cocotb")?;
+    let modules = py.import("sys")?.getattr("modules")?;
+    modules.set_item("cocotb", &cocotb)?;
+    for x in ["clock", "triggers", "handle", "types"] {
+        cocotb.setattr(x, &cocotb)?;
+        modules.set_item(format!("cocotb.{x}"), &cocotb)?;
+    }
+
+    let file = format!("{module}.py");
+    PyModule::from_code(py, &cstr(source)?, &cstr(&file)?, &cstr(module)?)?;
+
+    let handle = Py::new(py, PySimulator { sim: sim.clone() })?;
+    let ret = cocotb
+        .getattr("_run")?
+        .call1((handle.clone_ref(py), toplevel));
+    *sim = handle.borrow(py).sim.clone();
+    let (tests, records): (Vec<TestRecord>, Vec<(String, String)>) = ret?.extract()?;
+
+    let tests = tests
+        .into_iter()
+        .map(|(name, passed, message, time)| CocotbTest {
+            name,
+            passed,
+            message,
+            time,
+        })
+        .collect();
+    let messages = records
+        .into_iter()
+        .map(|(kind, text)| Message {
+            kind: level(&kind),
+            text,
+        })
+        .collect();
+
+    Ok(CocotbResult { tests, messages })
+}
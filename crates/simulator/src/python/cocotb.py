@@ -0,0 +1,426 @@
+"""Subset of cocotb API executed on the native simulator of Veryl.
+
+Signal handles, clock drivers and triggers follow cocotb, and they are
+scheduled by the event loop in this module instead of an external simulator.
+"""
+
+import heapq
+import logging
+import traceback
+
+# Time is tracked in picoseconds
+_UNITS = {
+    "step": 1,
+    "fs": 0.001,
+    "ps": 1,
+    "ns": 1000,
+    "us": 1000_000,
+    "ms": 1000_000_000,
+    "sec": 1000_000_000_000,
+}
+
+_tests = []
+_scheduler = None
+
+log = logging.getLogger("cocotb")
+
+
+def _to_steps(time, units):
+    if units not in _UNITS:
+        raise ValueError(f"unknown time unit: {units}")
+    return max(int(round(time * _UNITS[units])), 0)
+
+
+def get_sim_time(units="ns"):
+    return _scheduler.time / _UNITS[units] if _scheduler else 0
+
+
+class LogicArray:
+    """Value of signal which may have X/Z bits"""
+
+    def __init__(self, binstr):
+        self.binstr = binstr
+
+    @property
+    def is_resolvable(self):
+        return all(x in "01" for x in self.binstr)
+
+    @property
+    def integer(self):
+        if not self.is_resolvable:
+            raise ValueError(f"unresolvable value: {self.binstr}")
+        return int(self.binstr, 2) if self.binstr else 0
+
+    @property
+    def signed_integer(self):
+        x = self.integer
+        if self.binstr and self.binstr[0] == "1":
+            x -= 1 << len(self.binstr)
+        return x
+
+    def __int__(self):
+        return self.integer
+
+    def __index__(self):
+        return self.integer
+
+    def __bool__(self):
+        return self.is_resolvable and self.integer != 0
+
+    def __len__(self):
+        return len(self.binstr)
+
+    def __getitem__(self, index):
+        # index 0 is LSB like HDL
+        return LogicArray(self.binstr[::-1][index][::-1])
+
+    def __eq__(self, other):
+        if isinstance(other, LogicArray):
+            return self.binstr == other.binstr
+        if isinstance(other, str):
+            return self.binstr == other
+        if isinstance(other, int):
+            return self.is_resolvable and self.integer == other % (1 << len(self))
+        return NotImplemented
+
+    def __hash__(self):
+        return hash(self.binstr)
+
+    def __str__(self):
+        return self.binstr
+
+    def __repr__(self):
+        return f"LogicArray('{self.binstr}')"
+
+
+BinaryValue = LogicArray
+
+
+def _binstr(value, width):
+    if isinstance(value, LogicArray):
+        value = value.binstr
+    if isinstance(value, str):
+        return value.lower().rjust(width, "0")[-width:]
+    value = int(value) % (1 << width)
+    return format(value, "b").rjust(width, "0")
+
+
+class SimHandle:
+    """Handle of signal or instance which is accessed by attributes like `dut.u0.sig`"""
+
+    def __init__(self, name, path):
+        self._name = name
+        self._path = path
+        self._log = logging.getLogger(f"cocotb.{name}")
+
+    def _child(self, name):
+        return f"{self._path}.{name}" if self._path else name
+
+    def __getattr__(self, name):
+        if name.startswith("__"):
+            raise AttributeError(name)
+        path = self._child(name)
+        sim = _scheduler.sim
+        if sim.width(path) is None and not sim.has_scope(path):
+            raise AttributeError(f"{self._name} has no signal \"{name}\"")
+        return SimHandle(name, path)
+
+    def __len__(self):
+        return _scheduler.sim.width(self._path) or 0
+
+    @property
+    def value(self):
+        return LogicArray(_scheduler.sim.get(self._path))
+
+    @value.setter
+    def value(self, value):
+        _scheduler.write(self._path, _binstr(value, len(self)))
+
+    def setimmediatevalue(self, value):
+        _scheduler.sim.set(self._path, _binstr(value, len(self)))
+
+    def __repr__(self):
+        return f"SimHandle({self._path or self._name})"
+
+
+class Trigger:
+    def __await__(self):
+        return (yield self)
+
+
+class Timer(Trigger):
+    def __init__(self, time, units="step"):
+        self.steps = _to_steps(time, units)
+
+    def _register(self, task):
+        _scheduler.at(_scheduler.time + self.steps, task)
+
+
+class _Edge(Trigger):
+    def __init__(self, signal):
+        self.signal = signal
+
+    def _register(self, task):
+        _scheduler.watch(self.signal._path, self._fired, task)
+
+
+class RisingEdge(_Edge):
+    @staticmethod
+    def _fired(prev, current):
+        return prev != "1" and current == "1"
+
+
+class FallingEdge(_Edge):
+    @staticmethod
+    def _fired(prev, current):
+        return prev != "0" and current == "0"
+
+
+class Edge(_Edge):
+    @staticmethod
+    def _fired(prev, current):
+        return prev != current
+
+
+class ReadWrite(Trigger):
+    def _register(self, task):
+        _scheduler.delta(task)
+
+
+ReadOnly = ReadWrite
+NextTimeStep = ReadWrite
+
+
+class ClockCycles(Trigger):
+    def __init__(self, signal, num_cycles, rising=True):
+        self.edge = RisingEdge(signal) if rising else FallingEdge(signal)
+        self.num_cycles = num_cycles
+
+    def __await__(self):
+        for _ in range(self.num_cycles):
+            yield self.edge
+        return self
+
+
+class Join(Trigger):
+    def __init__(self, task):
+        self.task = task
+
+    def _register(self, task):
+        if self.task.done():
+            _scheduler.ready.append((task, self.task._result))
+        else:
+            self.task._joins.append(task)
+
+
+class Task:
+    def __init__(self, coro):
+        self._coro = coro
+        self._done = False
+        self._result = None
+        self._joins = []
+
+    def done(self):
+        return self._done
+
+    def result(self):
+        return self._result
+
+    def kill(self):
+        if not self._done:
+            self._done = True
+            self._coro.close()
+
+    cancel = kill
+
+    def __await__(self):
+        return (yield Join(self))
+
+
+def start_soon(coro):
+    if isinstance(coro, Task):
+        return coro
+    task = Task(coro)
+    _scheduler.ready.append((task, None))
+    return task
+
+
+async def start(coro):
+    task = start_soon(coro)
+    await ReadWrite()
+    return task
+
+
+class Clock:
+    def __init__(self, signal, period, units="step"):
+        self.signal = signal
+        self.half = _to_steps(period, units) // 2
+        self.task = None
+
+    async def _run(self, start_high):
+        high = start_high
+        while True:
+            self.signal.value = int(high)
+            await Timer(self.half)
+            high = not high
+
+    def start(self, start_high=True):
+        """The returned task is already started, and it can be passed to `start_soon` too"""
+        self.task = start_soon(self._run(start_high))
+        return self.task
+
+    def stop(self):
+        if self.task:
+            self.task.kill()
+
+
+class _Scheduler:
+    def __init__(self, sim, time):
+        self.sim = sim
+        self.time = time
+        self.ready = []
+        self.timers = []
+        self.count = 0
+        self.watchers = []
+        self.deltas = []
+        self.writes = []
+
+    def at(self, time, task):
+        self.count += 1
+        heapq.heappush(self.timers, (time, self.count, task))
+
+    def watch(self, path, fired, task):
+        self.watchers.append((path, fired, task))
+
+    def delta(self, task):
+        self.deltas.append(task)
+
+    def write(self, path, binstr):
+        self.writes.append((path, binstr))
+
+    def _apply_writes(self):
+        prev = {path: self.sim.get(path) for path, _, _ in self.watchers}
+        for path, binstr in self.writes:
+            self.sim.set(path, binstr)
+        self.writes = []
+
+        watchers = []
+        for path, fired, task in self.watchers:
+            if task.done():
+                continue
+            if fired(prev[path], self.sim.get(path)):
+                self.ready.append((task, None))
+            else:
+                watchers.append((path, fired, task))
+        self.watchers = watchers
+
+    def _resume(self, task, value):
+        if task.done():
+            return
+        try:
+            trigger = task._coro.send(value)
+        except StopIteration as e:
+            task._done = True
+            task._result = e.value
+            for x in task._joins:
+                self.ready.append((x, e.value))
+            return
+        trigger._register(task)
+
+    def run(self, main, timeout):
+        start = self.time
+        self.ready.append((main, None))
+        while not main.done():
+            if self.ready:
+                ready, self.ready = self.ready, []
+                for task, value in ready:
+                    self._resume(task, value)
+            elif self.writes:
+                self._apply_writes()
+            elif self.deltas:
+                self.ready = [(x, None) for x in self.deltas]
+                self.deltas = []
+            elif self.timers:
+                time = self.timers[0][0]
+                if timeout is not None and time - start > timeout:
+                    raise SimTimeoutError(f"test exceeds {timeout / 1000}ns")
+                self.sim.advance(time // 1000 - self.time // 1000)
+                self.time = time
+                while self.timers and self.timers[0][0] == time:
+                    _, _, task = heapq.heappop(self.timers)
+                    self.ready.append((task, None))
+            else:
+                raise RuntimeError("test is waiting for a trigger which never fires")
+        return main.result()
+
+
+class SimTimeoutError(Exception):
+    pass
+
+
+class _LogHandler(logging.Handler):
+    def __init__(self, records):
+        super().__init__()
+        self.records = records
+
+    def emit(self, record):
+        time = get_sim_time("ns")
+        text = f"{time:10.2f}ns {record.levelname:<8} {record.name:<16} {record.getMessage()}"
+        self.records.append((record.levelname, text))
+
+
+def test(func=None, *, skip=False, expect_fail=False, expect_error=(), timeout_time=None, timeout_unit="step", **_):
+    """Register the test. It can be used both `@test` and `@test(...)`."""
+
+    def register(func):
+        timeout = None if timeout_time is None else _to_steps(timeout_time, timeout_unit)
+        _tests.append((func, skip, expect_fail, expect_error, timeout))
+        return func
+
+    if func is not None:
+        return register(func)
+    return register
+
+
+def _run(sim, toplevel):
+    """Execute the registered tests in order, and return the results and log records"""
+    global _scheduler
+
+    records = []
+    handler = _LogHandler(records)
+    log.addHandler(handler)
+    log.setLevel(logging.DEBUG)
+    log.propagate = False
+
+    results = []
+    dut = SimHandle(toplevel, "")
+    for func, skip, expect_fail, expect_error, timeout in _tests:
+        name = func.__name__
+        if skip:
+            log.info(f"{name} skipped")
+            continue
+
+        # tests share the simulator, so the time continues from the previous test
+        _scheduler = _Scheduler(sim, _scheduler.time if _scheduler else 0)
+        start = _scheduler.time
+        message = None
+        try:
+            _scheduler.run(Task(func(dut)), timeout)
+            passed = not expect_fail and not expect_error
+            if not passed:
+                message = "test passed unexpectedly"
+        except AssertionError:
+            passed = bool(expect_fail)
+            message = traceback.format_exc()
+        except Exception as e:
+            passed = expect_error is True or (bool(expect_error) and isinstance(e, expect_error))
+            message = traceback.format_exc()
+
+        if passed:
+            log.info(f"{name} passed")
+        else:
+            log.error(f"{name} failed")
+        results.append((name, passed, message, _scheduler.time - start))
+
+    log.removeHandler(handler)
+    return results, records
@@ -0,0 +1,294 @@
+use crate::HashMap;
+use crate::compiler::Program;
+use crate::elaborator::Elaborator;
+use crate::evaluator::{self, Flow};
+use crate::format::format;
+use crate::ir::{
+    FfEvent, Ir, ProcessKind, SystemFunctionCall, SystemFunctionKind, VarId, Variable,
+};
+use crate::simulator_error::SimulatorError;
+use crate::wave::Wave;
+use std::io::{self, Write};
+use veryl_analyzer::namespace::Namespace;
+use veryl_analyzer::value::Value;
+use veryl_metadata::{Build, WaveFormFormat};
+
+/// Message reported by a system function like `$display`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub kind: SystemFunctionKind,
+    pub text: String,
+}
+
+#[derive(Clone)]
+pub struct Simulator {
+    ir: Ir,
+    top: String,
+    time: u64,
+    wave: Option<Wave>,
+    program: Option<Program>,
+}
+
+impl Simulator {
+    /// Elaborate the top module from the analyzed symbol table with the default build options
+    pub fn new(top: &str) -> Result<Self, SimulatorError> {
+        Self::with_build(top, &Build::default())
+    }
+
+    /// Elaborate the top module with the build options which decide implicit clock and reset types
+    pub fn with_build(top: &str, build: &Build) -> Result<Self, SimulatorError> {
+        Self::with_namespace(top, &Namespace::default(), build)
+    }
+
+    /// Elaborate the top module which is resolved from the namespace like the project
+    pub fn with_namespace(
+        top: &str,
+        namespace: &Namespace,
+        build: &Build,
+    ) -> Result<Self, SimulatorError> {
+        let ir = Elaborator::elaborate(top, namespace, build)?;
+        Ok(Self {
+            ir,
+            top: top.to_string(),
+            time: 0,
+            wave: None,
+            program: None,
+        })
+    }
+
+    /// Start recording value changes of all variables for waveform dump
+    pub fn enable_wave(&mut self) {
+        self.wave = Some(Wave::new(&self.top, &self.ir, self.time));
+    }
+
+    /// Write the recorded waveform. Each `tick` advances the time by two units.
+    pub fn write_wave(&self, w: &mut impl Write, format: WaveFormFormat) -> io::Result<()> {
+        let Some(wave) = &self.wave else {
+            return Err(io::Error::other("waveform recording is not enabled"));
+        };
+        wave.write(w, format)
+    }
+
+    /// Switch to the compiled mode which executes the design as a linear instruction sequence
+    /// instead of walking expression trees. The current values of variables are kept.
+    pub fn compile(&mut self) {
+        self.program = Some(Program::compile(&self.ir));
+    }
+
+    /// Whether the compiled mode is enabled
+    pub fn is_compiled(&self) -> bool {
+        self.program.is_some()
+    }
+
+    /// Current simulation time
+    pub fn time(&self) -> u64 {
+        self.time
+    }
+
+    /// Advance the simulation time which is used as the timestamp of waveform
+    pub fn advance(&mut self, time: u64) {
+        self.time += time;
+    }
+
+    /// Set value to the variable. Unknown names are ignored.
+    pub fn set(&mut self, name: &str, value: usize) {
+        self.set_value(name, &Value::new(value as u64, usize::BITS as usize, false));
+    }
+
+    /// Set arbitrary-width value to the variable. Unknown names are ignored.
+    pub fn set_value(&mut self, name: &str, value: &Value) {
+        if let Some(id) = self.ir.get_variable(name) {
+            let var = &mut self.ir.variables[id.0];
+            var.value = var.fit(value);
+        }
+    }
+
+    /// Drive the variable from testbench, and update registers triggered by the transition.
+    /// Unknown names are ignored.
+    pub fn drive(&mut self, name: &str, value: &Value) {
+        if let Some(id) = self.ir.get_variable(name) {
+            self.transition(id, value);
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.ir
+            .get_variable(name)
+            .map(|id| self.ir.variables[id.0].value.clone())
+    }
+
+    /// Evaluate combinational logic and asserted asynchronous resets until all variables are settled
+    pub fn step(&mut self) {
+        self.settle();
+
+        let targets: Vec<_> = if let Some(program) = &mut self.program {
+            (0..self.ir.ff.len())
+                .filter(|i| program.reset(*i, &mut self.ir.variables))
+                .collect()
+        } else {
+            self.ir
+                .ff
+                .iter()
+                .enumerate()
+                .filter_map(|(i, x)| {
+                    if let ProcessKind::Ff(event) = &x.kind
+                        && let Some(reset) = &event.reset
+                        && reset.asynchronous
+                        && evaluator::evaluate(&reset.cond, &self.ir.variables, 0).to_bool()
+                    {
+                        Some(i)
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        };
+
+        if !targets.is_empty() {
+            self.update_ff(&targets);
+            self.settle();
+        }
+
+        if let Some(wave) = &mut self.wave {
+            wave.sample(self.time, &self.ir.variables);
+        }
+    }
+
+    /// Give a clock cycle to the clock.
+    /// Registers triggered by the rising edge are updated first, then ones by the falling edge.
+    pub fn tick(&mut self, clock: &str) {
+        let Some(clock) = self.ir.get_variable(clock) else {
+            return;
+        };
+
+        self.step();
+        self.time += 1;
+        self.edge(clock, true);
+        self.time += 1;
+        self.edge(clock, false);
+    }
+
+    /// Execute initial blocks in declaration order until `$finish` or `$fatal`.
+    /// The design is settled in advance, so initial blocks observe the stable values.
+    pub fn initial(&mut self) -> Vec<Message> {
+        let mut messages = Vec::new();
+        let mut system = |x: &SystemFunctionCall, variables: &[Variable]| {
+            if x.kind != SystemFunctionKind::Finish {
+                let args: Vec<_> = x
+                    .args
+                    .iter()
+                    .map(|x| evaluator::evaluate(x, variables, 0))
+                    .collect();
+                messages.push(Message {
+                    kind: x.kind,
+                    text: format(x.format.as_deref(), &args),
+                });
+            }
+            if matches!(
+                x.kind,
+                SystemFunctionKind::Finish | SystemFunctionKind::Fatal
+            ) {
+                Flow::Finish
+            } else {
+                Flow::Next
+            }
+        };
+
+        self.step();
+        if let Some(program) = &mut self.program {
+            for i in 0..self.ir.initial.len() {
+                if program.initial(i, &mut self.ir.variables, &mut system) == Flow::Finish {
+                    break;
+                }
+            }
+            return messages;
+        }
+        'process: for process in &self.ir.initial {
+            for statement in &process.statements {
+                let flow =
+                    evaluator::execute_with(statement, &mut self.ir.variables, None, &mut system);
+                if flow == Flow::Finish {
+                    break 'process;
+                }
+            }
+        }
+        messages
+    }
+
+    fn edge(&mut self, clock: VarId, posedge: bool) {
+        self.transition(clock, &Value::from_bool(posedge));
+    }
+
+    fn transition(&mut self, id: VarId, value: &Value) {
+        // Clocks of registers are compared before and after the transition
+        // because the clock may be propagated through port connections
+        let prev: Vec<_> = self.ff_clocks().map(|(_, x)| x).collect();
+
+        let var = &mut self.ir.variables[id.0];
+        var.value = var.fit(value);
+        self.settle();
+
+        let targets: Vec<_> = self
+            .ff_clocks()
+            .zip(prev)
+            .enumerate()
+            .filter_map(|(i, ((event, current), prev))| {
+                let edge = if event.posedge {
+                    current.to_bool()
+                } else {
+                    current.is_false()
+                };
+                if edge && current != prev {
+                    Some(i)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        self.update_ff(&targets);
+        self.step();
+    }
+
+    fn ff_clocks(&self) -> impl Iterator<Item = (&FfEvent, Value)> {
+        self.ir.ff.iter().map(|x| {
+            let ProcessKind::Ff(event) = &x.kind else {
+                unreachable!();
+            };
+            (event, self.ir.variables[event.clock.0].value.clone())
+        })
+    }
+
+    fn settle(&mut self) {
+        if let Some(program) = &mut self.program {
+            program.settle(&mut self.ir.variables);
+            return;
+        }
+        for process in &self.ir.comb {
+            for statement in &process.statements {
+                evaluator::execute(statement, &mut self.ir.variables, None);
+            }
+        }
+    }
+
+    /// Sample all target registers, then commit them together
+    fn update_ff(&mut self, targets: &[usize]) {
+        let mut next = HashMap::default();
+        for i in targets {
+            if let Some(program) = &mut self.program {
+                program.ff(*i, &mut self.ir.variables, &mut next);
+                continue;
+            }
+            for statement in &self.ir.ff[*i].statements {
+                evaluator::execute(statement, &mut self.ir.variables, Some(&mut next));
+            }
+        }
+        for (id, value) in next {
+            self.ir.variables[id.0].value = value;
+        }
+    }
+
+    pub fn ir(&self) -> &Ir {
+        &self.ir
+    }
+}
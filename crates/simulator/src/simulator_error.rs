@@ -0,0 +1,181 @@
+use miette::{self, Diagnostic, SourceSpan};
+use thiserror::Error;
+use veryl_analyzer::multi_sources::{MultiSources, Source};
+use veryl_parser::token_range::TokenRange;
+
+#[derive(Error, Diagnostic, Debug)]
+pub enum SimulatorError {
+    #[diagnostic(severity(Error), code(top_not_found), help(""), url(""))]
+    #[error("Top module \"{name}\" is not found")]
+    TopNotFound { name: String },
+
+    #[diagnostic(severity(Error), code(unevaluatable_expression), help(""), url(""))]
+    #[error("Expression can't be evaluated at elaboration")]
+    UnevaluatableExpression {
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(severity(Error), code(unresolvable_identifier), help(""), url(""))]
+    #[error("\"{identifier}\" can't be resolved")]
+    UnresolvableIdentifier {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(severity(Error), code(exceed_limit), help(""), url(""))]
+    #[error("exceed {kind} limit")]
+    ExceedLimit {
+        kind: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(severity(Error), code(unsupported_syntax), help(""), url(""))]
+    #[error("{kind} is not supported by simulator")]
+    UnsupportedSyntax {
+        kind: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+    },
+
+    #[diagnostic(
+        severity(Error),
+        code(combinational_loop),
+        help("break the loop by a register or split the variable"),
+        url("")
+    )]
+    #[error("Combinational loop through \"{identifier}\"")]
+    CombinationalLoop {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label(collection, "in the loop")]
+        loop_context: Vec<SourceSpan>,
+    },
+
+    #[diagnostic(
+        severity(Error),
+        code(multiple_driver),
+        help("drive the variable from one statement"),
+        url("")
+    )]
+    #[error("\"{identifier}\" is driven by multiple statements")]
+    MultipleDriver {
+        identifier: String,
+        #[source_code]
+        input: MultiSources,
+        #[label("Error location")]
+        error_location: SourceSpan,
+        #[label(collection, "also driven here")]
+        driver_context: Vec<SourceSpan>,
+    },
+}
+
+fn source(token: &TokenRange) -> MultiSources {
+    let path = token.beg.source.to_string();
+    let text = token.beg.source.get_text();
+    MultiSources {
+        sources: vec![Source { path, text }],
+    }
+}
+
+/// Sources of the main token and the other tokens which may be in different files
+fn source_with_context(
+    token: &TokenRange,
+    context: &[TokenRange],
+) -> (MultiSources, Vec<SourceSpan>) {
+    let mut base = 0;
+    let mut ranges = Vec::new();
+    let mut sources = Vec::new();
+
+    for (i, x) in std::iter::once(token).chain(context.iter()).enumerate() {
+        let path = x.beg.source.to_string();
+        let text = x.beg.source.get_text();
+
+        if i != 0 {
+            let mut range = *x;
+            range.offset(base as u32);
+            ranges.push(range.into());
+        }
+
+        base += text.len();
+        sources.push(Source { path, text });
+    }
+
+    (MultiSources { sources }, ranges)
+}
+
+impl SimulatorError {
+    pub fn top_not_found(name: &str) -> Self {
+        SimulatorError::TopNotFound {
+            name: name.to_string(),
+        }
+    }
+
+    pub fn unevaluatable_expression(token: &TokenRange) -> Self {
+        SimulatorError::UnevaluatableExpression {
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unresolvable_identifier(identifier: &str, token: &TokenRange) -> Self {
+        SimulatorError::UnresolvableIdentifier {
+            identifier: identifier.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn exceed_limit(kind: &str, token: &TokenRange) -> Self {
+        SimulatorError::ExceedLimit {
+            kind: kind.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn unsupported_syntax(kind: &str, token: &TokenRange) -> Self {
+        SimulatorError::UnsupportedSyntax {
+            kind: kind.to_string(),
+            input: source(token),
+            error_location: token.into(),
+        }
+    }
+
+    pub fn combinational_loop(
+        identifier: &str,
+        token: &TokenRange,
+        context: &[TokenRange],
+    ) -> Self {
+        let (input, loop_context) = source_with_context(token, context);
+        SimulatorError::CombinationalLoop {
+            identifier: identifier.to_string(),
+            input,
+            error_location: token.into(),
+            loop_context,
+        }
+    }
+
+    pub fn multiple_driver(identifier: &str, token: &TokenRange, context: &[TokenRange]) -> Self {
+        let (input, driver_context) = source_with_context(token, context);
+        SimulatorError::MultipleDriver {
+            identifier: identifier.to_string(),
+            input,
+            error_location: token.into(),
+            driver_context,
+        }
+    }
+}
@@ -1,10 +1,9 @@
-use crate::Simulator;
-use veryl_analyzer::namespace::Namespace;
-use veryl_analyzer::symbol::SymbolKind;
-use veryl_analyzer::symbol_path::SymbolPath;
-use veryl_analyzer::{Analyzer, AnalyzerError, definition_table, symbol_table};
-use veryl_metadata::Metadata;
-use veryl_parser::{Parser, resource_table};
+use crate::ir::SystemFunctionKind;
+use crate::{Simulator, SimulatorError};
+use veryl_analyzer::value::Value;
+use veryl_analyzer::{Analyzer, AnalyzerError, symbol_table};
+use veryl_metadata::{Build, ClockType, Metadata, ResetType, WaveFormFormat};
+use veryl_parser::Parser;
 
 #[track_caller]
 fn analyze(code: &str) -> Vec<AnalyzerError> {
@@ -12,18 +11,25 @@ fn analyze(code: &str) -> Vec<AnalyzerError> {
 
     let metadata: Metadata =
         toml::from_str(&Metadata::create_default_toml("prj").unwrap()).unwrap();
-    let parser = Parser::parse(&code, &"").unwrap();
+    let parser = Parser::parse(code, &"").unwrap();
     let analyzer = Analyzer::new(&metadata);
 
     let mut errors = vec![];
-    errors.append(&mut analyzer.analyze_pass1(&"prj", &"", &parser.veryl));
+    errors.append(&mut analyzer.analyze_pass1("prj", "", &parser.veryl));
     errors.append(&mut Analyzer::analyze_post_pass1());
-    errors.append(&mut analyzer.analyze_pass2(&"prj", &"", &parser.veryl));
-    errors.append(&mut analyzer.analyze_pass3(&"prj", &"", &parser.veryl));
+    errors.append(&mut analyzer.analyze_pass2("prj", "", &parser.veryl));
+    errors.append(&mut analyzer.analyze_pass3("prj", "", &parser.veryl));
     dbg!(&errors);
     errors
 }
 
+#[track_caller]
+fn simulator(code: &str, top: &str) -> Simulator {
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+    Simulator::new(top).unwrap()
+}
+
 #[test]
 fn simple_sim() {
     let code = r#"
@@ -36,45 +42,1311 @@ fn simple_sim() {
     }
     "#;
 
-    // Call Veryl compiler
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 10);
+    sim.set("b", 20);
+    sim.step();
+
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(30));
+}
+
+#[test]
+fn dependency_order() {
+    let code = r#"
+    module Top (
+        a: input  logic<8>,
+        b: output logic<8>,
+    ) {
+        var x: logic<8>;
+        var y: logic<8>;
+        assign b = y + 1;
+        assign y = x * 2;
+        assign x = a + 1;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 3);
+    sim.step();
+
+    assert_eq!(sim.get("x").unwrap().to_usize(), Some(4));
+    assert_eq!(sim.get("y").unwrap().to_usize(), Some(8));
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(9));
+}
+
+#[test]
+fn always_comb() {
+    let code = r#"
+    module Top (
+        sel: input  logic<2>,
+        a  : input  logic<8>,
+        b  : input  logic<8>,
+        c  : output logic<8>,
+    ) {
+        always_comb {
+            if sel == 0 {
+                c = a;
+            } else if sel == 1 {
+                c = b;
+            } else {
+                c  = a;
+                c += b;
+            }
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 200);
+    sim.set("b", 100);
+
+    sim.set("sel", 0);
+    sim.step();
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(200));
+
+    sim.set("sel", 1);
+    sim.step();
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(100));
+
+    sim.set("sel", 2);
+    sim.step();
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(44));
+}
+
+#[test]
+fn select_concatenation() {
+    let code = r#"
+    module Top #(
+        param W: u32 = 4,
+    ) (
+        a: input  logic<8>,
+        b: output logic<8>,
+        c: output logic<W>,
+        d: output logic<2, 4>,
+        e: output logic,
+    ) {
+        assign b    = {a[3:0], a[7:4]};
+        assign c    = a[W - 1 + 2:2];
+        assign d[1] = a[3:0];
+        assign d[0] = a[7:4];
+        assign e    = a[msb];
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 0xa5);
+    sim.step();
+
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(0x5a));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(0x9));
+    assert_eq!(sim.get("d").unwrap().to_usize(), Some(0x5a));
+    assert_eq!(sim.get("e").unwrap().to_usize(), Some(1));
+}
+
+#[test]
+fn signed_arithmetic() {
+    let code = r#"
+    module Top (
+        a: input  signed logic<8>,
+        b: input  signed logic<8>,
+        c: output signed logic<16>,
+        d: output        logic,
+        e: output        logic<16>,
+    ) {
+        assign c = a * b;
+        assign d = a <: b;
+        assign e = '1;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 0xfe);
+    sim.set("b", 3);
+    sim.step();
+
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(0xfffa));
+    assert_eq!(sim.get("d").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("e").unwrap().to_usize(), Some(0xffff));
+}
+
+#[test]
+fn counter() {
+    let code = r#"
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        en : input  logic,
+        cnt: output logic<4>,
+    ) {
+        always_ff {
+            if_reset {
+                cnt = 0;
+            } else if en {
+                cnt += 1;
+            }
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    // reset is asynchronous active low by default
+    sim.set("rst", 0);
+    sim.step();
+    assert_eq!(sim.get("cnt").unwrap().to_usize(), Some(0));
+
+    sim.set("rst", 1);
+    sim.set("en", 1);
+    for _ in 0..3 {
+        sim.tick("clk");
+    }
+    assert_eq!(sim.get("cnt").unwrap().to_usize(), Some(3));
+
+    sim.set("en", 0);
+    sim.tick("clk");
+    assert_eq!(sim.get("cnt").unwrap().to_usize(), Some(3));
+
+    sim.set("en", 1);
+    for _ in 0..13 {
+        sim.tick("clk");
+    }
+    assert_eq!(sim.get("cnt").unwrap().to_usize(), Some(0));
+
+    sim.set("rst", 0);
+    sim.step();
+    assert_eq!(sim.get("cnt").unwrap().to_usize(), Some(0));
+}
+
+#[test]
+fn nonblocking() {
+    let code = r#"
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        a  : output logic<8>,
+        b  : output logic<8>,
+        c  : output logic<8>,
+    ) {
+        var d: logic<8>;
+
+        assign d = a + b;
+
+        always_ff {
+            if_reset {
+                a = 1;
+                b = 2;
+            } else {
+                a = b;
+                b = a;
+            }
+        }
+
+        always_ff {
+            c = d;
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("rst", 0);
+    sim.step();
+    sim.set("rst", 1);
+
+    sim.tick("clk");
+    assert_eq!(sim.get("a").unwrap().to_usize(), Some(2));
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(3));
+
+    sim.tick("clk");
+    assert_eq!(sim.get("a").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(2));
+}
+
+#[test]
+fn reset_type() {
+    let code = r#"
+    module A (
+        clk: input  clock,
+        rst: input  reset,
+        a  : output logic,
+        b  : output bit  ,
+    ) {
+        always_ff {
+            if_reset {
+                a = 1;
+            } else {
+                a = 0;
+            }
+        }
+
+        always_ff {
+            b = ~b;
+        }
+    }
+
+    module B (
+        clk: input  clock_negedge,
+        rst: input  reset_async_high,
+        a  : output logic,
+    ) {
+        always_ff {
+            if_reset {
+                a = 1;
+            } else {
+                a = 0;
+            }
+        }
+    }
+
+    module C (
+        clk: input  clock_posedge,
+        rst: input  reset_sync_low,
+        a  : output logic,
+    ) {
+        always_ff (clk, rst) {
+            if_reset {
+                a = 1;
+            } else {
+                a = 0;
+            }
+        }
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let build = Build {
+        clock_type: ClockType::NegEdge,
+        reset_type: ResetType::SyncHigh,
+        ..Default::default()
+    };
+    let mut a = Simulator::with_build("A", &build).unwrap();
+    let mut b = Simulator::with_build("B", &build).unwrap();
+    let mut c = Simulator::with_build("C", &build).unwrap();
+
+    a.set("rst", 1);
+    b.set("rst", 1);
+    c.set("rst", 0);
+    a.step();
+    b.step();
+    c.step();
+
+    // only asynchronous reset is applied without clock
+    assert!(a.get("a").unwrap().has_xz());
+    assert_eq!(b.get("a").unwrap().to_usize(), Some(1));
+    assert!(c.get("a").unwrap().has_xz());
+
+    a.tick("clk");
+    c.tick("clk");
+    assert_eq!(a.get("a").unwrap().to_usize(), Some(1));
+    assert_eq!(a.get("b").unwrap().to_usize(), Some(1));
+    assert_eq!(c.get("a").unwrap().to_usize(), Some(1));
+
+    a.set("rst", 0);
+    b.set("rst", 0);
+    c.set("rst", 1);
+    a.tick("clk");
+    b.tick("clk");
+    c.tick("clk");
+    assert_eq!(a.get("a").unwrap().to_usize(), Some(0));
+    assert_eq!(a.get("b").unwrap().to_usize(), Some(0));
+    assert_eq!(b.get("a").unwrap().to_usize(), Some(0));
+    assert_eq!(c.get("a").unwrap().to_usize(), Some(0));
+}
+
+#[test]
+fn wide_value() {
+    let code = r#"
+    module Top (
+        a: input  logic<256>,
+        b: input  logic<256>,
+        c: output logic<256>,
+        d: output logic<128>,
+        e: output logic<512>,
+        f: output logic,
+    ) {
+        assign c = a * b + 256'h1_00000000_00000000_00000000_00000000;
+        assign d = a[191:64];
+        assign e = {a, b};
+        assign f = a >: b;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    let a =
+        Value::from_literal("256'hffffffff_ffffffff_00000000_00000000_00000000_00000001").unwrap();
+    sim.set_value("a", &a);
+    sim.set("b", 3);
+    sim.step();
+
+    assert_eq!(
+        sim.get("c").unwrap().to_string(),
+        "256'h2fffffffffffffffe00000000000000000000000000000003"
+    );
+    assert_eq!(
+        sim.get("d").unwrap().to_string(),
+        "128'hffffffffffffffff0000000000000000"
+    );
+    assert_eq!(
+        sim.get("e").unwrap().to_string(),
+        "512'hffffffffffffffff00000000000000000000000000000001\
+         0000000000000000000000000000000000000000000000000000000000000003"
+    );
+    assert_eq!(sim.get("f").unwrap().to_usize(), Some(1));
+}
+
+#[test]
+fn unknown_value() {
+    let code = r#"
+    module Top (
+        a: input  logic<4>,
+        b: output logic<4>,
+        c: output logic<4>,
+        d: output logic<8>,
+        e: input  bit  <4>,
+        f: output bit  <4>,
+    ) {
+        assign b = a + 1;
+        assign c = a & 4'b0011;
+        assign d = {4'bx01z, a};
+        assign f = e + 1;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    // 4-state inputs which are not set yet are X, and 2-state ones are 0
+    sim.step();
+    assert_eq!(sim.get("b").unwrap().to_string(), "4'bxxxx");
+    assert_eq!(sim.get("c").unwrap().to_string(), "4'b00xx");
+    assert_eq!(sim.get("f").unwrap().to_usize(), Some(1));
+
+    sim.set("a", 5);
+    sim.set_value("e", &Value::x(4));
+    sim.step();
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(6));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("d").unwrap().to_string(), "8'bx01z0101");
+    assert_eq!(sim.get("f").unwrap().to_usize(), Some(1));
+}
+
+#[test]
+fn hierarchy() {
+    let code = r#"
+    module Counter #(
+        param W   : u32       = 4,
+        param INIT: logic<W> = 0,
+    ) (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        en : input  logic   ,
+        cnt: output logic<W>,
+    ) {
+        var count: logic<W>;
+
+        assign cnt = count;
+
+        always_ff {
+            if_reset {
+                count = INIT;
+            } else if en {
+                count += 1;
+            }
+        }
+    }
+
+    module Top (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        en : input  logic   ,
+        a  : output logic<4>,
+        b  : output logic<8>,
+        c  : output logic<4>,
+    ) {
+        inst u_a: Counter (
+            clk     ,
+            rst     ,
+            en      ,
+            cnt: a  ,
+        );
+
+        inst u_b: Counter #(
+            W   : 8 ,
+            INIT: 10,
+        ) (
+            clk     ,
+            rst     ,
+            en : ~en,
+            cnt: b  ,
+        );
+
+        inst u_c: Counter #(
+            W: 2,
+        ) (
+            clk              ,
+            rst              ,
+            en               ,
+            cnt: {c[3], c[0]},
+        );
+
+        assign c[2:1] = 0;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("rst", 0);
+    sim.step();
+    sim.set("rst", 1);
+    sim.set("en", 1);
+    for _ in 0..5 {
+        sim.tick("clk");
+    }
+
+    assert_eq!(sim.get("a").unwrap().to_usize(), Some(5));
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(10));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("u_a.count").unwrap().to_usize(), Some(5));
+    assert_eq!(sim.get("u_b.count").unwrap().to_usize(), Some(10));
+    assert_eq!(sim.get("u_b.en").unwrap().to_usize(), Some(0));
+    assert_eq!(sim.get("u_c.cnt").unwrap().to_usize(), Some(1));
+    assert_eq!(sim.get("u_c.count").unwrap().width, 2);
+
+    sim.set("en", 0);
+    sim.tick("clk");
+    assert_eq!(sim.get("a").unwrap().to_usize(), Some(5));
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(11));
+}
+
+#[test]
+fn interface_instance() {
+    let code = r#"
+    interface Bus {
+        var data : logic<8>;
+        var valid: logic   ;
+
+        modport master {
+            data : output,
+            valid: output,
+        }
+
+        modport slave {
+            data : input,
+            valid: input,
+        }
+    }
+
+    module Sender (
+        a  : input   logic<8>     ,
+        bus: modport Bus::master,
+    ) {
+        assign bus.data  = a + 1;
+        assign bus.valid = a != 0;
+    }
+
+    module Receiver (
+        bus: modport Bus::slave ,
+        b  : output  logic<8>,
+    ) {
+        assign b = if bus.valid ? bus.data : 0;
+    }
+
+    module Top (
+        a: input  logic<8>,
+        b: output logic<8>,
+        c: output logic<4>,
+    ) {
+        inst u_bus: Bus;
+
+        inst u_sender: Sender (
+            a       ,
+            bus: u_bus,
+        );
+
+        inst u_receiver: Receiver (
+            bus: u_bus,
+            b       ,
+        );
+
+        assign c = u_bus.data[7:4];
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 0);
+    sim.step();
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(0));
+
+    sim.set("a", 0x3f);
+    sim.step();
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(0x40));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(0x4));
+    assert_eq!(sim.get("u_bus.data").unwrap().to_usize(), Some(0x40));
+    assert_eq!(sim.get("u_receiver.b").unwrap().to_usize(), Some(0x40));
+}
+
+#[test]
+fn instance_limit() {
+    let code = r#"
+    module A (
+        a: input  logic,
+        b: output logic,
+    ) {
+        assign b = a;
+    }
+
+    module B (
+        a: input  logic,
+        b: output logic,
+    ) {
+        inst u0: A (a, b);
+    }
+
+    module Top (
+        a: input  logic<2>,
+        b: output logic<2>,
+    ) {
+        inst u0: B (a: a[0], b: b[0]);
+        inst u1: B (a: a[1], b: b[1]);
+    }
+    "#;
+
     let errors = analyze(code);
     assert!(errors.is_empty());
 
-    let top = resource_table::insert_str("Top");
-    let namespace = Namespace::default();
-    let path = SymbolPath::new(&[top]);
+    let mut sim = Simulator::new("Top").unwrap();
+    sim.set("a", 2);
+    sim.step();
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(2));
+    assert_eq!(sim.get("u1.u0.b").unwrap().to_usize(), Some(1));
+
+    let build = Build {
+        instance_depth_limit: 1,
+        ..Default::default()
+    };
+    assert!(matches!(
+        Simulator::with_build("Top", &build),
+        Err(SimulatorError::ExceedLimit { .. })
+    ));
+
+    let build = Build {
+        instance_total_limit: 3,
+        ..Default::default()
+    };
+    assert!(matches!(
+        Simulator::with_build("Top", &build),
+        Err(SimulatorError::ExceedLimit { .. })
+    ));
+}
 
-    // get top module definition
-    if let Ok(symbol) = symbol_table::resolve((&path, &namespace)) {
-        if let SymbolKind::Module(x) = &symbol.found.kind {
-            let top_define = definition_table::get(x.definition);
-            dbg!(top_define);
+#[test]
+fn wave_dump() {
+    let code = r#"
+    module Counter (
+        clk: input  clock,
+        rst: input  reset,
+        cnt: output logic<2>,
+    ) {
+        always_ff {
+            if_reset {
+                cnt = 0;
+            } else {
+                cnt += 1;
+            }
         }
     }
 
-    // get port/variable symbol information
-    for symbol in symbol_table::get_all() {
-        match symbol.kind {
-            SymbolKind::Port(x) => {
-                dbg!(x);
+    module Top (
+        clk: input  clock,
+        rst: input  reset,
+        cnt: output logic<2>,
+    ) {
+        inst u_cnt: Counter (clk, rst, cnt);
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.enable_wave();
+    sim.set("rst", 0);
+    sim.step();
+    sim.set("rst", 1);
+    for _ in 0..3 {
+        sim.tick("clk");
+    }
+    assert_eq!(sim.time(), 6);
+
+    let mut vcd = Vec::new();
+    sim.write_wave(&mut vcd, WaveFormFormat::Vcd).unwrap();
+    let vcd = String::from_utf8(vcd).unwrap();
+
+    // ports connected to the parent share the identifier code
+    assert!(vcd.contains("$scope module Top $end\n$var wire 1 ! clk $end"));
+    assert!(vcd.contains("$scope module u_cnt $end\n$var wire 1 ! clk $end"));
+    assert!(vcd.contains("$var wire 2 # cnt [1:0] $end"));
+    assert!(vcd.contains("#0\n$dumpvars\nx!\n1\"\nb00 #\n$end"));
+    assert!(vcd.contains("#1\n1!\nb01 #\n#2\n0!\n"));
+    assert!(vcd.ends_with("#5\n1!\nb11 #\n#6\n0!\n"));
+
+    let mut fst = Vec::new();
+    sim.write_wave(&mut fst, WaveFormFormat::Fst).unwrap();
+
+    // header block has start time 0 and end time 6
+    assert_eq!(fst[0], 0);
+    assert_eq!(fst[1..9], 329u64.to_be_bytes());
+    assert_eq!(fst[9..17], 0u64.to_be_bytes());
+    assert_eq!(fst[17..25], 6u64.to_be_bytes());
+}
+
+#[test]
+fn wave_fst_alias() {
+    let code = r#"
+    module Leaf (
+        i: input  logic,
+        o: output logic,
+    ) {
+        assign o = ~i;
+    }
+
+    module Mid (
+        i: input  logic,
+        o: output logic,
+    ) {
+        var m: logic;
+
+        inst u_a: Leaf (i, o: m);
+        inst u_b: Leaf (i: m, o);
+    }
+
+    module Top (
+        i: input  logic,
+        o: output logic,
+    ) {
+        inst u_mid: Mid (i, o);
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.enable_wave();
+    sim.set("i", 1);
+    sim.step();
+
+    let mut fst = Vec::new();
+    sim.write_wave(&mut fst, WaveFormFormat::Fst).unwrap();
+
+    // Find and decompress the hierarchy block
+    let mut pos = 0;
+    let hierarchy = loop {
+        let len = u64::from_be_bytes(fst[pos + 1..pos + 9].try_into().unwrap()) as usize;
+        if fst[pos] == 4 {
+            let mut ret = Vec::new();
+            let mut decoder = flate2::read::GzDecoder::new(&fst[pos + 17..pos + 1 + len]);
+            std::io::Read::read_to_end(&mut decoder, &mut ret).unwrap();
+            break ret;
+        }
+        pos += 1 + len;
+    };
+
+    fn c_str(data: &[u8], pos: &mut usize) -> String {
+        let end = *pos + data[*pos..].iter().position(|x| *x == 0).unwrap();
+        let ret = String::from_utf8(data[*pos..end].to_vec()).unwrap();
+        *pos = end + 1;
+        ret
+    }
+    fn varint(data: &[u8], pos: &mut usize) -> u64 {
+        let mut ret = 0;
+        let mut shift = 0;
+        loop {
+            let x = data[*pos];
+            *pos += 1;
+            ret |= ((x & 0x7f) as u64) << shift;
+            shift += 7;
+            if x & 0x80 == 0 {
+                return ret;
             }
-            SymbolKind::Variable(x) => {
-                dbg!(x);
+        }
+    }
+
+    // Handle of each signal, which is 1-origin and shared by aliases
+    let mut handles = std::collections::HashMap::new();
+    let mut scopes = Vec::new();
+    let mut pos = 0;
+    let mut count = 0;
+    while pos < hierarchy.len() {
+        match hierarchy[pos] {
+            254 => {
+                pos += 2;
+                scopes.push(c_str(&hierarchy, &mut pos));
+                c_str(&hierarchy, &mut pos);
+            }
+            255 => {
+                pos += 1;
+                scopes.pop();
+            }
+            _ => {
+                pos += 2;
+                let name = c_str(&hierarchy, &mut pos);
+                varint(&hierarchy, &mut pos);
+                let alias = varint(&hierarchy, &mut pos);
+                let handle = if alias == 0 {
+                    count += 1;
+                    count
+                } else {
+                    alias
+                };
+                handles.insert(format!("{}.{name}", scopes.join(".")), handle);
             }
-            _ => (),
         }
     }
 
-    // Create new simulator instance specifing "Top" as top module
-    let mut sim = Simulator::new("Top");
+    // Ports connected to the parent refer the handle of the parent signal
+    let handle = |x: &str| handles[x];
+    assert_eq!(handle("Top.u_mid.i"), handle("Top.i"));
+    assert_eq!(handle("Top.u_mid.o"), handle("Top.o"));
+    assert_eq!(handle("Top.u_mid.u_a.i"), handle("Top.i"));
+    assert_eq!(handle("Top.u_mid.u_a.o"), handle("Top.u_mid.m"));
+    assert_eq!(handle("Top.u_mid.u_b.i"), handle("Top.u_mid.m"));
+    assert_eq!(handle("Top.u_mid.u_b.o"), handle("Top.o"));
+    assert_ne!(handle("Top.u_mid.m"), handle("Top.i"));
+    assert_ne!(handle("Top.u_mid.m"), handle("Top.o"));
+    assert!(handles.values().all(|x| *x <= count));
+}
 
-    // Set values to input ports
-    sim.set("a", 10);
-    sim.set("b", 20);
+#[test]
+fn initial() {
+    let code = r#"
+    module Adder (
+        a: input  logic<8>,
+        b: input  logic<8>,
+        c: output logic<8>,
+    ) {
+        assign c = a + b;
+    }
+
+    module Top {
+        var a: logic<8>;
+        var b: logic<8>;
+        var c: logic<8>;
+        var s: signed logic<8>;
+        var x: logic<4>;
+
+        inst u_adder: Adder (a, b, c);
+
+        assign a = 8'h0f;
+        assign b = 8'h03;
+        assign s = 8'hfe;
+        assign x = 4'bx01z;
+
+        initial {
+            $display("c=%0d hex=%h bin=%b s=%d x=%0d %b %%", c, c, c[3:0], s, x, x);
+            $display(a, b);
+            if c != 8'h11 {
+                $error("unexpected %d", c);
+            }
+            $finish();
+            $display("unreachable");
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    let messages = sim.initial();
+    let texts: Vec<_> = messages.iter().map(|x| (x.kind, x.text.as_str())).collect();
+    assert_eq!(
+        texts,
+        vec![
+            (
+                SystemFunctionKind::Display,
+                "c=18 hex=12 bin=0010 s=  -2 x=X x01z %"
+            ),
+            (SystemFunctionKind::Display, " 15  3"),
+            (SystemFunctionKind::Error, "unexpected  18"),
+        ]
+    );
+
+    let mut sim = Simulator::new("Top").unwrap();
+    sim.compile();
+    assert_eq!(sim.initial(), messages);
+}
+
+#[test]
+fn function() {
+    let code = r#"
+    package PkgA {
+        /// Index of the first set bit from LSB, or 8 if no bit is set
+        function first_one (
+            a: input logic<8>,
+        ) -> logic<4> {
+            var ret: logic<4>;
+            ret = 8;
+            for i: u32 in 0..8 {
+                if a[i] {
+                    ret = i;
+                    break;
+                }
+            }
+            return ret;
+        }
+
+        function kind (
+            a: input logic<8>,
+        ) -> logic<2> {
+            case a {
+                0      : return 0;
+                1..=3  : return 1;
+                8'b1xxx_xxxx: return 2;
+                default: return 3;
+            }
+        }
+    }
+
+    module Top (
+        a: input  logic<8>,
+        b: output logic<4>,
+        c: output logic<2>,
+        d: output logic<8>,
+        e: output logic   ,
+        f: output logic<2>,
+    ) {
+        function sum_even (
+            x: input  logic<8>,
+            y: output logic<8>,
+        ) {
+            y = 0;
+            for i: u32 in 0..8 step += 2 {
+                y += x[i];
+            }
+        }
+
+        assign b = PkgA::first_one(a);
+        assign c = PkgA::kind(a);
+        always_comb {
+            sum_even(a, d);
+        }
+        assign e = inside a {1, 4..6};
+        always_comb {
+            switch {
+                a == 0 : f = 0;
+                a <: 10: f = 1;
+                default: f = 2;
+            }
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    let cases = [
+        (0b0000_0000, 8, 0, 0, 0, 0),
+        (0b0000_0010, 1, 1, 0, 0, 1),
+        (0b0000_0101, 0, 3, 2, 1, 1),
+        (0b1010_0100, 2, 2, 1, 0, 2),
+    ];
+    for (a, b, c, d, e, f) in cases {
+        sim.set("a", a);
+        sim.step();
+        assert_eq!(sim.get("b").unwrap().to_usize(), Some(b));
+        assert_eq!(sim.get("c").unwrap().to_usize(), Some(c));
+        assert_eq!(sim.get("d").unwrap().to_usize(), Some(d));
+        assert_eq!(sim.get("e").unwrap().to_usize(), Some(e));
+        assert_eq!(sim.get("f").unwrap().to_usize(), Some(f));
+    }
+}
+
+#[test]
+fn generic_function() {
+    let code = r#"
+    package PkgA {
+        const WIDTH: u32 = 6;
+
+        function calc_width (
+            entries: input u32,
+        ) -> u32 {
+            return if entries >= 2 ? $clog2(entries) : 1;
+        }
+    }
+
+    module Top (
+        a: input  logic<8>,
+        b: output logic<4>,
+        c: output logic<8>,
+    ) {
+        function add::<W: const, V: const = 1> (
+            x: input logic<W>,
+        ) -> logic<W> {
+            return x + V;
+        }
+
+        const N: u32 = PkgA::calc_width(10);
 
-    // Execute 1 clock cycle simulation
+        assign b = add::<N>(a[3:0]);
+        assign c = add::<8, PkgA::WIDTH>(a);
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    sim.set("a", 0x2f);
+    sim.step();
+
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(0x0));
+    assert_eq!(sim.get("c").unwrap().to_usize(), Some(0x35));
+}
+
+#[test]
+fn generate() {
+    let code = r#"
+    module countones #(
+        param W    : u32 = 16                           ,
+        const CLOGW: u32 = if W >: 1 ? $clog2(W) + 1 : 1,
+    ) (
+        i_data: input  logic<W>    ,
+        o_ones: output logic<CLOGW>,
+    ) {
+        if (W == 1) :gen_base_case {
+            assign o_ones = i_data;
+        } else :gen_rec_case {
+            const WBOT: u32         = W / 2;
+            const WTOP: u32         = W - WBOT;
+            let data_bot: logic<WBOT> = i_data[WBOT - 1:0];
+            let data_top: logic<WTOP> = i_data[W - 1:WBOT];
+
+            const CLOGWBOT: u32 = if WBOT >: 1 ? $clog2(WBOT) + 1 : 1;
+            const CLOGWTOP: u32 = if WTOP >: 1 ? $clog2(WTOP) + 1 : 1;
+            var ones_bot: logic<CLOGWBOT>;
+            var ones_top: logic<CLOGWTOP>;
+
+            inst u_bot: countones #(
+                W: WBOT,
+            ) (
+                i_data: data_bot,
+                o_ones: ones_bot,
+            );
+            inst u_top: countones #(
+                W: WTOP,
+            ) (
+                i_data: data_top,
+                o_ones: ones_top,
+            );
+            assign o_ones = {1'b0 repeat (W - WTOP), ones_top} + {1'b0 repeat (W - WBOT), ones_bot};
+        }
+    }
+
+    module Top (
+        a: input  logic<7>,
+        b: output logic<3>,
+        c: output logic<7>,
+    ) {
+        inst u: countones #(
+            W: 7,
+        ) (
+            i_data: a,
+            o_ones: b,
+        );
+
+        for i in 0..7 :g {
+            const J: u32 = 6 - i;
+            assign c[i] = a[J];
+        }
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+
+    for a in [0b000_0000, 0b101_0110, 0b111_1111, 0b000_1001] {
+        sim.set("a", a);
+        sim.step();
+        let reversed = (0..7).fold(0, |acc, i| acc | (((a >> i) & 1) << (6 - i)));
+        assert_eq!(
+            sim.get("b").unwrap().to_usize(),
+            Some(a.count_ones() as usize)
+        );
+        assert_eq!(sim.get("c").unwrap().to_usize(), Some(reversed));
+    }
+
+    assert!(
+        sim.get("u.gen_rec_case.u_top.gen_rec_case.ones_bot")
+            .is_some()
+    );
+    assert!(sim.get("g[3].J").is_none());
+}
+
+#[test]
+fn compiled() {
+    let code = r#"
+    module Top (
+        clk: input  clock       ,
+        rst: input  reset       ,
+        a  : input  logic    <8>,
+        b  : input  signed logic<8>,
+        c  : output logic    <8>,
+        d  : output signed logic<10>,
+        e  : output logic    <4>,
+        f  : output logic    <8>,
+    ) {
+        var cnt : logic<8>;
+        var mem : logic<4, 8>;
+        var hi  : logic<4>;
+        var lo  : logic<4>;
+        var sel : logic<2>;
+
+        function popcount (
+            x: input logic<8>,
+        ) -> logic<4> {
+            var ret: logic<4>;
+            ret = 0;
+            for i: u32 in 0..8 {
+                if x[i] {
+                    ret += 1;
+                }
+                if ret == 5 {
+                    break;
+                }
+            }
+            return ret;
+        }
+
+        always_ff {
+            if_reset {
+                cnt = 0;
+                mem = 0;
+                sel = 0;
+            } else {
+                cnt       = cnt + a;
+                mem[sel]  = cnt;
+                sel       = sel + 1;
+            }
+        }
+
+        assign {hi, lo} = cnt;
+
+        always_comb {
+            case sel {
+                0      : f = mem[0];
+                1..=2  : f = {lo, hi};
+                default: f = if a[0] ? 8'hx : mem[3];
+            }
+        }
+
+        assign c = cnt ^ ~a;
+        assign d = b * 2 + (-b >>> 1);
+        assign e = popcount(cnt);
+    }
+    "#;
+
+    let mut interpreted = simulator(code, "Top");
+    let mut compiled = Simulator::new("Top").unwrap();
+    compiled.compile();
+    assert!(compiled.is_compiled());
+
+    for sim in [&mut interpreted, &mut compiled] {
+        sim.set("rst", 0);
+        sim.step();
+        sim.set("rst", 1);
+        sim.set("b", 0xf3);
+    }
+
+    for i in 0..32 {
+        for sim in [&mut interpreted, &mut compiled] {
+            sim.set("a", i * 37 % 256);
+            sim.tick("clk");
+        }
+        for (x, y) in interpreted
+            .ir()
+            .variables
+            .iter()
+            .zip(compiled.ir().variables.iter())
+        {
+            assert_eq!(x.value, y.value, "{} at cycle {i}", x.name);
+        }
+    }
+    assert_eq!(compiled.get("cnt").unwrap().to_usize(), Some(176));
+}
+
+#[test]
+fn combinational_loop() {
+    let code = r#"
+    module Inv (
+        a: input  logic,
+        b: output logic,
+    ) {
+        assign b = ~a;
+    }
+
+    module Top (
+        a: input  logic<2>,
+        b: output logic<2>,
+    ) {
+        var x: logic<4>;
+        var y: logic;
+
+        // disjoint bits of the same variable are not a loop
+        assign x[0] = a[0];
+        assign x[1] = x[0];
+
+        inst u0: Inv (a: y, b: x[2]);
+        always_comb {
+            y = x[2] & a[1];
+        }
+        assign x[3] = 0;
+        assign b = x[1:0];
+    }
+    "#;
+
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let Err(err) = Simulator::new("Top") else {
+        panic!("combinational loop is not detected");
+    };
+    let SimulatorError::CombinationalLoop { loop_context, .. } = &err else {
+        panic!("unexpected error: {err:?}");
+    };
+    // always_comb, port connection, and assign in Inv
+    assert_eq!(loop_context.len(), 2);
+
+    let code = r#"
+    module Top (
+        a: input  logic<2>,
+        b: output logic<2>,
+    ) {
+        var x: logic<2>;
+        assign x[0] = a[0];
+        assign x[1] = x[0] ^ a[1];
+        assign b    = x;
+    }
+    "#;
+
+    let mut sim = simulator(code, "Top");
+    sim.set("a", 3);
     sim.step();
+    assert_eq!(sim.get("b").unwrap().to_usize(), Some(1));
+}
+
+#[test]
+fn multiple_driver() {
+    let code = r#"
+    module Buf (
+        a: input  logic<2>,
+        b: output logic<2>,
+    ) {
+        assign b = a;
+    }
+
+    module Top (
+        a: input  logic<2>,
+        b: output logic<4>,
+    ) {
+        inst u0: Buf (a, b: b[1:0]);
+        inst u1: Buf (a, b: b[2:1]);
+        assign b[3] = 0;
+    }
+    "#;
+
+    // overlapping output connections are not caught by analyzer
+    let errors = analyze(code);
+    assert!(errors.is_empty());
+
+    let Err(err) = Simulator::new("Top") else {
+        panic!("multiple driver is not detected");
+    };
+    let SimulatorError::MultipleDriver {
+        identifier,
+        driver_context,
+        ..
+    } = &err
+    else {
+        panic!("unexpected error: {err:?}");
+    };
+    assert_eq!(identifier, "b");
+    assert_eq!(driver_context.len(), 1);
+}
+
+#[cfg(feature = "python")]
+#[test]
+fn cocotb() {
+    let code = r#"
+    module Top (
+        clk: input  clock   ,
+        rst: input  reset   ,
+        en : input  logic   ,
+        cnt: output logic<8>,
+    ) {
+        always_ff {
+            if_reset {
+                cnt = 0;
+            } else if en {
+                cnt += 1;
+            }
+        }
+    }
+    "#;
+
+    let source = r#"
+import cocotb
+from cocotb.clock import Clock
+from cocotb.triggers import ClockCycles, FallingEdge, RisingEdge, Timer
+
+@cocotb.test()
+async def count(dut):
+    cocotb.start_soon(Clock(dut.clk, 10, units="ns").start())
+    dut.en.value = 0
+    dut.rst.value = 0
+    await ClockCycles(dut.clk, 2)
+    assert dut.cnt.value == 0
+    dut.rst.value = 1
+    dut.en.value = 1
+    await ClockCycles(dut.clk, 5)
+    await FallingEdge(dut.clk)
+    dut._log.info(f"cnt = {int(dut.cnt.value)}")
+    assert dut.cnt.value == 5
+
+@cocotb.test(expect_fail=True)
+async def wrong(dut):
+    await Timer(1, units="ns")
+    assert dut.cnt.value.integer == 0
+
+@cocotb.test()
+async def fail(dut):
+    await RisingEdge(dut.clk)
+"#;
+
+    let mut sim = simulator(code, "Top");
+    let result = crate::python::run_cocotb(&mut sim, "Top", "test_top", source).unwrap();
+
+    let tests: Vec<_> = result
+        .tests
+        .iter()
+        .map(|x| (x.name.as_str(), x.passed))
+        .collect();
+    assert_eq!(tests, [("count", true), ("wrong", true), ("fail", false)]);
+    assert_eq!(result.tests[0].time, 65000);
+    assert!(
+        result.tests[2]
+            .message
+            .as_ref()
+            .unwrap()
+            .contains("never fires")
+    );
+    assert!(
+        result
+            .messages
+            .iter()
+            .any(|x| x.kind == SystemFunctionKind::Info && x.text.contains("cnt = 5"))
+    );
+    assert!(!result.success());
 
-    // Get values from output ports
-    //assert_eq!(sim.get("c"), 30);
+    let error = crate::python::run_cocotb(&mut sim, "Top", "test_top", "def f(:").unwrap_err();
+    assert!(error.contains("SyntaxError"));
 }
@@ -0,0 +1,475 @@
+use crate::HashMap;
+use crate::format::bits;
+use crate::ir::{Ir, VarId, Variable};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use std::io::{self, Write};
+use veryl_analyzer::value::Value;
+use veryl_metadata::WaveFormFormat;
+
+/// Timescale exponent of dumped waveforms (1ns)
+const TIMESCALE: i8 = -9;
+
+#[derive(Clone, Debug, Default)]
+struct Scope {
+    name: String,
+    signals: Vec<(String, VarId)>,
+    scopes: Vec<Scope>,
+}
+
+impl Scope {
+    fn insert(&mut self, path: &[&str], var: VarId) {
+        match path {
+            [name] => self.signals.push((name.to_string(), var)),
+            [scope, rest @ ..] => {
+                let index = if let Some(x) = self.scopes.iter().position(|x| x.name == *scope) {
+                    x
+                } else {
+                    self.scopes.push(Scope {
+                        name: scope.to_string(),
+                        ..Default::default()
+                    });
+                    self.scopes.len() - 1
+                };
+                self.scopes[index].insert(rest, var);
+            }
+            [] => (),
+        }
+    }
+
+    fn count(&self) -> usize {
+        1 + self.scopes.iter().map(|x| x.count()).sum::<usize>()
+    }
+}
+
+/// Waveform recorder which keeps value changes of all variables.
+/// Scopes follow the instance hierarchy of the elaborated design,
+/// and ports connected to the parent are shown in both scopes.
+#[derive(Clone, Debug)]
+pub struct Wave {
+    scope: Scope,
+    widths: Vec<usize>,
+    is_4state: Vec<bool>,
+    start: u64,
+    /// Value changes of each variable
+    changes: Vec<Vec<(u64, Value)>>,
+}
+
+/// Signal dumped by the waveform writer
+struct Signal {
+    var: VarId,
+    /// Index among signals which have own value.
+    /// Aliases share the handle of the first signal which refers the same variable.
+    handle: usize,
+    alias: bool,
+}
+
+impl Wave {
+    pub fn new(top: &str, ir: &Ir, time: u64) -> Self {
+        let mut names: Vec<_> = ir.names.iter().collect();
+        names.sort_by(|a, b| (a.1, a.0).cmp(&(b.1, b.0)));
+
+        let mut scope = Scope {
+            name: top.to_string(),
+            ..Default::default()
+        };
+        for (name, var) in names {
+            let path: Vec<_> = name.split('.').collect();
+            scope.insert(&path, *var);
+        }
+
+        let mut ret = Self {
+            scope,
+            widths: ir.variables.iter().map(|x| x.width).collect(),
+            is_4state: ir.variables.iter().map(|x| x.is_4state).collect(),
+            start: time,
+            changes: vec![Vec::new(); ir.variables.len()],
+        };
+        ret.sample(time, &ir.variables);
+        ret
+    }
+
+    /// Record values of variables at the time.
+    /// Multiple samples at the same time are merged into the last one.
+    pub fn sample(&mut self, time: u64, variables: &[Variable]) {
+        for (changes, var) in self.changes.iter_mut().zip(variables) {
+            if let Some((last, _)) = changes.last()
+                && *last == time
+            {
+                changes.pop();
+            }
+            if changes.last().is_none_or(|(_, x)| *x != var.value) {
+                changes.push((time, var.value.clone()));
+            }
+        }
+    }
+
+    pub fn write(&self, w: &mut impl Write, format: WaveFormFormat) -> io::Result<()> {
+        match format {
+            WaveFormFormat::Vcd => self.write_vcd(w),
+            WaveFormFormat::Fst => self.write_fst(w),
+        }
+    }
+
+    /// Signals in the order of hierarchy traversal
+    fn signals(&self) -> Vec<Signal> {
+        fn traverse(scope: &Scope, handles: &mut HashMap<VarId, usize>, ret: &mut Vec<Signal>) {
+            for (_, var) in &scope.signals {
+                let alias = handles.contains_key(var);
+                let next = handles.len();
+                let handle = *handles.entry(*var).or_insert(next);
+                ret.push(Signal {
+                    var: *var,
+                    handle,
+                    alias,
+                });
+            }
+            for x in &scope.scopes {
+                traverse(x, handles, ret);
+            }
+        }
+
+        let mut ret = Vec::new();
+        traverse(&self.scope, &mut HashMap::default(), &mut ret);
+        ret
+    }
+
+    /// Times at which any value is changed after the start
+    fn times(&self) -> Vec<u64> {
+        let mut ret: Vec<_> = self
+            .changes
+            .iter()
+            .flatten()
+            .map(|(time, _)| *time)
+            .filter(|x| *x > self.start)
+            .collect();
+        ret.sort();
+        ret.dedup();
+        ret
+    }
+
+    fn end(&self) -> u64 {
+        self.times().last().copied().unwrap_or(self.start)
+    }
+
+    fn write_vcd(&self, w: &mut impl Write) -> io::Result<()> {
+        fn vcd_scope(
+            w: &mut impl Write,
+            scope: &Scope,
+            ids: &mut dyn Iterator<Item = String>,
+            widths: &[usize],
+        ) -> io::Result<()> {
+            writeln!(w, "$scope module {} $end", scope.name)?;
+            for (name, var) in &scope.signals {
+                let width = widths[var.0];
+                let id = ids.next().unwrap();
+                if width == 1 {
+                    writeln!(w, "$var wire 1 {id} {name} $end")?;
+                } else {
+                    writeln!(w, "$var wire {width} {id} {name} [{}:0] $end", width - 1)?;
+                }
+            }
+            for x in &scope.scopes {
+                vcd_scope(w, x, ids, widths)?;
+            }
+            writeln!(w, "$upscope $end")
+        }
+
+        let signals = self.signals();
+        let ids: Vec<_> = signals.iter().map(|x| vcd_id(x.handle)).collect();
+
+        writeln!(w, "$version Veryl {} $end", env!("CARGO_PKG_VERSION"))?;
+        writeln!(w, "$timescale 1ns $end")?;
+        vcd_scope(w, &self.scope, &mut ids.iter().cloned(), &self.widths)?;
+        writeln!(w, "$enddefinitions $end")?;
+
+        // Variables which have own identifier
+        let targets: Vec<_> = signals
+            .iter()
+            .zip(&ids)
+            .filter(|(x, _)| !x.alias)
+            .map(|(x, id)| (x.var, id))
+            .collect();
+
+        writeln!(w, "#{}", self.start)?;
+        writeln!(w, "$dumpvars")?;
+        for (var, id) in &targets {
+            vcd_value(w, &self.changes[var.0][0].1, id)?;
+        }
+        writeln!(w, "$end")?;
+
+        let mut index = vec![1; self.changes.len()];
+        for time in self.times() {
+            writeln!(w, "#{time}")?;
+            for (var, id) in &targets {
+                let changes = &self.changes[var.0];
+                if let Some((t, value)) = changes.get(index[var.0])
+                    && *t == time
+                {
+                    vcd_value(w, value, id)?;
+                    index[var.0] += 1;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_fst(&self, w: &mut impl Write) -> io::Result<()> {
+        let signals = self.signals();
+        let handles: Vec<_> = signals.iter().filter(|x| !x.alias).map(|x| x.var).collect();
+        let times = self.times();
+        let end = self.end();
+
+        let mut out = Vec::new();
+
+        // Header block
+        out.push(FST_BL_HDR);
+        out.extend(329u64.to_be_bytes());
+        out.extend(self.start.to_be_bytes());
+        out.extend(end.to_be_bytes());
+        out.extend(std::f64::consts::E.to_le_bytes());
+        out.extend(0u64.to_be_bytes());
+        out.extend((self.scope.count() as u64).to_be_bytes());
+        out.extend((signals.len() as u64).to_be_bytes());
+        out.extend((handles.len() as u64).to_be_bytes());
+        out.extend(1u64.to_be_bytes());
+        out.push(TIMESCALE as u8);
+        fixed_str(
+            &mut out,
+            &format!("Veryl {}", env!("CARGO_PKG_VERSION")),
+            128,
+        );
+        fixed_str(&mut out, "", 119);
+        out.push(0);
+        out.extend(0u64.to_be_bytes());
+
+        // Value change block
+        let mut body = Vec::new();
+        body.extend(self.start.to_be_bytes());
+        body.extend(end.to_be_bytes());
+
+        let mut frame = Vec::new();
+        for var in &handles {
+            frame.extend(bits(&self.changes[var.0][0].1));
+        }
+
+        // Value changes are stored uncompressed
+        let mut data = vec![b'Z'];
+        let mut offsets = Vec::new();
+        let mut memory = 0;
+        for var in &handles {
+            let changes = &self.changes[var.0][1..];
+            if changes.is_empty() {
+                offsets.push(None);
+                continue;
+            }
+
+            let mut chunk = Vec::new();
+            let mut prev = 0;
+            for (time, value) in changes {
+                let index = times.binary_search(time).unwrap();
+                let delta = (index - prev) as u64;
+                prev = index;
+                if value.width == 1 {
+                    let vli = match bits(value)[0] {
+                        b'0' => delta << 2,
+                        b'1' => (delta << 2) | 0b10,
+                        b'x' => (delta << 4) | 0b0001,
+                        _ => (delta << 4) | 0b0011,
+                    };
+                    varint(&mut chunk, vli);
+                } else if value.has_xz() {
+                    varint(&mut chunk, (delta << 1) | 1);
+                    chunk.extend(bits(value));
+                } else {
+                    varint(&mut chunk, delta << 1);
+                    let mut packed = vec![0u8; value.width.div_ceil(8)];
+                    for (i, x) in bits(value).iter().enumerate() {
+                        packed[i / 8] |= (x - b'0') << (7 - (i % 8));
+                    }
+                    chunk.extend(packed);
+                }
+            }
+
+            offsets.push(Some(data.len() as u64));
+            memory += chunk.len() as u64;
+            varint(&mut data, 0);
+            data.extend(chunk);
+        }
+
+        body.extend(memory.to_be_bytes());
+        varint(&mut body, frame.len() as u64);
+        varint(&mut body, frame.len() as u64);
+        varint(&mut body, handles.len() as u64);
+        body.extend(frame);
+        varint(&mut body, handles.len() as u64);
+        body.extend(data);
+
+        // Position table of value changes
+        let mut chain = Vec::new();
+        let mut prev = 0;
+        let mut zeros = 0;
+        for offset in offsets {
+            if let Some(offset) = offset {
+                if zeros != 0 {
+                    varint(&mut chain, zeros << 1);
+                    zeros = 0;
+                }
+                signed_varint(&mut chain, (((offset - prev) << 1) | 1) as i64);
+                prev = offset;
+            } else {
+                zeros += 1;
+            }
+        }
+        if zeros != 0 {
+            varint(&mut chain, zeros << 1);
+        }
+        body.extend(&chain);
+        body.extend((chain.len() as u64).to_be_bytes());
+
+        // Time table is stored as uncompressed deltas
+        let mut table = Vec::new();
+        let mut prev = 0;
+        for time in &times {
+            varint(&mut table, time - prev);
+            prev = *time;
+        }
+        body.extend(&table);
+        body.extend((table.len() as u64).to_be_bytes());
+        body.extend((table.len() as u64).to_be_bytes());
+        body.extend((times.len() as u64).to_be_bytes());
+
+        out.push(FST_BL_VCDATA_DYN_ALIAS2);
+        out.extend((body.len() as u64 + 8).to_be_bytes());
+        out.extend(body);
+
+        // Geometry block which is stored uncompressed
+        let mut geometry = Vec::new();
+        for var in &handles {
+            varint(&mut geometry, self.widths[var.0] as u64);
+        }
+        out.push(FST_BL_GEOM);
+        out.extend((geometry.len() as u64 + 24).to_be_bytes());
+        out.extend((geometry.len() as u64).to_be_bytes());
+        out.extend((handles.len() as u64).to_be_bytes());
+        out.extend(geometry);
+
+        // Hierarchy block
+        let mut hierarchy = Vec::new();
+        self.fst_scope(&mut hierarchy, &self.scope, &mut signals.iter());
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&hierarchy)?;
+        let compressed = encoder.finish()?;
+        out.push(FST_BL_HIER);
+        out.extend((compressed.len() as u64 + 16).to_be_bytes());
+        out.extend((hierarchy.len() as u64).to_be_bytes());
+        out.extend(compressed);
+
+        w.write_all(&out)
+    }
+
+    fn fst_scope<'a>(
+        &self,
+        out: &mut Vec<u8>,
+        scope: &Scope,
+        signals: &mut impl Iterator<Item = &'a Signal>,
+    ) {
+        out.push(FST_ST_VCD_SCOPE);
+        out.push(FST_ST_VCD_MODULE);
+        c_str(out, &scope.name);
+        c_str(out, "");
+        for (name, var) in &scope.signals {
+            let signal = signals.next().unwrap();
+            let var_type = if self.is_4state[var.0] {
+                FST_VT_SV_LOGIC
+            } else {
+                FST_VT_SV_BIT
+            };
+            out.push(var_type);
+            out.push(FST_VD_IMPLICIT);
+            c_str(out, name);
+            varint(out, self.widths[var.0] as u64);
+            // Alias refers 1-origin handle
+            let alias = if signal.alias {
+                signal.handle as u64 + 1
+            } else {
+                0
+            };
+            varint(out, alias);
+        }
+        for x in &scope.scopes {
+            self.fst_scope(out, x, signals);
+        }
+        out.push(FST_ST_VCD_UPSCOPE);
+    }
+}
+
+const FST_BL_HDR: u8 = 0;
+const FST_BL_GEOM: u8 = 3;
+const FST_BL_HIER: u8 = 4;
+const FST_BL_VCDATA_DYN_ALIAS2: u8 = 8;
+const FST_ST_VCD_MODULE: u8 = 0;
+const FST_ST_VCD_SCOPE: u8 = 254;
+const FST_ST_VCD_UPSCOPE: u8 = 255;
+const FST_VT_SV_BIT: u8 = 22;
+const FST_VT_SV_LOGIC: u8 = 23;
+const FST_VD_IMPLICIT: u8 = 0;
+
+/// Printable identifier code of VCD
+fn vcd_id(mut index: usize) -> String {
+    let mut ret = String::new();
+    loop {
+        ret.push((b'!' + (index % 94) as u8) as char);
+        index /= 94;
+        if index == 0 {
+            break;
+        }
+        index -= 1;
+    }
+    ret
+}
+
+fn vcd_value(w: &mut impl Write, value: &Value, id: &str) -> io::Result<()> {
+    let text = String::from_utf8(bits(value)).unwrap();
+    if value.width == 1 {
+        writeln!(w, "{text}{id}")
+    } else {
+        writeln!(w, "b{text} {id}")
+    }
+}
+
+fn varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn signed_varint(out: &mut Vec<u8>, mut value: i64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        let done = (value == 0 && byte & 0x40 == 0) || (value == -1 && byte & 0x40 != 0);
+        if done {
+            out.push(byte);
+            break;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn c_str(out: &mut Vec<u8>, value: &str) {
+    out.extend(value.as_bytes());
+    out.push(0);
+}
+
+fn fixed_str(out: &mut Vec<u8>, value: &str, len: usize) {
+    let mut bytes = value.as_bytes().to_vec();
+    bytes.resize(len, 0);
+    out.extend(bytes);
+}
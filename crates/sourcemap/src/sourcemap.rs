@@ -148,4 +148,21 @@ impl SourceMap {
             None
         }
     }
+
+    /// Range of destination lines which are mapped from the source lines between `beg` and `end`
+    pub fn lookup_dst_lines(&self, beg: u32, end: u32) -> Option<(u32, u32)> {
+        let x = self.source_map.as_ref()?;
+        let mut ret: Option<(u32, u32)> = None;
+        for token in x.tokens() {
+            let src_line = token.get_src_line() + 1;
+            if beg <= src_line && src_line <= end {
+                let dst_line = token.get_dst_line() + 1;
+                ret = match ret {
+                    Some((min, max)) => Some((min.min(dst_line), max.max(dst_line))),
+                    None => Some((dst_line, dst_line)),
+                };
+            }
+        }
+        ret
+    }
 }
@@ -0,0 +1,144 @@
+use criterion::{Criterion, Throughput, black_box, criterion_group, criterion_main};
+use veryl_analyzer::Analyzer;
+use veryl_metadata::Metadata;
+use veryl_parser::Parser;
+use veryl_simulator::Simulator;
+
+#[cfg(target_os = "linux")]
+mod perf;
+
+const CYCLES: usize = 1000;
+
+const CODE: &str = r#"
+module Lfsr (
+    i_clk: input  clock    ,
+    i_rst: input  reset    ,
+    o_val: output logic<32>,
+) {
+    var val: logic<32>;
+
+    always_ff {
+        if_reset {
+            val = 32'h1;
+        } else {
+            val = {val[30:0], val[31] ^ val[21] ^ val[1] ^ val[0]};
+        }
+    }
+
+    assign o_val = val;
+}
+
+module Top (
+    i_clk: input  clock    ,
+    i_rst: input  reset    ,
+    o_acc: output logic<32>,
+    o_cnt: output logic<8> ,
+) {
+    var val : logic<32>;
+    var prod: logic<32>;
+    var acc : logic<32>;
+    var cnt : logic<8>;
+
+    function popcount (
+        x: input logic<32>,
+    ) -> logic<8> {
+        var ret: logic<8>;
+        ret = 0;
+        for i: u32 in 0..32 {
+            ret += x[i];
+        }
+        return ret;
+    }
+
+    inst u_lfsr: Lfsr (
+        i_clk      ,
+        i_rst      ,
+        o_val: val ,
+    );
+
+    assign prod = val[15:0] * val[31:16];
+
+    always_comb {
+        case val[1:0] {
+            0      : cnt = popcount(val);
+            1      : cnt = popcount(prod);
+            default: cnt = val[7:0] ^ prod[7:0];
+        }
+    }
+
+    always_ff {
+        if_reset {
+            acc = 0;
+        } else {
+            acc = acc + prod + cnt;
+        }
+    }
+
+    assign o_acc = acc;
+    assign o_cnt = cnt;
+}
+"#;
+
+fn run(sim: &mut Simulator) {
+    sim.set("i_rst", 0);
+    sim.step();
+    sim.set("i_rst", 1);
+    for _ in 0..CYCLES {
+        sim.tick("i_clk");
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let metadata_path = Metadata::search_from_current().unwrap();
+    let metadata = Metadata::load(&metadata_path).unwrap();
+
+    let parser = Parser::parse(CODE, &"").unwrap();
+    let prj = &metadata.project.name;
+    let analyzer = Analyzer::new(&metadata);
+    let mut errors = Vec::new();
+    errors.append(&mut analyzer.analyze_pass1(prj, "", &parser.veryl));
+    errors.append(&mut Analyzer::analyze_post_pass1());
+    errors.append(&mut analyzer.analyze_pass2(prj, "", &parser.veryl));
+    errors.append(&mut analyzer.analyze_pass3(prj, "", &parser.veryl));
+    if !errors.is_empty() {
+        panic!("failed to analyze the benchmark design: {errors:?}");
+    }
+
+    let interpreted = Simulator::new("Top").unwrap();
+    let mut compiled = Simulator::new("Top").unwrap();
+    compiled.compile();
+
+    let mut group = c.benchmark_group("simulator");
+    group.throughput(Throughput::Elements(CYCLES as u64));
+    group.bench_function("interpret", |b| {
+        b.iter_batched_ref(
+            || black_box(interpreted.clone()),
+            run,
+            criterion::BatchSize::LargeInput,
+        )
+    });
+    group.bench_function("compile", |b| {
+        b.iter_batched_ref(
+            || black_box(compiled.clone()),
+            run,
+            criterion::BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+#[cfg(target_os = "linux")]
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(perf::FlamegraphProfiler::new(100));
+    targets = criterion_benchmark
+}
+
+#[cfg(not(target_os = "linux"))]
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = criterion_benchmark
+}
+
+criterion_main!(benches);
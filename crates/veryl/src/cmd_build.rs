@@ -29,7 +29,7 @@ impl CmdBuild {
     pub fn exec(&self, metadata: &mut Metadata, include_tests: bool, quiet: bool) -> Result<bool> {
         let paths = metadata.paths(&self.opt.files, true)?;
 
-        let mut check_error = CheckError::default();
+        let mut check_error = CheckError::new(&metadata.lint);
         let mut contexts = Vec::new();
 
         for path in &paths {
@@ -3,8 +3,9 @@ use log::info;
 use miette::{self, Diagnostic, IntoDiagnostic, Result, Severity, WrapErr};
 use std::fs;
 use thiserror::Error;
+use veryl_analyzer::lint::{self, LintError};
 use veryl_analyzer::{Analyzer, AnalyzerError};
-use veryl_metadata::Metadata;
+use veryl_metadata::{Lint, Metadata};
 use veryl_parser::Parser;
 
 pub struct CmdCheck {
@@ -15,12 +16,21 @@ pub struct CmdCheck {
 #[error("veryl check failed")]
 pub struct CheckError {
     #[related]
-    pub related: Vec<AnalyzerError>,
+    pub related: Vec<LintError>,
+    lint: Lint,
 }
 
 impl CheckError {
+    pub fn new(lint: &Lint) -> Self {
+        Self {
+            related: Vec::new(),
+            lint: lint.clone(),
+        }
+    }
+
     pub fn append(mut self, x: &mut Vec<AnalyzerError>) -> Self {
-        self.related.append(x);
+        let mut errors = lint::apply(std::mem::take(x), &self.lint);
+        self.related.append(&mut errors);
         self
     }
 
@@ -53,7 +63,7 @@ impl CmdCheck {
     pub fn exec(&self, metadata: &mut Metadata) -> Result<bool> {
         let paths = metadata.paths(&self.opt.files, true)?;
 
-        let mut check_error = CheckError::default();
+        let mut check_error = CheckError::new(&metadata.lint);
         let mut contexts = Vec::new();
 
         for path in &paths {
@@ -40,7 +40,7 @@ impl CmdPublish {
             }
         }
 
-        let mut check_error = CheckError::default();
+        let mut check_error = CheckError::new(&metadata.lint);
         let mut contexts = Vec::new();
 
         for path in &paths {
@@ -1,11 +1,14 @@
 use crate::cmd_build::CmdBuild;
-use crate::runner::{Cocotb, CocotbSource, Vcs, Verilator, Vivado};
+use crate::runner::{Cocotb, CocotbSource, Native, Vcs, Verilator, Vivado};
 use crate::{OptBuild, OptTest};
-use log::{error, info};
+use log::{error, info, warn};
 use miette::Result;
-use veryl_analyzer::symbol::{SymbolKind, TestType};
+use veryl_analyzer::attribute::Attribute as Attr;
+use veryl_analyzer::attribute_table;
+use veryl_analyzer::symbol::{SymbolKind, TestProperty, TestType};
 use veryl_analyzer::symbol_table;
 use veryl_metadata::{FilelistType, Metadata, SimType};
+use veryl_parser::veryl_token::TokenSource;
 
 pub struct CmdTest {
     opt: OptTest,
@@ -26,14 +29,50 @@ impl CmdTest {
         });
         build.exec(metadata, true, false)?;
 
+        let sim_type = if let Some(x) = self.opt.sim {
+            x.into()
+        } else {
+            metadata.test.simulator
+        };
+
         let tests: Vec<_> = symbol_table::get_all()
             .into_iter()
             .filter_map(|symbol| {
                 if symbol.namespace.to_string() == metadata.project.name {
-                    if let SymbolKind::Test(x) = symbol.kind {
-                        Some((symbol.token.text, x))
-                    } else {
-                        None
+                    match symbol.kind {
+                        SymbolKind::Test(x) => Some((symbol.token.text, x)),
+                        // testbench module written in Veryl
+                        SymbolKind::Module(_) => {
+                            let TokenSource::File { path, .. } = symbol.token.source else {
+                                return None;
+                            };
+                            attribute_table::get(&symbol.token)
+                                .into_iter()
+                                .find_map(|x| {
+                                    if let Attr::Test(x, _) = x {
+                                        Some(x.text)
+                                    } else {
+                                        None
+                                    }
+                                })
+                                .and_then(|x| {
+                                    // testbench module can be executed by native simulator only
+                                    if sim_type != SimType::Native {
+                                        warn!(
+                                            "Skipped test ({}) because it requires native simulator",
+                                            x
+                                        );
+                                        return None;
+                                    }
+                                    let property = TestProperty {
+                                        r#type: TestType::Inline,
+                                        path,
+                                        top: Some(symbol.token.text),
+                                    };
+                                    Some((x, property))
+                                })
+                        }
+                        _ => None,
                     }
                 } else {
                     None
@@ -41,12 +80,6 @@ impl CmdTest {
             })
             .collect();
 
-        let sim_type = if let Some(x) = self.opt.sim {
-            x.into()
-        } else {
-            metadata.test.simulator
-        };
-
         let mut success = 0;
         let mut failure = 0;
         for (test, property) in &tests {
@@ -55,9 +88,12 @@ impl CmdTest {
                     SimType::Verilator => Verilator::new().runner(),
                     SimType::Vcs => Vcs::new().runner(),
                     SimType::Vivado => Vivado::new().runner(),
+                    SimType::Native => Native::new().runner(),
                 },
-                TestType::CocotbEmbed(x) => Cocotb::new(CocotbSource::Embed(x)).runner(),
-                TestType::CocotbInclude(x) => Cocotb::new(CocotbSource::Include(x)).runner(),
+                TestType::CocotbEmbed(x) => Cocotb::new(CocotbSource::Embed(x), sim_type).runner(),
+                TestType::CocotbInclude(x) => {
+                    Cocotb::new(CocotbSource::Include(x), sim_type).runner()
+                }
             };
 
             if runner.run(metadata, *test, property.top, property.path, self.opt.wave)? {
@@ -170,6 +170,8 @@ pub enum SimType {
     Vcs,
     /// AMD Vivado Simulator
     Vivado,
+    /// Veryl native simulator
+    Native,
 }
 
 impl From<SimType> for veryl_metadata::SimType {
@@ -178,6 +180,7 @@ impl From<SimType> for veryl_metadata::SimType {
             SimType::Verilator => veryl_metadata::SimType::Verilator,
             SimType::Vcs => veryl_metadata::SimType::Vcs,
             SimType::Vivado => veryl_metadata::SimType::Vivado,
+            SimType::Native => veryl_metadata::SimType::Native,
         }
     }
 }
@@ -8,6 +8,7 @@ use miette::{IntoDiagnostic, Result};
 use std::process::ExitCode;
 use std::str::FromStr;
 use std::time::Instant;
+use veryl_analyzer::analyzer_error::ANALYZER_ERROR_CODES;
 use veryl_metadata::Metadata;
 
 use veryl::*;
@@ -78,6 +79,7 @@ fn main() -> Result<ExitCode> {
         _ => {
             let metadata_path = Metadata::search_from_current()?;
             let metadata = Metadata::load(metadata_path)?;
+            metadata.lint.check_rules(ANALYZER_ERROR_CODES)?;
 
             let dot_build = metadata.project_dot_build_path();
             let dot_build_lock = veryl_path::lock_dir(&dot_build)?;
@@ -10,10 +10,12 @@ use veryl_parser::resource_table::{PathId, StrId};
 use veryl_sourcemap::SourceMap;
 
 mod cocotb;
+mod native;
 mod vcs;
 mod verilator;
 mod vivado;
 pub use cocotb::*;
+pub use native::*;
 pub use vcs::*;
 pub use verilator::*;
 pub use vivado::*;
@@ -109,7 +111,15 @@ pub fn copy_wave(
     // The file always has a `.vcd` extension, because `$dumpfile` doesn't have the metadata information
     let wave_src_path = work_path.join(format!("{}.vcd", test_name));
 
-    // but let's rename the target file to the correct extension, based on the selected format
+    let wave_dst_path = wave_path(test_name, test_path, metadata)?;
+
+    fs::copy(wave_src_path, wave_dst_path).into_diagnostic()?;
+    Ok(())
+}
+
+/// Get the waveform path of the test, and create the parent directory if it doesn't exist
+pub fn wave_path(test_name: StrId, test_path: PathId, metadata: &Metadata) -> Result<PathBuf> {
+    // let's name the target file with the correct extension, based on the selected format
     let target_name = format!(
         "{}.{}",
         test_name,
@@ -129,6 +139,5 @@ pub fn copy_wave(
         fs::create_dir_all(wave_dst_dir).into_diagnostic()?;
     }
 
-    fs::copy(wave_src_path, wave_dst_path).into_diagnostic()?;
-    Ok(())
+    Ok(wave_dst_path)
 }
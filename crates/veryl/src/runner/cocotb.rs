@@ -8,7 +8,7 @@ use std::process::Stdio;
 use tokio::process::{Child, Command};
 use tokio::runtime::Runtime;
 use tokio_util::codec::{FramedRead, LinesCodec};
-use veryl_metadata::{Metadata, WaveFormFormat};
+use veryl_metadata::{Metadata, SimType, WaveFormFormat};
 use veryl_parser::resource_table::{self, PathId, StrId};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
@@ -27,14 +27,16 @@ pub enum CocotbSource {
 
 pub struct Cocotb {
     source: CocotbSource,
+    sim_type: SimType,
     state: State,
     success: bool,
 }
 
 impl Cocotb {
-    pub fn new(source: CocotbSource) -> Self {
+    pub fn new(source: CocotbSource, sim_type: SimType) -> Self {
         Self {
             source,
+            sim_type,
             state: State::Idle,
             success: true,
         }
@@ -110,6 +112,99 @@ impl Cocotb {
         }
         Ok(())
     }
+
+    fn source_text(&self, path: PathId) -> Result<String> {
+        match self.source {
+            CocotbSource::Embed(x) => {
+                let src_text = x.to_string();
+                let src_text = src_text.strip_prefix("{{{").unwrap();
+                let src_text = src_text.strip_suffix("}}}").unwrap();
+                Ok(src_text.to_string())
+            }
+            CocotbSource::Include(x) => {
+                let include_path = resource_table::get_path_value(path).unwrap();
+                let include_path = include_path
+                    .parent()
+                    .unwrap()
+                    .join(x.to_string().trim_matches('"'));
+                fs::read_to_string(include_path).into_diagnostic()
+            }
+        }
+    }
+
+    /// Execute the test by the embedded Python and the native simulator instead of an external simulator
+    #[cfg(feature = "python")]
+    fn run_native(
+        &mut self,
+        metadata: &Metadata,
+        test: StrId,
+        top: StrId,
+        path: PathId,
+        wave: bool,
+    ) -> Result<bool> {
+        use crate::runner::wave_path;
+        use std::fs::File;
+        use std::io::BufWriter;
+        use veryl_analyzer::namespace::Namespace;
+        use veryl_simulator::Simulator;
+        use veryl_simulator::ir::SystemFunctionKind;
+        use veryl_simulator::python::run_cocotb;
+
+        info!("Elaborating test ({})", test);
+
+        let mut namespace = Namespace::new();
+        namespace.push(resource_table::insert_str(&metadata.project.name));
+
+        let mut sim = match Simulator::with_namespace(&top.to_string(), &namespace, &metadata.build)
+        {
+            Ok(x) => x,
+            Err(x) => {
+                self.error(&format!("{:?}", miette::Report::new(x)));
+                error!("Failed compile ({})", test);
+                return Ok(false);
+            }
+        };
+
+        info!("Executing test ({})", test);
+
+        if wave {
+            sim.enable_wave();
+        }
+
+        let source = self.source_text(path)?;
+        match run_cocotb(&mut sim, &top.to_string(), &test.to_string(), &source) {
+            Ok(result) => {
+                for message in &result.messages {
+                    match message.kind {
+                        SystemFunctionKind::Info => self.info(&message.text),
+                        SystemFunctionKind::Warning => self.warning(&message.text),
+                        SystemFunctionKind::Error => self.error(&message.text),
+                        SystemFunctionKind::Fatal => self.fatal(&message.text),
+                        _ => self.debug(&message.text),
+                    }
+                }
+                for x in result.tests.iter().filter(|x| !x.passed) {
+                    if let Some(message) = &x.message {
+                        self.error(message);
+                    }
+                    self.failure();
+                }
+            }
+            Err(x) => {
+                self.error(&x);
+                error!("cocotb failed by Python error");
+                self.failure();
+            }
+        }
+
+        if wave {
+            let file = File::create(wave_path(test, path, metadata)?).into_diagnostic()?;
+            sim.write_wave(&mut BufWriter::new(file), metadata.test.waveform_format)
+                .into_diagnostic()?;
+        }
+
+        Ok(self.success)
+    }
 }
 
 impl Runner for Cocotb {
@@ -123,36 +218,45 @@ impl Runner for Cocotb {
     ) -> Result<bool> {
         self.success = true;
 
+        if self.sim_type == SimType::Native {
+            let top = top.unwrap();
+
+            #[cfg(feature = "python")]
+            let success = self.run_native(metadata, test, top, path, wave)?;
+
+            #[cfg(not(feature = "python"))]
+            let success = {
+                let _ = top;
+                self.error(&format!(
+                    "test ({}) requires veryl built with \"python\" feature to be executed by native simulator",
+                    test
+                ));
+                false
+            };
+
+            if success {
+                info!("Succeeded test ({})", test);
+            } else {
+                error!("Failed test ({})", test);
+            }
+            return Ok(success);
+        }
+
         let temp_dir = tempfile::tempdir().into_diagnostic()?;
 
         info!("Executing test ({})", test);
 
         let src_path = temp_dir.path().join(format!("{}.py", test));
 
-        match self.source {
-            CocotbSource::Embed(x) => {
-                let src_text = x.to_string();
-                let src_text = src_text.strip_prefix("{{{").unwrap();
-                let src_text = src_text.strip_suffix("}}}").unwrap();
-
-                let mut file = OpenOptions::new()
-                    .create(true)
-                    .write(true)
-                    .truncate(true)
-                    .open(&src_path)
-                    .into_diagnostic()?;
-                file.write_all(src_text.as_bytes()).into_diagnostic()?;
-                file.flush().into_diagnostic()?;
-            }
-            CocotbSource::Include(x) => {
-                let include_path = resource_table::get_path_value(path).unwrap();
-                let include_path = include_path
-                    .parent()
-                    .unwrap()
-                    .join(x.to_string().trim_matches('"'));
-                fs::copy(include_path, src_path).into_diagnostic()?;
-            }
-        }
+        let src_text = self.source_text(path)?;
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&src_path)
+            .into_diagnostic()?;
+        file.write_all(src_text.as_bytes()).into_diagnostic()?;
+        file.flush().into_diagnostic()?;
 
         let file_list = fs::read_to_string(metadata.filelist_path()).into_diagnostic()?;
         let mut sources = String::new();
@@ -0,0 +1,182 @@
+use crate::runner::{Runner, wave_path};
+use log::{error, info};
+use miette::{IntoDiagnostic, Report, Result};
+use std::fs::File;
+use std::io::BufWriter;
+use veryl_analyzer::namespace::Namespace;
+use veryl_metadata::Metadata;
+use veryl_parser::resource_table::{self, PathId, StrId};
+use veryl_simulator::Simulator;
+use veryl_simulator::ir::SystemFunctionKind;
+
+pub struct Native {
+    success: bool,
+}
+
+impl Native {
+    pub fn new() -> Self {
+        Self { success: true }
+    }
+
+    pub fn runner(self) -> Box<dyn Runner> {
+        Box::new(self) as Box<dyn Runner>
+    }
+}
+
+impl Default for Native {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Runner for Native {
+    fn run(
+        &mut self,
+        metadata: &Metadata,
+        test: StrId,
+        top: Option<StrId>,
+        path: PathId,
+        wave: bool,
+    ) -> Result<bool> {
+        self.success = true;
+
+        // Only testbench modules written in Veryl can be simulated
+        let Some(top) = top else {
+            self.error(&format!(
+                "test ({}) is not a Veryl module, so it can't be executed by native simulator",
+                test
+            ));
+            error!("Failed compile ({})", test);
+            return Ok(false);
+        };
+
+        info!("Elaborating test ({})", test);
+
+        let mut namespace = Namespace::new();
+        namespace.push(resource_table::insert_str(&metadata.project.name));
+
+        let mut sim = match Simulator::with_namespace(&top.to_string(), &namespace, &metadata.build)
+        {
+            Ok(x) => x,
+            Err(x) => {
+                self.error(&format!("{:?}", Report::new(x)));
+                error!("Failed compile ({})", test);
+                return Ok(false);
+            }
+        };
+
+        info!("Executing test ({})", test);
+
+        if wave {
+            sim.enable_wave();
+        }
+
+        for message in sim.initial() {
+            match message.kind {
+                SystemFunctionKind::Display => self.debug(&message.text),
+                SystemFunctionKind::Info => self.info(&message.text),
+                SystemFunctionKind::Warning => self.warning(&message.text),
+                SystemFunctionKind::Error => self.error(&message.text),
+                SystemFunctionKind::Fatal => self.fatal(&message.text),
+                SystemFunctionKind::Finish => (),
+            }
+        }
+
+        if wave {
+            let file = File::create(wave_path(test, path, metadata)?).into_diagnostic()?;
+            sim.write_wave(&mut BufWriter::new(file), metadata.test.waveform_format)
+                .into_diagnostic()?;
+        }
+
+        if self.success {
+            info!("Succeeded test ({})", test);
+            Ok(true)
+        } else {
+            error!("Failed test ({})", test);
+            Ok(false)
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "Native"
+    }
+
+    fn failure(&mut self) {
+        self.success = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use veryl_analyzer::{Analyzer, symbol_table};
+    use veryl_parser::Parser;
+
+    const CODE: &str = r#"
+    #[test(pass_test)]
+    module PassTest {
+        var a: logic<8>;
+
+        assign a = 8'h0f;
+
+        initial {
+            if a != 8'h0f {
+                $error("unexpected %d", a);
+            }
+            $finish();
+        }
+    }
+
+    #[test(fail_test)]
+    module FailTest {
+        var a: logic<8>;
+
+        assign a = 8'h0f;
+
+        initial {
+            if a != 8'h10 {
+                $error("unexpected %d", a);
+            }
+            $finish();
+        }
+    }
+    "#;
+
+    fn analyze(metadata: &Metadata) {
+        symbol_table::clear();
+
+        let parser = Parser::parse(CODE, &"").unwrap();
+        let analyzer = Analyzer::new(metadata);
+        let prj = &metadata.project.name;
+
+        let mut errors = vec![];
+        errors.append(&mut analyzer.analyze_pass1(prj, "", &parser.veryl));
+        errors.append(&mut Analyzer::analyze_post_pass1());
+        errors.append(&mut analyzer.analyze_pass2(prj, "", &parser.veryl));
+        errors.append(&mut analyzer.analyze_pass3(prj, "", &parser.veryl));
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn run() {
+        let metadata: Metadata = Metadata::create_default_toml("prj")
+            .unwrap()
+            .parse()
+            .unwrap();
+        analyze(&metadata);
+
+        let path = resource_table::insert_path(&std::path::PathBuf::from("test.veryl"));
+        let mut runner = Native::new().runner();
+
+        let test = resource_table::insert_str("pass_test");
+        let top = resource_table::insert_str("PassTest");
+        assert!(runner.run(&metadata, test, Some(top), path, false).unwrap());
+
+        let test = resource_table::insert_str("fail_test");
+        let top = resource_table::insert_str("FailTest");
+        assert!(!runner.run(&metadata, test, Some(top), path, false).unwrap());
+
+        // Test which is not a Veryl module fails
+        assert!(!runner.run(&metadata, test, None, path, false).unwrap());
+    }
+}